@@ -0,0 +1,84 @@
+// Parçacıklar kameraya bakan dörtgenler olarak, her parçacık bir instance
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+    right: vec4<f32>,
+    up: vec4<f32>,
+};
+
+struct EmitterUniform {
+    origin: vec4<f32>,
+    direction: vec4<f32>,
+    shape: vec4<f32>,
+    ranges: vec4<f32>,
+    forces: vec4<f32>,
+    spawn: vec4<u32>,
+    time: vec4<f32>,
+    color: array<vec4<f32>, 16>,
+    size: array<vec4<f32>, 4>,
+    speed: array<vec4<f32>, 4>,
+};
+
+@group(0) @binding(0) var<uniform> camera: CameraUniform;
+
+@group(1) @binding(0) var<uniform> emitter: EmitterUniform;
+@group(1) @binding(1) var particle_texture: texture_2d<f32>;
+@group(1) @binding(2) var particle_sampler: sampler;
+
+struct ParticleInput {
+    // w: yaş
+    @location(0) position: vec4<f32>,
+    // w: ömür
+    @location(1) velocity: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+fn sample_size(t: f32) -> f32 {
+    let x = clamp(t, 0.0, 1.0) * 15.0;
+    let i = u32(floor(x));
+    let j = min(i + 1u, 15u);
+    return mix(emitter.size[i / 4u][i % 4u], emitter.size[j / 4u][j % 4u], x - floor(x));
+}
+
+fn sample_color(t: f32) -> vec4<f32> {
+    let x = clamp(t, 0.0, 1.0) * 15.0;
+    let i = u32(floor(x));
+    let j = min(i + 1u, 15u);
+    return mix(emitter.color[i], emitter.color[j], x - floor(x));
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32, particle: ParticleInput) -> VertexOutput {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, -1.0), vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, 1.0), vec2<f32>(-1.0, 1.0),
+    );
+    let corner = corners[vertex_index];
+    var out: VertexOutput;
+    out.uv = vec2<f32>(corner.x, -corner.y) * 0.5 + vec2<f32>(0.5);
+
+    let age = particle.position.w;
+    let lifetime = particle.velocity.w;
+    if (age >= lifetime) {
+        // ölü parçacık, tüm köşeler aynı noktada ve kırpılıyor
+        out.clip_position = vec4<f32>(0.0, 0.0, -1.0, 1.0);
+        out.color = vec4<f32>(0.0);
+        return out;
+    }
+    let t = age / lifetime;
+    let half_size = sample_size(t) * 0.5;
+    let world = particle.position.xyz + (camera.right.xyz * corner.x + camera.up.xyz * corner.y) * half_size;
+    out.clip_position = camera.view_proj * vec4<f32>(world, 1.0);
+    out.color = sample_color(t);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(particle_texture, particle_sampler, in.uv) * in.color;
+}
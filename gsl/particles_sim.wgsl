@@ -0,0 +1,117 @@
+// Parçacık simülasyonu, her thread bir slot. CPU yedeği particles.rs'de aynı adımları izliyor
+
+const PI: f32 = 3.14159265;
+const SHAPE_SPHERE: u32 = 1u;
+const SHAPE_BOX: u32 = 2u;
+const SHAPE_CONE: u32 = 3u;
+
+struct EmitterUniform {
+    // xyz: konum
+    origin: vec4<f32>,
+    // xyz: yön, w: yayılma açısı
+    direction: vec4<f32>,
+    // x: şekil türü, yzw: şekil parametreleri
+    shape: vec4<f32>,
+    // xy: ömür aralığı, zw: hız aralığı
+    ranges: vec4<f32>,
+    // xyz: yerçekimi, w: sürtünme
+    forces: vec4<f32>,
+    // x: ilk slot, y: doğacak parçacık sayısı, z: kapasite, w: seed
+    spawn: vec4<u32>,
+    // x: dt
+    time: vec4<f32>,
+    color: array<vec4<f32>, 16>,
+    size: array<vec4<f32>, 4>,
+    speed: array<vec4<f32>, 4>,
+};
+
+struct Particle {
+    position: vec3<f32>,
+    age: f32,
+    velocity: vec3<f32>,
+    lifetime: f32,
+};
+
+@group(0) @binding(0) var<uniform> emitter: EmitterUniform;
+@group(0) @binding(1) var<storage, read_write> particles: array<Particle>;
+
+fn pcg(v: u32) -> u32 {
+    let state = v * 747796405u + 2891336453u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+fn random(index: u32, k: u32) -> f32 {
+    return f32(pcg(emitter.spawn.w ^ pcg(index * 8u + k))) / 4294967295.0;
+}
+
+// eksen etrafında en fazla `angle` açılı rastgele yön
+fn cone_direction(axis: vec3<f32>, angle: f32, u: f32, v: f32) -> vec3<f32> {
+    let cos_theta = mix(1.0, cos(angle), u);
+    let sin_theta = sqrt(max(1.0 - cos_theta * cos_theta, 0.0));
+    let phi = v * 2.0 * PI;
+    let helper = select(vec3<f32>(1.0, 0.0, 0.0), vec3<f32>(0.0, 1.0, 0.0), abs(axis.x) > 0.9);
+    let t = normalize(cross(helper, axis));
+    let b = cross(axis, t);
+    return (t * cos(phi) + b * sin(phi)) * sin_theta + axis * cos_theta;
+}
+
+fn sample_speed(t: f32) -> f32 {
+    let x = clamp(t, 0.0, 1.0) * 15.0;
+    let i = u32(floor(x));
+    let j = min(i + 1u, 15u);
+    return mix(emitter.speed[i / 4u][i % 4u], emitter.speed[j / 4u][j % 4u], x - floor(x));
+}
+
+fn spawn(index: u32) -> Particle {
+    let axis = emitter.direction.xyz;
+    var position = emitter.origin.xyz;
+    var direction = cone_direction(axis, emitter.direction.w, random(index, 0u), random(index, 1u));
+    let kind = u32(emitter.shape.x);
+    if (kind == SHAPE_SPHERE) {
+        // küre içinde düzgün dağılım, yön dışarı doğru
+        let d = cone_direction(vec3<f32>(0.0, 1.0, 0.0), PI, random(index, 2u), random(index, 3u));
+        position += d * emitter.shape.y * pow(random(index, 4u), 1.0 / 3.0);
+        direction = d;
+    } else if (kind == SHAPE_BOX) {
+        let r = vec3<f32>(random(index, 2u), random(index, 3u), random(index, 4u));
+        position += (r * 2.0 - vec3<f32>(1.0)) * emitter.shape.yzw;
+    } else if (kind == SHAPE_CONE) {
+        // eksene dik taban diski
+        let disk = cone_direction(axis, PI * 0.5, 1.0, random(index, 2u));
+        position += disk * emitter.shape.y * sqrt(random(index, 4u));
+        direction = cone_direction(axis, emitter.shape.z, random(index, 0u), random(index, 1u));
+    }
+    let speed = mix(emitter.ranges.z, emitter.ranges.w, random(index, 5u));
+    let lifetime = mix(emitter.ranges.x, emitter.ranges.y, random(index, 6u));
+    return Particle(position, 0.0, direction * speed, lifetime);
+}
+
+@compute @workgroup_size(64)
+fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let capacity = emitter.spawn.z;
+    let index = id.x;
+    if (index >= capacity) {
+        return;
+    }
+    let dt = emitter.time.x;
+    var p = particles[index];
+    var step = dt;
+
+    // halka buffer, bu frame doğanlar frame boyunca dağıtılıyor
+    let offset = (index + capacity - emitter.spawn.x) % capacity;
+    let count = emitter.spawn.y;
+    if (offset < count) {
+        p = spawn(index);
+        step = dt * (f32(count - offset) - 0.5) / f32(count);
+    }
+
+    if (p.age < p.lifetime) {
+        let t = p.age / p.lifetime;
+        p.velocity += emitter.forces.xyz * step;
+        p.velocity *= max(1.0 - emitter.forces.w * step, 0.0);
+        p.position += p.velocity * sample_speed(t) * step;
+        p.age += step;
+    }
+    particles[index] = p;
+}
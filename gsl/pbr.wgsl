@@ -0,0 +1,310 @@
+// Forward metallic-roughness PBR, ışık modeli glTF spesifikasyonundaki BRDF ile aynı
+
+override ALPHA_MASK: bool = false;
+// post-processing açıkken tone mapping orada yapılıyor, burada HDR renk çıkıyor
+override TONEMAP: bool = true;
+
+const PI: f32 = 3.14159265;
+const MAX_LIGHTS: u32 = 16u;
+const LIGHT_DIRECTIONAL: f32 = 0.0;
+const LIGHT_POINT: f32 = 1.0;
+const LIGHT_SPOT: f32 = 2.0;
+const MAX_CASCADES: u32 = 4u;
+const MAX_SPOT_SHADOWS: u32 = 4u;
+const MAX_PCF_RADIUS: i32 = 4;
+
+struct CameraUniform {
+    view_proj: mat4x4<f32>,
+    position: vec4<f32>,
+};
+
+struct Light {
+    // w: ışık tipi
+    position: vec4<f32>,
+    // w: menzil
+    direction: vec4<f32>,
+    // w: yoğunluk
+    color: vec4<f32>,
+    // x: cos(iç açı), y: cos(dış açı)
+    cone: vec4<f32>,
+};
+
+struct LightsUniform {
+    // rgb: ortam rengi, a: ortam yoğunluğu
+    ambient: vec4<f32>,
+    // x: exposure, y: environment mip sayısı
+    params: vec4<f32>,
+    // x: ışık sayısı
+    counts: vec4<u32>,
+    lights: array<Light, MAX_LIGHTS>,
+};
+
+struct ShadowUniform {
+    cascade_view_proj: array<mat4x4<f32>, MAX_CASCADES>,
+    spot_view_proj: array<mat4x4<f32>, MAX_SPOT_SHADOWS>,
+    // her cascade'in bittiği görüş derinliği
+    cascade_splits: vec4<f32>,
+    camera_forward: vec4<f32>,
+    // x: depth bias, y: normal bias, z: PCF yarıçapı, w: 1 / çözünürlük
+    params: vec4<f32>,
+    // x: cascade sayısı, y: gölgeli yönlü ışığın indeksi, z: spot sayısı, w: ilk spot katmanı
+    counts: vec4<u32>,
+    // her spot katmanının ışık indeksi
+    spot_lights: vec4<u32>,
+};
+
+struct MaterialUniform {
+    base_color: vec4<f32>,
+    // w: normal scale
+    emissive: vec4<f32>,
+    // x: metallic, y: roughness, z: occlusion strength, w: alpha cutoff
+    params: vec4<f32>,
+};
+
+@group(0) @binding(0) var<uniform> camera: CameraUniform;
+@group(0) @binding(1) var<uniform> lights: LightsUniform;
+@group(0) @binding(2) var env_map: texture_2d<f32>;
+@group(0) @binding(3) var env_sampler: sampler;
+@group(0) @binding(4) var<uniform> shadows: ShadowUniform;
+@group(0) @binding(5) var shadow_map: texture_depth_2d_array;
+@group(0) @binding(6) var shadow_sampler: sampler_comparison;
+
+@group(1) @binding(0) var<uniform> material: MaterialUniform;
+@group(1) @binding(1) var base_color_map: texture_2d<f32>;
+@group(1) @binding(2) var metallic_roughness_map: texture_2d<f32>;
+@group(1) @binding(3) var normal_map: texture_2d<f32>;
+@group(1) @binding(4) var occlusion_map: texture_2d<f32>;
+@group(1) @binding(5) var emissive_map: texture_2d<f32>;
+@group(1) @binding(6) var material_sampler: sampler;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) tangent: vec4<f32>,
+};
+
+// instance başına veri, ikinci vertex buffer'dan
+struct InstanceInput {
+    @location(4) model_0: vec4<f32>,
+    @location(5) model_1: vec4<f32>,
+    @location(6) model_2: vec4<f32>,
+    @location(7) model_3: vec4<f32>,
+    @location(8) normal_0: vec4<f32>,
+    @location(9) normal_1: vec4<f32>,
+    @location(10) normal_2: vec4<f32>,
+    @location(11) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+    @location(3) tangent: vec4<f32>,
+    @location(4) color: vec4<f32>,
+};
+
+@vertex
+fn vs_main(in: VertexInput, instance: InstanceInput) -> VertexOutput {
+    var out: VertexOutput;
+    let model = mat4x4<f32>(instance.model_0, instance.model_1, instance.model_2, instance.model_3);
+    let normal_matrix = mat3x3<f32>(instance.normal_0.xyz, instance.normal_1.xyz, instance.normal_2.xyz);
+    let world = model * vec4<f32>(in.position, 1.0);
+    out.clip_position = camera.view_proj * world;
+    out.world_position = world.xyz;
+    out.normal = normal_matrix * in.normal;
+    out.tangent = vec4<f32>((model * vec4<f32>(in.tangent.xyz, 0.0)).xyz, in.tangent.w);
+    out.uv = in.uv;
+    out.color = instance.color;
+    return out;
+}
+
+fn distribution_ggx(n_dot_h: f32, alpha: f32) -> f32 {
+    let a2 = alpha * alpha;
+    let d = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    return a2 / (PI * d * d);
+}
+
+fn visibility_smith(n_dot_l: f32, n_dot_v: f32, alpha: f32) -> f32 {
+    let a2 = alpha * alpha;
+    let gv = n_dot_l * sqrt(n_dot_v * n_dot_v * (1.0 - a2) + a2);
+    let gl = n_dot_v * sqrt(n_dot_l * n_dot_l * (1.0 - a2) + a2);
+    return 0.5 / max(gv + gl, 1e-5);
+}
+
+fn fresnel_schlick(f0: vec3<f32>, v_dot_h: f32) -> vec3<f32> {
+    return f0 + (vec3<f32>(1.0) - f0) * pow(1.0 - v_dot_h, 5.0);
+}
+
+// Karis'in mobil için analitik env BRDF yaklaşımı, LUT texture'a gerek kalmıyor
+fn env_brdf_approx(f0: vec3<f32>, roughness: f32, n_dot_v: f32) -> vec3<f32> {
+    let c0 = vec4<f32>(-1.0, -0.0275, -0.572, 0.022);
+    let c1 = vec4<f32>(1.0, 0.0425, 1.04, -0.04);
+    let r = roughness * c0 + c1;
+    let a004 = min(r.x * r.x, exp2(-9.28 * n_dot_v)) * r.x + r.y;
+    let ab = vec2<f32>(-1.04, 1.04) * a004 + r.zw;
+    return f0 * ab.x + ab.y;
+}
+
+fn equirect_uv(dir: vec3<f32>) -> vec2<f32> {
+    let d = normalize(dir);
+    return vec2<f32>(atan2(d.z, d.x) / (2.0 * PI) + 0.5, acos(clamp(d.y, -1.0, 1.0)) / PI);
+}
+
+fn aces(x: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    return clamp((x * (a * x + b)) / (x * (c * x + d) + e), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// 1 aydınlık, 0 tamamen gölgede
+fn sample_shadow(layer: u32, view_proj: mat4x4<f32>, world_position: vec3<f32>, geo_n: vec3<f32>, l: vec3<f32>) -> f32 {
+    // ışığa dik yüzeylerde daha fazla it, acne en çok orada çıkıyor
+    let slope = 1.0 - max(dot(geo_n, l), 0.0);
+    let offset = geo_n * shadows.params.y * (0.5 + slope);
+    let clip = view_proj * vec4<f32>(world_position + offset, 1.0);
+    let ndc = clip.xyz / clip.w;
+    let uv = ndc.xy * vec2<f32>(0.5, -0.5) + vec2<f32>(0.5);
+    if (any(uv < vec2<f32>(0.0)) || any(uv > vec2<f32>(1.0)) || ndc.z > 1.0) {
+        return 1.0;
+    }
+    let depth = ndc.z - shadows.params.x;
+    let radius = min(i32(shadows.params.z), MAX_PCF_RADIUS);
+    let texel = shadows.params.w;
+    var lit = 0.0;
+    var taps = 0.0;
+    for (var y = -radius; y <= radius; y = y + 1) {
+        for (var x = -radius; x <= radius; x = x + 1) {
+            let o = vec2<f32>(f32(x), f32(y)) * texel;
+            lit += textureSampleCompareLevel(shadow_map, shadow_sampler, uv + o, layer, depth);
+            taps += 1.0;
+        }
+    }
+    return lit / taps;
+}
+
+fn shadow_factor(index: u32, world_position: vec3<f32>, geo_n: vec3<f32>, l: vec3<f32>) -> f32 {
+    if (shadows.counts.x > 0u && index == shadows.counts.y) {
+        let view_depth = dot(world_position - camera.position.xyz, shadows.camera_forward.xyz);
+        for (var c = 0u; c < shadows.counts.x; c = c + 1u) {
+            if (view_depth < shadows.cascade_splits[c]) {
+                return sample_shadow(c, shadows.cascade_view_proj[c], world_position, geo_n, l);
+            }
+        }
+        return 1.0;
+    }
+    for (var s = 0u; s < shadows.counts.z; s = s + 1u) {
+        if (shadows.spot_lights[s] == index) {
+            return sample_shadow(shadows.counts.w + s, shadows.spot_view_proj[s], world_position, geo_n, l);
+        }
+    }
+    return 1.0;
+}
+
+fn light_contribution(index: u32, world_position: vec3<f32>, geo_n: vec3<f32>, n: vec3<f32>, v: vec3<f32>,
+                      albedo: vec3<f32>, f0: vec3<f32>, metallic: f32, alpha: f32) -> vec3<f32> {
+    let light = lights.lights[index];
+    var l: vec3<f32>;
+    var attenuation = 1.0;
+    let kind = light.position.w;
+    if (kind == LIGHT_DIRECTIONAL) {
+        l = normalize(-light.direction.xyz);
+    } else {
+        let to_light = light.position.xyz - world_position;
+        let distance = length(to_light);
+        l = to_light / max(distance, 1e-4);
+        // KHR_lights_punctual'daki menzil penceresi
+        let range = light.direction.w;
+        var window = 1.0;
+        if (range > 0.0) {
+            window = clamp(1.0 - pow(distance / range, 4.0), 0.0, 1.0);
+        }
+        attenuation = window / max(distance * distance, 1e-4);
+        if (kind == LIGHT_SPOT) {
+            let cd = dot(normalize(light.direction.xyz), -l);
+            attenuation *= smoothstep(light.cone.y, light.cone.x, cd);
+        }
+    }
+
+    let n_dot_l = max(dot(n, l), 0.0);
+    if (n_dot_l <= 0.0 || attenuation <= 0.0) {
+        return vec3<f32>(0.0);
+    }
+    attenuation *= shadow_factor(index, world_position, geo_n, l);
+
+    let h = normalize(l + v);
+    let n_dot_v = max(dot(n, v), 1e-4);
+    let n_dot_h = max(dot(n, h), 0.0);
+    let v_dot_h = max(dot(v, h), 0.0);
+
+    let f = fresnel_schlick(f0, v_dot_h);
+    let specular = f * distribution_ggx(n_dot_h, alpha) * visibility_smith(n_dot_l, n_dot_v, alpha);
+    let diffuse = (vec3<f32>(1.0) - f) * (1.0 - metallic) * albedo / PI;
+    let radiance = light.color.rgb * light.color.w * attenuation;
+    return (diffuse + specular) * radiance * n_dot_l;
+}
+
+@fragment
+fn fs_main(in: VertexOutput, @builtin(front_facing) front_facing: bool) -> @location(0) vec4<f32> {
+    let base = textureSample(base_color_map, material_sampler, in.uv) * material.base_color * in.color;
+    let mr = textureSample(metallic_roughness_map, material_sampler, in.uv);
+    let normal_sample = textureSample(normal_map, material_sampler, in.uv).xyz;
+    let occlusion_sample = textureSample(occlusion_map, material_sampler, in.uv).r;
+    let emissive_sample = textureSample(emissive_map, material_sampler, in.uv).rgb;
+
+    if (ALPHA_MASK && base.a < material.params.w) {
+        discard;
+    }
+
+    // glTF: roughness yeşil, metallic mavi kanalda
+    let metallic = clamp(material.params.x * mr.b, 0.0, 1.0);
+    let roughness = clamp(material.params.y * mr.g, 0.04, 1.0);
+    let alpha = roughness * roughness;
+
+    var geo_n = normalize(in.normal);
+    var t = in.tangent.xyz;
+    if (!front_facing) {
+        // çift taraflı malzemelerde arka yüz
+        geo_n = -geo_n;
+        t = -t;
+    }
+    t = normalize(t - geo_n * dot(geo_n, t));
+    let b = cross(geo_n, t) * in.tangent.w;
+    var tn = normal_sample * 2.0 - vec3<f32>(1.0);
+    tn = vec3<f32>(tn.xy * material.emissive.w, tn.z);
+    let n = normalize(mat3x3<f32>(t, b, geo_n) * tn);
+
+    let v = normalize(camera.position.xyz - in.world_position);
+    let albedo = base.rgb;
+    let f0 = mix(vec3<f32>(0.04), albedo, metallic);
+
+    var color = vec3<f32>(0.0);
+    let count = min(lights.counts.x, MAX_LIGHTS);
+    for (var i = 0u; i < count; i = i + 1u) {
+        color += light_contribution(i, in.world_position, geo_n, n, v, albedo, f0, metallic, alpha);
+    }
+
+    // image based ambient: difüz için en küçük mip, yansıma için pürüzlülüğe göre mip
+    let n_dot_v = max(dot(n, v), 1e-4);
+    let max_mip = max(lights.params.y - 1.0, 0.0);
+    let irradiance = textureSampleLevel(env_map, env_sampler, equirect_uv(n), max_mip).rgb;
+    let r = reflect(-v, n);
+    let prefiltered = textureSampleLevel(env_map, env_sampler, equirect_uv(r), roughness * max_mip).rgb;
+    let ambient_light = lights.ambient.rgb * lights.ambient.a;
+    let spec_ibl = prefiltered * env_brdf_approx(f0, roughness, n_dot_v);
+    let diff_ibl = irradiance * albedo * (1.0 - metallic);
+    let occlusion = mix(1.0, occlusion_sample, material.params.z);
+    color += (diff_ibl + spec_ibl) * ambient_light * occlusion;
+
+    color += emissive_sample * material.emissive.rgb;
+
+    color *= lights.params.x;
+    if (TONEMAP) {
+        color = aces(color);
+    }
+    return vec4<f32>(color, base.a);
+}
@@ -0,0 +1,221 @@
+// Tam ekran post-processing geçişleri: bloom, tone mapping, LUT, vignette, FXAA
+
+// ilk bloom geçişi parlaklık eşiğini uygular
+override PREFILTER: bool = false;
+
+const TONEMAP_NONE: f32 = 0.0;
+const TONEMAP_ACES: f32 = 1.0;
+
+struct PostUniform {
+    // x: exposure, y: tonemapper, z: bloom yoğunluğu, w: LUT gücü
+    params: vec4<f32>,
+    // x: eşik, y: knee
+    bloom: vec4<f32>,
+    // x: yoğunluk, y: yarıçap, z: yumuşaklık
+    vignette: vec4<f32>,
+    // x: LUT boyutu
+    lut: vec4<f32>,
+    // x: bloom, y: color grading, z: vignette
+    flags: vec4<u32>,
+};
+
+@group(0) @binding(0) var<uniform> post: PostUniform;
+@group(0) @binding(1) var source: texture_2d<f32>;
+@group(0) @binding(2) var linear_sampler: sampler;
+@group(0) @binding(3) var bloom_map: texture_2d<f32>;
+@group(0) @binding(4) var lut_map: texture_2d<f32>;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+// vertex buffer yok, ekranı kaplayan tek üçgen
+@vertex
+fn vs_fullscreen(@builtin(vertex_index) index: u32) -> VertexOutput {
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    var out: VertexOutput;
+    out.clip_position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+fn luminance(c: vec3<f32>) -> f32 {
+    return dot(c, vec3<f32>(0.2126, 0.7152, 0.0722));
+}
+
+// yumuşak eşik, knee bölgesinde karesel geçiş
+fn bloom_prefilter(c: vec3<f32>) -> vec3<f32> {
+    let threshold = post.bloom.x;
+    let knee = max(post.bloom.y * threshold, 1e-5);
+    let brightness = max(c.r, max(c.g, c.b));
+    var soft = clamp(brightness - threshold + knee, 0.0, 2.0 * knee);
+    soft = soft * soft / (4.0 * knee);
+    let contribution = max(soft, brightness - threshold) / max(brightness, 1e-5);
+    return c * contribution;
+}
+
+@fragment
+fn fs_bloom_down(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(source));
+    // yarım texel kaydırılmış 4 örnek, bilinear ile 16 texel ortalaması
+    let a = textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(-1.0, -1.0)).rgb;
+    let b = textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(1.0, -1.0)).rgb;
+    let c = textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(-1.0, 1.0)).rgb;
+    let d = textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(1.0, 1.0)).rgb;
+    var color: vec3<f32>;
+    if (PREFILTER) {
+        // Karis ortalaması, tek parlak pikselin yanıp sönmesini engeller
+        let wa = 1.0 / (1.0 + luminance(a));
+        let wb = 1.0 / (1.0 + luminance(b));
+        let wc = 1.0 / (1.0 + luminance(c));
+        let wd = 1.0 / (1.0 + luminance(d));
+        color = (a * wa + b * wb + c * wc + d * wd) / (wa + wb + wc + wd);
+        color = bloom_prefilter(color);
+    } else {
+        color = (a + b + c + d) * 0.25;
+    }
+    return vec4<f32>(color, 1.0);
+}
+
+// 3x3 tent filtresi, sonuç bir üst seviyeye toplanarak (additive blend) yazılır
+@fragment
+fn fs_bloom_up(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(source));
+    var color = textureSample(source, linear_sampler, in.uv).rgb * 4.0;
+    color += textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(-1.0, 0.0)).rgb * 2.0;
+    color += textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(1.0, 0.0)).rgb * 2.0;
+    color += textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(0.0, -1.0)).rgb * 2.0;
+    color += textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(0.0, 1.0)).rgb * 2.0;
+    color += textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(-1.0, -1.0)).rgb;
+    color += textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(1.0, -1.0)).rgb;
+    color += textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(-1.0, 1.0)).rgb;
+    color += textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(1.0, 1.0)).rgb;
+    return vec4<f32>(color / 16.0, 1.0);
+}
+
+fn aces(x: vec3<f32>) -> vec3<f32> {
+    let a = 2.51;
+    let b = 0.03;
+    let c = 2.43;
+    let d = 0.59;
+    let e = 0.14;
+    return clamp((x * (a * x + b)) / (x * (c * x + d) + e), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+fn hable_partial(x: vec3<f32>) -> vec3<f32> {
+    let a = 0.15;
+    let b = 0.50;
+    let c = 0.10;
+    let d = 0.20;
+    let e = 0.02;
+    let f = 0.30;
+    return ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f)) - e / f;
+}
+
+fn filmic(x: vec3<f32>) -> vec3<f32> {
+    let exposure_bias = 2.0;
+    let white = hable_partial(vec3<f32>(11.2));
+    return clamp(hable_partial(x * exposure_bias) / white, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+fn linear_to_srgb(c: vec3<f32>) -> vec3<f32> {
+    let low = c * 12.92;
+    let high = 1.055 * pow(c, vec3<f32>(1.0 / 2.4)) - vec3<f32>(0.055);
+    return select(high, low, c <= vec3<f32>(0.0031308));
+}
+
+fn srgb_to_linear(c: vec3<f32>) -> vec3<f32> {
+    let low = c / 12.92;
+    let high = pow((c + vec3<f32>(0.055)) / 1.055, vec3<f32>(2.4));
+    return select(high, low, c <= vec3<f32>(0.04045));
+}
+
+// LUT yatay bir şerit: n tane n x n dilim, mavi kanal dilimi seçer
+fn sample_lut(c: vec3<f32>) -> vec3<f32> {
+    let n = post.lut.x;
+    let blue = c.b * (n - 1.0);
+    let slice0 = floor(blue);
+    let slice1 = min(slice0 + 1.0, n - 1.0);
+    let x = (c.r * (n - 1.0) + 0.5) / (n * n);
+    let y = (c.g * (n - 1.0) + 0.5) / n;
+    let a = textureSampleLevel(lut_map, linear_sampler, vec2<f32>(slice0 / n + x, y), 0.0).rgb;
+    let b = textureSampleLevel(lut_map, linear_sampler, vec2<f32>(slice1 / n + x, y), 0.0).rgb;
+    return mix(a, b, blue - slice0);
+}
+
+@fragment
+fn fs_composite(in: VertexOutput) -> @location(0) vec4<f32> {
+    var color = textureSample(source, linear_sampler, in.uv).rgb;
+    let bloom = textureSample(bloom_map, linear_sampler, in.uv).rgb;
+    if (post.flags.x != 0u) {
+        color += bloom * post.params.z;
+    }
+    color *= post.params.x;
+
+    let tonemapper = post.params.y;
+    if (tonemapper == TONEMAP_NONE) {
+        color = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0));
+    } else if (tonemapper == TONEMAP_ACES) {
+        color = aces(color);
+    } else {
+        color = filmic(color);
+    }
+
+    if (post.flags.y != 0u) {
+        // LUT'lar sRGB uzayında hazırlanıyor
+        let graded = srgb_to_linear(sample_lut(linear_to_srgb(color)));
+        color = mix(color, graded, post.params.w);
+    }
+
+    if (post.flags.z != 0u) {
+        let d = distance(in.uv, vec2<f32>(0.5));
+        let falloff = 1.0 - smoothstep(post.vignette.y - post.vignette.z, post.vignette.y, d);
+        color *= mix(1.0, falloff, post.vignette.x);
+    }
+    return vec4<f32>(color, 1.0);
+}
+
+// Lottes'in FXAA'sının sade sürümü
+const FXAA_REDUCE_MIN: f32 = 1.0 / 128.0;
+const FXAA_REDUCE_MUL: f32 = 1.0 / 8.0;
+const FXAA_SPAN_MAX: f32 = 8.0;
+
+// kaynak sRGB texture'dan lineer okunuyor, kenar tespiti algısal parlaklıkta daha iyi
+fn fxaa_luma(c: vec3<f32>) -> f32 {
+    return sqrt(luminance(c));
+}
+
+@fragment
+fn fs_fxaa(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(source));
+    let rgb_m = textureSample(source, linear_sampler, in.uv).rgb;
+    let luma_nw = fxaa_luma(textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(-1.0, -1.0)).rgb);
+    let luma_ne = fxaa_luma(textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(1.0, -1.0)).rgb);
+    let luma_sw = fxaa_luma(textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(-1.0, 1.0)).rgb);
+    let luma_se = fxaa_luma(textureSample(source, linear_sampler, in.uv + texel * vec2<f32>(1.0, 1.0)).rgb);
+    let luma_m = fxaa_luma(rgb_m);
+
+    let luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    let luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    var dir = vec2<f32>(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se),
+    );
+    let dir_reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * 0.25 * FXAA_REDUCE_MUL, FXAA_REDUCE_MIN);
+    let rcp_dir_min = 1.0 / (min(abs(dir.x), abs(dir.y)) + dir_reduce);
+    dir = clamp(dir * rcp_dir_min, vec2<f32>(-FXAA_SPAN_MAX), vec2<f32>(FXAA_SPAN_MAX)) * texel;
+
+    let rgb_a = 0.5 * (
+        textureSample(source, linear_sampler, in.uv + dir * (1.0 / 3.0 - 0.5)).rgb +
+        textureSample(source, linear_sampler, in.uv + dir * (2.0 / 3.0 - 0.5)).rgb);
+    let rgb_b = rgb_a * 0.5 + 0.25 * (
+        textureSample(source, linear_sampler, in.uv + dir * -0.5).rgb +
+        textureSample(source, linear_sampler, in.uv + dir * 0.5).rgb);
+    let luma_b = fxaa_luma(rgb_b);
+    if (luma_b < luma_min || luma_b > luma_max) {
+        return vec4<f32>(rgb_a, 1.0);
+    }
+    return vec4<f32>(rgb_b, 1.0);
+}
@@ -0,0 +1,53 @@
+// src/bin/atlas_builder.rs
+//
+// Packs the images of directories into one atlas:
+//   atlas_builder <output.png> <dir>... [--padding N] [--extrude N] [--max-size N]
+// The manifest is written next to the image with a .json extension.
+
+use std::path::Path;
+
+use realgameengine::renderer::AtlasBuilder;
+
+fn fail(message: String) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+fn main() {
+    let mut builder = AtlasBuilder::new();
+    let mut inputs = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut number = |name: &str| -> u32 {
+            args.next().and_then(|v| v.parse().ok()).unwrap_or_else(|| fail(format!("{} needs a number", name)))
+        };
+        builder = match arg.as_str() {
+            "--padding" => builder.with_padding(number("--padding")),
+            "--extrude" => builder.with_extrusion(number("--extrude")),
+            "--max-size" => builder.with_max_size(number("--max-size")),
+            _ => {
+                inputs.push(arg);
+                builder
+            }
+        };
+    }
+    if inputs.len() < 2 {
+        fail("usage: atlas_builder <output.png> <dir>... [--padding N] [--extrude N] [--max-size N]".to_string());
+    }
+
+    let output = Path::new(&inputs[0]);
+    for dir in &inputs[1..] {
+        match builder.add_dir(dir) {
+            Ok(count) => println!("{}: {} images", dir, count),
+            Err(e) => fail(format!("couldn't read {}: {}", dir, e)),
+        }
+    }
+    let name = output.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let (image, manifest) = builder.build(&name).unwrap_or_else(|e| fail(format!("packing failed: {}", e)));
+    let png = image.encode_png().unwrap_or_else(|e| fail(format!("png encoding failed: {}", e)));
+    let manifest_path = output.with_extension("json");
+    if let Err(e) = std::fs::write(output, png).and_then(|_| std::fs::write(&manifest_path, manifest.to_json())) {
+        fail(format!("couldn't write the atlas: {}", e));
+    }
+    println!("wrote {}x{} atlas with {} regions to {}", image.width, image.height, manifest.regions.len(), output.display());
+}
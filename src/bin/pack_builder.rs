@@ -0,0 +1,34 @@
+// src/bin/pack_builder.rs
+//
+// Packs asset directories into one archive:
+//   pack_builder <output.pak> <dir>[=<virtual dir>]... [--store]
+// `--store` writes every file uncompressed.
+
+use realgameengine::vfs::PackBuilder;
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let store = args.iter().any(|a| a == "--store");
+    args.retain(|a| a != "--store");
+    if args.len() < 2 {
+        eprintln!("usage: pack_builder <output.pak> <dir>[=<virtual dir>]... [--store]");
+        std::process::exit(2);
+    }
+
+    let mut builder = PackBuilder::new().with_compression(!store);
+    for input in &args[1..] {
+        let (dir, point) = input.split_once('=').unwrap_or((input, ""));
+        match builder.add_dir(dir, point) {
+            Ok(count) => println!("{}: {} files", dir, count),
+            Err(e) => {
+                eprintln!("couldn't read {}: {}", dir, e);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Err(e) = builder.write(&args[0]) {
+        eprintln!("couldn't write {}: {}", args[0], e);
+        std::process::exit(1);
+    }
+    println!("wrote {} files into {}", builder.len(), args[0]);
+}
@@ -0,0 +1,113 @@
+// src/engine/assets/handle.rs
+
+use std::any::TypeId;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::{Arc, Weak};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AssetId(pub(crate) u64);
+
+impl fmt::Display for AssetId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Shared by every handle of one asset, the server only keeps a `Weak` to it
+#[derive(Debug)]
+pub(crate) struct HandleRef {
+    pub(crate) id: AssetId,
+}
+
+/// Keeps an asset loaded. When the last handle is dropped the asset is unloaded
+/// on the next `AssetServer::update`.
+pub struct Handle<T> {
+    pub(crate) inner: Arc<HandleRef>,
+    // T'ye sahip değil, Send/Sync T'ye bağlı olmasın
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    pub(crate) fn from_ref(inner: Arc<HandleRef>) -> Self {
+        Self { inner, _marker: PhantomData }
+    }
+
+    pub fn id(&self) -> AssetId {
+        self.inner.id
+    }
+
+    pub fn untyped(&self) -> UntypedHandle
+    where
+        T: 'static,
+    {
+        UntypedHandle { inner: self.inner.clone(), type_id: TypeId::of::<T>() }
+    }
+
+    pub(crate) fn downgrade(&self) -> Weak<HandleRef> {
+        Arc::downgrade(&self.inner)
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        Self::from_ref(self.inner.clone())
+    }
+}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id().hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle<{}>({})", std::any::type_name::<T>(), self.id())
+    }
+}
+
+/// A handle whose asset type is only known at runtime, used for dependencies
+#[derive(Debug, Clone)]
+pub struct UntypedHandle {
+    pub(crate) inner: Arc<HandleRef>,
+    type_id: TypeId,
+}
+
+impl UntypedHandle {
+    pub fn id(&self) -> AssetId {
+        self.inner.id
+    }
+
+    pub fn type_id(&self) -> TypeId {
+        self.type_id
+    }
+
+    /// `None` when the asset isn't a `T`
+    pub fn typed<T: 'static>(&self) -> Option<Handle<T>> {
+        (self.type_id == TypeId::of::<T>()).then(|| Handle::from_ref(self.inner.clone()))
+    }
+}
+
+impl PartialEq for UntypedHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for UntypedHandle {}
+
+impl Hash for UntypedHandle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id().hash(state);
+    }
+}
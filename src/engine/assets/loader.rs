@@ -0,0 +1,130 @@
+// src/engine/assets/loader.rs
+
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::engine::assets::handle::{Handle, UntypedHandle};
+use crate::engine::assets::server::{normalize, AssetServer};
+
+/// Anything a loader can produce. Loaders run on the worker thread, so assets stay
+/// on the CPU; GPU resources are created from them by whoever owns the device.
+pub trait Asset: Any + Send + Sync {}
+
+impl<T: Any + Send + Sync> Asset for T {}
+
+#[derive(Debug)]
+pub enum AssetError {
+    Io(std::io::Error),
+    /// no loader for this extension produces the requested type
+    NoLoader { path: PathBuf, asset_type: &'static str },
+    /// fetch on the web failed
+    Fetch(String),
+    /// the file was read but the loader couldn't decode it
+    Decode(String),
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetError::Io(e) => write!(f, "io error: {}", e),
+            AssetError::NoLoader { path, asset_type } => {
+                write!(f, "no loader for {} as {}", path.display(), asset_type)
+            }
+            AssetError::Fetch(msg) => write!(f, "fetch failed: {}", msg),
+            AssetError::Decode(msg) => write!(f, "decode failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+impl From<std::io::Error> for AssetError {
+    fn from(e: std::io::Error) -> Self {
+        AssetError::Io(e)
+    }
+}
+
+/// Turns the bytes of a file into an asset. Registered with `AssetServer::add_loader`,
+/// picked by file extension and asset type.
+pub trait AssetLoader: Send + Sync + 'static {
+    type Asset: Asset;
+
+    /// lowercase, without the dot
+    fn extensions(&self) -> &[&str];
+
+    fn load(&self, bytes: Vec<u8>, context: &mut LoadContext) -> Result<Self::Asset, AssetError>;
+}
+
+/// Given to `AssetLoader::load`, loads the dependencies of the asset
+pub struct LoadContext<'a> {
+    server: &'a AssetServer,
+    path: &'a Path,
+    pub(crate) dependencies: Vec<UntypedHandle>,
+}
+
+impl<'a> LoadContext<'a> {
+    pub(crate) fn new(server: &'a AssetServer, path: &'a Path) -> Self {
+        Self { server, path, dependencies: Vec::new() }
+    }
+
+    /// path of the asset being loaded, relative to the asset root
+    pub fn path(&self) -> &Path {
+        self.path
+    }
+
+    /// `path` relative to this asset as a path relative to the asset root
+    pub fn resolve(&self, path: impl AsRef<Path>) -> PathBuf {
+        let dir = self.path.parent().unwrap_or(Path::new(""));
+        normalize(&dir.join(path))
+    }
+
+    /// Reads a side file (relative to this asset) through the `Vfs`, from the same mounts
+    /// the asset comes from. Editing it doesn't reload the asset, and on the web only files
+    /// in mounted packs can be read.
+    pub fn read(&self, path: impl AsRef<Path>) -> std::io::Result<Vec<u8>> {
+        self.server.read_file(self.resolve(path))
+    }
+
+    pub fn server(&self) -> &AssetServer {
+        self.server
+    }
+
+    /// Loads `path` (relative to this asset) as a dependency. The dependency stays loaded
+    /// as long as this asset does.
+    pub fn load<T: Asset>(&mut self, path: impl AsRef<Path>) -> Handle<T> {
+        let handle = self.server.load(self.resolve(path));
+        self.depend_on(handle.untyped());
+        handle
+    }
+
+    pub fn depend_on(&mut self, handle: UntypedHandle) {
+        if !self.dependencies.contains(&handle) {
+            self.dependencies.push(handle);
+        }
+    }
+}
+
+pub(crate) type BoxedAsset = Arc<dyn Any + Send + Sync>;
+
+/// `AssetLoader` without the associated type, so loaders can share one registry
+pub(crate) trait ErasedLoader: Send + Sync {
+    fn asset_type(&self) -> TypeId;
+    fn extensions(&self) -> &[&str];
+    fn load_boxed(&self, bytes: Vec<u8>, context: &mut LoadContext) -> Result<BoxedAsset, AssetError>;
+}
+
+impl<L: AssetLoader> ErasedLoader for L {
+    fn asset_type(&self) -> TypeId {
+        TypeId::of::<L::Asset>()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        AssetLoader::extensions(self)
+    }
+
+    fn load_boxed(&self, bytes: Vec<u8>, context: &mut LoadContext) -> Result<BoxedAsset, AssetError> {
+        Ok(Arc::new(self.load(bytes, context)?))
+    }
+}
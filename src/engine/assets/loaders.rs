@@ -0,0 +1,251 @@
+// src/engine/assets/loaders.rs
+//
+// Loaders for the formats the renderer already understands. They only decode, uploading
+// to the GPU is left to the owner of the device (ör. `Texture::from_image`, `GltfScene::upload`).
+
+use std::fmt::Display;
+
+use crate::engine::assets::loader::{AssetError, AssetLoader, LoadContext};
+use crate::engine::assets::server::AssetServer;
+use crate::engine::audio::{Music, Sound};
+use crate::engine::renderer::atlas::AtlasManifest;
+use crate::engine::renderer::gltf_loader::{GltfScene, ImageData};
+use crate::engine::renderer::text::Font;
+use crate::engine::renderer::tiled::TiledMap;
+use crate::engine::scene::SceneData;
+
+fn decode_error(e: impl Display) -> AssetError {
+    AssetError::Decode(e.to_string())
+}
+
+/// png / jpeg as rgba8
+pub struct ImageLoader;
+
+impl AssetLoader for ImageLoader {
+    type Asset = ImageData;
+
+    fn extensions(&self) -> &[&str] {
+        &["png", "jpg", "jpeg"]
+    }
+
+    fn load(&self, bytes: Vec<u8>, _context: &mut LoadContext) -> Result<ImageData, AssetError> {
+        ImageData::decode(&bytes).map_err(decode_error)
+    }
+}
+
+/// utf-8 text, shaders included
+pub struct TextLoader;
+
+impl AssetLoader for TextLoader {
+    type Asset = String;
+
+    fn extensions(&self) -> &[&str] {
+        &["txt", "wgsl", "json"]
+    }
+
+    fn load(&self, bytes: Vec<u8>, _context: &mut LoadContext) -> Result<String, AssetError> {
+        String::from_utf8(bytes).map_err(decode_error)
+    }
+}
+
+pub struct FontLoader;
+
+impl AssetLoader for FontLoader {
+    type Asset = Font;
+
+    fn extensions(&self) -> &[&str] {
+        &["ttf", "otf"]
+    }
+
+    fn load(&self, bytes: Vec<u8>, _context: &mut LoadContext) -> Result<Font, AssetError> {
+        Font::from_bytes(bytes).map_err(decode_error)
+    }
+}
+
+/// External buffers and images are read next to the file through the `Vfs`, on the web
+/// only from mounted packs
+pub struct GltfLoader;
+
+impl AssetLoader for GltfLoader {
+    type Asset = GltfScene;
+
+    fn extensions(&self) -> &[&str] {
+        &["gltf", "glb"]
+    }
+
+    fn load(&self, bytes: Vec<u8>, context: &mut LoadContext) -> Result<GltfScene, AssetError> {
+        GltfScene::from_slice_with(&bytes, |uri| context.read(uri)).map_err(decode_error)
+    }
+}
+
+/// .tmx / .tmj maps, external tilesets are read through the `Vfs` like the map. Tileset
+/// images are loaded as `ImageData` dependencies and their paths are asset paths, so
+/// `server.load::<ImageData>(path)` finds them already loaded.
+pub struct TiledLoader;
+
+impl AssetLoader for TiledLoader {
+    type Asset = TiledMap;
+
+    fn extensions(&self) -> &[&str] {
+        &["tmx", "tmj"]
+    }
+
+    fn load(&self, bytes: Vec<u8>, context: &mut LoadContext) -> Result<TiledMap, AssetError> {
+        let text = String::from_utf8(bytes).map_err(decode_error)?;
+        // yollar haritaya göre, resimler sonra köke göre yazılıyor
+        let mut map = TiledMap::parse_with(&text, None, |path| context.read(path)).map_err(decode_error)?;
+        for tileset in &mut map.tilesets {
+            let Some(image) = tileset.image.take() else {
+                continue;
+            };
+            context.load::<ImageData>(&image);
+            tileset.image = Some(context.resolve(&image));
+        }
+        Ok(map)
+    }
+}
+
+/// Atlas manifests and Aseprite / TexturePacker sheets. The image is loaded as an
+/// `ImageData` dependency and `image` is replaced with its asset path.
+pub struct AtlasLoader;
+
+impl AssetLoader for AtlasLoader {
+    type Asset = AtlasManifest;
+
+    fn extensions(&self) -> &[&str] {
+        &["json", "atlas"]
+    }
+
+    fn load(&self, bytes: Vec<u8>, context: &mut LoadContext) -> Result<AtlasManifest, AssetError> {
+        let text = String::from_utf8(bytes).map_err(decode_error)?;
+        let mut manifest = AtlasManifest::from_json(&text).map_err(decode_error)?;
+        let image = context.resolve(&manifest.image);
+        context.load::<ImageData>(&manifest.image);
+        manifest.image = image.to_string_lossy().replace('\\', "/");
+        Ok(manifest)
+    }
+}
+
+/// .wav / .ogg decoded completely, for sound effects
+pub struct SoundLoader;
+
+impl AssetLoader for SoundLoader {
+    type Asset = Sound;
+
+    fn extensions(&self) -> &[&str] {
+        &["wav", "ogg"]
+    }
+
+    fn load(&self, bytes: Vec<u8>, _context: &mut LoadContext) -> Result<Sound, AssetError> {
+        Sound::decode(&bytes).map_err(decode_error)
+    }
+}
+
+/// .ogg / .wav streamed while playing, for long tracks
+pub struct MusicLoader;
+
+impl AssetLoader for MusicLoader {
+    type Asset = Music;
+
+    fn extensions(&self) -> &[&str] {
+        &["ogg", "wav"]
+    }
+
+    fn load(&self, bytes: Vec<u8>, _context: &mut LoadContext) -> Result<Music, AssetError> {
+        Music::decode(bytes).map_err(decode_error)
+    }
+}
+
+/// Scenes and prefabs written by `SceneData::to_json`
+pub struct SceneLoader;
+
+impl AssetLoader for SceneLoader {
+    type Asset = SceneData;
+
+    fn extensions(&self) -> &[&str] {
+        &["scene", "prefab"]
+    }
+
+    fn load(&self, bytes: Vec<u8>, _context: &mut LoadContext) -> Result<SceneData, AssetError> {
+        let text = String::from_utf8(bytes).map_err(decode_error)?;
+        SceneData::from_json(&text).map_err(decode_error)
+    }
+}
+
+impl AssetServer {
+    /// Registers the loaders of this module
+    pub fn add_default_loaders(&self) {
+        self.add_loader(ImageLoader);
+        self.add_loader(TextLoader);
+        self.add_loader(FontLoader);
+        self.add_loader(GltfLoader);
+        self.add_loader(TiledLoader);
+        self.add_loader(AtlasLoader);
+        self.add_loader(SoundLoader);
+        self.add_loader(MusicLoader);
+        self.add_loader(SceneLoader);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+
+    use base64::Engine;
+
+    use crate::engine::assets::{Asset, Handle, LoadState};
+    use crate::engine::vfs::{Pack, PackBuilder};
+
+    fn wait<T: Asset>(server: &AssetServer, handle: &Handle<T>) -> std::sync::Arc<T> {
+        let start = Instant::now();
+        while server.recursive_load_state(handle.id()) == LoadState::Loading && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(server.recursive_load_state(handle.id()), LoadState::Loaded);
+        server.get(handle).unwrap()
+    }
+
+    #[test]
+    fn side_files_come_from_packs(){
+        // üçgen, verisi ayrı bir .bin dosyasında
+        let bin = base64::engine::general_purpose::STANDARD
+            .decode("AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAA=").unwrap();
+        let gltf = r#"{
+            "asset": { "version": "2.0" },
+            "nodes": [ { "mesh": 0 } ],
+            "meshes": [ { "primitives": [ { "attributes": { "POSITION": 0 }, "indices": 1 } ] } ],
+            "buffers": [ { "byteLength": 44, "uri": "tri%20data.bin" } ],
+            "bufferViews": [ { "buffer": 0, "byteLength": 36 }, { "buffer": 0, "byteOffset": 36, "byteLength": 6 } ],
+            "accessors": [
+                { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0] },
+                { "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }
+            ]
+        }"#;
+        let map = r#"{
+            "width": 1, "height": 1, "tilewidth": 16, "tileheight": 16, "orientation": "orthogonal",
+            "tilesets": [ { "firstgid": 1, "source": "../tiles/ground.tsj" } ],
+            "layers": [ { "type": "tilelayer", "name": "base", "width": 1, "height": 1, "data": [1] } ]
+        }"#;
+        let tileset = r#"{ "name": "ground", "tilewidth": 16, "tileheight": 16, "columns": 1, "tilecount": 1, "image": "ground.png" }"#;
+        let image = ImageData { width: 16, height: 16, rgba: vec![255; 16 * 16 * 4] }.encode_png().unwrap();
+
+        let mut builder = PackBuilder::new();
+        builder.add("models/tri.gltf", gltf.as_bytes().to_vec());
+        builder.add("models/tri data.bin", bin);
+        builder.add("maps/level.tmj", map.as_bytes().to_vec());
+        builder.add("tiles/ground.tsj", tileset.as_bytes().to_vec());
+        builder.add("tiles/ground.png", image);
+        // diskteki kökte hiçbiri yok
+        let server = AssetServer::new(std::env::temp_dir().join(format!("loaders_test_{}", std::process::id())));
+        server.add_default_loaders();
+        server.mount("", 1, Pack::from_bytes(builder.to_bytes().unwrap()).unwrap());
+
+        let scene = wait(&server, &server.load::<GltfScene, _>("models/tri.gltf"));
+        assert_eq!(scene.meshes[0].primitives[0].data.vertices.len(), 3);
+        let level = wait(&server, &server.load::<TiledMap, _>("maps/level.tmj"));
+        assert_eq!(level.tilesets[0].image.as_deref(), Some(Path::new("tiles/ground.png")));
+        assert!(server.get(&server.load::<ImageData, _>("tiles/ground.png")).is_some());
+    }
+}
@@ -0,0 +1,17 @@
+// src/engine/assets/mod.rs
+//
+// Asset server. `AssetServer::load` returns a typed `Handle` right away and the file is
+// read and decoded in the background by the loader registered for its extension.
+// Assets stay in memory while a handle to them (or to an asset depending on them) exists.
+// Natively the files can be watched and reloaded while the game runs.
+
+pub mod handle;
+pub mod loader;
+pub mod server;
+pub mod loaders;
+#[cfg(not(target_family = "wasm"))]
+pub mod watcher;
+pub use handle::{AssetId, Handle, UntypedHandle};
+pub use loader::{Asset, AssetError, AssetLoader, LoadContext};
+pub use server::{AssetEvent, AssetServer, LoadState};
+pub use loaders::{AtlasLoader, FontLoader, GltfLoader, ImageLoader, MusicLoader, SceneLoader, SoundLoader, TextLoader, TiledLoader};
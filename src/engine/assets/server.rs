@@ -0,0 +1,586 @@
+// src/engine/assets/server.rs
+
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::SystemTime;
+
+use crate::engine::assets::handle::{AssetId, Handle, HandleRef, UntypedHandle};
+use crate::engine::assets::loader::{Asset, AssetError, AssetLoader, BoxedAsset, ErasedLoader, LoadContext};
+use crate::engine::vfs::{MountSource, Pack, PackError, Vfs};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadState {
+    /// unknown id or already unloaded
+    NotLoaded,
+    Loading,
+    Loaded,
+    Failed(String),
+}
+
+/// Returned by `AssetServer::update`
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssetEvent {
+    Loaded { id: AssetId, path: PathBuf },
+    /// Loading failed. A failed reload keeps the previous version of the asset.
+    Failed { id: AssetId, path: PathBuf, error: String },
+    /// Reloaded after its file changed, or one of its dependencies was reloaded
+    Modified { id: AssetId, path: PathBuf },
+    /// every handle was dropped
+    Unloaded { id: AssetId, path: Option<PathBuf> },
+    /// a pack couldn't be downloaded on the web
+    MountFailed { url: String, error: String },
+}
+
+struct Slot {
+    /// `None` for assets added from memory
+    path: Option<PathBuf>,
+    type_id: TypeId,
+    handle: Weak<HandleRef>,
+    state: LoadState,
+    asset: Option<BoxedAsset>,
+    /// strong handles, dependencies live as long as this slot
+    dependencies: Vec<UntypedHandle>,
+    /// of the file when it was last read
+    modified: Option<SystemTime>,
+}
+
+#[derive(Default)]
+struct Storage {
+    next_id: u64,
+    slots: HashMap<AssetId, Slot>,
+    by_path: HashMap<(PathBuf, TypeId), AssetId>,
+    events: Vec<AssetEvent>,
+}
+
+impl Storage {
+    fn insert(&mut self, slot: impl FnOnce(Weak<HandleRef>) -> Slot) -> Arc<HandleRef> {
+        let id = AssetId(self.next_id);
+        self.next_id += 1;
+        let handle = Arc::new(HandleRef { id });
+        let slot = slot(Arc::downgrade(&handle));
+        if let Some(path) = &slot.path {
+            self.by_path.insert((path.clone(), slot.type_id), id);
+        }
+        self.slots.insert(id, slot);
+        handle
+    }
+
+    /// `Modified` for everything depending on `id`, directly or not
+    fn push_modified_dependents(&mut self, id: AssetId) {
+        let mut visited = HashSet::from([id]);
+        let mut stack = vec![id];
+        while let Some(changed) = stack.pop() {
+            for (dependent, slot) in &self.slots {
+                if slot.dependencies.iter().any(|d| d.id() == changed) && visited.insert(*dependent) {
+                    stack.push(*dependent);
+                    if let Some(path) = &slot.path {
+                        self.events.push(AssetEvent::Modified { id: *dependent, path: path.clone() });
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct Job {
+    id: AssetId,
+    path: PathBuf,
+    loader: Arc<dyn ErasedLoader>,
+}
+
+pub(super) struct Inner {
+    root: PathBuf,
+    vfs: RwLock<Vfs>,
+    /// extension -> loaders, the last registered one wins
+    loaders: RwLock<HashMap<String, Vec<Arc<dyn ErasedLoader>>>>,
+    storage: Mutex<Storage>,
+    /// started with the first load
+    #[cfg(not(target_family = "wasm"))]
+    worker: Mutex<Option<std::sync::mpsc::Sender<Job>>>,
+    #[cfg(not(target_family = "wasm"))]
+    pub(super) watching: std::sync::atomic::AtomicBool,
+    /// packs being downloaded and the jobs waiting for them
+    #[cfg(target_family = "wasm")]
+    downloads: Mutex<(usize, Vec<Job>)>,
+}
+
+/// Loads files in the background and hands out `Handle`s to them. Files are read from the
+/// mounts of its `Vfs` on a worker thread; natively `root` is mounted at the top with
+/// priority 0, on the web files that aren't in a mounted pack are fetched from `root`
+/// (relative to the page). Cheap to clone, every clone is the same server.
+#[derive(Clone)]
+pub struct AssetServer {
+    pub(super) inner: Arc<Inner>,
+}
+
+impl AssetServer {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        let root = root.into();
+        #[allow(unused_mut)]
+        let mut vfs = Vfs::new();
+        #[cfg(not(target_family = "wasm"))]
+        vfs.mount("", 0, crate::engine::vfs::Directory::new(&root));
+        Self {
+            inner: Arc::new(Inner {
+                root,
+                vfs: RwLock::new(vfs),
+                loaders: RwLock::new(HashMap::new()),
+                storage: Mutex::new(Storage::default()),
+                #[cfg(not(target_family = "wasm"))]
+                worker: Mutex::new(None),
+                #[cfg(not(target_family = "wasm"))]
+                watching: Default::default(),
+                #[cfg(target_family = "wasm")]
+                downloads: Default::default(),
+            }),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.inner.root
+    }
+
+    /// Mounts `source` in the `Vfs`, see `Vfs::mount`. Loaded assets aren't reloaded,
+    /// the watcher picks up files whose modification time changed with the new mount.
+    pub fn mount<P: AsRef<Path>, S: MountSource + 'static>(&self, point: P, priority: i32, source: S) {
+        self.inner.vfs.write().unwrap().mount(point, priority, source);
+    }
+
+    pub fn unmount<P: AsRef<Path>>(&self, point: P) -> usize {
+        self.inner.vfs.write().unwrap().unmount(point)
+    }
+
+    /// Mounts the pack file at `path`. On the web `path` is a url, the pack is downloaded
+    /// in the background and loads wait for it; a failed download is reported as
+    /// `AssetEvent::MountFailed`.
+    pub fn mount_pack<P: AsRef<Path>>(&self, path: &str, point: P, priority: i32) -> Result<(), PackError> {
+        #[cfg(not(target_family = "wasm"))]
+        self.mount(point, priority, Pack::open(path)?);
+        #[cfg(target_family = "wasm")]
+        {
+            self.inner.downloads.lock().unwrap().0 += 1;
+            let server = self.clone();
+            let url = path.to_string();
+            let point = point.as_ref().to_path_buf();
+            wasm_bindgen_futures::spawn_local(async move {
+                let pack = fetch(&url).await
+                    .map_err(|e| e.to_string())
+                    .and_then(|bytes| Pack::from_bytes(bytes).map_err(|e| e.to_string()));
+                match pack {
+                    Ok(pack) => server.mount(point, priority, pack),
+                    Err(error) => server.inner.storage.lock().unwrap().events.push(AssetEvent::MountFailed { url, error }),
+                }
+                let waiting = {
+                    let mut downloads = server.inner.downloads.lock().unwrap();
+                    downloads.0 -= 1;
+                    if downloads.0 == 0 { std::mem::take(&mut downloads.1) } else { Vec::new() }
+                };
+                for job in waiting {
+                    server.spawn(job);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Reads a file through the `Vfs` right away
+    pub fn read_file<P: AsRef<Path>>(&self, path: P) -> std::io::Result<Vec<u8>> {
+        self.inner.vfs.read().unwrap().read(path)
+    }
+
+    /// every file of every mount
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.inner.vfs.read().unwrap().files()
+    }
+
+    pub(super) fn modified(&self, path: &Path) -> Option<SystemTime> {
+        self.inner.vfs.read().unwrap().modified(path)
+    }
+
+    pub fn add_loader<L: AssetLoader>(&self, loader: L) {
+        let loader: Arc<dyn ErasedLoader> = Arc::new(loader);
+        let mut loaders = self.inner.loaders.write().unwrap();
+        for extension in loader.extensions() {
+            loaders.entry(extension.to_lowercase()).or_default().push(loader.clone());
+        }
+    }
+
+    fn find_loader(&self, path: &Path, type_id: TypeId) -> Option<Arc<dyn ErasedLoader>> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        let loaders = self.inner.loaders.read().unwrap();
+        loaders.get(&extension)?.iter().rev().find(|l| l.asset_type() == type_id).cloned()
+    }
+
+    /// Starts loading `path` (relative to the root) unless it's already loaded as a `T`,
+    /// the asset is available through `get` once its state is `Loaded`
+    pub fn load<T: Asset, P: AsRef<Path>>(&self, path: P) -> Handle<T> {
+        let path = normalize(path.as_ref());
+        let type_id = TypeId::of::<T>();
+        let mut storage = self.inner.storage.lock().unwrap();
+        if let Some(&id) = storage.by_path.get(&(path.clone(), type_id)) {
+            let slot = storage.slots.get_mut(&id).unwrap();
+            if let Some(inner) = slot.handle.upgrade() {
+                return Handle::from_ref(inner);
+            }
+            // handle'ları düşmüş ama henüz kaldırılmamış, geri kazanılıyor
+            let handle = Handle::from_ref(Arc::new(HandleRef { id }));
+            slot.handle = handle.downgrade();
+            return handle;
+        }
+
+        let loader = self.find_loader(&path, type_id);
+        let state = match &loader {
+            Some(_) => LoadState::Loading,
+            None => LoadState::Failed(AssetError::NoLoader { path: path.clone(), asset_type: std::any::type_name::<T>() }.to_string()),
+        };
+        let inner = storage.insert(|handle| Slot {
+            path: Some(path.clone()),
+            type_id,
+            handle,
+            state: state.clone(),
+            asset: None,
+            dependencies: Vec::new(),
+            modified: None,
+        });
+        let id = inner.id;
+        match loader {
+            Some(loader) => {
+                drop(storage);
+                self.spawn(Job { id, path, loader });
+            }
+            None => {
+                if let LoadState::Failed(error) = state {
+                    storage.events.push(AssetEvent::Failed { id, path, error });
+                }
+            }
+        }
+        Handle::from_ref(inner)
+    }
+
+    /// Adds an asset made in code, it's unloaded like the others
+    pub fn add<T: Asset>(&self, asset: T) -> Handle<T> {
+        let mut storage = self.inner.storage.lock().unwrap();
+        Handle::from_ref(storage.insert(|handle| Slot {
+            path: None,
+            type_id: TypeId::of::<T>(),
+            handle,
+            state: LoadState::Loaded,
+            asset: Some(Arc::new(asset)),
+            dependencies: Vec::new(),
+            modified: None,
+        }))
+    }
+
+    pub fn get<T: Asset>(&self, handle: &Handle<T>) -> Option<Arc<T>> {
+        let storage = self.inner.storage.lock().unwrap();
+        storage.slots.get(&handle.id())?.asset.clone()?.downcast::<T>().ok()
+    }
+
+    pub fn load_state(&self, id: AssetId) -> LoadState {
+        let storage = self.inner.storage.lock().unwrap();
+        storage.slots.get(&id).map_or(LoadState::NotLoaded, |slot| slot.state.clone())
+    }
+
+    /// State of the asset and everything it depends on: the first failure if any failed,
+    /// `Loading` until all of them are loaded
+    pub fn recursive_load_state(&self, id: AssetId) -> LoadState {
+        let storage = self.inner.storage.lock().unwrap();
+        let mut visited = HashSet::new();
+        let mut stack = vec![id];
+        let mut state = LoadState::Loaded;
+        while let Some(id) = stack.pop() {
+            if !visited.insert(id) {
+                continue;
+            }
+            let Some(slot) = storage.slots.get(&id) else {
+                return LoadState::NotLoaded;
+            };
+            match &slot.state {
+                LoadState::Failed(e) => return LoadState::Failed(e.clone()),
+                LoadState::Loaded => (),
+                _ => state = LoadState::Loading,
+            }
+            stack.extend(slot.dependencies.iter().map(UntypedHandle::id));
+        }
+        state
+    }
+
+    pub fn is_loaded_with_dependencies(&self, id: AssetId) -> bool {
+        self.recursive_load_state(id) == LoadState::Loaded
+    }
+
+    pub fn path(&self, id: AssetId) -> Option<PathBuf> {
+        self.inner.storage.lock().unwrap().slots.get(&id)?.path.clone()
+    }
+
+    /// direct dependencies declared by the loader
+    pub fn dependencies(&self, id: AssetId) -> Vec<AssetId> {
+        let storage = self.inner.storage.lock().unwrap();
+        storage.slots.get(&id).map_or(Vec::new(), |slot| slot.dependencies.iter().map(UntypedHandle::id).collect())
+    }
+
+    /// number of assets in memory or loading
+    pub fn len(&self) -> usize {
+        self.inner.storage.lock().unwrap().slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Loads every asset read from `path` again, whatever its type. The old version
+    /// stays available until the new one is decoded. Returns how many were queued.
+    pub fn reload<P: AsRef<Path>>(&self, path: P) -> usize {
+        let path = normalize(path.as_ref());
+        let ids: Vec<AssetId> = self.inner.storage.lock().unwrap().slots.iter()
+            .filter(|(_, slot)| slot.path.as_ref() == Some(&path))
+            .map(|(id, _)| *id)
+            .collect();
+        ids.into_iter().filter(|id| self.reload_id(*id, None)).count()
+    }
+
+    /// Queues the asset again, `modified` is remembered so the watcher doesn't queue it twice
+    pub(super) fn reload_id(&self, id: AssetId, modified: Option<SystemTime>) -> bool {
+        let mut storage = self.inner.storage.lock().unwrap();
+        let Some(slot) = storage.slots.get_mut(&id) else {
+            return false;
+        };
+        let Some(path) = slot.path.clone() else {
+            return false;
+        };
+        let Some(loader) = self.find_loader(&path, slot.type_id) else {
+            return false;
+        };
+        if modified.is_some() {
+            slot.modified = modified;
+        }
+        drop(storage);
+        self.spawn(Job { id, path, loader });
+        true
+    }
+
+    /// Files of the assets that aren't loading right now, with the time they were read
+    pub(super) fn watched_files(&self) -> Vec<(AssetId, PathBuf, Option<SystemTime>)> {
+        let storage = self.inner.storage.lock().unwrap();
+        storage.slots.iter()
+            .filter(|(_, slot)| slot.state != LoadState::Loading)
+            .filter_map(|(id, slot)| Some((*id, slot.path.clone()?, slot.modified)))
+            .collect()
+    }
+
+    /// Unloads assets without handles (and then the dependencies only they kept alive),
+    /// returns what happened since the last call. Called once per frame by the engine.
+    pub fn update(&self) -> Vec<AssetEvent> {
+        let mut storage = self.inner.storage.lock().unwrap();
+        loop {
+            let dropped: Vec<AssetId> = storage.slots.iter()
+                .filter(|(_, slot)| slot.handle.strong_count() == 0)
+                .map(|(id, _)| *id)
+                .collect();
+            if dropped.is_empty() {
+                break;
+            }
+            for id in dropped {
+                let slot = storage.slots.remove(&id).unwrap();
+                if let Some(path) = &slot.path {
+                    storage.by_path.remove(&(path.clone(), slot.type_id));
+                }
+                storage.events.push(AssetEvent::Unloaded { id, path: slot.path });
+                // bağımlılıkların handle'ları burada düşüyor, sonraki turda onlar da kaldırılır
+            }
+        }
+        std::mem::take(&mut storage.events)
+    }
+
+    /// Runs the loader on the bytes of a job, called from the worker (or the fetch future)
+    fn finish(&self, job: Job, bytes: Result<Vec<u8>, AssetError>, modified: Option<SystemTime>) {
+        if !self.inner.storage.lock().unwrap().slots.contains_key(&job.id) {
+            // yüklenirken bütün handle'lar bırakıldı
+            return;
+        }
+        let mut context = LoadContext::new(self, &job.path);
+        let result = bytes.and_then(|bytes| job.loader.load_boxed(bytes, &mut context));
+        let dependencies = std::mem::take(&mut context.dependencies);
+
+        let mut storage = self.inner.storage.lock().unwrap();
+        let Some(slot) = storage.slots.get_mut(&job.id) else {
+            return;
+        };
+        if modified.is_some() {
+            slot.modified = modified;
+        }
+        let reloaded = slot.asset.is_some();
+        match result {
+            Ok(asset) => {
+                slot.asset = Some(asset);
+                slot.state = LoadState::Loaded;
+                slot.dependencies = dependencies;
+                if reloaded {
+                    storage.events.push(AssetEvent::Modified { id: job.id, path: job.path });
+                    storage.push_modified_dependents(job.id);
+                } else {
+                    storage.events.push(AssetEvent::Loaded { id: job.id, path: job.path });
+                }
+            }
+            Err(e) => {
+                // yeniden yükleme bozuksa eski sürüm kullanılmaya devam ediyor
+                if !reloaded {
+                    slot.state = LoadState::Failed(e.to_string());
+                }
+                storage.events.push(AssetEvent::Failed { id: job.id, path: job.path, error: e.to_string() });
+            }
+        }
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn spawn(&self, job: Job) {
+        let mut worker = self.inner.worker.lock().unwrap();
+        let sender = worker.get_or_insert_with(|| {
+            let (sender, jobs) = std::sync::mpsc::channel::<Job>();
+            // zayıf referans: son AssetServer düşünce kanal kapanır ve thread biter
+            let inner = Arc::downgrade(&self.inner);
+            std::thread::Builder::new()
+                .name("asset-loader".to_string())
+                .spawn(move || {
+                    while let Ok(job) = jobs.recv() {
+                        let Some(inner) = inner.upgrade() else { break };
+                        let server = AssetServer { inner };
+                        let modified = server.modified(&job.path);
+                        let bytes = server.read_file(&job.path).map_err(AssetError::from);
+                        server.finish(job, bytes, modified);
+                    }
+                })
+                .expect("couldn't start the asset loader thread");
+            sender
+        });
+        // thread yalnızca sunucu düşünce kapanır, gönderim başarısız olamaz
+        let _ = sender.send(job);
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn spawn(&self, job: Job) {
+        {
+            let mut downloads = self.inner.downloads.lock().unwrap();
+            if downloads.0 > 0 {
+                // dosya henüz inmemiş bir pakette olabilir
+                downloads.1.push(job);
+                return;
+            }
+        }
+        let server = self.clone();
+        let url = format!("{}/{}", self.root().display(), job.path.display()).replace('\\', "/");
+        wasm_bindgen_futures::spawn_local(async move {
+            let bytes = match server.read_file(&job.path) {
+                Ok(bytes) => Ok(bytes),
+                Err(_) => fetch(&url).await,
+            };
+            server.finish(job, bytes, None);
+        });
+    }
+}
+
+#[cfg(target_family = "wasm")]
+async fn fetch(url: &str) -> Result<Vec<u8>, AssetError> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    let error = |e: wasm_bindgen::JsValue| AssetError::Fetch(format!("{}: {:?}", url, e));
+    let window = web_sys::window().ok_or_else(|| AssetError::Fetch("no window".to_string()))?;
+    let response: web_sys::Response = JsFuture::from(window.fetch_with_str(url)).await
+        .map_err(error)?
+        .dyn_into()
+        .map_err(error)?;
+    if !response.ok() {
+        return Err(AssetError::Fetch(format!("{}: status {}", url, response.status())));
+    }
+    let buffer = JsFuture::from(response.array_buffer().map_err(error)?).await.map_err(error)?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+/// `a/./b/../c` -> `a/c`, leading `/` and `..` past the root are dropped
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            _ => (),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    struct LinesLoader;
+
+    impl AssetLoader for LinesLoader {
+        type Asset = Vec<String>;
+
+        fn extensions(&self) -> &[&str] {
+            &["lines"]
+        }
+
+        // "@dosya" satırları bağımlılık
+        fn load(&self, bytes: Vec<u8>, context: &mut LoadContext) -> Result<Self::Asset, AssetError> {
+            let text = String::from_utf8(bytes).map_err(|e| AssetError::Decode(e.to_string()))?;
+            for line in text.lines() {
+                if let Some(dependency) = line.strip_prefix('@') {
+                    context.load::<Vec<String>>(dependency);
+                }
+            }
+            Ok(text.lines().map(str::to_string).collect())
+        }
+    }
+
+    fn wait(server: &AssetServer, id: AssetId) -> LoadState {
+        let start = Instant::now();
+        while server.recursive_load_state(id) == LoadState::Loading && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        server.recursive_load_state(id)
+    }
+
+    #[test]
+    fn normalize_paths(){
+        assert_eq!(normalize(Path::new("/maps/./a/../b.tmx")), PathBuf::from("maps/b.tmx"));
+        assert_eq!(normalize(Path::new("../x.png")), PathBuf::from("x.png"));
+    }
+
+    #[test]
+    fn load_dependencies_and_unload(){
+        let root = std::env::temp_dir().join(format!("assets_test_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("main.lines"), "hello\n@sub/dep.lines").unwrap();
+        std::fs::write(root.join("sub/dep.lines"), "dep\n@../missing.lines").unwrap();
+
+        let server = AssetServer::new(&root);
+        server.add_loader(LinesLoader);
+        let main = server.load::<Vec<String>, _>("main.lines");
+        assert_eq!(server.load::<Vec<String>, _>("./main.lines"), main);
+        assert!(matches!(wait(&server, main.id()), LoadState::Failed(_)));
+        assert_eq!(server.get(&main).unwrap()[0], "hello");
+        assert_eq!(server.dependencies(main.id()).len(), 1);
+        let text = server.load::<String, _>("main.lines");
+        assert!(matches!(server.load_state(text.id()), LoadState::Failed(_)));
+
+        let other = server.load::<Vec<String>, _>("sub/dep.lines");
+        drop((main, text));
+        server.update();
+        // dep hâlâ tutuluyor, main kaldırıldı
+        assert_eq!(server.load_state(other.id()), LoadState::Loaded);
+        assert_eq!(server.len(), 2);
+        drop(other);
+        let events = server.update();
+        assert!(events.iter().filter(|e| matches!(e, AssetEvent::Unloaded { .. })).count() >= 2);
+        assert!(server.is_empty());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,91 @@
+// src/engine/assets/watcher.rs
+//
+// Hot reloading on native builds. A background thread compares the modification time of
+// every loaded file with the one it had when it was read and reloads what changed;
+// `AssetServer::update` then reports it as `AssetEvent::Modified`.
+
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::engine::assets::server::AssetServer;
+
+impl AssetServer {
+    /// Starts polling the loaded files every `interval`, once per server.
+    /// Stops by itself when the last clone of the server is dropped.
+    pub fn watch_for_changes(&self, interval: Duration) {
+        if self.inner.watching.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let inner = Arc::downgrade(&self.inner);
+        std::thread::Builder::new()
+            .name("asset-watcher".to_string())
+            .spawn(move || loop {
+                std::thread::sleep(interval);
+                let Some(inner) = inner.upgrade() else { break };
+                AssetServer { inner }.reload_changed();
+            })
+            .expect("couldn't start the asset watcher thread");
+    }
+
+    pub fn is_watching(&self) -> bool {
+        self.inner.watching.load(Ordering::SeqCst)
+    }
+
+    /// Queues every file whose modification time differs from the last read,
+    /// returns how many were queued
+    pub fn reload_changed(&self) -> usize {
+        let mut queued = 0;
+        for (id, path, read) in self.watched_files() {
+            // silinmiş ya da yarım yazılmış dosya: bir sonraki turda tekrar bakılır
+            let Some(modified) = self.modified(&path) else {
+                continue;
+            };
+            if read != Some(modified) && self.reload_id(id, Some(modified)) {
+                queued += 1;
+            }
+        }
+        queued
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use crate::engine::assets::loaders::TextLoader;
+    use crate::engine::assets::server::{AssetEvent, LoadState};
+
+    use super::*;
+
+    #[test]
+    fn reload_edited_file(){
+        let root = std::env::temp_dir().join(format!("assets_watch_test_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("a.txt"), "one").unwrap();
+
+        let server = AssetServer::new(&root);
+        server.add_loader(TextLoader);
+        let text = server.load::<String, _>("a.txt");
+        let start = Instant::now();
+        while server.load_state(text.id()) == LoadState::Loading && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(server.reload_changed(), 0);
+
+        // mtime çözünürlüğü kaba olabilir, değişene kadar yazılıyor
+        let before = std::fs::metadata(root.join("a.txt")).unwrap().modified().unwrap();
+        while std::fs::metadata(root.join("a.txt")).unwrap().modified().unwrap() == before {
+            std::thread::sleep(Duration::from_millis(10));
+            std::fs::write(root.join("a.txt"), "two").unwrap();
+        }
+        assert_eq!(server.reload_changed(), 1);
+        let mut events = Vec::new();
+        while !events.iter().any(|e| matches!(e, AssetEvent::Modified { .. })) && start.elapsed() < Duration::from_secs(5) {
+            events.extend(server.update());
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(server.get(&text).unwrap().as_str(), "two");
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
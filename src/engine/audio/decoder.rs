@@ -0,0 +1,342 @@
+// src/engine/audio/decoder.rs
+//
+// WAV and Ogg Vorbis. Sounds are decoded to f32 once and played from memory, music keeps
+// the Vorbis data compressed and decodes it packet by packet while it plays.
+
+use std::io::Cursor;
+use std::sync::Arc;
+
+use lewton::inside_ogg::OggStreamReader;
+
+use crate::engine::audio::AudioError;
+
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_FLOAT: u16 = 3;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Decoded audio, interleaved f32 samples. Cheap to clone.
+#[derive(Clone)]
+pub struct Sound {
+    samples: Arc<[f32]>,
+    channels: u16,
+    sample_rate: u32,
+}
+
+impl std::fmt::Debug for Sound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sound")
+            .field("frames", &self.frames())
+            .field("channels", &self.channels)
+            .field("sample_rate", &self.sample_rate)
+            .finish()
+    }
+}
+
+impl Sound {
+    pub fn from_samples(samples: Vec<f32>, channels: u16, sample_rate: u32) -> Result<Self, AudioError> {
+        if channels == 0 || sample_rate == 0 {
+            return Err(AudioError::Unsupported(format!("{} channels at {} hz", channels, sample_rate)));
+        }
+        Ok(Self { samples: samples.into(), channels, sample_rate })
+    }
+
+    /// WAV or Ogg Vorbis, told apart by the header
+    pub fn decode(bytes: &[u8]) -> Result<Self, AudioError> {
+        match bytes.get(..4) {
+            Some(b"RIFF") => Self::from_wav(bytes),
+            Some(b"OggS") => Self::from_ogg(bytes),
+            _ => Err(AudioError::Unsupported("not a wav or ogg file".to_string())),
+        }
+    }
+
+    /// 8/16/24/32 bit integer or 32/64 bit float PCM
+    pub fn from_wav(bytes: &[u8]) -> Result<Self, AudioError> {
+        let wav = parse_wav(bytes)?;
+        Self::from_samples(wav.samples()?, wav.channels, wav.sample_rate)
+    }
+
+    /// Decodes the whole stream, use `Music` for long tracks
+    pub fn from_ogg(bytes: &[u8]) -> Result<Self, AudioError> {
+        let mut reader = OggStreamReader::new(Cursor::new(bytes)).map_err(decode_error)?;
+        let channels = reader.ident_hdr.audio_channels as u16;
+        let sample_rate = reader.ident_hdr.audio_sample_rate;
+        let mut samples = Vec::new();
+        while let Some(packet) = reader.read_dec_packet_itl().map_err(decode_error)? {
+            samples.extend(packet.iter().map(|&s| s as f32 / 32768.0));
+        }
+        Self::from_samples(samples, channels, sample_rate)
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels as usize
+    }
+
+    /// seconds
+    pub fn duration(&self) -> f32 {
+        self.frames() as f32 / self.sample_rate as f32
+    }
+
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+}
+
+#[derive(Clone)]
+enum MusicData {
+    Vorbis { bytes: Arc<[u8]>, sample_rate: u32 },
+    Pcm(Sound),
+}
+
+/// A long track streamed while it plays. Ogg stays compressed in memory, WAV is decoded up front.
+#[derive(Clone)]
+pub struct Music {
+    data: MusicData,
+}
+
+impl Music {
+    pub fn decode(bytes: Vec<u8>) -> Result<Self, AudioError> {
+        if bytes.starts_with(b"OggS") {
+            // başlıklar şimdi okunuyor ki bozuk dosya çalarken değil yüklerken fark edilsin
+            let reader = OggStreamReader::new(Cursor::new(&bytes[..])).map_err(decode_error)?;
+            let sample_rate = reader.ident_hdr.audio_sample_rate;
+            return Ok(Self { data: MusicData::Vorbis { bytes: bytes.into(), sample_rate } });
+        }
+        Ok(Self { data: MusicData::Pcm(Sound::decode(&bytes)?) })
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        match &self.data {
+            MusicData::Vorbis { sample_rate, .. } => *sample_rate,
+            MusicData::Pcm(sound) => sound.sample_rate(),
+        }
+    }
+
+    pub(crate) fn reader(&self) -> Result<Box<dyn Reader>, AudioError> {
+        match &self.data {
+            MusicData::Vorbis { bytes, .. } => Ok(Box::new(VorbisReader::new(bytes.clone())?)),
+            MusicData::Pcm(sound) => Ok(Box::new(SoundReader::new(sound.clone()))),
+        }
+    }
+}
+
+fn decode_error(e: impl std::fmt::Display) -> AudioError {
+    AudioError::Decode(e.to_string())
+}
+
+/// Source of a voice, read one frame at a time at its own sample rate
+pub(crate) trait Reader: Send {
+    fn sample_rate(&self) -> u32;
+    /// next frame as stereo, `None` at the end
+    fn next_frame(&mut self) -> Option<[f32; 2]>;
+    /// back to the first frame, for looping
+    fn rewind(&mut self);
+}
+
+/// mono is played on both sides, channels after the first two are dropped
+fn stereo(frame: &[f32]) -> [f32; 2] {
+    match frame {
+        [mono] => [*mono, *mono],
+        [left, right, ..] => [*left, *right],
+        [] => [0.0, 0.0],
+    }
+}
+
+pub(crate) struct SoundReader {
+    sound: Sound,
+    frame: usize,
+}
+
+impl SoundReader {
+    pub(crate) fn new(sound: Sound) -> Self {
+        Self { sound, frame: 0 }
+    }
+}
+
+impl Reader for SoundReader {
+    fn sample_rate(&self) -> u32 {
+        self.sound.sample_rate
+    }
+
+    fn next_frame(&mut self) -> Option<[f32; 2]> {
+        let channels = self.sound.channels as usize;
+        let frame = self.sound.samples.get(self.frame * channels..(self.frame + 1) * channels)?;
+        self.frame += 1;
+        Some(stereo(frame))
+    }
+
+    fn rewind(&mut self) {
+        self.frame = 0;
+    }
+}
+
+struct VorbisReader {
+    bytes: Arc<[u8]>,
+    ogg: OggStreamReader<Cursor<Arc<[u8]>>>,
+    channels: usize,
+    /// last decoded packet, interleaved
+    packet: Vec<i16>,
+    position: usize,
+}
+
+impl VorbisReader {
+    fn new(bytes: Arc<[u8]>) -> Result<Self, AudioError> {
+        let ogg = OggStreamReader::new(Cursor::new(bytes.clone())).map_err(decode_error)?;
+        let channels = (ogg.ident_hdr.audio_channels as usize).max(1);
+        Ok(Self { bytes, ogg, channels, packet: Vec::new(), position: 0 })
+    }
+}
+
+impl Reader for VorbisReader {
+    fn sample_rate(&self) -> u32 {
+        self.ogg.ident_hdr.audio_sample_rate
+    }
+
+    fn next_frame(&mut self) -> Option<[f32; 2]> {
+        while self.position + self.channels > self.packet.len() {
+            // bozuk paketten sonrası çalınmıyor, parça orada bitiyor
+            self.packet = self.ogg.read_dec_packet_itl().ok()??;
+            self.position = 0;
+        }
+        let sample = |i: usize| self.packet[self.position + i] as f32 / 32768.0;
+        let frame = if self.channels == 1 { [sample(0), sample(0)] } else { [sample(0), sample(1)] };
+        self.position += self.channels;
+        Some(frame)
+    }
+
+    fn rewind(&mut self) {
+        // başlıklar yüklenirken okunabildi, tekrar açmak başarısız olmamalı
+        if let Ok(ogg) = OggStreamReader::new(Cursor::new(self.bytes.clone())) {
+            self.ogg = ogg;
+        }
+        self.packet.clear();
+        self.position = 0;
+    }
+}
+
+struct Wav<'a> {
+    format: u16,
+    channels: u16,
+    sample_rate: u32,
+    bits: u16,
+    data: &'a [u8],
+}
+
+fn le_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn le_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+fn parse_wav(bytes: &[u8]) -> Result<Wav<'_>, AudioError> {
+    if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(AudioError::Decode("not a wav file".to_string()));
+    }
+    let mut fmt = None;
+    let mut data = None;
+    let mut at = 12;
+    while at + 8 <= bytes.len() {
+        let id = &bytes[at..at + 4];
+        let size = le_u32(bytes, at + 4) as usize;
+        let body = &bytes[at + 8..(at + 8).saturating_add(size).min(bytes.len())];
+        match id {
+            b"fmt " if body.len() >= 16 => fmt = Some(body),
+            b"data" => data = Some(body),
+            _ => (),
+        }
+        // parçalar çift uzunluğa tamamlanıyor
+        at = at.saturating_add(8 + size + (size & 1));
+    }
+    let (Some(fmt), Some(data)) = (fmt, data) else {
+        return Err(AudioError::Decode("wav file without fmt or data chunk".to_string()));
+    };
+    let mut format = le_u16(fmt, 0);
+    if format == WAVE_FORMAT_EXTENSIBLE && fmt.len() >= 26 {
+        // alt formatın GUID'inin ilk iki baytı
+        format = le_u16(fmt, 24);
+    }
+    Ok(Wav {
+        format,
+        channels: le_u16(fmt, 2),
+        sample_rate: le_u32(fmt, 4),
+        bits: le_u16(fmt, 14),
+        data,
+    })
+}
+
+impl Wav<'_> {
+    fn samples(&self) -> Result<Vec<f32>, AudioError> {
+        let data = self.data;
+        let samples = match (self.format, self.bits) {
+            (WAVE_FORMAT_PCM, 8) => data.iter().map(|&s| (s as f32 - 128.0) / 128.0).collect(),
+            (WAVE_FORMAT_PCM, 16) => data.chunks_exact(2)
+                .map(|s| i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0)
+                .collect(),
+            (WAVE_FORMAT_PCM, 24) => data.chunks_exact(3)
+                .map(|s| i32::from_le_bytes([0, s[0], s[1], s[2]]) as f32 / 2147483648.0)
+                .collect(),
+            (WAVE_FORMAT_PCM, 32) => data.chunks_exact(4)
+                .map(|s| i32::from_le_bytes(s.try_into().unwrap()) as f32 / 2147483648.0)
+                .collect(),
+            (WAVE_FORMAT_FLOAT, 32) => data.chunks_exact(4)
+                .map(|s| f32::from_le_bytes(s.try_into().unwrap()))
+                .collect(),
+            (WAVE_FORMAT_FLOAT, 64) => data.chunks_exact(8)
+                .map(|s| f64::from_le_bytes(s.try_into().unwrap()) as f32)
+                .collect(),
+            (format, bits) => {
+                return Err(AudioError::Unsupported(format!("wav format {} with {} bits", format, bits)));
+            }
+        };
+        Ok(samples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 16 bit PCM wav with an extra chunk before the data
+    fn wav(samples: &[i16], channels: u16, sample_rate: u32) -> Vec<u8> {
+        let mut bytes = b"RIFF\0\0\0\0WAVE".to_vec();
+        bytes.extend_from_slice(b"fmt \x10\0\0\0");
+        bytes.extend_from_slice(&WAVE_FORMAT_PCM.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * channels as u32 * 2).to_le_bytes());
+        bytes.extend_from_slice(&(channels * 2).to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"LIST\x03\0\0\0abc\0");
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(samples.len() as u32 * 2).to_le_bytes());
+        for s in samples {
+            bytes.extend_from_slice(&s.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn decode_wav(){
+        let sound = Sound::decode(&wav(&[0, 16384, -32768, 32767], 2, 22050)).unwrap();
+        assert_eq!(sound.channels(), 2);
+        assert_eq!(sound.sample_rate(), 22050);
+        assert_eq!(sound.frames(), 2);
+        assert_eq!(&sound.samples()[..3], &[0.0, 0.5, -1.0]);
+
+        let mut reader = SoundReader::new(Sound::from_samples(vec![0.25, 0.5], 1, 8000).unwrap());
+        assert_eq!(reader.next_frame(), Some([0.25, 0.25]));
+        assert_eq!(reader.next_frame(), Some([0.5, 0.5]));
+        assert_eq!(reader.next_frame(), None);
+
+        assert!(matches!(Sound::decode(b"nope"), Err(AudioError::Unsupported(_))));
+    }
+}
@@ -0,0 +1,546 @@
+// src/engine/audio/mixer.rs
+//
+// Software mixer. Voices (playing sounds and music) are resampled to the output rate,
+// panned and summed; each voice plays on a bus, buses form a tree under the master bus
+// and can duck each other, e.g. the music getting quieter while someone talks.
+
+use crate::engine::audio::AudioError;
+use crate::engine::audio::decoder::{Music, Reader, Sound, SoundReader};
+use crate::engine::audio::spatial::Spatialization;
+
+/// gains change linearly over a block, so volume changes don't click
+const BLOCK_FRAMES: usize = 256;
+
+/// A playing sound, stays valid (and does nothing) after it ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VoiceId(u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BusId(usize);
+
+impl BusId {
+    pub const MASTER: BusId = BusId(0);
+    pub const MUSIC: BusId = BusId(1);
+    pub const EFFECTS: BusId = BusId(2);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlayParams {
+    pub volume: f32,
+    /// -1 left, 1 right
+    pub pan: f32,
+    /// playback speed, 2 is an octave up
+    pub pitch: f32,
+    pub looping: bool,
+    pub bus: BusId,
+    /// seconds to reach `volume` from silence
+    pub fade_in: f32,
+}
+
+impl Default for PlayParams {
+    fn default() -> Self {
+        Self { volume: 1.0, pan: 0.0, pitch: 1.0, looping: false, bus: BusId::EFFECTS, fade_in: 0.0 }
+    }
+}
+
+impl PlayParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub fn with_pan(mut self, pan: f32) -> Self {
+        self.pan = pan;
+        self
+    }
+
+    pub fn with_pitch(mut self, pitch: f32) -> Self {
+        self.pitch = pitch;
+        self
+    }
+
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    pub fn with_bus(mut self, bus: BusId) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    pub fn with_fade_in(mut self, seconds: f32) -> Self {
+        self.fade_in = seconds;
+        self
+    }
+}
+
+/// While anything plays on `trigger` (or a bus below it), `target` is turned down to `gain`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ducking {
+    pub trigger: BusId,
+    pub target: BusId,
+    pub gain: f32,
+    /// seconds to go down to `gain`
+    pub attack: f32,
+    /// seconds to come back up
+    pub release: f32,
+}
+
+impl Ducking {
+    pub fn new(trigger: BusId, target: BusId) -> Self {
+        Self { trigger, target, gain: 0.3, attack: 0.05, release: 0.5 }
+    }
+
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    pub fn with_attack(mut self, seconds: f32) -> Self {
+        self.attack = seconds;
+        self
+    }
+
+    pub fn with_release(mut self, seconds: f32) -> Self {
+        self.release = seconds;
+        self
+    }
+}
+
+struct DuckState {
+    ducking: Ducking,
+    /// current gain of the target, between `gain` and 1
+    level: f32,
+}
+
+struct Bus {
+    name: String,
+    /// always a bus created before this one
+    parent: Option<BusId>,
+    volume: f32,
+    muted: bool,
+    /// volume with the parents and ducking applied, at the end of the last block
+    gain: f32,
+}
+
+struct Fade {
+    target: f32,
+    /// volume change per second
+    speed: f32,
+    /// stop the voice when the target is reached
+    stop: bool,
+}
+
+struct Voice {
+    id: VoiceId,
+    reader: Box<dyn Reader>,
+    bus: BusId,
+    volume: f32,
+    pan: f32,
+    pitch: f32,
+    looping: bool,
+    paused: bool,
+    fade: Option<Fade>,
+    /// set by `SpatialAudio` for voices played at an emitter
+    spatial: Spatialization,
+    /// one pole low-pass, coefficient and the last output
+    lowpass: Option<(f32, [f32; 2])>,
+    /// the source frames `position` is between
+    frames: [[f32; 2]; 2],
+    position: f64,
+    /// the reader ran out, `frames[1]` is silence
+    ended: bool,
+    /// faded out, ends with the current block
+    stopping: bool,
+    finished: bool,
+    /// gain at the end of the last block, the next block starts from it
+    gain: f32,
+    /// not mixed yet
+    fresh: bool,
+}
+
+impl Voice {
+    /// next source frame, rewinds looping voices
+    fn read(&mut self) -> Option<[f32; 2]> {
+        match self.reader.next_frame() {
+            Some(frame) => Some(frame),
+            None if self.looping => {
+                self.reader.rewind();
+                self.reader.next_frame()
+            }
+            None => None,
+        }
+    }
+
+    fn advance(&mut self) {
+        if self.ended {
+            self.finished = true;
+            return;
+        }
+        self.frames[0] = self.frames[1];
+        match self.read() {
+            Some(frame) => self.frames[1] = frame,
+            None => {
+                self.frames[1] = [0.0; 2];
+                self.ended = true;
+            }
+        }
+    }
+
+    /// volume at the end of a block `seconds` long, applying the fade
+    fn fade_volume(&mut self, seconds: f32) -> f32 {
+        if let Some(fade) = &self.fade {
+            let step = fade.speed * seconds;
+            if (fade.target - self.volume).abs() <= step {
+                self.volume = fade.target;
+                self.stopping |= fade.stop;
+                self.fade = None;
+            } else {
+                self.volume += step.copysign(fade.target - self.volume);
+            }
+        }
+        self.volume
+    }
+}
+
+/// linear balance, the center plays both sides at full volume
+fn pan_gains(pan: f32) -> (f32, f32) {
+    let pan = pan.clamp(-1.0, 1.0);
+    ((1.0 - pan).min(1.0), (1.0 + pan).min(1.0))
+}
+
+pub struct Mixer {
+    sample_rate: u32,
+    voices: Vec<Voice>,
+    buses: Vec<Bus>,
+    duckings: Vec<DuckState>,
+    next_voice: u64,
+}
+
+impl Mixer {
+    /// Starts with the master, music and effects buses
+    pub fn new(sample_rate: u32) -> Self {
+        let mut mixer = Self { sample_rate, voices: Vec::new(), buses: Vec::new(), duckings: Vec::new(), next_voice: 0 };
+        mixer.buses.push(Bus { name: "master".to_string(), parent: None, volume: 1.0, muted: false, gain: 1.0 });
+        mixer.add_bus("music", BusId::MASTER);
+        mixer.add_bus("effects", BusId::MASTER);
+        mixer
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn add_bus<S: Into<String>>(&mut self, name: S, parent: BusId) -> BusId {
+        let gain = self.buses[parent.0].gain;
+        self.buses.push(Bus { name: name.into(), parent: Some(parent), volume: 1.0, muted: false, gain });
+        BusId(self.buses.len() - 1)
+    }
+
+    pub fn bus(&self, name: &str) -> Option<BusId> {
+        self.buses.iter().position(|b| b.name == name).map(BusId)
+    }
+
+    pub fn set_bus_volume(&mut self, bus: BusId, volume: f32) {
+        self.buses[bus.0].volume = volume.max(0.0);
+    }
+
+    pub fn bus_volume(&self, bus: BusId) -> f32 {
+        self.buses[bus.0].volume
+    }
+
+    pub fn set_bus_muted(&mut self, bus: BusId, muted: bool) {
+        self.buses[bus.0].muted = muted;
+    }
+
+    /// true when `bus` is `ancestor` or below it
+    fn is_under(&self, mut bus: BusId, ancestor: BusId) -> bool {
+        loop {
+            if bus == ancestor {
+                return true;
+            }
+            match self.buses[bus.0].parent {
+                Some(parent) => bus = parent,
+                None => return false,
+            }
+        }
+    }
+
+    /// Replaces the ducking of the same trigger and target
+    pub fn add_ducking(&mut self, ducking: Ducking) {
+        self.remove_ducking(ducking.trigger, ducking.target);
+        self.duckings.push(DuckState { ducking, level: 1.0 });
+    }
+
+    pub fn remove_ducking(&mut self, trigger: BusId, target: BusId) {
+        self.duckings.retain(|d| d.ducking.trigger != trigger || d.ducking.target != target);
+    }
+
+    pub fn play(&mut self, sound: &Sound, params: PlayParams) -> VoiceId {
+        self.start(Box::new(SoundReader::new(sound.clone())), params)
+    }
+
+    /// Streams `music`, usually with `PlayParams::new().with_bus(BusId::MUSIC)`
+    pub fn play_music(&mut self, music: &Music, params: PlayParams) -> Result<VoiceId, AudioError> {
+        Ok(self.start(music.reader()?, params))
+    }
+
+    fn start(&mut self, reader: Box<dyn Reader>, params: PlayParams) -> VoiceId {
+        let id = VoiceId(self.next_voice);
+        self.next_voice += 1;
+        let volume = params.volume.max(0.0);
+        let mut voice = Voice {
+            id,
+            reader,
+            bus: params.bus,
+            volume: if params.fade_in > 0.0 { 0.0 } else { volume },
+            pan: params.pan,
+            pitch: params.pitch,
+            looping: params.looping,
+            paused: false,
+            fade: (params.fade_in > 0.0).then(|| Fade { target: volume, speed: volume / params.fade_in, stop: false }),
+            spatial: Spatialization::default(),
+            lowpass: None,
+            frames: [[0.0; 2]; 2],
+            position: 0.0,
+            ended: false,
+            stopping: false,
+            finished: false,
+            gain: 0.0,
+            fresh: true,
+        };
+        voice.gain = voice.volume * self.buses[params.bus.0].gain;
+        // ilk iki kare, aradaki konum ilk kareden başlıyor
+        voice.advance();
+        voice.advance();
+        self.voices.push(voice);
+        id
+    }
+
+    fn voice(&mut self, id: VoiceId) -> Option<&mut Voice> {
+        self.voices.iter_mut().find(|v| v.id == id)
+    }
+
+    pub fn is_playing(&self, id: VoiceId) -> bool {
+        self.voices.iter().any(|v| v.id == id)
+    }
+
+    /// voices that haven't ended, paused ones included
+    pub fn voice_count(&self) -> usize {
+        self.voices.len()
+    }
+
+    pub fn set_volume(&mut self, id: VoiceId, volume: f32) {
+        if let Some(voice) = self.voice(id) {
+            voice.volume = volume.max(0.0);
+            voice.fade = None;
+        }
+    }
+
+    pub fn set_pan(&mut self, id: VoiceId, pan: f32) {
+        if let Some(voice) = self.voice(id) {
+            voice.pan = pan;
+        }
+    }
+
+    pub fn set_pitch(&mut self, id: VoiceId, pitch: f32) {
+        if let Some(voice) = self.voice(id) {
+            voice.pitch = pitch;
+        }
+    }
+
+    pub fn set_paused(&mut self, id: VoiceId, paused: bool) {
+        if let Some(voice) = self.voice(id) {
+            voice.paused = paused;
+        }
+    }
+
+    pub(crate) fn set_spatial(&mut self, id: VoiceId, spatial: Spatialization) {
+        let sample_rate = self.sample_rate as f32;
+        let Some(bus_gain) = self.voices.iter().find(|v| v.id == id).map(|v| self.buses[v.bus.0].gain) else {
+            return;
+        };
+        if let Some(voice) = self.voice(id) {
+            if voice.fresh {
+                // ilk blok tam sesten değil, konumun sesinden başlıyor
+                voice.gain = voice.volume * spatial.gain * bus_gain;
+            }
+            voice.lowpass = match spatial.cutoff {
+                Some(cutoff) => {
+                    let coefficient = 1.0 - (-2.0 * std::f32::consts::PI * cutoff / sample_rate).exp();
+                    Some((coefficient, voice.lowpass.map_or([0.0; 2], |(_, last)| last)))
+                }
+                None => None,
+            };
+            voice.spatial = spatial;
+        }
+    }
+
+    /// Changes the volume over `seconds`
+    pub fn fade(&mut self, id: VoiceId, volume: f32, seconds: f32) {
+        if let Some(voice) = self.voice(id) {
+            let volume = volume.max(0.0);
+            let speed = (volume - voice.volume).abs() / seconds.max(f32::EPSILON);
+            voice.fade = Some(Fade { target: volume, speed, stop: false });
+        }
+    }
+
+    /// Fades to silence over `seconds` and stops, 0 stops right away
+    pub fn stop(&mut self, id: VoiceId, seconds: f32) {
+        if let Some(voice) = self.voice(id) {
+            let speed = voice.volume / seconds.max(f32::EPSILON);
+            voice.fade = Some(Fade { target: 0.0, speed, stop: true });
+        }
+    }
+
+    /// Stops every voice of `bus` and the buses below it
+    pub fn stop_bus(&mut self, bus: BusId, seconds: f32) {
+        let ids: Vec<VoiceId> = self.voices.iter().filter(|v| self.is_under(v.bus, bus)).map(|v| v.id).collect();
+        for id in ids {
+            self.stop(id, seconds);
+        }
+    }
+
+    /// Moves the ducking levels and the bus gains to the end of a block
+    fn update_buses(&mut self, seconds: f32) {
+        for i in 0..self.duckings.len() {
+            let ducking = self.duckings[i].ducking;
+            let active = self.voices.iter().any(|v| !v.paused && self.is_under(v.bus, ducking.trigger));
+            let (target, time) = if active { (ducking.gain, ducking.attack) } else { (1.0, ducking.release) };
+            let state = &mut self.duckings[i];
+            let step = (1.0 - ducking.gain).abs() * seconds / time.max(f32::EPSILON);
+            if (target - state.level).abs() <= step {
+                state.level = target;
+            } else {
+                state.level += step.copysign(target - state.level);
+            }
+        }
+        for i in 0..self.buses.len() {
+            let bus = &self.buses[i];
+            let parent = bus.parent.map_or(1.0, |p| self.buses[p.0].gain);
+            let duck: f32 = self.duckings.iter()
+                .filter(|d| d.ducking.target.0 == i)
+                .map(|d| d.level)
+                .product();
+            let volume = if bus.muted { 0.0 } else { bus.volume };
+            self.buses[i].gain = volume * duck * parent;
+        }
+    }
+
+    /// Fills `out` with interleaved stereo samples
+    pub fn mix(&mut self, out: &mut [f32]) {
+        out.fill(0.0);
+        for block in out.chunks_mut(BLOCK_FRAMES * 2) {
+            self.mix_block(block);
+        }
+        for sample in out.iter_mut() {
+            *sample = sample.clamp(-1.0, 1.0);
+        }
+    }
+
+    fn mix_block(&mut self, block: &mut [f32]) {
+        let frames = block.len() / 2;
+        if frames == 0 {
+            return;
+        }
+        let seconds = frames as f32 / self.sample_rate as f32;
+        self.update_buses(seconds);
+        let buses = &self.buses;
+        let output_rate = self.sample_rate as f64;
+        for voice in self.voices.iter_mut() {
+            if voice.paused {
+                // devam ederken sessizlikten başlıyor
+                voice.gain = 0.0;
+                continue;
+            }
+            voice.fresh = false;
+            let start = voice.gain;
+            let end = voice.fade_volume(seconds) * voice.spatial.gain * buses[voice.bus.0].gain;
+            voice.gain = end;
+            let (left, right) = pan_gains(voice.pan + voice.spatial.pan);
+            let pitch = voice.pitch * voice.spatial.pitch;
+            let step = pitch.max(0.0) as f64 * voice.reader.sample_rate() as f64 / output_rate;
+            for (i, frame) in block.chunks_exact_mut(2).enumerate() {
+                let gain = start + (end - start) * (i + 1) as f32 / frames as f32;
+                let t = voice.position as f32;
+                let [a, b] = voice.frames;
+                let mut sample = [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t];
+                if let Some((coefficient, last)) = &mut voice.lowpass {
+                    for (s, l) in sample.iter_mut().zip(last.iter_mut()) {
+                        *l += *coefficient * (*s - *l);
+                        *s = *l;
+                    }
+                }
+                frame[0] += sample[0] * gain * left;
+                frame[1] += sample[1] * gain * right;
+                voice.position += step;
+                while voice.position >= 1.0 && !voice.finished {
+                    voice.advance();
+                    voice.position -= 1.0;
+                }
+                if voice.finished {
+                    break;
+                }
+            }
+            // durdurulan ses bu blok boyunca sessizliğe indi
+            voice.finished |= voice.stopping;
+        }
+        self.voices.retain(|v| !v.finished);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constant(value: f32, frames: usize, sample_rate: u32) -> Sound {
+        Sound::from_samples(vec![value; frames], 1, sample_rate).unwrap()
+    }
+
+    #[test]
+    fn resample_and_pan(){
+        let mut mixer = Mixer::new(48000);
+        // yarım hızda kaydedilmiş ses iki kat kare sürüyor
+        let id = mixer.play(&constant(0.5, 1000, 24000), PlayParams::new().with_pan(1.0));
+        let mut out = vec![0.0; 2 * 1000];
+        mixer.mix(&mut out);
+        assert_eq!(out[0], 0.0);
+        assert!((out[1] - 0.5).abs() < 1e-6);
+        assert!(mixer.is_playing(id));
+        let mut out = vec![0.0; 2 * 1200];
+        mixer.mix(&mut out);
+        assert!(!mixer.is_playing(id));
+        assert!(out[2 * 1100..].iter().all(|&s| s == 0.0));
+
+        let id = mixer.play(&constant(0.5, 10, 48000), PlayParams::new().with_looping(true).with_pitch(2.0));
+        mixer.mix(&mut out);
+        assert!(mixer.is_playing(id));
+        mixer.stop(id, 0.0);
+        mixer.mix(&mut out);
+        assert_eq!(mixer.voice_count(), 0);
+    }
+
+    #[test]
+    fn ducking(){
+        let mut mixer = Mixer::new(1000);
+        mixer.add_ducking(Ducking::new(BusId::EFFECTS, BusId::MUSIC).with_gain(0.25).with_attack(0.1).with_release(0.1));
+        mixer.set_bus_volume(BusId::MASTER, 0.5);
+        mixer.play(&constant(1.0, 10, 1000), PlayParams::new().with_bus(BusId::MUSIC).with_looping(true));
+        let mut out = vec![0.0; 2 * 500];
+        mixer.mix(&mut out);
+        assert!((out[998] - 0.5).abs() < 1e-6);
+
+        // sessiz efekt de müziği kısıyor, sadece çalıyor olması yetiyor
+        let voice = mixer.play(&constant(0.0, 1000, 1000), PlayParams::new());
+        mixer.mix(&mut out);
+        assert!((out[998] - 0.125).abs() < 1e-6);
+        mixer.stop(voice, 0.0);
+        mixer.mix(&mut out);
+        assert!((out[998] - 0.5).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,134 @@
+// src/engine/audio/mod.rs
+//
+// Audio. Everything is mixed in software by `Mixer`, a backend only moves the mixed samples
+// to the speakers: SDL3 audio streams natively, WebAudio in the browser. `NullBackend` plays
+// nowhere, for headless runs and tests. `SpatialAudio` positions the voices played at emitters.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::engine::time::now_ms;
+
+pub mod decoder;
+pub mod mixer;
+pub mod null_backend;
+pub mod spatial;
+#[cfg(not(target_family = "wasm"))]
+pub mod sdl_backend;
+#[cfg(target_family = "wasm")]
+pub mod web_backend;
+pub use decoder::{Music, Sound};
+pub use mixer::{BusId, Ducking, Mixer, PlayParams, VoiceId};
+pub use null_backend::NullBackend;
+pub use spatial::{DistanceModel, Listener, Occluder, SoundEmitter, SoundEmitterId, SpatialAudio};
+
+/// output rate asked from the device, the backends convert if it differs
+pub const SAMPLE_RATE: u32 = 48000;
+
+#[derive(Debug)]
+pub enum AudioError {
+    /// no device, or it couldn't be opened
+    Device(String),
+    Decode(String),
+    Unsupported(String),
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AudioError::Device(msg) => write!(f, "audio device error: {}", msg),
+            AudioError::Decode(msg) => write!(f, "audio decode error: {}", msg),
+            AudioError::Unsupported(msg) => write!(f, "unsupported audio: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AudioError {}
+
+pub type SharedMixer = Arc<Mutex<Mixer>>;
+
+pub trait AudioBackend: Send {
+    fn sample_rate(&self) -> u32;
+    /// starts pulling (or pushing) samples from `mixer`
+    fn start(&mut self, mixer: SharedMixer) -> Result<(), AudioError>;
+    fn set_paused(&mut self, paused: bool);
+    /// called every tick, for backends that push samples themselves
+    fn update(&mut self);
+}
+
+pub struct Audio {
+    mixer: SharedMixer,
+    backend: Box<dyn AudioBackend>,
+    /// listener, emitters and how positions are heard
+    pub spatial: SpatialAudio,
+    /// ms of the last update
+    last_update: Option<f64>,
+}
+
+impl Audio {
+    /// The platform's backend
+    pub fn new() -> Result<Self, AudioError> {
+        #[cfg(target_family = "wasm")]
+        let backend = web_backend::WebAudio::new()?;
+        #[cfg(not(target_family = "wasm"))]
+        let backend = sdl_backend::SdlAudio::new()?;
+        Self::with_backend(Box::new(backend))
+    }
+
+    pub fn with_backend(mut backend: Box<dyn AudioBackend>) -> Result<Self, AudioError> {
+        let mixer = Arc::new(Mutex::new(Mixer::new(backend.sample_rate())));
+        backend.start(mixer.clone())?;
+        Ok(Self { mixer, backend, spatial: SpatialAudio::new(), last_update: None })
+    }
+
+    /// Plays nothing, voices still advance with time
+    pub fn null() -> Self {
+        let mixer = Arc::new(Mutex::new(Mixer::new(SAMPLE_RATE)));
+        let mut backend = NullBackend::new(SAMPLE_RATE);
+        // NullBackend::start hata vermiyor
+        let _ = backend.start(mixer.clone());
+        Self { mixer, backend: Box::new(backend), spatial: SpatialAudio::new(), last_update: None }
+    }
+
+    /// Locks the mixer, the device waits while it's held
+    pub fn mixer(&self) -> MutexGuard<'_, Mixer> {
+        self.mixer.lock().unwrap()
+    }
+
+    pub fn play(&self, sound: &Sound, params: PlayParams) -> VoiceId {
+        self.mixer().play(sound, params)
+    }
+
+    pub fn play_music(&self, music: &Music, params: PlayParams) -> Result<VoiceId, AudioError> {
+        self.mixer().play_music(music, params)
+    }
+
+    /// Plays `sound` at the emitter, `None` when there is no such emitter
+    pub fn play_at(&mut self, emitter: SoundEmitterId, sound: &Sound, params: PlayParams) -> Option<VoiceId> {
+        self.spatial.emitter(emitter)?;
+        let mut mixer = self.mixer.lock().unwrap();
+        let voice = mixer.play(sound, params);
+        self.spatial.attach(&mut mixer, voice, emitter);
+        Some(voice)
+    }
+
+    /// A one shot at a fixed position, the emitter goes away when the sound ends
+    pub fn play_at_position(&mut self, emitter: SoundEmitter, sound: &Sound, params: PlayParams) -> VoiceId {
+        let id = self.spatial.add_temporary(emitter);
+        let mut mixer = self.mixer.lock().unwrap();
+        let voice = mixer.play(sound, params);
+        self.spatial.attach(&mut mixer, voice, id);
+        voice
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.backend.set_paused(paused);
+    }
+
+    pub fn update(&mut self) {
+        let now = now_ms();
+        let dt = self.last_update.replace(now).map_or(0.0, |last| ((now - last) / 1000.0) as f32);
+        self.spatial.update(&mut self.mixer.lock().unwrap(), dt);
+        self.backend.update();
+    }
+}
@@ -0,0 +1,91 @@
+// src/engine/audio/null_backend.rs
+
+use std::sync::{Arc, Mutex};
+
+use crate::engine::audio::{AudioBackend, AudioError, SharedMixer};
+use crate::engine::time::now_ms;
+
+/// Mixes as much as real time asks for and throws it away, or keeps it when capturing
+pub struct NullBackend {
+    sample_rate: u32,
+    mixer: Option<SharedMixer>,
+    paused: bool,
+    /// ms of the last update
+    last: Option<f64>,
+    captured: Option<Arc<Mutex<Vec<f32>>>>,
+}
+
+impl NullBackend {
+    pub fn new(sample_rate: u32) -> Self {
+        Self { sample_rate, mixer: None, paused: false, last: None, captured: None }
+    }
+
+    /// Keeps everything mixed in the returned buffer, interleaved stereo
+    pub fn capturing(sample_rate: u32) -> (Self, Arc<Mutex<Vec<f32>>>) {
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let mut backend = Self::new(sample_rate);
+        backend.captured = Some(captured.clone());
+        (backend, captured)
+    }
+
+    /// Mixes `frames` right away, whatever the time is
+    pub fn render(&mut self, frames: usize) {
+        let Some(mixer) = &self.mixer else {
+            return;
+        };
+        let mut out = vec![0.0; frames * 2];
+        mixer.lock().unwrap().mix(&mut out);
+        if let Some(captured) = &self.captured {
+            captured.lock().unwrap().extend_from_slice(&out);
+        }
+    }
+}
+
+impl AudioBackend for NullBackend {
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn start(&mut self, mixer: SharedMixer) -> Result<(), AudioError> {
+        self.mixer = Some(mixer);
+        Ok(())
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.last = None;
+    }
+
+    fn update(&mut self) {
+        if self.paused {
+            return;
+        }
+        let now = now_ms();
+        let last = self.last.replace(now).unwrap_or(now);
+        // uzun duraklamalar (debugger, arka plan) tek seferde karıştırılmıyor
+        let seconds = ((now - last) / 1000.0).min(0.25);
+        self.render((seconds * self.sample_rate as f64) as usize);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::audio::{Audio, PlayParams, Sound};
+
+    #[test]
+    fn capture(){
+        let (mut backend, captured) = NullBackend::capturing(8000);
+        let mixer = Arc::new(Mutex::new(crate::engine::audio::Mixer::new(8000)));
+        backend.start(mixer.clone()).unwrap();
+        let sound = Sound::from_samples(vec![0.25, -0.25, 0.5, -0.5], 2, 8000).unwrap();
+        mixer.lock().unwrap().play(&sound, PlayParams::new().with_volume(2.0));
+        backend.render(4);
+        assert_eq!(*captured.lock().unwrap(), vec![0.5, -0.5, 1.0, -1.0, 0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(mixer.lock().unwrap().voice_count(), 0);
+
+        let audio = Audio::null();
+        audio.play(&sound, PlayParams::new());
+        assert_eq!(audio.mixer().voice_count(), 1);
+    }
+}
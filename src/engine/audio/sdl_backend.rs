@@ -0,0 +1,70 @@
+// src/engine/audio/sdl_backend.rs
+
+use sdl3::audio::{AudioCallback, AudioFormat, AudioSpec, AudioStream, AudioStreamWithCallback};
+use sdl3::AudioSubsystem;
+
+use crate::engine::audio::{AudioBackend, AudioError, SAMPLE_RATE, SharedMixer};
+
+/// Runs on SDL's audio thread
+struct MixerCallback {
+    mixer: SharedMixer,
+    buffer: Vec<f32>,
+}
+
+impl AudioCallback<f32> for MixerCallback {
+    fn callback(&mut self, stream: &mut AudioStream, requested: i32) {
+        // istenen örnek sayısı iki kanalın toplamı
+        self.buffer.resize(requested.max(0) as usize & !1, 0.0);
+        match self.mixer.lock() {
+            Ok(mut mixer) => mixer.mix(&mut self.buffer),
+            Err(_) => self.buffer.fill(0.0),
+        }
+        let _ = stream.put_data_f32(&self.buffer);
+    }
+}
+
+pub struct SdlAudio {
+    subsystem: AudioSubsystem,
+    stream: Option<AudioStreamWithCallback<MixerCallback>>,
+}
+
+impl SdlAudio {
+    /// SDL is already initialized by the window, this only adds the audio subsystem
+    pub fn new() -> Result<Self, AudioError> {
+        let sdl = sdl3::init().map_err(|e| AudioError::Device(e.to_string()))?;
+        let subsystem = sdl.audio().map_err(|e| AudioError::Device(e.to_string()))?;
+        Ok(Self { subsystem, stream: None })
+    }
+}
+
+impl AudioBackend for SdlAudio {
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn start(&mut self, mixer: SharedMixer) -> Result<(), AudioError> {
+        let spec = AudioSpec {
+            freq: Some(SAMPLE_RATE as i32),
+            channels: Some(2),
+            format: Some(AudioFormat::f32_sys()),
+        };
+        let callback = MixerCallback { mixer, buffer: Vec::new() };
+        let stream = self.subsystem.open_playback_stream(&spec, callback)
+            .map_err(|e| AudioError::Device(e.to_string()))?;
+        // cihaz duraklatılmış açılıyor
+        stream.resume().map_err(|e| AudioError::Device(e.to_string()))?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        if let Some(stream) = &self.stream {
+            let _ = if paused { stream.pause() } else { stream.resume() };
+        }
+    }
+
+    /// SDL pulls the samples itself
+    fn update(&mut self) {}
+}
+
+unsafe impl Send for SdlAudio {}
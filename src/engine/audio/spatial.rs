@@ -0,0 +1,361 @@
+// src/engine/audio/spatial.rs
+//
+// Positional audio. Voices played at an emitter get their volume, pan and pitch from where
+// the emitter is relative to the listener, updated every tick. Works the same in 2D, where
+// everything is on the z = 0 plane and the listener is the orthographic camera.
+
+use glam::Vec3;
+
+use crate::engine::audio::mixer::{Mixer, VoiceId};
+use crate::engine::renderer::camera::{Camera, Projection};
+
+/// no velocity (so no Doppler) across longer ticks, a hitch or a teleport would sound like a jet
+const MAX_VELOCITY_DT: f32 = 0.25;
+/// cutoff when nothing is occluded, the filter is off then
+const OPEN_CUTOFF: f32 = 20000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Listener {
+    pub position: Vec3,
+    /// where the listener looks, right is `forward × up`
+    pub forward: Vec3,
+    pub up: Vec3,
+}
+
+impl Default for Listener {
+    fn default() -> Self {
+        Self { position: Vec3::ZERO, forward: Vec3::NEG_Z, up: Vec3::Y }
+    }
+}
+
+impl Listener {
+    /// Perspective cameras listen at their position, orthographic ones at their target,
+    /// the plane a 2D game is drawn on
+    pub fn from_camera(camera: &Camera) -> Self {
+        let position = match camera.projection {
+            Projection::Perspective { .. } => camera.position,
+            Projection::Orthographic { .. } => camera.target,
+        };
+        Self {
+            position,
+            forward: (camera.target - camera.position).normalize_or(Vec3::NEG_Z),
+            up: camera.up,
+        }
+    }
+
+    fn right(&self) -> Vec3 {
+        self.forward.cross(self.up).normalize_or(Vec3::X)
+    }
+}
+
+/// Volume over distance, the same models as OpenAL and WebAudio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceModel {
+    /// full volume until `min_distance`, silent at `max_distance`
+    Linear,
+    /// `min / (min + rolloff * (d - min))`, the physically plausible one
+    Inverse,
+    /// `(d / min) ^ -rolloff`
+    Exponential,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SoundEmitter {
+    pub position: Vec3,
+    pub volume: f32,
+    pub model: DistanceModel,
+    pub min_distance: f32,
+    /// the volume doesn't change past this distance
+    pub max_distance: f32,
+    pub rolloff: f32,
+    /// walls etc. are asked about this emitter, see `Occluder`
+    pub occludable: bool,
+}
+
+impl SoundEmitter {
+    pub fn new(position: Vec3) -> Self {
+        Self {
+            position,
+            volume: 1.0,
+            model: DistanceModel::Inverse,
+            min_distance: 1.0,
+            max_distance: 100.0,
+            rolloff: 1.0,
+            occludable: true,
+        }
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub fn with_model(mut self, model: DistanceModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    pub fn with_distance(mut self, min_distance: f32, max_distance: f32) -> Self {
+        self.min_distance = min_distance;
+        self.max_distance = max_distance;
+        self
+    }
+
+    pub fn with_rolloff(mut self, rolloff: f32) -> Self {
+        self.rolloff = rolloff;
+        self
+    }
+
+    pub fn with_occludable(mut self, occludable: bool) -> Self {
+        self.occludable = occludable;
+        self
+    }
+
+    /// gain at `distance` from the listener
+    pub fn attenuation(&self, distance: f32) -> f32 {
+        let min = self.min_distance.max(f32::EPSILON);
+        let max = self.max_distance.max(min);
+        let d = distance.clamp(min, max);
+        let gain = match self.model {
+            DistanceModel::Linear if max > min => 1.0 - self.rolloff * (d - min) / (max - min),
+            DistanceModel::Linear => 1.0,
+            DistanceModel::Inverse => min / (min + self.rolloff * (d - min)),
+            DistanceModel::Exponential => (d / min).powf(-self.rolloff),
+        };
+        gain.clamp(0.0, 1.0)
+    }
+}
+
+/// Tells how much is between the listener and an emitter: 0 nothing, 1 fully blocked.
+/// Usually a raycast against level geometry; closures work too.
+pub trait Occluder: Send {
+    fn occlusion(&self, listener: Vec3, emitter: Vec3) -> f32;
+}
+
+impl<F: Fn(Vec3, Vec3) -> f32 + Send> Occluder for F {
+    fn occlusion(&self, listener: Vec3, emitter: Vec3) -> f32 {
+        self(listener, emitter)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SoundEmitterId(u64);
+
+/// What positioning does to a voice, on top of its own volume, pan and pitch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Spatialization {
+    pub gain: f32,
+    pub pan: f32,
+    pub pitch: f32,
+    /// low-pass cutoff in hz, None for no filter
+    pub cutoff: Option<f32>,
+}
+
+impl Default for Spatialization {
+    fn default() -> Self {
+        Self { gain: 1.0, pan: 0.0, pitch: 1.0, cutoff: None }
+    }
+}
+
+struct EmitterState {
+    id: SoundEmitterId,
+    emitter: SoundEmitter,
+    /// position at the last update, for the velocity
+    last_position: Option<Vec3>,
+    velocity: Vec3,
+    /// made by `play_at_position`, removed when its voices end
+    temporary: bool,
+}
+
+pub struct SpatialAudio {
+    pub listener: Listener,
+    /// the engine moves the listener with the camera of the renderer
+    pub follow_camera: bool,
+    /// in world units per second, 343 for meters, a lot more for pixels
+    pub speed_of_sound: f32,
+    /// 0 turns Doppler off, 1 is realistic
+    pub doppler_factor: f32,
+    /// gain of a fully occluded emitter
+    pub occlusion_gain: f32,
+    /// low-pass cutoff of a fully occluded emitter, hz
+    pub occlusion_cutoff: f32,
+    occluder: Option<Box<dyn Occluder>>,
+    emitters: Vec<EmitterState>,
+    voices: Vec<(VoiceId, SoundEmitterId)>,
+    last_listener: Option<Vec3>,
+    listener_velocity: Vec3,
+    next_id: u64,
+}
+
+impl Default for SpatialAudio {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpatialAudio {
+    pub fn new() -> Self {
+        Self {
+            listener: Listener::default(),
+            follow_camera: true,
+            speed_of_sound: 343.0,
+            doppler_factor: 1.0,
+            occlusion_gain: 0.3,
+            occlusion_cutoff: 800.0,
+            occluder: None,
+            emitters: Vec::new(),
+            voices: Vec::new(),
+            last_listener: None,
+            listener_velocity: Vec3::ZERO,
+            next_id: 0,
+        }
+    }
+
+    pub fn add_emitter(&mut self, emitter: SoundEmitter) -> SoundEmitterId {
+        self.insert(emitter, false)
+    }
+
+    fn insert(&mut self, emitter: SoundEmitter, temporary: bool) -> SoundEmitterId {
+        let id = SoundEmitterId(self.next_id);
+        self.next_id += 1;
+        self.emitters.push(EmitterState { id, emitter, last_position: None, velocity: Vec3::ZERO, temporary });
+        id
+    }
+
+    /// Voices of the emitter keep playing where it was last
+    pub fn remove_emitter(&mut self, id: SoundEmitterId) -> Option<SoundEmitter> {
+        let index = self.emitters.iter().position(|e| e.id == id)?;
+        self.voices.retain(|(_, emitter)| *emitter != id);
+        Some(self.emitters.remove(index).emitter)
+    }
+
+    pub fn emitter(&self, id: SoundEmitterId) -> Option<&SoundEmitter> {
+        self.emitters.iter().find(|e| e.id == id).map(|e| &e.emitter)
+    }
+
+    /// Move emitters through this, their velocity is worked out from the movement
+    pub fn emitter_mut(&mut self, id: SoundEmitterId) -> Option<&mut SoundEmitter> {
+        self.emitters.iter_mut().find(|e| e.id == id).map(|e| &mut e.emitter)
+    }
+
+    pub fn emitter_count(&self) -> usize {
+        self.emitters.len()
+    }
+
+    pub fn set_occluder<O: Occluder + 'static>(&mut self, occluder: O) {
+        self.occluder = Some(Box::new(occluder));
+    }
+
+    pub fn clear_occluder(&mut self) {
+        self.occluder = None;
+    }
+
+    /// `voice` follows `emitter` from now on, it's positioned right away so it
+    /// doesn't start at full volume
+    pub(crate) fn attach(&mut self, mixer: &mut Mixer, voice: VoiceId, emitter: SoundEmitterId) {
+        if let Some(state) = self.emitters.iter().find(|e| e.id == emitter) {
+            mixer.set_spatial(voice, self.spatialize(state));
+        }
+        self.voices.push((voice, emitter));
+    }
+
+    /// a one shot emitter for `play_at_position`
+    pub(crate) fn add_temporary(&mut self, emitter: SoundEmitter) -> SoundEmitterId {
+        self.insert(emitter, true)
+    }
+
+    fn spatialize(&self, state: &EmitterState) -> Spatialization {
+        let emitter = &state.emitter;
+        let listener = &self.listener;
+        let to_emitter = emitter.position - listener.position;
+        let distance = to_emitter.length();
+        let mut gain = emitter.volume * emitter.attenuation(distance);
+        let mut spatial = Spatialization::default();
+        if distance > f32::EPSILON {
+            let direction = to_emitter / distance;
+            // dinleyicinin dibindeki ses iki kulağa da geliyor
+            let closeness = (distance / emitter.min_distance.max(f32::EPSILON)).min(1.0);
+            spatial.pan = direction.dot(listener.right()) * closeness;
+
+            if self.doppler_factor > 0.0 && self.speed_of_sound > 0.0 {
+                let c = self.speed_of_sound;
+                let limit = c * 0.5;
+                let toward = (self.listener_velocity.dot(direction) * self.doppler_factor).clamp(-limit, limit);
+                let away = (state.velocity.dot(direction) * self.doppler_factor).clamp(-limit, limit);
+                spatial.pitch = ((c + toward) / (c + away)).clamp(0.5, 2.0);
+            }
+        }
+        if let (true, Some(occluder)) = (emitter.occludable, &self.occluder) {
+            let occlusion = occluder.occlusion(listener.position, emitter.position).clamp(0.0, 1.0);
+            if occlusion > 0.0 {
+                gain *= 1.0 - occlusion * (1.0 - self.occlusion_gain);
+                // kesim frekansı logaritmik olarak iniyor
+                spatial.cutoff = Some(OPEN_CUTOFF * (self.occlusion_cutoff / OPEN_CUTOFF).powf(occlusion));
+            }
+        }
+        spatial.gain = gain;
+        spatial
+    }
+
+    /// Moves the voices to where their emitters are, `dt` seconds after the last update
+    pub(crate) fn update(&mut self, mixer: &mut Mixer, dt: f32) {
+        self.voices.retain(|(voice, _)| mixer.is_playing(*voice));
+        let voices = &self.voices;
+        self.emitters.retain(|e| !e.temporary || voices.iter().any(|(_, id)| *id == e.id));
+
+        let velocity = |last: Option<Vec3>, position: Vec3| match last {
+            Some(last) if dt > 0.0 && dt <= MAX_VELOCITY_DT => (position - last) / dt,
+            _ => Vec3::ZERO,
+        };
+        self.listener_velocity = velocity(self.last_listener, self.listener.position);
+        self.last_listener = Some(self.listener.position);
+        for state in &mut self.emitters {
+            state.velocity = velocity(state.last_position, state.emitter.position);
+            state.last_position = Some(state.emitter.position);
+        }
+
+        for (voice, id) in &self.voices {
+            if let Some(state) = self.emitters.iter().find(|e| e.id == *id) {
+                mixer.set_spatial(*voice, self.spatialize(state));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::audio::{PlayParams, Sound};
+
+    #[test]
+    fn attenuation_pan_and_doppler(){
+        let emitter = SoundEmitter::new(Vec3::ZERO).with_distance(2.0, 10.0);
+        assert_eq!(emitter.attenuation(1.0), 1.0);
+        assert_eq!(emitter.attenuation(4.0), 0.5);
+        assert_eq!(emitter.attenuation(50.0), emitter.attenuation(10.0));
+        let linear = emitter.with_model(DistanceModel::Linear);
+        assert_eq!(linear.attenuation(6.0), 0.5);
+        assert_eq!(linear.attenuation(10.0), 0.0);
+
+        let mut mixer = Mixer::new(1000);
+        let mut spatial = SpatialAudio::new();
+        spatial.speed_of_sound = 10.0;
+        let id = spatial.add_emitter(SoundEmitter::new(Vec3::new(4.0, 0.0, 0.0)));
+        let voice = mixer.play(&Sound::from_samples(vec![1.0; 100], 1, 1000).unwrap(), PlayParams::new());
+        spatial.attach(&mut mixer, voice, id);
+        spatial.update(&mut mixer, 0.1);
+        // dinleyici -z'ye bakıyor, +x sağ taraf
+        let state = &spatial.emitters[0];
+        assert_eq!(spatial.spatialize(state), Spatialization { gain: 0.25, pan: 1.0, pitch: 1.0, cutoff: None });
+
+        // saniyede 2 birim uzaklaşıyor: 10 / (10 + 2)
+        spatial.emitter_mut(id).unwrap().position.x = 4.2;
+        spatial.update(&mut mixer, 0.1);
+        assert!((spatial.spatialize(&spatial.emitters[0]).pitch - 10.0 / 12.0).abs() < 1e-4);
+
+        spatial.set_occluder(|_: Vec3, _: Vec3| 1.0);
+        let occluded = spatial.spatialize(&spatial.emitters[0]);
+        assert!((occluded.gain - 0.3 / 4.2).abs() < 1e-4);
+        assert!((occluded.cutoff.unwrap() - 800.0).abs() < 0.1);
+    }
+}
@@ -0,0 +1,108 @@
+// src/engine/audio/web_backend.rs
+//
+// WebAudio. Mixed blocks are scheduled as AudioBuffers a little ahead of the context's
+// clock on every tick, so the sound stutters when the tab stops calling requestAnimationFrame.
+
+use wasm_bindgen::JsValue;
+use web_sys::{AudioContext, AudioContextState};
+
+use crate::engine::audio::{AudioBackend, AudioError, SharedMixer};
+
+const BLOCK_FRAMES: usize = 1024;
+/// seconds scheduled ahead of the clock
+const LEAD: f64 = 0.1;
+/// ms between resume requests while the browser keeps the context suspended
+const RESUME_INTERVAL: f64 = 500.0;
+
+pub struct WebAudio {
+    context: AudioContext,
+    mixer: Option<SharedMixer>,
+    paused: bool,
+    /// context time the next block starts at
+    next_time: f64,
+    last_resume: f64,
+    block: Vec<f32>,
+    left: Vec<f32>,
+    right: Vec<f32>,
+}
+
+impl WebAudio {
+    pub fn new() -> Result<Self, AudioError> {
+        let context = AudioContext::new().map_err(|e| AudioError::Device(format!("{:?}", e)))?;
+        Ok(Self {
+            context,
+            mixer: None,
+            paused: false,
+            next_time: 0.0,
+            last_resume: f64::NEG_INFINITY,
+            block: vec![0.0; BLOCK_FRAMES * 2],
+            left: vec![0.0; BLOCK_FRAMES],
+            right: vec![0.0; BLOCK_FRAMES],
+        })
+    }
+
+    fn schedule(&mut self) -> Result<(), JsValue> {
+        if let Some(mixer) = &self.mixer {
+            mixer.lock().unwrap().mix(&mut self.block);
+        }
+        for (i, frame) in self.block.chunks_exact(2).enumerate() {
+            self.left[i] = frame[0];
+            self.right[i] = frame[1];
+        }
+        let sample_rate = self.context.sample_rate();
+        let buffer = self.context.create_buffer(2, BLOCK_FRAMES as u32, sample_rate)?;
+        buffer.copy_to_channel(&mut self.left, 0)?;
+        buffer.copy_to_channel(&mut self.right, 1)?;
+        let source = self.context.create_buffer_source()?;
+        source.set_buffer(Some(&buffer));
+        source.connect_with_audio_node(&self.context.destination())?;
+        source.start_with_when(self.next_time)?;
+        self.next_time += BLOCK_FRAMES as f64 / sample_rate as f64;
+        Ok(())
+    }
+}
+
+impl AudioBackend for WebAudio {
+    fn sample_rate(&self) -> u32 {
+        self.context.sample_rate() as u32
+    }
+
+    fn start(&mut self, mixer: SharedMixer) -> Result<(), AudioError> {
+        self.mixer = Some(mixer);
+        Ok(())
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        let _ = if paused { self.context.suspend() } else { self.context.resume() };
+    }
+
+    fn update(&mut self) {
+        if self.paused {
+            return;
+        }
+        // tarayıcı ilk kullanıcı etkileşimine kadar sesi başlatmıyor
+        if self.context.state() == AudioContextState::Suspended {
+            let now = js_sys::Date::now();
+            if now - self.last_resume > RESUME_INTERVAL {
+                self.last_resume = now;
+                let _ = self.context.resume();
+            }
+            return;
+        }
+        let now = self.context.current_time();
+        if self.next_time < now {
+            // geride kalındı, boşluktan sonra devam
+            self.next_time = now + 0.01;
+        }
+        while self.next_time < now + LEAD {
+            if let Err(e) = self.schedule() {
+                let mut msgs = crate::PENDING_MESSAGES.lock().unwrap();
+                msgs.push(crate::Message::Log(crate::LogMsg::Error(format!("WebAudio: {:?}", e))));
+                break;
+            }
+        }
+    }
+}
+
+unsafe impl Send for WebAudio {}
@@ -0,0 +1,94 @@
+// src/engine/events.rs
+//
+// What happened in the engine during a tick, for the game. Events are gathered while `tick`
+// runs and can be read once it returns: handlers added with `Engine::on_event` see each one
+// at the end of the tick (the way to get them under `game_loop`), a game calling `tick` itself
+// can `drain_events` instead. The next tick drops the ones nobody drained.
+
+use std::path::PathBuf;
+
+use crate::engine::assets::AssetId;
+use crate::engine::physics::CollisionEvent;
+use crate::engine::renderer::GraphicsEvent;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineEvent {
+    /// An asset file (path relative to the asset root) was reloaded, or one it depends on
+    AssetChanged { id: AssetId, path: PathBuf },
+    /// Two colliders started or stopped touching during one of the tick's physics steps
+    Collision(CollisionEvent),
+    /// The surface or the device was lost. After `DeviceRecreated` scene components that
+    /// use meshes, materials or textures of the old device aren't drawn until replaced.
+    Graphics(GraphicsEvent),
+}
+
+type Handler = Box<dyn FnMut(&EngineEvent)>;
+
+/// Events of the current tick and the handlers they go to
+#[derive(Default)]
+pub struct EngineEvents {
+    events: Vec<EngineEvent>,
+    handlers: Vec<Handler>,
+}
+
+impl EngineEvents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, event: EngineEvent) {
+        self.events.push(event);
+    }
+
+    pub fn on_event<F: FnMut(&EngineEvent) + 'static>(&mut self, handler: F) {
+        self.handlers.push(Box::new(handler));
+    }
+
+    /// Start of a tick, events of the last one that weren't drained are dropped
+    pub fn begin_tick(&mut self) {
+        self.events.clear();
+    }
+
+    /// End of a tick, every handler sees every event in order
+    pub fn dispatch(&mut self) {
+        for handler in &mut self.handlers {
+            for event in &self.events {
+                handler(event);
+            }
+        }
+    }
+
+    pub fn drain(&mut self) -> std::vec::Drain<'_, EngineEvent> {
+        self.events.drain(..)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn events_last_one_tick(){
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut events = EngineEvents::new();
+        let log = seen.clone();
+        events.on_event(move |e| log.borrow_mut().push(e.clone()));
+        let changed = |n: u64| EngineEvent::AssetChanged { id: AssetId(n), path: PathBuf::from("hero.png") };
+
+        events.begin_tick();
+        events.push(changed(1));
+        events.dispatch();
+        assert_eq!(*seen.borrow(), [changed(1)]);
+        assert_eq!(events.drain().collect::<Vec<_>>(), [changed(1)]);
+
+        // alınmayanlar sonraki tick'te gidiyor
+        events.push(changed(2));
+        events.begin_tick();
+        events.push(changed(3));
+        events.dispatch();
+        assert_eq!(events.drain().collect::<Vec<_>>(), [changed(3)]);
+        assert_eq!(*seen.borrow(), [changed(1), changed(3)]);
+    }
+}
@@ -5,14 +5,15 @@ use ts_rs::TS;
 use once_cell::sync::Lazy;
 
 use crate::LogMsg;
+use crate::physics::CollisionEvent;
+use crate::renderer::{GraphicsEvent, RendererSettings};
 
 pub type MessageVecType = Lazy<Arc<Mutex<Vec<Message>>>>;
 
 pub static PENDING_MESSAGES:MessageVecType = Lazy::new(||{
     let v = Vec::new();
     let v = Mutex::new(v);
-    let v = Arc::new(v);
-    return v
+    Arc::new(v)
 });
 
 
@@ -27,6 +28,36 @@ pub enum Message{
     Log(LogMsg),
     Say(String),
     ChangeTitle(String),
+    SetRendererSettings(RendererSettings),
+    /// Saves the next frame as a png, on the web it's downloaded with the file's name
+    Screenshot(String),
+    /// Saves every frame into `directory` at a fixed `fps` timestep,
+    /// until `StopFrameCapture` when `frames` is None
+    StartFrameCapture { directory: String, fps: f32, frames: Option<u32> },
+    StopFrameCapture,
+    /// Surface or device loss and what the engine did about it
+    Graphics(GraphicsEvent),
+    /// Logs every graphics adapter of the configured backends
+    ListAdapters,
+    /// Frame time graph and counters in the top left corner
+    ShowStats(bool),
+    /// Logs the stats of the last frame once
+    LogStats,
+    /// An asset file (path relative to the asset root) was reloaded, or one it depends on
+    AssetChanged(String),
+    /// Mounts a pack file (a url on the web) at the virtual directory `point`,
+    /// higher `priority` mounts cover lower ones
+    MountPack { path: String, point: String, priority: i32 },
+    /// Removes every mount at the virtual directory
+    Unmount(String),
+    /// Volume of an audio bus by name ("master", "music", "effects" or one added by the game)
+    SetBusVolume { bus: String, volume: f32 },
+    /// Pauses the audio device, e.g. while the game is in the background
+    PauseAudio(bool),
+    /// Two colliders started or stopped touching during a physics step
+    Collision(CollisionEvent),
+    /// Rate of the fixed timestep physics is stepped at, in hz
+    SetFixedTimestep(f32),
     #[cfg(target_family = "wasm")]
     SetCanvasId(String),
     // for development
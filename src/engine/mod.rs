@@ -4,13 +4,21 @@ use once_cell::sync::Lazy;
 #[cfg(target_family = "wasm")]
 use wasm_bindgen::prelude::Closure;
 
-use crate::{engine::{messages::{Message, PENDING_MESSAGES}, window::GameWindow}, log::{Logger, NewDefaultLogger}, renderer::GraphicsContext};
+use crate::{engine::{assets::{AssetEvent, AssetId, AssetServer, Handle}, audio::{Audio, Listener}, events::{EngineEvent, EngineEvents}, messages::{Message, PENDING_MESSAGES}, physics::PhysicsWorld, scene::{ComponentRegistry, LostResources, Scene}, time::FixedTimestep, window::GameWindow}, log::{Logger, NewDefaultLogger}, renderer::{adapter::{describe_adapter, list_adapters}, CaptureError, CaptureTarget, GraphicsConfig, GraphicsContext, GraphicsError, GraphicsEvent, RendererSettings, RendererState, TextureAsset, TextureAssets}};
 
 pub mod window;
 pub mod messages;
 pub mod log;
 pub mod renderer;
 pub mod flags;
+pub mod assets;
+pub mod vfs;
+pub mod audio;
+pub mod physics;
+pub mod spatial;
+pub mod scene;
+pub mod time;
+pub mod events;
 
 
 
@@ -30,6 +38,33 @@ pub struct Engine{
     status:EngineStatus,
     pub logger:Box<dyn Logger>,
     pub graphics_context: Option<GraphicsContext>,
+    pub renderer_settings: RendererSettings,
+    /// adapter selection, read when graphics are initialized
+    pub graphics_config: GraphicsConfig,
+    /// files under `assets/`, updated every tick
+    pub assets: AssetServer,
+    /// falls back to `Audio::null` when there is no audio device
+    pub audio: Audio,
+    /// stepped `fixed_timestep.step` seconds at a time in `tick`
+    pub physics: PhysicsWorld,
+    pub fixed_timestep: FixedTimestep,
+    /// updated and drawn every tick
+    pub scene: Scene,
+    /// components saved with the scene, its `AssetRef`s load from `assets`
+    pub components: ComponentRegistry,
+    /// pipeline cache shaders whose source comes from an asset, see `bind_shader`
+    shader_assets: Vec<(&'static str, Handle<String>)>,
+    /// textures made from image assets, see `load_texture`
+    texture_assets: TextureAssets,
+    /// what happened during the current tick, see `drain_events`
+    events: EngineEvents,
+    /// renderer of a lost device, waiting for the new one
+    lost_renderer: Option<RendererState>,
+    /// scene resources of lost devices, their components aren't drawn
+    lost_resources: LostResources,
+    /// filled by the async device creation on the web
+    #[cfg(target_family = "wasm")]
+    pending_graphics: std::rc::Rc<std::cell::RefCell<Option<Result<GraphicsContext, GraphicsError>>>>,
 }
 
 impl Engine {
@@ -39,11 +74,32 @@ impl Engine {
         let w = GameWindow::new(title);
         let mut logger = NewDefaultLogger();
         logger.info("starting the engine");
+        let assets = default_assets();
+        let components = ComponentRegistry::new().with_assets(assets.clone());
+        let audio = Audio::new().unwrap_or_else(|e| {
+            logger.warn(&format!("Running without sound: {}", e));
+            Audio::null()
+        });
         Self {
             window: w,
             status:EngineStatus::Uninited,
-            logger:logger,
+            logger,
             graphics_context:None,
+            renderer_settings:RendererSettings::default(),
+            graphics_config:GraphicsConfig::default().with_env(),
+            assets,
+            audio,
+            physics:PhysicsWorld::new(),
+            fixed_timestep:FixedTimestep::default(),
+            scene:Scene::new(),
+            components,
+            shader_assets:Vec::new(),
+            texture_assets:TextureAssets::new(),
+            events:EngineEvents::new(),
+            lost_renderer:None,
+            lost_resources:LostResources::new(),
+            #[cfg(target_family = "wasm")]
+            pending_graphics:Default::default(),
         }        
     }
     pub fn handle_messages(&mut self){
@@ -74,6 +130,90 @@ impl Engine {
                 Message::Log(log_msg) => {
                     self.logger.log(log_msg);
                 },
+                Message::SetRendererSettings(settings) => {
+                    self.renderer_settings = *settings;
+                    // grafik henüz hazır değilse init_graphics bu ayarları kullanacak
+                    if let (Some(context), Some(sm)) = (&self.graphics_context, &mut self.window.surface_manager) {
+                        let applied = sm.apply_settings(context.adapter(), &context.device, *settings);
+                        if applied != *settings {
+                            self.logger.warn(&format!("Renderer settings adjusted to {:?}", applied));
+                        }
+                        self.renderer_settings = applied;
+                    }
+                },
+                Message::Screenshot(path) => {
+                    match &mut self.window.surface_manager {
+                        Some(sm) => sm.capture.screenshot(CaptureTarget::path(path)),
+                        None => self.logger.warn("Screenshot ignored, graphics aren't initialized"),
+                    }
+                },
+                Message::StartFrameCapture { directory, fps, frames } => {
+                    let result = match &mut self.window.surface_manager {
+                        Some(sm) => sm.capture.start_sequence(directory, *fps, *frames),
+                        None => Err(CaptureError::Unsupported("graphics aren't initialized".to_string())),
+                    };
+                    match result {
+                        Ok(()) => self.logger.info(&format!("Capturing frames into {}", directory)),
+                        Err(e) => self.logger.error(&format!("Frame capture failed: {}", e)),
+                    }
+                },
+                Message::StopFrameCapture => {
+                    if let Some(sm) = &mut self.window.surface_manager {
+                        sm.capture.stop_sequence();
+                    }
+                },
+                Message::Graphics(event) => {
+                    match event {
+                        GraphicsEvent::FrameSkipped => (),
+                        GraphicsEvent::DeviceRecreated => self.logger.info("Graphics device recreated"),
+                        GraphicsEvent::SurfaceReconfigured(reason) => self.logger.warn(&format!("Surface reconfigured: {}", reason)),
+                        GraphicsEvent::DeviceLost(reason) => self.logger.error(&format!("Graphics device lost: {}", reason)),
+                    }
+                },
+                Message::ListAdapters => {
+                    self.log_adapters();
+                },
+                Message::ShowStats(show) => {
+                    if let Some(sm) = &mut self.window.surface_manager {
+                        sm.stats.overlay = *show;
+                    }
+                },
+                Message::LogStats => {
+                    if let Some(sm) = &self.window.surface_manager {
+                        self.logger.info(&sm.stats.last().to_string());
+                    }
+                },
+                Message::AssetChanged(path) => {
+                    self.logger.info(&format!("Reloaded {}", path));
+                },
+                Message::MountPack { path, point, priority } => {
+                    match self.assets.mount_pack(path, point, *priority) {
+                        Ok(()) => self.logger.info(&format!("Mounted {} at /{}", path, point)),
+                        Err(e) => self.logger.error(&format!("Couldn't mount {}: {}", path, e)),
+                    }
+                },
+                Message::Unmount(point) => {
+                    self.assets.unmount(point);
+                },
+                Message::SetBusVolume { bus, volume } => {
+                    let mut mixer = self.audio.mixer();
+                    match mixer.bus(bus) {
+                        Some(id) => mixer.set_bus_volume(id, *volume),
+                        None => self.logger.warn(&format!("No audio bus named {}", bus)),
+                    }
+                },
+                Message::PauseAudio(paused) => {
+                    self.audio.set_paused(*paused);
+                },
+                // oyuna `EngineEvent::Collision` olarak step_physics'te veriliyor
+                Message::Collision(_) => (),
+                Message::SetFixedTimestep(hz) => {
+                    if *hz > 0.0 {
+                        self.fixed_timestep.step = 1.0 / hz;
+                    } else {
+                        self.logger.warn(&format!("Invalid fixed timestep rate {}", hz));
+                    }
+                },
             }
         }
 
@@ -83,17 +223,191 @@ impl Engine {
     // YENİ: Async Grafik Bağlamı Başlatıcı
     pub async fn init_graphics(&mut self) {
         self.logger.info("Initializing Graphics Context...");
-        let graphics_context = GraphicsContext::new().await;
-        self.graphics_context = Some(graphics_context);
-        self.logger.info("Graphics Context Initialized!");
-        
-        // SurfaceManager'ı oluştur ve Window'a ata
-        {
-            let sm = self.graphics_context.as_ref().unwrap().create_surface_manager(&self.window);
-            self.window.surface_manager = Some(sm);
-            self.logger.info("Window Surface Manager Initialized!");
+        let result = GraphicsContext::new(&self.graphics_config).await;
+        self.install_graphics(result);
+    }
+
+    /// Creates the SurfaceManager of `context`, bringing back the renderer of a lost device
+    fn install_graphics(&mut self, result: Result<GraphicsContext, GraphicsError>) {
+        let context = match result {
+            Ok(context) => context,
+            Err(e) => return self.graphics_failed(e),
+        };
+        self.logger.info(&format!("Graphics Context Initialized on {}", describe_adapter(&context.adapter().get_info())));
+        let mut sm = match context.create_surface_manager(&self.window, self.renderer_settings) {
+            Ok(sm) => sm,
+            Err(e) => return self.graphics_failed(e),
+        };
+        self.logger.info("Window Surface Manager Initialized!");
+        if let Some(state) = self.lost_renderer.take() {
+            for warning in sm.restore_state(&context.device, &context.queue, state) {
+                self.logger.warn(&warning);
+            }
+            self.graphics_event(GraphicsEvent::DeviceRecreated);
         }
-        
+        self.window.surface_manager = Some(sm);
+        // yeni cihazda da asset'ten gelen shader kaynakları kullanılıyor
+        let shaders: Vec<AssetId> = self.shader_assets.iter().map(|(_, handle)| handle.id()).collect();
+        for id in shaders {
+            self.apply_shader(&context, id);
+        }
+        self.texture_assets.device_changed(&self.assets);
+        self.graphics_context = Some(context);
+    }
+
+    /// Keeps running without graphics if the config allows it, stops the engine otherwise
+    fn graphics_failed(&mut self, error: GraphicsError) {
+        self.logger.error(&format!("Graphics initialization failed: {}", error));
+        self.log_adapters();
+        if self.graphics_config.allow_headless {
+            self.logger.warn("Running headless, nothing will be drawn");
+        } else {
+            self.status = EngineStatus::Kill;
+        }
+    }
+
+    fn log_adapters(&mut self) {
+        for info in list_adapters(self.graphics_config.wgpu_backends()) {
+            self.logger.info(&format!("Adapter: {}", describe_adapter(&info)));
+        }
+    }
+
+    /// true when there is no GPU to draw with (failed or not initialized yet)
+    pub fn is_headless(&self) -> bool {
+        self.graphics_context.is_none()
+    }
+
+    /// Starts creating a context without blocking, `tick` installs it when it's ready
+    #[cfg(target_family = "wasm")]
+    fn request_graphics(&mut self) {
+        let pending = self.pending_graphics.clone();
+        let config = self.graphics_config.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = GraphicsContext::new(&config).await;
+            *pending.borrow_mut() = Some(result);
+        });
+    }
+
+    /// Sent as `Message::Graphics` and given to the game
+    fn graphics_event(&mut self, event: GraphicsEvent) {
+        PENDING_MESSAGES.lock().unwrap().push(Message::Graphics(event.clone()));
+        self.events.push(EngineEvent::Graphics(event));
+    }
+
+    /// Saves the CPU side of the renderer, drops the old device and creates a new one.
+    /// Scene components made on the old device stop being drawn, the game replaces them
+    /// after `GraphicsEvent::DeviceRecreated`.
+    fn recover_device(&mut self, reason: String) {
+        self.graphics_event(GraphicsEvent::DeviceLost(reason));
+        self.lost_resources.add_scene(&self.scene);
+        // yüzey cihazdan önce bırakılmalı
+        if let Some(sm) = self.window.surface_manager.take() {
+            self.lost_renderer = Some(sm.save_state());
+        }
+        self.graphics_context = None;
+        #[cfg(not(target_family = "wasm"))]
+        pollster::block_on(self.init_graphics());
+        #[cfg(target_family = "wasm")]
+        self.request_graphics();
+    }
+    /// Unloads dropped assets, logs the loads that finished since the last tick and
+    /// applies reloaded files: bound shaders are replaced, bound textures uploaded again,
+    /// `AssetChanged` is sent and given to the game for each
+    fn update_assets(&mut self) {
+        for event in self.assets.update() {
+            match event {
+                AssetEvent::Loaded { id, path } => {
+                    self.logger.info(&format!("Loaded {}", path.display()));
+                    self.shader_asset_loaded(id);
+                    self.texture_assets.image_changed(&self.assets, id);
+                },
+                AssetEvent::Modified { id, path } => {
+                    self.shader_asset_loaded(id);
+                    self.texture_assets.image_changed(&self.assets, id);
+                    PENDING_MESSAGES.lock().unwrap().push(Message::AssetChanged(path.display().to_string()));
+                    self.events.push(EngineEvent::AssetChanged { id, path });
+                },
+                AssetEvent::Failed { path, error, .. } => self.logger.error(&format!("Couldn't load {}: {}", path.display(), error)),
+                AssetEvent::MountFailed { url, error } => self.logger.error(&format!("Couldn't mount {}: {}", url, error)),
+                AssetEvent::Unloaded { .. } => (),
+            }
+        }
+    }
+
+    /// Takes the source of pipeline cache shader `name` (e.g. `PBR_SHADER`) from the asset at
+    /// `path` from now on, edits of the file are applied while the game runs
+    pub fn bind_shader<P: AsRef<std::path::Path>>(&mut self, name: &'static str, path: P) {
+        let handle = self.assets.load::<String, _>(path);
+        self.shader_assets.retain(|(bound, _)| *bound != name);
+        self.shader_assets.push((name, handle));
+    }
+
+    /// A texture of the image at `path` that is uploaded again whenever the file changes.
+    /// `get` returns it once the image is loaded and graphics are up.
+    pub fn load_texture<P: AsRef<std::path::Path>>(&mut self, path: P, srgb: bool) -> TextureAsset {
+        self.texture_assets.bind(&self.assets, path, srgb)
+    }
+
+    fn shader_asset_loaded(&mut self, id: AssetId) {
+        if let Some(context) = self.graphics_context.take() {
+            self.apply_shader(&context, id);
+            self.graphics_context = Some(context);
+        }
+    }
+
+    /// Starts replacing the shaders bound to asset `id`, see `log_shader_reloads`
+    fn apply_shader(&mut self, context: &GraphicsContext, id: AssetId) {
+        let Some(sm) = &mut self.window.surface_manager else {
+            return;
+        };
+        for (name, handle) in self.shader_assets.iter().filter(|(_, handle)| handle.id() == id) {
+            let Some(source) = self.assets.get(handle) else {
+                continue;
+            };
+            sm.reload_shader(&context.device, name, &source);
+        }
+    }
+
+    /// Sources that failed validation are logged, the old shader stays in use
+    fn log_shader_reloads(&mut self) {
+        let Some(sm) = &mut self.window.surface_manager else {
+            return;
+        };
+        for (name, result) in sm.take_shader_reloads() {
+            match result {
+                Ok(()) => self.logger.info(&format!("Shader {} replaced", name)),
+                Err(e) => self.logger.error(&format!("Shader {} not replaced: {}", name, e)),
+            }
+        }
+    }
+    /// Runs the fixed steps the time since the last tick makes, frame captures advance by their
+    /// own timestep so recordings play at the right speed
+    fn step_physics(&mut self) {
+        let capture = self.window.surface_manager.as_ref().and_then(|sm| sm.capture.timestep());
+        let steps = match capture {
+            Some(seconds) => self.fixed_timestep.advance_by(seconds),
+            None => self.fixed_timestep.advance(),
+        };
+        for _ in 0..steps {
+            self.physics.step(self.fixed_timestep.step);
+        }
+        let events = self.physics.take_events();
+        if !events.is_empty() {
+            PENDING_MESSAGES.lock().unwrap().extend(events.iter().copied().map(Message::Collision));
+            for event in events {
+                self.events.push(EngineEvent::Collision(event));
+            }
+        }
+    }
+    /// Calls `handler` with every event at the end of each tick, the way to see them when
+    /// `game_loop` owns the engine
+    pub fn on_event<F: FnMut(&EngineEvent) + 'static>(&mut self, handler: F) {
+        self.events.on_event(handler);
+    }
+
+    /// Events of the last `tick`, available once it returns until the next one starts
+    pub fn drain_events(&mut self) -> std::vec::Drain<'_, EngineEvent> {
+        self.events.drain()
     }
     pub fn render(&mut self){
         
@@ -101,18 +415,63 @@ impl Engine {
 
     pub fn tick(&mut self){
                 
+        self.events.begin_tick();
         self.handle_messages();
         self.window.poll_events();
+        self.update_assets();
+        self.log_shader_reloads();
+        self.step_physics();
+        self.scene.update();
+        if let (true, Some(sm)) = (self.audio.spatial.follow_camera, &self.window.surface_manager) {
+            self.audio.spatial.listener = Listener::from_camera(&sm.camera);
+        }
+        self.audio.update();
+        #[cfg(target_family = "wasm")]
+        if let Some(result) = self.pending_graphics.borrow_mut().take() {
+            self.install_graphics(result);
+        }
+        if let Some(reason) = self.graphics_context.as_ref().and_then(GraphicsContext::device_lost) {
+            self.recover_device(reason);
+        }
         // Çizim Mantığı:
-        if let Some(ref context) = self.graphics_context {
-            if let Some(ref mut sm) = self.window.surface_manager {
-                if let Err(e) = sm.render(&context.device, &context.queue) {
-                     // Hata yönetimi (SurfaceLost vb.)
-                     self.logger.error(&format!("Render error: {:?}", e));
+        let mut out_of_memory = false;
+        if let Some(ref context) = self.graphics_context
+            && let Some(ref mut sm) = self.window.surface_manager
+        {
+            self.texture_assets.upload(&context.device, &context.queue);
+            // JS'den istenen ekran görüntüleri
+            #[cfg(target_family = "wasm")]
+            for target in crate::renderer::capture::take_js_screenshot_requests() {
+                sm.capture.screenshot(target);
+            }
+            self.lost_resources.release_dropped();
+            self.scene.queue_draws_skipping(&mut sm.world_sprites, &mut sm.pbr, &self.lost_resources);
+            match sm.render(&context.device, &context.queue) {
+                Ok(()) => (),
+                Err(wgpu::SurfaceError::Timeout) => {
+                    PENDING_MESSAGES.lock().unwrap().push(Message::Graphics(GraphicsEvent::FrameSkipped));
+                    self.events.push(EngineEvent::Graphics(GraphicsEvent::FrameSkipped));
+                },
+                Err(e @ (wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated)) => {
+                    sm.reconfigure(&context.device, self.window.inner.size());
+                    let event = GraphicsEvent::SurfaceReconfigured(e.to_string());
+                    PENDING_MESSAGES.lock().unwrap().push(Message::Graphics(event.clone()));
+                    self.events.push(EngineEvent::Graphics(event));
+                },
+                Err(wgpu::SurfaceError::OutOfMemory) => out_of_memory = true,
+                Err(e) => self.logger.error(&format!("Render error: {}", e)),
+            }
+            for result in sm.capture.take_results() {
+                match result {
+                    Ok(path) => self.logger.info(&format!("Saved {}", path.display())),
+                    Err(e) => self.logger.error(&format!("Capture failed: {}", e)),
                 }
             }
         }
-        
+        if out_of_memory {
+            self.recover_device("out of memory".to_string());
+        }
+        self.events.dispatch();
     }
 
     /// takes ownership of the game and starts the game loop untill killed
@@ -127,6 +486,9 @@ impl Engine {
         use std::rc::Rc;
         use std::cell::RefCell;
 
+        // grafik hazır olunca tick içinde kuruluyor
+        self.request_graphics();
+
         // 1. Wrap engine in Rc<RefCell> so it can be shared with the closure
         let engine = Rc::new(RefCell::new(self));
         let f = Rc::new(RefCell::new(None));
@@ -176,6 +538,21 @@ impl Engine {
     }
 }
 
+/// Debug builds reload edited files natively. A shipped `assets.pak` is mounted under the
+/// `assets` directory, so files there still override it during development.
+fn default_assets() -> AssetServer {
+    let assets = AssetServer::new("assets");
+    assets.add_default_loaders();
+    #[cfg(not(target_family = "wasm"))]
+    if std::path::Path::new("assets.pak").is_file() {
+        // hata engine kurulunca loglanamıyor, paket yoksa sayılıyor
+        let _ = assets.mount_pack("assets.pak", "", -1);
+    }
+    #[cfg(all(not(target_family = "wasm"), debug_assertions))]
+    assets.watch_for_changes(std::time::Duration::from_millis(500));
+    assets
+}
+
 #[cfg(target_family = "wasm")]
 fn request_animation_frame(f: &Closure<dyn FnMut()>) {
     use wasm_bindgen::JsCast;
@@ -192,7 +569,6 @@ fn request_animation_frame(f: &Closure<dyn FnMut()>) {
 mod tests{
     #[test]
     #[cfg(not(target_family = "wasm"))]
-
     fn platform(){
         let s = sdl3::get_platform();
         println!("{}",s);
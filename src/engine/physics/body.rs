@@ -0,0 +1,299 @@
+// src/engine/physics/body.rs
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+#[cfg(target_family = "wasm")]
+use ts_rs::TS;
+
+use crate::engine::physics::shape::{MassData, Shape, Transform2};
+
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct BodyId(pub(crate) u64);
+
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ColliderId(pub(crate) u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyType {
+    /// moved by gravity, forces and contacts
+    Dynamic,
+    /// moved only by its velocity, pushes dynamic bodies without being pushed
+    Kinematic,
+    /// never moves
+    Static,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RigidBody {
+    pub body_type: BodyType,
+    /// the origin of the colliders, not the center of mass
+    pub position: Vec2,
+    /// radians, counter clockwise
+    pub angle: f32,
+    /// of the center of mass
+    pub linear_velocity: Vec2,
+    pub angular_velocity: f32,
+    pub gravity_scale: f32,
+    pub linear_damping: f32,
+    pub angular_damping: f32,
+    /// contacts don't make it spin
+    pub fixed_rotation: bool,
+    mass: f32,
+    inv_mass: f32,
+    inertia: f32,
+    inv_inertia: f32,
+    /// center of mass relative to `position`, unrotated
+    local_center: Vec2,
+    force: Vec2,
+    torque: f32,
+}
+
+impl RigidBody {
+    pub fn new(body_type: BodyType) -> Self {
+        let mut body = Self {
+            body_type,
+            position: Vec2::ZERO,
+            angle: 0.0,
+            linear_velocity: Vec2::ZERO,
+            angular_velocity: 0.0,
+            gravity_scale: 1.0,
+            linear_damping: 0.0,
+            angular_damping: 0.0,
+            fixed_rotation: false,
+            mass: 0.0,
+            inv_mass: 0.0,
+            inertia: 0.0,
+            inv_inertia: 0.0,
+            local_center: Vec2::ZERO,
+            force: Vec2::ZERO,
+            torque: 0.0,
+        };
+        body.set_mass_data(MassData::default());
+        body
+    }
+
+    pub fn dynamic() -> Self {
+        Self::new(BodyType::Dynamic)
+    }
+
+    pub fn kinematic() -> Self {
+        Self::new(BodyType::Kinematic)
+    }
+
+    pub fn fixed() -> Self {
+        Self::new(BodyType::Static)
+    }
+
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_angle(mut self, angle: f32) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    pub fn with_linear_velocity(mut self, velocity: Vec2) -> Self {
+        self.linear_velocity = velocity;
+        self
+    }
+
+    pub fn with_angular_velocity(mut self, velocity: f32) -> Self {
+        self.angular_velocity = velocity;
+        self
+    }
+
+    pub fn with_gravity_scale(mut self, scale: f32) -> Self {
+        self.gravity_scale = scale;
+        self
+    }
+
+    pub fn with_damping(mut self, linear: f32, angular: f32) -> Self {
+        self.linear_damping = linear;
+        self.angular_damping = angular;
+        self
+    }
+
+    pub fn with_fixed_rotation(mut self, fixed: bool) -> Self {
+        self.fixed_rotation = fixed;
+        self
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        self.body_type == BodyType::Dynamic
+    }
+
+    /// 0 for kinematic and static bodies
+    pub fn mass(&self) -> f32 {
+        self.mass
+    }
+
+    pub fn inertia(&self) -> f32 {
+        self.inertia
+    }
+
+    pub fn transform(&self) -> Transform2 {
+        Transform2::new(self.position, self.angle)
+    }
+
+    /// center of mass in world space
+    pub fn world_center(&self) -> Vec2 {
+        self.transform().apply(self.local_center)
+    }
+
+    /// velocity of a world space point moving with the body
+    pub fn velocity_at(&self, point: Vec2) -> Vec2 {
+        self.linear_velocity + cross_sv(self.angular_velocity, point - self.world_center())
+    }
+
+    /// Applied during the next step, at the center of mass
+    pub fn apply_force(&mut self, force: Vec2) {
+        self.force += force;
+    }
+
+    /// Applied during the next step, at a world space point
+    pub fn apply_force_at(&mut self, force: Vec2, point: Vec2) {
+        self.force += force;
+        self.torque += (point - self.world_center()).perp_dot(force);
+    }
+
+    pub fn apply_torque(&mut self, torque: f32) {
+        self.torque += torque;
+    }
+
+    /// Changes the velocity right away, at the center of mass
+    pub fn apply_impulse(&mut self, impulse: Vec2) {
+        self.linear_velocity += impulse * self.inv_mass;
+    }
+
+    pub fn apply_impulse_at(&mut self, impulse: Vec2, point: Vec2) {
+        self.linear_velocity += impulse * self.inv_mass;
+        self.angular_velocity += self.inv_inertia * (point - self.world_center()).perp_dot(impulse);
+    }
+
+    pub fn apply_angular_impulse(&mut self, impulse: f32) {
+        self.angular_velocity += self.inv_inertia * impulse;
+    }
+
+    pub(crate) fn inv_mass(&self) -> f32 {
+        self.inv_mass
+    }
+
+    pub(crate) fn inv_inertia(&self) -> f32 {
+        self.inv_inertia
+    }
+
+    pub(crate) fn take_forces(&mut self) -> (Vec2, f32) {
+        (std::mem::take(&mut self.force), std::mem::take(&mut self.torque))
+    }
+
+    /// Moves the center of mass, keeping the rotation around it
+    pub(crate) fn set_world_center(&mut self, center: Vec2) {
+        self.position = center - Vec2::from_angle(self.angle).rotate(self.local_center);
+    }
+
+    /// `data` is the sum of the colliders, inertia around `data.center`
+    pub(crate) fn set_mass_data(&mut self, data: MassData) {
+        self.local_center = data.center;
+        if self.body_type != BodyType::Dynamic {
+            (self.mass, self.inv_mass, self.inertia, self.inv_inertia) = (0.0, 0.0, 0.0, 0.0);
+            return;
+        }
+        // collider'ı olmayan dinamik cisim de düşebilmeli
+        self.mass = if data.mass > 0.0 { data.mass } else { 1.0 };
+        self.inv_mass = 1.0 / self.mass;
+        self.inertia = if self.fixed_rotation { 0.0 } else { data.inertia };
+        self.inv_inertia = if self.inertia > 0.0 { 1.0 / self.inertia } else { 0.0 };
+    }
+}
+
+/// cross product of a scalar (z axis) and a vector
+pub(crate) fn cross_sv(s: f32, v: Vec2) -> Vec2 {
+    Vec2::new(-s * v.y, s * v.x)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Collider {
+    pub shape: Shape,
+    /// relative to the body
+    pub offset: Vec2,
+    pub rotation: f32,
+    pub density: f32,
+    pub friction: f32,
+    pub restitution: f32,
+    /// reports overlaps but doesn't push anything
+    pub sensor: bool,
+    /// bits of the layers this collider is in
+    pub layer: u32,
+    /// layers it collides with, both sides have to accept each other
+    pub mask: u32,
+}
+
+impl Collider {
+    pub fn new(shape: Shape) -> Self {
+        Self {
+            shape,
+            offset: Vec2::ZERO,
+            rotation: 0.0,
+            density: 1.0,
+            friction: 0.6,
+            restitution: 0.0,
+            sensor: false,
+            layer: 1,
+            mask: u32::MAX,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: Vec2, rotation: f32) -> Self {
+        self.offset = offset;
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_density(mut self, density: f32) -> Self {
+        self.density = density;
+        self
+    }
+
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.restitution = restitution;
+        self
+    }
+
+    pub fn with_sensor(mut self, sensor: bool) -> Self {
+        self.sensor = sensor;
+        self
+    }
+
+    pub fn with_layers(mut self, layer: u32, mask: u32) -> Self {
+        self.layer = layer;
+        self.mask = mask;
+        self
+    }
+
+    pub fn local_transform(&self) -> Transform2 {
+        Transform2::new(self.offset, self.rotation)
+    }
+
+    /// true when the layers of both accept each other
+    pub fn can_collide(&self, other: &Collider) -> bool {
+        self.layer & other.mask != 0 && other.layer & self.mask != 0
+    }
+
+    /// mass data in body space, sensors weigh nothing
+    pub(crate) fn mass(&self) -> MassData {
+        if self.sensor {
+            return MassData::default();
+        }
+        let data = self.shape.mass(self.density);
+        MassData { center: self.local_transform().apply(data.center), ..data }
+    }
+}
@@ -0,0 +1,263 @@
+// src/engine/physics/collision.rs
+//
+// Narrow phase between two cores in world space. Overlapping cores are clipped against the
+// face of least penetration (up to two points, like Box2D), otherwise the closest points of
+// the cores give one point, which is what happens with circles and rounded ends.
+
+use glam::Vec2;
+
+use crate::engine::physics::shape::{closest_on_segment, Core};
+
+/// a face of B has to be this much better to be picked over A's, keeps the choice stable
+const FACE_TOLERANCE: f32 = 0.001;
+/// id of the point found from the closest points of the cores
+const DISTANCE_ID: u32 = u32::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ManifoldPoint {
+    pub point: Vec2,
+    /// negative when overlapping
+    pub separation: f32,
+    /// same features give the same id from step to step, for warm starting
+    pub id: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Manifold {
+    /// from A to B
+    pub normal: Vec2,
+    pub points: Vec<ManifoldPoint>,
+}
+
+impl Manifold {
+    pub fn touching(&self) -> bool {
+        self.points.iter().any(|p| p.separation <= 0.0)
+    }
+}
+
+/// face of `a` that separates the cores the most and by how much
+fn max_separation(a: &Core, b: &Core) -> (usize, f32) {
+    let mut best = (0, f32::NEG_INFINITY);
+    for (i, (n, v)) in a.normals.iter().zip(&a.vertices).enumerate() {
+        let separation = b.vertices.iter().map(|w| n.dot(*w - *v)).fold(f32::INFINITY, f32::min);
+        if separation > best.1 {
+            best = (i, separation);
+        }
+    }
+    best
+}
+
+/// Contact points closer than `margin`, `None` when the shapes are further apart
+pub(crate) fn collide(a: &Core, b: &Core, margin: f32) -> Option<Manifold> {
+    if a.normals.is_empty() && b.normals.is_empty() {
+        return closest(a, b, margin);
+    }
+    let (edge_a, separation_a) = max_separation(a, b);
+    let (edge_b, separation_b) = max_separation(b, a);
+    let flip = a.normals.is_empty() || (!b.normals.is_empty() && separation_b > separation_a + FACE_TOLERANCE);
+    let (reference, incident, edge, separation) = if flip {
+        (b, a, edge_b, separation_b)
+    } else {
+        (a, b, edge_a, separation_a)
+    };
+    if separation > a.radius + b.radius + margin {
+        return None;
+    }
+    if separation > 0.0 {
+        // çekirdekler ayrık: en yakın özellikler iki köşeyse yüz değil köşe normali geçerli
+        let n = reference.vertices.len();
+        let (i1, i2) = incident_edge(reference, incident, edge);
+        let (f1, f2) = segment_fractions(
+            reference.vertices[edge], reference.vertices[(edge + 1) % n],
+            incident.vertices[i1], incident.vertices[i2],
+        );
+        let at_end = |f: f32| f == 0.0 || f == 1.0;
+        if at_end(f1) && (i1 == i2 || at_end(f2)) {
+            return closest(a, b, margin);
+        }
+    }
+    let manifold = clip(reference, incident, edge, margin);
+    // nokta ile doğru parçası aynı doğru üzerindeyse kırpma bir şey bırakmıyor
+    match manifold {
+        Some(m) if flip => Some(Manifold { normal: -m.normal, points: m.points }),
+        Some(m) => Some(m),
+        None => closest(a, b, margin),
+    }
+}
+
+/// Where the closest points of segments `p1 q1` and `p2 q2` are, as fractions along them
+fn segment_fractions(p1: Vec2, q1: Vec2, p2: Vec2, q2: Vec2) -> (f32, f32) {
+    let d1 = q1 - p1;
+    let d2 = q2 - p2;
+    let r = p1 - p2;
+    let a = d1.length_squared();
+    let e = d2.length_squared();
+    let f = d2.dot(r);
+    if a <= f32::EPSILON && e <= f32::EPSILON {
+        return (0.0, 0.0);
+    }
+    if a <= f32::EPSILON {
+        return (0.0, (f / e).clamp(0.0, 1.0));
+    }
+    let c = d1.dot(r);
+    if e <= f32::EPSILON {
+        return ((-c / a).clamp(0.0, 1.0), 0.0);
+    }
+    let b = d1.dot(d2);
+    let denominator = a * e - b * b;
+    let s = if denominator > f32::EPSILON { ((b * f - c * e) / denominator).clamp(0.0, 1.0) } else { 0.0 };
+    let t = (b * s + f) / e;
+    if t < 0.0 {
+        ((-c / a).clamp(0.0, 1.0), 0.0)
+    } else if t > 1.0 {
+        (((b - c) / a).clamp(0.0, 1.0), 1.0)
+    } else {
+        (s, t)
+    }
+}
+
+/// edge of `incident` facing face `edge` of `reference` the most, a point is its own edge
+fn incident_edge(reference: &Core, incident: &Core, edge: usize) -> (usize, usize) {
+    if incident.normals.is_empty() {
+        return (0, 0);
+    }
+    let normal = reference.normals[edge];
+    let m = incident.vertices.len();
+    let i = (0..m).min_by(|&x, &y| normal.dot(incident.normals[x]).total_cmp(&normal.dot(incident.normals[y]))).unwrap();
+    (i, (i + 1) % m)
+}
+
+/// Distance between the rounded shapes (negative when only the radii overlap, meaningless
+/// when the cores overlap), the normal from A to B and the point halfway between them
+pub(crate) fn distance(a: &Core, b: &Core) -> (f32, Vec2, Vec2) {
+    let mut best = (Vec2::ZERO, Vec2::ZERO, f32::INFINITY);
+    for &(a1, a2) in &a.segments() {
+        for &(b1, b2) in &b.segments() {
+            // kesişmeyen iki doğru parçasının en yakın noktalarından biri bir uçta
+            let candidates = [
+                (a1, closest_on_segment(a1, b1, b2)),
+                (a2, closest_on_segment(a2, b1, b2)),
+                (closest_on_segment(b1, a1, a2), b1),
+                (closest_on_segment(b2, a1, a2), b2),
+            ];
+            for (pa, pb) in candidates {
+                let d = pa.distance_squared(pb);
+                if d < best.2 {
+                    best = (pa, pb, d);
+                }
+            }
+        }
+    }
+    let (pa, pb, d) = best;
+    let distance = d.sqrt();
+    let normal = if distance > f32::EPSILON { (pb - pa) / distance } else { Vec2::Y };
+    let point = ((pa + normal * a.radius) + (pb - normal * b.radius)) * 0.5;
+    (distance - a.radius - b.radius, normal, point)
+}
+
+/// One point from the closest points of the cores
+fn closest(a: &Core, b: &Core, margin: f32) -> Option<Manifold> {
+    let (separation, normal, point) = distance(a, b);
+    if separation > margin {
+        return None;
+    }
+    Some(Manifold { normal, points: vec![ManifoldPoint { point, separation, id: DISTANCE_ID }] })
+}
+
+/// keeps the part of `points` where `dot(normal, p) <= offset`
+fn clip_segment(points: [(Vec2, u32); 2], normal: Vec2, offset: f32, clipped_id: u32) -> Option<[(Vec2, u32); 2]> {
+    let d0 = normal.dot(points[0].0) - offset;
+    let d1 = normal.dot(points[1].0) - offset;
+    let mut out = Vec::with_capacity(2);
+    if d0 <= 0.0 {
+        out.push(points[0]);
+    }
+    if d1 <= 0.0 {
+        out.push(points[1]);
+    }
+    if d0 * d1 < 0.0 {
+        let t = d0 / (d0 - d1);
+        out.push((points[0].0 + t * (points[1].0 - points[0].0), clipped_id));
+    }
+    match out[..] {
+        [p0, p1] => Some([p0, p1]),
+        _ => None,
+    }
+}
+
+/// Clips the incident edge of `incident` against face `edge` of `reference`, normal from reference to incident
+fn clip(reference: &Core, incident: &Core, edge: usize, margin: f32) -> Option<Manifold> {
+    let n = reference.vertices.len();
+    let normal = reference.normals[edge];
+    let v1 = reference.vertices[edge];
+    let v2 = reference.vertices[(edge + 1) % n];
+
+    let (i1, i2) = incident_edge(reference, incident, edge);
+    let base = (edge as u32) << 16;
+    let points = [(incident.vertices[i1], base | i1 as u32), (incident.vertices[i2], base | i2 as u32)];
+
+    let radius = reference.radius + incident.radius;
+    let tangent = (v2 - v1).normalize_or_zero();
+    let points = clip_segment(points, -tangent, -tangent.dot(v1) + radius, base | 0x100)?;
+    let points = clip_segment(points, tangent, tangent.dot(v2) + radius, base | 0x200)?;
+
+    let front = normal.dot(v1);
+    let mut result = Vec::with_capacity(2);
+    for (i, (p, id)) in points.iter().enumerate() {
+        let core_separation = normal.dot(*p) - front;
+        let separation = core_separation - radius;
+        // aynı köşe iki kez kalmışsa (nokta şekli) tek nokta yeter
+        if separation > margin || (i == 1 && i1 == i2 && points[0].0 == *p) {
+            continue;
+        }
+        let on_reference = *p - normal * (core_separation - reference.radius);
+        let on_incident = *p - normal * incident.radius;
+        result.push(ManifoldPoint { point: (on_reference + on_incident) * 0.5, separation, id: *id });
+    }
+    if result.is_empty() {
+        return None;
+    }
+    Some(Manifold { normal, points: result })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::physics::shape::{Shape, Transform2};
+
+    fn core(shape: Shape, x: f32, y: f32, angle: f32) -> Core {
+        shape.core().transformed(&Transform2::new(Vec2::new(x, y), angle))
+    }
+
+    #[test]
+    fn manifolds(){
+        // kutu zemine 0.1 girmiş: iki nokta, normal yukarı
+        let ground = core(Shape::rect(10.0, 1.0), 0.0, 0.0, 0.0);
+        let crate_ = core(Shape::rect(1.0, 1.0), 0.0, 0.9, 0.0);
+        let m = collide(&ground, &crate_, 0.0).unwrap();
+        assert!(m.normal.abs_diff_eq(Vec2::Y, 1e-6));
+        assert_eq!(m.points.len(), 2);
+        assert!(m.points.iter().all(|p| (p.separation + 0.1).abs() < 1e-5));
+
+        let circle = core(Shape::circle(0.5), 3.0, 0.0, 0.0);
+        let other = core(Shape::circle(1.0), 4.0, 0.0, 0.0);
+        let m = collide(&circle, &other, 0.0).unwrap();
+        assert_eq!(m.normal, Vec2::X);
+        assert!((m.points[0].separation + 0.5).abs() < 1e-6);
+        assert!(collide(&circle, &core(Shape::circle(1.0), 5.0, 0.0, 0.0), 0.0).is_none());
+
+        // kapsülün yuvarlak ucu kutunun üstünde
+        let capsule = core(Shape::capsule(1.0, 0.5), 0.0, 1.95, 0.0);
+        let m = collide(&ground, &capsule, 0.0).unwrap();
+        assert!(m.normal.abs_diff_eq(Vec2::Y, 1e-6));
+        assert!((m.points[0].separation + 0.05).abs() < 1e-5);
+        // yatık kapsül iki noktayla duruyor
+        let lying = core(Shape::capsule(1.0, 0.5), 0.0, 0.95, std::f32::consts::FRAC_PI_2);
+        assert_eq!(collide(&ground, &lying, 0.0).unwrap().points.len(), 2);
+
+        // iç içe daire ile kutu: normal en yakın yüzden dışarı
+        let inside = core(Shape::circle(0.25), 4.8, 0.2, 0.0);
+        let m = collide(&ground, &inside, 0.0).unwrap();
+        assert!(m.normal.abs_diff_eq(Vec2::X, 1e-6));
+    }
+}
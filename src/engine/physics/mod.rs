@@ -0,0 +1,39 @@
+// src/engine/physics/mod.rs
+//
+// 2D rigid body physics. A `PhysicsWorld` holds bodies and the colliders attached to them,
+// the engine steps it from its fixed timestep and sends the collision events on the message
+// bus. Units are up to the game, the defaults (gravity, margins) are tuned for meters.
+
+use serde::{Deserialize, Serialize};
+#[cfg(target_family = "wasm")]
+use ts_rs::TS;
+
+pub mod body;
+pub mod collision;
+pub mod shape;
+pub mod world;
+pub use body::{BodyId, BodyType, Collider, ColliderId, RigidBody};
+pub use shape::{PhysicsError, Shape, Transform2};
+pub use crate::engine::spatial::Aabb;
+pub use world::{PhysicsWorld, RayHit};
+
+/// Two colliders, `collider_a` has the lower id
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Collision {
+    pub collider_a: ColliderId,
+    pub collider_b: ColliderId,
+    pub body_a: BodyId,
+    pub body_b: BodyId,
+    /// one of them is a sensor, they only overlap
+    pub sensor: bool,
+}
+
+/// Given to the game as `EngineEvent::Collision` and sent as `Message::Collision`
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CollisionEvent {
+    Started(Collision),
+    /// also sent when one of the colliders is removed
+    Stopped(Collision),
+}
@@ -0,0 +1,329 @@
+// src/engine/physics/shape.rs
+//
+// Every shape is a convex core (a point, a segment or a polygon) grown by a radius: a circle
+// is a rounded point, a capsule a rounded segment. The narrow phase only knows about cores.
+
+use std::f32::consts::PI;
+use std::fmt;
+
+use glam::Vec2;
+
+use crate::engine::spatial::Aabb;
+
+#[derive(Debug)]
+pub enum PhysicsError {
+    InvalidShape(String),
+}
+
+impl fmt::Display for PhysicsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PhysicsError::InvalidShape(msg) => write!(f, "invalid shape: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PhysicsError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Circle { radius: f32 },
+    Box { half_extents: Vec2 },
+    /// convex, counter clockwise
+    Polygon { vertices: Vec<Vec2> },
+    /// vertical, `half_height` is the straight part without the caps
+    Capsule { half_height: f32, radius: f32 },
+}
+
+impl Shape {
+    pub fn circle(radius: f32) -> Self {
+        Shape::Circle { radius }
+    }
+
+    pub fn rect(width: f32, height: f32) -> Self {
+        Shape::Box { half_extents: Vec2::new(width, height) * 0.5 }
+    }
+
+    pub fn capsule(half_height: f32, radius: f32) -> Self {
+        Shape::Capsule { half_height, radius }
+    }
+
+    /// The convex hull of `points`
+    pub fn polygon(points: &[Vec2]) -> Result<Self, PhysicsError> {
+        let vertices = convex_hull(points);
+        if vertices.len() < 3 {
+            return Err(PhysicsError::InvalidShape("a polygon needs 3 points that aren't on a line".to_string()));
+        }
+        Ok(Shape::Polygon { vertices })
+    }
+
+    pub(crate) fn core(&self) -> Core {
+        match self {
+            Shape::Circle { radius } => Core::new(vec![Vec2::ZERO], *radius),
+            Shape::Box { half_extents: h } => Core::new(vec![
+                Vec2::new(-h.x, -h.y), Vec2::new(h.x, -h.y), Vec2::new(h.x, h.y), Vec2::new(-h.x, h.y),
+            ], 0.0),
+            Shape::Polygon { vertices } => Core::new(vertices.clone(), 0.0),
+            Shape::Capsule { half_height, radius } => {
+                Core::new(vec![Vec2::new(0.0, -half_height), Vec2::new(0.0, *half_height)], *radius)
+            }
+        }
+    }
+
+    /// mass, centroid and rotational inertia around the centroid
+    pub(crate) fn mass(&self, density: f32) -> MassData {
+        match self {
+            Shape::Circle { radius } => {
+                let mass = density * PI * radius * radius;
+                MassData { mass, center: Vec2::ZERO, inertia: 0.5 * mass * radius * radius }
+            }
+            Shape::Box { half_extents: h } => {
+                let mass = density * 4.0 * h.x * h.y;
+                MassData { mass, center: Vec2::ZERO, inertia: mass * h.length_squared() / 3.0 }
+            }
+            Shape::Polygon { vertices } => polygon_mass(vertices, density),
+            Shape::Capsule { half_height: h, radius: r } => {
+                let box_mass = density * 4.0 * r * h;
+                let circle_mass = density * PI * r * r;
+                // yarım dairelerin ağırlık merkezi düz kısmın ucundan 4r/3π uzakta
+                let lc = 4.0 * r / (3.0 * PI);
+                let circle_inertia = circle_mass * (0.5 * r * r + h * h + 2.0 * h * lc);
+                let box_inertia = box_mass * (r * r + h * h) / 3.0;
+                MassData { mass: box_mass + circle_mass, center: Vec2::ZERO, inertia: box_inertia + circle_inertia }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) struct MassData {
+    pub mass: f32,
+    pub center: Vec2,
+    pub inertia: f32,
+}
+
+fn polygon_mass(vertices: &[Vec2], density: f32) -> MassData {
+    // üçgen yelpazesi, ilk köşe orijin
+    let origin = vertices[0];
+    let mut area = 0.0;
+    let mut center = Vec2::ZERO;
+    let mut inertia = 0.0;
+    for i in 1..vertices.len() - 1 {
+        let e1 = vertices[i] - origin;
+        let e2 = vertices[i + 1] - origin;
+        let d = e1.perp_dot(e2);
+        let triangle = 0.5 * d;
+        area += triangle;
+        center += triangle * (e1 + e2) / 3.0;
+        let x2 = e1.x * e1.x + e2.x * e1.x + e2.x * e2.x;
+        let y2 = e1.y * e1.y + e2.y * e1.y + e2.y * e2.y;
+        inertia += 0.25 / 3.0 * d * (x2 + y2);
+    }
+    let mass = density * area;
+    let center = center / area;
+    MassData {
+        mass,
+        center: center + origin,
+        inertia: density * inertia - mass * center.length_squared(),
+    }
+}
+
+/// counter clockwise, collinear points dropped
+fn convex_hull(points: &[Vec2]) -> Vec<Vec2> {
+    let mut points = points.to_vec();
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    let mut hull: Vec<Vec2> = Vec::with_capacity(points.len() * 2);
+    // Andrew'un monoton zinciri: önce alt, sonra üst zincir
+    for pass in 0..2 {
+        let start = hull.len();
+        let iter: Box<dyn Iterator<Item = &Vec2>> = if pass == 0 { Box::new(points.iter()) } else { Box::new(points.iter().rev()) };
+        for &p in iter {
+            while hull.len() >= start + 2 {
+                let a = hull[hull.len() - 2];
+                let b = hull[hull.len() - 1];
+                if (b - a).perp_dot(p - a) > f32::EPSILON {
+                    break;
+                }
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        hull.pop();
+    }
+    hull
+}
+
+/// Position and rotation of a body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform2 {
+    pub position: Vec2,
+    /// cos and sin of the angle
+    pub rotation: Vec2,
+}
+
+impl Transform2 {
+    pub fn new(position: Vec2, angle: f32) -> Self {
+        Self { position, rotation: Vec2::from_angle(angle) }
+    }
+
+    pub fn apply(&self, point: Vec2) -> Vec2 {
+        self.position + self.rotation.rotate(point)
+    }
+
+    pub fn rotate(&self, vector: Vec2) -> Vec2 {
+        self.rotation.rotate(vector)
+    }
+
+    pub fn inverse_apply(&self, point: Vec2) -> Vec2 {
+        let rotation = Vec2::new(self.rotation.x, -self.rotation.y);
+        rotation.rotate(point - self.position)
+    }
+}
+
+/// Point, segment or polygon with a radius, counter clockwise with outward edge normals
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Core {
+    pub vertices: Vec<Vec2>,
+    /// normal of the edge from vertex i to i + 1, none for a point
+    pub normals: Vec<Vec2>,
+    pub radius: f32,
+}
+
+impl Core {
+    pub fn new(vertices: Vec<Vec2>, radius: f32) -> Self {
+        let n = vertices.len();
+        let normals = if n < 2 {
+            Vec::new()
+        } else {
+            (0..n).map(|i| {
+                let edge = vertices[(i + 1) % n] - vertices[i];
+                Vec2::new(edge.y, -edge.x).normalize_or_zero()
+            }).collect()
+        };
+        Self { vertices, normals, radius }
+    }
+
+    pub fn transformed(&self, transform: &Transform2) -> Core {
+        Core {
+            vertices: self.vertices.iter().map(|&v| transform.apply(v)).collect(),
+            normals: self.normals.iter().map(|&n| transform.rotate(n)).collect(),
+            radius: self.radius,
+        }
+    }
+
+    pub fn aabb(&self) -> Aabb {
+        let mut min = Vec2::splat(f32::INFINITY);
+        let mut max = Vec2::splat(f32::NEG_INFINITY);
+        for &v in &self.vertices {
+            min = min.min(v);
+            max = max.max(v);
+        }
+        Aabb { min, max }.grow(self.radius)
+    }
+
+    /// edges as segments, a point is a zero length segment
+    pub fn segments(&self) -> Vec<(Vec2, Vec2)> {
+        match self.vertices.len() {
+            1 => vec![(self.vertices[0], self.vertices[0])],
+            2 => vec![(self.vertices[0], self.vertices[1])],
+            n => (0..n).map(|i| (self.vertices[i], self.vertices[(i + 1) % n])).collect(),
+        }
+    }
+
+    /// Fraction of `translation` where the segment from `origin` enters the rounded shape and
+    /// the normal there, `None` when it misses or starts inside
+    pub fn raycast(&self, origin: Vec2, translation: Vec2, max_fraction: f32) -> Option<(f32, Vec2)> {
+        if self.contains(origin) {
+            return None;
+        }
+        let mut best: Option<(f32, Vec2)> = None;
+        let mut keep = |fraction: f32, normal: Vec2| {
+            if (0.0..=max_fraction).contains(&fraction) && best.is_none_or(|b| fraction < b.0) {
+                best = Some((fraction, normal));
+            }
+        };
+        // yuvarlak köşeler
+        if self.radius > 0.0 {
+            let a = translation.length_squared();
+            for &center in &self.vertices {
+                let offset = origin - center;
+                let b = offset.dot(translation);
+                let c = offset.length_squared() - self.radius * self.radius;
+                let discriminant = b * b - a * c;
+                if a > f32::EPSILON && discriminant >= 0.0 {
+                    let fraction = (-b - discriminant.sqrt()) / a;
+                    keep(fraction, (offset + translation * fraction) / self.radius);
+                }
+            }
+        }
+        // yarıçap kadar dışarı itilmiş kenarlar
+        let n = self.vertices.len();
+        for (i, &normal) in self.normals.iter().enumerate() {
+            let approach = normal.dot(translation);
+            if approach >= 0.0 {
+                continue;
+            }
+            let (v1, v2) = (self.vertices[i], self.vertices[(i + 1) % n]);
+            let fraction = (normal.dot(v1) + self.radius - normal.dot(origin)) / approach;
+            let point = origin + translation * fraction - normal * self.radius;
+            let edge = v2 - v1;
+            let along = (point - v1).dot(edge) / edge.length_squared();
+            if (0.0..=1.0).contains(&along) {
+                keep(fraction, normal);
+            }
+        }
+        best
+    }
+
+    pub fn translated(&self, offset: Vec2) -> Core {
+        Core {
+            vertices: self.vertices.iter().map(|&v| v + offset).collect(),
+            normals: self.normals.clone(),
+            radius: self.radius,
+        }
+    }
+
+    /// true when `point` is inside the rounded shape
+    pub fn contains(&self, point: Vec2) -> bool {
+        if self.vertices.len() >= 3 && self.normals.iter().zip(&self.vertices).all(|(n, v)| n.dot(point - *v) <= 0.0) {
+            return true;
+        }
+        self.segments().iter().any(|&(a, b)| closest_on_segment(point, a, b).distance_squared(point) <= self.radius * self.radius)
+    }
+}
+
+pub(crate) fn closest_on_segment(point: Vec2, a: Vec2, b: Vec2) -> Vec2 {
+    let ab = b - a;
+    let length = ab.length_squared();
+    if length <= f32::EPSILON {
+        return a;
+    }
+    a + ab * ((point - a).dot(ab) / length).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hull_and_mass(){
+        let square = [Vec2::new(1.0, 1.0), Vec2::new(-1.0, -1.0), Vec2::new(0.0, 0.0), Vec2::new(1.0, -1.0), Vec2::new(-1.0, 1.0), Vec2::new(0.0, 1.0)];
+        let Shape::Polygon { vertices } = Shape::polygon(&square).unwrap() else { unreachable!() };
+        assert_eq!(vertices, vec![Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0)]);
+        assert!(Shape::polygon(&[Vec2::ZERO, Vec2::X, Vec2::X * 2.0]).is_err());
+
+        // aynı kare polygon ve kutu olarak aynı kütleyi veriyor
+        let polygon = Shape::Polygon { vertices }.mass(2.0);
+        let rect = Shape::rect(2.0, 2.0).mass(2.0);
+        assert!((polygon.mass - rect.mass).abs() < 1e-5 && (polygon.inertia - rect.inertia).abs() < 1e-5);
+        assert!(polygon.center.length() < 1e-6);
+
+        let core = Shape::capsule(1.0, 0.5).core();
+        assert_eq!(core.normals, vec![Vec2::X, Vec2::NEG_X]);
+        assert!(core.contains(Vec2::new(0.0, 1.4)) && !core.contains(Vec2::new(0.4, 1.4)));
+    }
+}
@@ -0,0 +1,689 @@
+// src/engine/physics/world.rs
+//
+// One step: colliders follow their bodies, an AABB tree finds the pairs whose boxes
+// overlap, the narrow phase updates their contacts (sending start/stop events), then
+// a sequential impulse solver (Box2D style, warm started from the last step) runs on the
+// velocities and the bodies move. Contacts are kept a little before touching, so fast
+// bodies slow down in time instead of sinking in.
+
+use std::collections::BTreeMap;
+
+use glam::Vec2;
+
+use crate::engine::physics::body::{cross_sv, BodyId, BodyType, Collider, ColliderId, RigidBody};
+use crate::engine::physics::collision::{collide, distance};
+use crate::engine::physics::shape::{Core, MassData, Shape, Transform2};
+use crate::engine::physics::{Collision, CollisionEvent};
+use crate::engine::spatial::{Aabb, AabbTree, ProxyId, SpatialIndex};
+
+/// contacts are made this far before touching
+const SPECULATIVE_DISTANCE: f32 = 0.02;
+/// overlap that is left alone, so resting contacts don't jitter
+const LINEAR_SLOP: f32 = 0.005;
+/// fraction of the overlap pushed out per step
+const BAUMGARTE: f32 = 0.2;
+/// slower hits don't bounce
+const RESTITUTION_THRESHOLD: f32 = 1.0;
+/// a shape cast stops this close to what it hits
+const CAST_TOLERANCE: f32 = 0.001;
+
+struct BodyEntry {
+    id: BodyId,
+    body: RigidBody,
+    colliders: Vec<ColliderId>,
+    /// mass has to be computed again before the next step
+    dirty: bool,
+}
+
+struct ColliderEntry {
+    id: ColliderId,
+    body: BodyId,
+    collider: Collider,
+    /// in world space, as of the end of the last step
+    core: Core,
+    proxy: ProxyId,
+}
+
+struct ContactPoint {
+    id: u32,
+    /// from the centers of mass
+    anchor_a: Vec2,
+    anchor_b: Vec2,
+    point: Vec2,
+    separation: f32,
+    normal_impulse: f32,
+    tangent_impulse: f32,
+    normal_mass: f32,
+    tangent_mass: f32,
+    /// normal velocity the solver aims for
+    target: f32,
+}
+
+struct Contact {
+    pair: Collision,
+    touching: bool,
+    friction: f32,
+    restitution: f32,
+    normal: Vec2,
+    points: Vec<ContactPoint>,
+    /// indices of the bodies during a step
+    index_a: usize,
+    index_b: usize,
+}
+
+/// What a raycast or a shape cast hit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub collider: ColliderId,
+    pub body: BodyId,
+    pub point: Vec2,
+    /// of the surface that was hit
+    pub normal: Vec2,
+    /// of the translation
+    pub fraction: f32,
+}
+
+pub struct PhysicsWorld {
+    pub gravity: Vec2,
+    pub velocity_iterations: u32,
+    /// sorted by id
+    bodies: Vec<BodyEntry>,
+    /// sorted by id
+    colliders: Vec<ColliderEntry>,
+    broad_phase: AabbTree<ColliderId>,
+    /// ordered so the solver gives the same result every run
+    contacts: BTreeMap<(ColliderId, ColliderId), Contact>,
+    events: Vec<CollisionEvent>,
+    next_id: u64,
+}
+
+impl Default for PhysicsWorld {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PhysicsWorld {
+    /// Earth gravity in meters
+    pub fn new() -> Self {
+        Self {
+            gravity: Vec2::new(0.0, -9.81),
+            velocity_iterations: 8,
+            bodies: Vec::new(),
+            colliders: Vec::new(),
+            broad_phase: AabbTree::default(),
+            contacts: BTreeMap::new(),
+            events: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn with_gravity(mut self, gravity: Vec2) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn body_index(&self, id: BodyId) -> Option<usize> {
+        self.bodies.binary_search_by_key(&id, |b| b.id).ok()
+    }
+
+    fn collider_index(&self, id: ColliderId) -> Option<usize> {
+        self.colliders.binary_search_by_key(&id, |c| c.id).ok()
+    }
+
+    pub fn add_body(&mut self, body: RigidBody) -> BodyId {
+        let id = BodyId(self.next_id());
+        self.bodies.push(BodyEntry { id, body, colliders: Vec::new(), dirty: true });
+        id
+    }
+
+    /// Removes the body with its colliders, their contacts stop
+    pub fn remove_body(&mut self, id: BodyId) -> Option<RigidBody> {
+        let index = self.body_index(id)?;
+        for collider in self.bodies[index].colliders.clone() {
+            self.remove_collider(collider);
+        }
+        Some(self.bodies.remove(index).body)
+    }
+
+    pub fn body(&self, id: BodyId) -> Option<&RigidBody> {
+        self.body_index(id).map(|i| &self.bodies[i].body)
+    }
+
+    /// Changes of the body type or `fixed_rotation` are applied from the next step
+    pub fn body_mut(&mut self, id: BodyId) -> Option<&mut RigidBody> {
+        let index = self.body_index(id)?;
+        let entry = &mut self.bodies[index];
+        entry.dirty = true;
+        Some(&mut entry.body)
+    }
+
+    pub fn bodies(&self) -> impl Iterator<Item = (BodyId, &RigidBody)> {
+        self.bodies.iter().map(|b| (b.id, &b.body))
+    }
+
+    pub fn body_count(&self) -> usize {
+        self.bodies.len()
+    }
+
+    /// `None` when there is no such body
+    pub fn add_collider(&mut self, body: BodyId, collider: Collider) -> Option<ColliderId> {
+        let index = self.body_index(body)?;
+        let id = ColliderId(self.next_id());
+        let core = world_core(&collider, &self.bodies[index].body);
+        let proxy = self.broad_phase.insert(core.aabb().grow(SPECULATIVE_DISTANCE), id);
+        self.colliders.push(ColliderEntry { id, body, collider, core, proxy });
+        let entry = &mut self.bodies[index];
+        entry.colliders.push(id);
+        entry.dirty = true;
+        Some(id)
+    }
+
+    pub fn remove_collider(&mut self, id: ColliderId) -> Option<Collider> {
+        let index = self.collider_index(id)?;
+        let entry = self.colliders.remove(index);
+        self.broad_phase.remove(entry.proxy);
+        if let Some(body) = self.body_index(entry.body) {
+            self.bodies[body].colliders.retain(|c| *c != id);
+            self.bodies[body].dirty = true;
+        }
+        let events = &mut self.events;
+        self.contacts.retain(|&(a, b), contact| {
+            let keep = a != id && b != id;
+            if !keep && contact.touching {
+                events.push(CollisionEvent::Stopped(contact.pair));
+            }
+            keep
+        });
+        Some(entry.collider)
+    }
+
+    pub fn collider(&self, id: ColliderId) -> Option<&Collider> {
+        self.collider_index(id).map(|i| &self.colliders[i].collider)
+    }
+
+    /// Shape and density changes move the center of mass from the next step
+    pub fn collider_mut(&mut self, id: ColliderId) -> Option<&mut Collider> {
+        let index = self.collider_index(id)?;
+        if let Some(body) = self.body_index(self.colliders[index].body) {
+            self.bodies[body].dirty = true;
+        }
+        Some(&mut self.colliders[index].collider)
+    }
+
+    /// body the collider is attached to
+    pub fn collider_body(&self, id: ColliderId) -> Option<BodyId> {
+        self.collider_index(id).map(|i| self.colliders[i].body)
+    }
+
+    pub fn colliders_of(&self, body: BodyId) -> &[ColliderId] {
+        self.body_index(body).map_or(&[], |i| &self.bodies[i].colliders)
+    }
+
+    /// true while the two colliders touch (or overlap, for sensors)
+    pub fn touching(&self, a: ColliderId, b: ColliderId) -> bool {
+        let key = if a < b { (a, b) } else { (b, a) };
+        self.contacts.get(&key).is_some_and(|c| c.touching)
+    }
+
+    /// Collision events since the last call, the engine sends them as `Message::Collision`
+    pub fn take_events(&mut self) -> Vec<CollisionEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Advances by `dt` seconds, call it with a fixed `dt`
+    pub fn step(&mut self, dt: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+        // elle taşınan ya da değişen cisimlerin collider'ları
+        let changed: Vec<usize> = (0..self.bodies.len()).filter(|&i| self.bodies[i].dirty).collect();
+        self.update_masses();
+        for index in changed {
+            self.update_colliders(index);
+        }
+        self.update_contacts();
+        self.integrate_velocities(dt);
+        self.prepare_contacts(dt);
+        for _ in 0..self.velocity_iterations {
+            self.solve_contacts();
+        }
+        for entry in &mut self.bodies {
+            let body = &mut entry.body;
+            if body.body_type == BodyType::Static {
+                continue;
+            }
+            let center = body.world_center() + body.linear_velocity * dt;
+            body.angle += body.angular_velocity * dt;
+            body.set_world_center(center);
+        }
+        for index in 0..self.bodies.len() {
+            if self.bodies[index].body.body_type != BodyType::Static {
+                self.update_colliders(index);
+            }
+        }
+    }
+
+    fn update_masses(&mut self) {
+        for entry in self.bodies.iter_mut().filter(|b| b.dirty) {
+            entry.dirty = false;
+            let parts: Vec<MassData> = entry.colliders.iter()
+                .filter_map(|id| self.colliders.binary_search_by_key(id, |c| c.id).ok())
+                .map(|i| self.colliders[i].collider.mass())
+                .collect();
+            let mass: f32 = parts.iter().map(|p| p.mass).sum();
+            let center = if mass > 0.0 { parts.iter().map(|p| p.center * p.mass).sum::<Vec2>() / mass } else { Vec2::ZERO };
+            // paralel eksen teoremi: her parçanın ataleti ortak merkeze taşınıyor
+            let inertia = parts.iter().map(|p| p.inertia + p.mass * p.center.distance_squared(center)).sum();
+            // kütle merkezi kayarken cisim yerinde kalıyor, hızı yeni merkezinki oluyor
+            let body = &mut entry.body;
+            let old_center = body.world_center();
+            body.set_mass_data(MassData { mass, center, inertia });
+            body.linear_velocity += cross_sv(body.angular_velocity, body.world_center() - old_center);
+        }
+    }
+
+    /// Moves the colliders of a body where the body is
+    fn update_colliders(&mut self, index: usize) {
+        let entry = &self.bodies[index];
+        for id in &entry.colliders {
+            let Ok(i) = self.colliders.binary_search_by_key(id, |c| c.id) else {
+                continue;
+            };
+            let collider = &mut self.colliders[i];
+            collider.core = world_core(&collider.collider, &entry.body);
+            self.broad_phase.update(collider.proxy, collider.core.aabb().grow(SPECULATIVE_DISTANCE));
+        }
+    }
+
+    fn body_type(&self, id: BodyId) -> BodyType {
+        self.body(id).map_or(BodyType::Static, |b| b.body_type)
+    }
+
+    /// Pairs of collider indices whose boxes overlap and that may collide, lower id first
+    fn find_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for (i, a) in self.colliders.iter().enumerate() {
+            let Some(aabb) = self.broad_phase.bounds(a.proxy) else {
+                continue;
+            };
+            self.broad_phase.query_aabb(aabb, |_, &other| {
+                // her çift bir kez, küçük id'den
+                if other > a.id
+                    && let Some(j) = self.collider_index(other)
+                    && self.should_collide(a, &self.colliders[j])
+                {
+                    pairs.push((i, j));
+                }
+                true
+            });
+        }
+        pairs
+    }
+
+    fn should_collide(&self, a: &ColliderEntry, b: &ColliderEntry) -> bool {
+        if a.body == b.body || !a.collider.can_collide(&b.collider) {
+            return false;
+        }
+        let (type_a, type_b) = (self.body_type(a.body), self.body_type(b.body));
+        if a.collider.sensor || b.collider.sensor {
+            // hareket etmeyen iki cisim arasında değişen bir şey yok
+            type_a != BodyType::Static || type_b != BodyType::Static
+        } else {
+            type_a == BodyType::Dynamic || type_b == BodyType::Dynamic
+        }
+    }
+
+    fn update_contacts(&mut self) {
+        let mut contacts = BTreeMap::new();
+        for (i, j) in self.find_pairs() {
+            let (a, b) = (&self.colliders[i], &self.colliders[j]);
+            let key = (a.id, b.id);
+            let old = self.contacts.remove(&key);
+            let was_touching = old.as_ref().is_some_and(|c| c.touching);
+            let pair = Collision {
+                collider_a: a.id,
+                collider_b: b.id,
+                body_a: a.body,
+                body_b: b.body,
+                sensor: a.collider.sensor || b.collider.sensor,
+            };
+            // sensörler sadece örtüşmeye bakıyor
+            let margin = if pair.sensor { 0.0 } else { SPECULATIVE_DISTANCE };
+            let manifold = collide(&a.core, &b.core, margin);
+            let touching = manifold.as_ref().is_some_and(|m| m.touching());
+            if touching != was_touching {
+                self.events.push(if touching { CollisionEvent::Started(pair) } else { CollisionEvent::Stopped(pair) });
+            }
+            let Some(manifold) = manifold else {
+                continue;
+            };
+            let points = if pair.sensor { Vec::new() } else {
+                manifold.points.iter().map(|p| {
+                    // aynı özelliklerden gelen nokta geçen adımın itkisiyle başlıyor
+                    let previous = old.as_ref().and_then(|c| c.points.iter().find(|q| q.id == p.id));
+                    ContactPoint {
+                        id: p.id,
+                        anchor_a: Vec2::ZERO,
+                        anchor_b: Vec2::ZERO,
+                        point: p.point,
+                        separation: p.separation,
+                        normal_impulse: previous.map_or(0.0, |q| q.normal_impulse),
+                        tangent_impulse: previous.map_or(0.0, |q| q.tangent_impulse),
+                        normal_mass: 0.0,
+                        tangent_mass: 0.0,
+                        target: 0.0,
+                    }
+                }).collect()
+            };
+            contacts.insert(key, Contact {
+                pair,
+                touching,
+                friction: (a.collider.friction * b.collider.friction).sqrt(),
+                restitution: a.collider.restitution.max(b.collider.restitution),
+                normal: manifold.normal,
+                points,
+                index_a: 0,
+                index_b: 0,
+            });
+        }
+        // kutuları artık örtüşmeyen çiftler
+        for contact in std::mem::replace(&mut self.contacts, contacts).into_values() {
+            if contact.touching {
+                self.events.push(CollisionEvent::Stopped(contact.pair));
+            }
+        }
+    }
+
+    fn integrate_velocities(&mut self, dt: f32) {
+        for entry in &mut self.bodies {
+            let body = &mut entry.body;
+            let (force, torque) = body.take_forces();
+            if !body.is_dynamic() {
+                continue;
+            }
+            body.linear_velocity += dt * (self.gravity * body.gravity_scale + force * body.inv_mass());
+            body.angular_velocity += dt * torque * body.inv_inertia();
+            body.linear_velocity *= 1.0 / (1.0 + dt * body.linear_damping);
+            body.angular_velocity *= 1.0 / (1.0 + dt * body.angular_damping);
+        }
+    }
+
+    fn prepare_contacts(&mut self, dt: f32) {
+        let bodies = &mut self.bodies;
+        for contact in self.contacts.values_mut().filter(|c| !c.points.is_empty()) {
+            let (Ok(index_a), Ok(index_b)) = (
+                bodies.binary_search_by_key(&contact.pair.body_a, |b| b.id),
+                bodies.binary_search_by_key(&contact.pair.body_b, |b| b.id),
+            ) else {
+                contact.points.clear();
+                continue;
+            };
+            (contact.index_a, contact.index_b) = (index_a, index_b);
+            let (a, b) = (&bodies[index_a].body, &bodies[index_b].body);
+            let (center_a, center_b) = (a.world_center(), b.world_center());
+            let (mass_a, mass_b) = (a.inv_mass(), b.inv_mass());
+            let (inertia_a, inertia_b) = (a.inv_inertia(), b.inv_inertia());
+            let normal = contact.normal;
+            let tangent = normal.perp();
+            for p in &mut contact.points {
+                p.anchor_a = p.point - center_a;
+                p.anchor_b = p.point - center_b;
+                let effective_mass = |axis: Vec2| {
+                    let ra = p.anchor_a.perp_dot(axis);
+                    let rb = p.anchor_b.perp_dot(axis);
+                    let k = mass_a + mass_b + inertia_a * ra * ra + inertia_b * rb * rb;
+                    if k > 0.0 { 1.0 / k } else { 0.0 }
+                };
+                p.normal_mass = effective_mass(normal);
+                p.tangent_mass = effective_mass(tangent);
+                let approach = normal.dot(b.velocity_at(p.point) - a.velocity_at(p.point));
+                p.target = if p.separation > 0.0 {
+                    // aradaki boşluğu bu adımda kapatacak kadar yaklaşabilir
+                    -p.separation / dt
+                } else {
+                    BAUMGARTE / dt * (-p.separation - LINEAR_SLOP).max(0.0)
+                };
+                // bu adımda çarpışacaksa sekiyor
+                if approach < -RESTITUTION_THRESHOLD && p.separation + approach * dt <= 0.0 {
+                    p.target = p.target.max(-contact.restitution * approach);
+                }
+            }
+            let impulses: Vec<(Vec2, Vec2, Vec2)> = contact.points.iter()
+                .map(|p| (normal * p.normal_impulse + tangent * p.tangent_impulse, p.anchor_a, p.anchor_b))
+                .collect();
+            for (impulse, anchor_a, anchor_b) in impulses {
+                apply_impulse(bodies, index_a, index_b, impulse, anchor_a, anchor_b);
+            }
+        }
+    }
+
+    fn solve_contacts(&mut self) {
+        let bodies = &mut self.bodies;
+        for contact in self.contacts.values_mut().filter(|c| !c.points.is_empty()) {
+            let (index_a, index_b) = (contact.index_a, contact.index_b);
+            let normal = contact.normal;
+            let tangent = normal.perp();
+            // önce sürtünme, sınırı normal itkiden geliyor
+            for p in &mut contact.points {
+                let dv = relative_velocity(bodies, index_a, index_b, p.anchor_a, p.anchor_b);
+                let limit = contact.friction * p.normal_impulse;
+                let total = (p.tangent_impulse - p.tangent_mass * tangent.dot(dv)).clamp(-limit, limit);
+                let impulse = total - p.tangent_impulse;
+                p.tangent_impulse = total;
+                apply_impulse(bodies, index_a, index_b, tangent * impulse, p.anchor_a, p.anchor_b);
+            }
+            for p in &mut contact.points {
+                let dv = relative_velocity(bodies, index_a, index_b, p.anchor_a, p.anchor_b);
+                let total = (p.normal_impulse - p.normal_mass * (normal.dot(dv) - p.target)).max(0.0);
+                let impulse = total - p.normal_impulse;
+                p.normal_impulse = total;
+                apply_impulse(bodies, index_a, index_b, normal * impulse, p.anchor_a, p.anchor_b);
+            }
+        }
+    }
+
+    /// First collider the segment from `origin` to `origin + translation` enters, sensors and
+    /// colliders outside the `mask` layers are skipped
+    pub fn raycast(&self, origin: Vec2, translation: Vec2, mask: u32) -> Option<RayHit> {
+        // ağaç en yakını seçiyor, normali de en yakınınki kalmalı
+        let mut closest = (f32::INFINITY, Vec2::ZERO);
+        let (proxy, fraction) = self.broad_phase.raycast(origin, translation, |_, &id| {
+            let entry = &self.colliders[self.collider_index(id)?];
+            if !self.accepts(entry, mask) {
+                return None;
+            }
+            let (fraction, normal) = entry.core.raycast(origin, translation, 1.0)?;
+            if fraction <= closest.0 {
+                closest = (fraction, normal);
+            }
+            Some(fraction)
+        })?;
+        Some(self.hit(proxy, origin + translation * fraction, closest.1, fraction))
+    }
+
+    /// Moves `shape` placed at `transform` by `translation`, the first collider it touches
+    pub fn shape_cast(&self, shape: &Shape, transform: Transform2, translation: Vec2, mask: u32) -> Option<RayHit> {
+        let cast = shape.core().transformed(&transform);
+        let bounds = cast.aabb();
+        let mut closest = (f32::INFINITY, Vec2::ZERO, Vec2::ZERO);
+        let (proxy, fraction) = self.broad_phase.cast_aabb(bounds, translation, |_, &id| {
+            let entry = &self.colliders[self.collider_index(id)?];
+            if !self.accepts(entry, mask) {
+                return None;
+            }
+            let (fraction, point, normal) = time_of_impact(&cast, &entry.core, translation)?;
+            if fraction <= closest.0 {
+                closest = (fraction, point, normal);
+            }
+            Some(fraction)
+        })?;
+        Some(self.hit(proxy, closest.1, closest.2, fraction))
+    }
+
+    /// Colliders containing `point`
+    pub fn query_point(&self, point: Vec2, mask: u32) -> Vec<ColliderId> {
+        let mut found = Vec::new();
+        self.broad_phase.query_point(point, |_, &id| {
+            if let Some(entry) = self.collider_index(id).map(|i| &self.colliders[i])
+                && entry.collider.layer & mask != 0
+                && entry.core.contains(point)
+            {
+                found.push(id);
+            }
+            true
+        });
+        found
+    }
+
+    /// Colliders overlapping `aabb`
+    pub fn query_aabb(&self, aabb: Aabb, mask: u32) -> Vec<ColliderId> {
+        let region = Shape::Box { half_extents: aabb.size() * 0.5 }.core().translated(aabb.center());
+        let mut found = Vec::new();
+        self.broad_phase.query_aabb(aabb, |_, &id| {
+            if let Some(entry) = self.collider_index(id).map(|i| &self.colliders[i])
+                && entry.collider.layer & mask != 0
+                && collide(&region, &entry.core, 0.0).is_some_and(|m| m.touching())
+            {
+                found.push(id);
+            }
+            true
+        });
+        found
+    }
+
+    fn accepts(&self, entry: &ColliderEntry, mask: u32) -> bool {
+        !entry.collider.sensor && entry.collider.layer & mask != 0
+    }
+
+    fn hit(&self, proxy: ProxyId, point: Vec2, normal: Vec2, fraction: f32) -> RayHit {
+        let collider = *self.broad_phase.get(proxy).unwrap();
+        let body = self.collider_body(collider).unwrap();
+        RayHit { collider, body, point, normal, fraction }
+    }
+}
+
+/// collider in world space when its body is at `body`
+fn world_core(collider: &Collider, body: &RigidBody) -> Core {
+    collider.shape.core().transformed(&collider.local_transform()).transformed(&body.transform())
+}
+
+/// Fraction of `translation` where `moving` first touches `target`, with the point and the
+/// normal of `target` there. Steps by the distance over the approach speed, which never passes
+/// the hit for convex shapes.
+fn time_of_impact(moving: &Core, target: &Core, translation: Vec2) -> Option<(f32, Vec2, Vec2)> {
+    if let Some(manifold) = collide(moving, target, 0.0).filter(|m| m.touching()) {
+        return Some((0.0, manifold.points[0].point, -manifold.normal));
+    }
+    let mut fraction = 0.0;
+    for _ in 0..20 {
+        let (separation, normal, point) = distance(&moving.translated(translation * fraction), target);
+        if separation < CAST_TOLERANCE {
+            return Some((fraction, point, -normal));
+        }
+        let approach = translation.dot(normal);
+        if approach <= 0.0 {
+            return None;
+        }
+        fraction += separation / approach;
+        if fraction > 1.0 {
+            return None;
+        }
+    }
+    None
+}
+
+/// velocity of B relative to A at the contact point
+fn relative_velocity(bodies: &[BodyEntry], a: usize, b: usize, anchor_a: Vec2, anchor_b: Vec2) -> Vec2 {
+    let (a, b) = (&bodies[a].body, &bodies[b].body);
+    b.linear_velocity + cross_sv(b.angular_velocity, anchor_b) - a.linear_velocity - cross_sv(a.angular_velocity, anchor_a)
+}
+
+/// pushes B by `impulse` and A the other way
+fn apply_impulse(bodies: &mut [BodyEntry], a: usize, b: usize, impulse: Vec2, anchor_a: Vec2, anchor_b: Vec2) {
+    let body = &mut bodies[a].body;
+    body.linear_velocity -= impulse * body.inv_mass();
+    body.angular_velocity -= body.inv_inertia() * anchor_a.perp_dot(impulse);
+    let body = &mut bodies[b].body;
+    body.linear_velocity += impulse * body.inv_mass();
+    body.angular_velocity += body.inv_inertia() * anchor_b.perp_dot(impulse);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::physics::shape::Shape;
+
+    #[test]
+    fn falling_box(){
+        let mut world = PhysicsWorld::new();
+        let ground = world.add_body(RigidBody::fixed());
+        let floor = world.add_collider(ground, Collider::new(Shape::rect(20.0, 1.0))).unwrap();
+        let zone = world.add_collider(ground, Collider::new(Shape::rect(1.0, 1.0)).with_offset(Vec2::new(0.0, 3.0), 0.0).with_sensor(true)).unwrap();
+
+        let boxed = world.add_body(RigidBody::dynamic().with_position(Vec2::new(0.0, 5.0)).with_angle(0.1));
+        let crate_ = world.add_collider(boxed, Collider::new(Shape::rect(1.0, 1.0))).unwrap();
+        // başka katmanda: zeminin içinden düşüp gidiyor
+        let ghost = world.add_body(RigidBody::dynamic().with_position(Vec2::new(3.0, 5.0)));
+        world.add_collider(ghost, Collider::new(Shape::circle(0.5)).with_layers(2, 2)).unwrap();
+
+        let mut events = Vec::new();
+        for _ in 0..180 {
+            world.step(1.0 / 60.0);
+            events.extend(world.take_events());
+        }
+        let body = world.body(boxed).unwrap();
+        // yan düşen kutu düzleşip zeminde duruyor
+        assert!((body.position.y - 1.0).abs() < 0.02, "{}", body.position);
+        assert!(body.angle.abs() < 0.01 && body.linear_velocity.length() < 0.01);
+        assert!(world.body(ghost).unwrap().position.y < -5.0);
+        assert!(world.touching(floor, crate_));
+
+        let started: Vec<_> = events.iter().filter_map(|e| match e {
+            CollisionEvent::Started(c) => Some(c.collider_a),
+            _ => None,
+        }).collect();
+        assert_eq!(started, vec![zone, floor]);
+        assert!(events.contains(&CollisionEvent::Stopped(Collision { collider_a: zone, collider_b: crate_, body_a: ground, body_b: boxed, sensor: true })));
+
+        world.remove_body(boxed);
+        assert!(matches!(world.take_events()[..], [CollisionEvent::Stopped(Collision { sensor: false, .. })]));
+    }
+
+    #[test]
+    fn queries(){
+        let mut world = PhysicsWorld::new();
+        let ground = world.add_body(RigidBody::fixed());
+        let floor = world.add_collider(ground, Collider::new(Shape::rect(20.0, 1.0))).unwrap();
+        let ball = world.add_body(RigidBody::kinematic().with_position(Vec2::new(2.0, 3.0)));
+        let round = world.add_collider(ball, Collider::new(Shape::circle(1.0)).with_layers(2, u32::MAX)).unwrap();
+        let zone = world.add_collider(ground, Collider::new(Shape::rect(1.0, 1.0)).with_offset(Vec2::new(-2.0, 3.0), 0.0).with_sensor(true)).unwrap();
+
+        let hit = world.raycast(Vec2::new(2.0, 10.0), Vec2::new(0.0, -20.0), u32::MAX).unwrap();
+        assert_eq!((hit.collider, hit.body), (round, ball));
+        assert!(hit.point.abs_diff_eq(Vec2::new(2.0, 4.0), 1e-4) && hit.normal.abs_diff_eq(Vec2::Y, 1e-4));
+        // katman 1 sadece zemin, sensörler atlanıyor
+        let hit = world.raycast(Vec2::new(2.0, 10.0), Vec2::new(0.0, -20.0), 1).unwrap();
+        assert_eq!(hit.collider, floor);
+        assert!((hit.fraction - 0.475).abs() < 1e-5);
+        assert!(world.raycast(Vec2::new(-2.0, 10.0), Vec2::new(0.0, -8.0), u32::MAX).is_none());
+
+        // kapsül sağa kayıp daireye çarpıyor
+        let cast = world.shape_cast(&Shape::capsule(0.5, 0.25), Transform2::new(Vec2::new(-5.0, 3.0), 0.0), Vec2::new(10.0, 0.0), 2).unwrap();
+        assert_eq!(cast.collider, round);
+        assert!((cast.fraction - 0.575).abs() < 1e-3, "{}", cast.fraction);
+        assert!(cast.normal.abs_diff_eq(Vec2::NEG_X, 1e-3));
+
+        assert_eq!(world.query_point(Vec2::new(2.5, 3.5), u32::MAX), vec![round]);
+        let region = Aabb::new(Vec2::new(-3.0, 0.0), Vec2::new(1.2, 3.0));
+        let mut found = world.query_aabb(region, u32::MAX);
+        found.sort();
+        assert_eq!(found, vec![floor, round, zone]);
+        assert_eq!(world.query_aabb(region, 2), vec![round]);
+    }
+}
@@ -0,0 +1,234 @@
+// src/engine/renderer/adapter.rs
+//
+// Picking the GPU. `GraphicsConfig` says which backends and adapter to use, the standard
+// WGPU_* environment variables override it. Failures are returned as `GraphicsError`
+// so the engine can fall back to a software adapter or run headless.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+#[cfg(target_family = "wasm")]
+use ts_rs::TS;
+use wgpu::{Adapter, Instance};
+
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Backend {
+    Vulkan,
+    Metal,
+    Dx12,
+    Gl,
+    BrowserWebGpu,
+}
+
+impl Backend {
+    pub fn to_wgpu(self) -> wgpu::Backends {
+        match self {
+            Backend::Vulkan => wgpu::Backends::VULKAN,
+            Backend::Metal => wgpu::Backends::METAL,
+            Backend::Dx12 => wgpu::Backends::DX12,
+            Backend::Gl => wgpu::Backends::GL,
+            Backend::BrowserWebGpu => wgpu::Backends::BROWSER_WEBGPU,
+        }
+    }
+}
+
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PowerPreference {
+    #[default]
+    Default,
+    LowPower,
+    HighPerformance,
+}
+
+impl PowerPreference {
+    pub fn to_wgpu(self) -> wgpu::PowerPreference {
+        match self {
+            PowerPreference::Default => wgpu::PowerPreference::None,
+            PowerPreference::LowPower => wgpu::PowerPreference::LowPower,
+            PowerPreference::HighPerformance => wgpu::PowerPreference::HighPerformance,
+        }
+    }
+}
+
+/// How `GraphicsContext::new` picks the adapter
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphicsConfig {
+    /// empty uses the primary backends of the platform
+    pub backends: Vec<Backend>,
+    pub power_preference: PowerPreference,
+    /// first adapter whose name contains this, case insensitive. Native only.
+    pub adapter_name: Option<String>,
+    /// skips the hardware adapters
+    pub force_fallback_adapter: bool,
+    /// tries a software adapter when no hardware one works
+    pub allow_fallback_adapter: bool,
+    /// keeps the engine running without rendering when no adapter works at all
+    pub allow_headless: bool,
+}
+
+impl Default for GraphicsConfig {
+    fn default() -> Self {
+        Self {
+            backends: Vec::new(),
+            power_preference: PowerPreference::Default,
+            adapter_name: None,
+            force_fallback_adapter: false,
+            allow_fallback_adapter: true,
+            allow_headless: true,
+        }
+    }
+}
+
+impl GraphicsConfig {
+    /// Applies `WGPU_BACKEND` (comma list), `WGPU_POWER_PREF` (low/high/none),
+    /// `WGPU_ADAPTER_NAME` and `WGPU_FORCE_FALLBACK_ADAPTER` (1/0) when they are set
+    pub fn with_env(mut self) -> Self {
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+            if let Some(backends) = var("WGPU_BACKEND") {
+                self.backends = parse_backends(&backends);
+            }
+            if let Some(power) = var("WGPU_POWER_PREF") {
+                self.power_preference = match power.to_lowercase().as_str() {
+                    "low" => PowerPreference::LowPower,
+                    "high" => PowerPreference::HighPerformance,
+                    _ => PowerPreference::Default,
+                };
+            }
+            if let Some(name) = var("WGPU_ADAPTER_NAME") {
+                self.adapter_name = Some(name);
+            }
+            if let Some(force) = var("WGPU_FORCE_FALLBACK_ADAPTER") {
+                self.force_fallback_adapter = force == "1" || force.eq_ignore_ascii_case("true");
+            }
+        }
+        self
+    }
+
+    pub fn wgpu_backends(&self) -> wgpu::Backends {
+        if self.backends.is_empty() {
+            return wgpu::Backends::PRIMARY;
+        }
+        self.backends.iter().fold(wgpu::Backends::empty(), |all, b| all | b.to_wgpu())
+    }
+}
+
+/// `vulkan,gl` style list, unknown names are skipped
+pub fn parse_backends(list: &str) -> Vec<Backend> {
+    list.split(',')
+        .filter_map(|name| match name.trim().to_lowercase().as_str() {
+            "vulkan" | "vk" => Some(Backend::Vulkan),
+            "metal" | "mtl" => Some(Backend::Metal),
+            "dx12" | "d3d12" => Some(Backend::Dx12),
+            "gl" | "gles" | "opengl" | "webgl" => Some(Backend::Gl),
+            "webgpu" | "browser_webgpu" => Some(Backend::BrowserWebGpu),
+            _ => None,
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+pub enum GraphicsError {
+    /// no adapter of the configured backends could be used
+    NoAdapter(wgpu::RequestAdapterError),
+    /// `adapter_name` matched none of these adapters
+    AdapterNotFound { name: String, available: Vec<String> },
+    RequestDevice(wgpu::RequestDeviceError),
+    CreateSurface(String),
+    /// the adapter can't present to the window
+    SurfaceUnsupported(String),
+}
+
+impl fmt::Display for GraphicsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphicsError::NoAdapter(e) => write!(f, "no usable graphics adapter: {}", e),
+            GraphicsError::AdapterNotFound { name, available } => {
+                write!(f, "no adapter named \"{}\", available: [{}]", name, available.join(", "))
+            }
+            GraphicsError::RequestDevice(e) => write!(f, "device creation failed: {}", e),
+            GraphicsError::CreateSurface(msg) => write!(f, "surface creation failed: {}", msg),
+            GraphicsError::SurfaceUnsupported(adapter) => write!(f, "{} can't present to the window", adapter),
+        }
+    }
+}
+
+impl std::error::Error for GraphicsError {}
+
+impl From<wgpu::RequestAdapterError> for GraphicsError {
+    fn from(e: wgpu::RequestAdapterError) -> Self {
+        GraphicsError::NoAdapter(e)
+    }
+}
+
+impl From<wgpu::RequestDeviceError> for GraphicsError {
+    fn from(e: wgpu::RequestDeviceError) -> Self {
+        GraphicsError::RequestDevice(e)
+    }
+}
+
+impl From<wgpu::CreateSurfaceError> for GraphicsError {
+    fn from(e: wgpu::CreateSurfaceError) -> Self {
+        GraphicsError::CreateSurface(e.to_string())
+    }
+}
+
+/// Every adapter of `backends`, always empty on the web where they can't be enumerated
+pub fn list_adapters(backends: wgpu::Backends) -> Vec<wgpu::AdapterInfo> {
+    #[cfg(not(target_family = "wasm"))]
+    {
+        let instance = Instance::new(&wgpu::InstanceDescriptor { backends, ..Default::default() });
+        instance.enumerate_adapters(backends).iter().map(Adapter::get_info).collect()
+    }
+    #[cfg(target_family = "wasm")]
+    {
+        let _ = backends;
+        Vec::new()
+    }
+}
+
+/// one line description for logs
+pub fn describe_adapter(info: &wgpu::AdapterInfo) -> String {
+    format!("{} ({:?}, {:?})", info.name, info.backend, info.device_type)
+}
+
+/// The adapter `config` asks for, a software one if allowed and nothing else works
+pub(crate) async fn select_adapter(instance: &Instance, config: &GraphicsConfig) -> Result<Adapter, GraphicsError> {
+    #[cfg(not(target_family = "wasm"))]
+    if let Some(name) = &config.adapter_name {
+        let adapters = instance.enumerate_adapters(config.wgpu_backends());
+        let wanted = name.to_lowercase();
+        let available: Vec<String> = adapters.iter().map(|a| a.get_info().name).collect();
+        return adapters.into_iter()
+            .find(|a| a.get_info().name.to_lowercase().contains(&wanted))
+            .ok_or_else(|| GraphicsError::AdapterNotFound { name: name.clone(), available });
+    }
+
+    let request = |force_fallback_adapter| instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: config.power_preference.to_wgpu(),
+        compatible_surface: None,
+        force_fallback_adapter,
+    });
+    match request(config.force_fallback_adapter).await {
+        Ok(adapter) => Ok(adapter),
+        Err(_) if config.allow_fallback_adapter && !config.force_fallback_adapter => Ok(request(true).await?),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_list(){
+        assert_eq!(parse_backends("vulkan, GL,unknown"), vec![Backend::Vulkan, Backend::Gl]);
+        let config = GraphicsConfig { backends: parse_backends("metal,dx12"), ..Default::default() };
+        assert_eq!(config.wgpu_backends(), wgpu::Backends::METAL | wgpu::Backends::DX12);
+        assert_eq!(GraphicsConfig::default().wgpu_backends(), wgpu::Backends::PRIMARY);
+    }
+}
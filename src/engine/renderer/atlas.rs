@@ -0,0 +1,463 @@
+// src/engine/renderer/atlas.rs
+//
+// Texture atlases. `AtlasBuilder` packs many images into one (skyline bottom-left) with
+// padding between them and extruded edges against filtering bleed; the named regions are
+// described by a serde `AtlasManifest`. Sprite sheets exported by Aseprite or TexturePacker
+// are read into the same manifest (see sprite_sheet.rs), `TextureAtlas` turns regions into sprites.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use glam::{UVec2, Vec2};
+use serde::{Deserialize, Serialize};
+use wgpu::{Device, Queue};
+
+use crate::engine::renderer::gltf_loader::ImageData;
+use crate::engine::renderer::sprite::{Rect, Sprite};
+use crate::engine::renderer::sprite_sheet;
+use crate::engine::renderer::texture::Texture;
+
+#[derive(Debug)]
+pub enum AtlasError {
+    Io(std::io::Error),
+    Image(image::ImageError),
+    Json(serde_json::Error),
+    /// the images don't fit into `max_size` x `max_size`
+    TooLarge { max_size: u32 },
+    Invalid(String),
+}
+
+impl fmt::Display for AtlasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AtlasError::Io(e) => write!(f, "io error: {}", e),
+            AtlasError::Image(e) => write!(f, "image error: {}", e),
+            AtlasError::Json(e) => write!(f, "json error: {}", e),
+            AtlasError::TooLarge { max_size } => write!(f, "images don't fit into a {0}x{0} atlas", max_size),
+            AtlasError::Invalid(msg) => write!(f, "invalid sprite sheet: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AtlasError {}
+
+impl From<std::io::Error> for AtlasError {
+    fn from(e: std::io::Error) -> Self {
+        AtlasError::Io(e)
+    }
+}
+
+impl From<image::ImageError> for AtlasError {
+    fn from(e: image::ImageError) -> Self {
+        AtlasError::Image(e)
+    }
+}
+
+impl From<serde_json::Error> for AtlasError {
+    fn from(e: serde_json::Error) -> Self {
+        AtlasError::Json(e)
+    }
+}
+
+/// Transparent border removed from a region
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trim {
+    /// where the region starts inside the original image
+    pub x: u32,
+    pub y: u32,
+    pub source_width: u32,
+    pub source_height: u32,
+}
+
+/// Pixels of the atlas image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AtlasRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim: Option<Trim>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AnimationDirection {
+    #[default]
+    Forward,
+    Reverse,
+    PingPong,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpriteFrame {
+    pub region: String,
+    /// seconds
+    pub duration: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SpriteAnimation {
+    pub frames: Vec<SpriteFrame>,
+    #[serde(default)]
+    pub direction: AnimationDirection,
+}
+
+impl SpriteAnimation {
+    /// frame indices of one loop, ping pong doesn't repeat the end frames
+    fn sequence(&self) -> Vec<usize> {
+        let n = self.frames.len();
+        match self.direction {
+            AnimationDirection::Forward => (0..n).collect(),
+            AnimationDirection::Reverse => (0..n).rev().collect(),
+            AnimationDirection::PingPong => (0..n).chain((1..n.saturating_sub(1)).rev()).collect(),
+        }
+    }
+
+    /// seconds of one loop
+    pub fn duration(&self) -> f32 {
+        self.sequence().iter().map(|&i| self.frames[i].duration).sum()
+    }
+
+    /// Frame shown `time` seconds after the start, looping
+    pub fn frame_at(&self, time: f32) -> Option<&SpriteFrame> {
+        let sequence = self.sequence();
+        let total = self.duration();
+        if total <= 0.0 {
+            return sequence.first().map(|&i| &self.frames[i]);
+        }
+        let mut t = time.rem_euclid(total);
+        for &i in &sequence {
+            if t < self.frames[i].duration {
+                return Some(&self.frames[i]);
+            }
+            t -= self.frames[i].duration;
+        }
+        sequence.last().map(|&i| &self.frames[i])
+    }
+}
+
+/// Named regions of an atlas image, written next to it as JSON
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct AtlasManifest {
+    /// the atlas image, relative to the manifest
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+    pub regions: BTreeMap<String, AtlasRegion>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub animations: BTreeMap<String, SpriteAnimation>,
+}
+
+impl AtlasManifest {
+    /// Reads a manifest written by `AtlasBuilder`, or an Aseprite / TexturePacker
+    /// JSON sprite sheet (hash or array)
+    pub fn from_json(text: &str) -> Result<Self, AtlasError> {
+        // Value'ya çevrilmiyor, sprite sheet'lerde anahtar sırası önemli
+        #[derive(Deserialize)]
+        struct Probe {
+            regions: Option<serde::de::IgnoredAny>,
+        }
+        if serde_json::from_str::<Probe>(text)?.regions.is_some() {
+            Ok(serde_json::from_str(text)?)
+        } else {
+            sprite_sheet::import(text)
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("manifest is always serializable")
+    }
+
+    /// uv rectangle of region `name`
+    pub fn uv(&self, name: &str) -> Option<Rect> {
+        let region = self.regions.get(name)?;
+        let size = Vec2::new(self.width as f32, self.height as f32);
+        let min = Vec2::new(region.x as f32, region.y as f32);
+        Some(Rect::new(min / size, (min + Vec2::new(region.width as f32, region.height as f32)) / size))
+    }
+}
+
+/// Skyline bottom-left packer, nodes are (x, y, width) segments of the skyline
+struct Skyline {
+    width: u32,
+    height: u32,
+    nodes: Vec<(u32, u32, u32)>,
+}
+
+impl Skyline {
+    fn new(width: u32, height: u32) -> Self {
+        Self { width, height, nodes: vec![(0, 0, width)] }
+    }
+
+    /// y of a `w` x `h` rectangle placed at node `i`
+    fn fit(&self, i: usize, w: u32, h: u32) -> Option<u32> {
+        let x = self.nodes[i].0;
+        if x + w > self.width {
+            return None;
+        }
+        let mut y = 0;
+        let mut left = w as i64;
+        let mut j = i;
+        while left > 0 {
+            y = y.max(self.nodes[j].1);
+            if y + h > self.height {
+                return None;
+            }
+            left -= self.nodes[j].2 as i64;
+            j += 1;
+        }
+        Some(y)
+    }
+
+    fn insert(&mut self, w: u32, h: u32) -> Option<(u32, u32)> {
+        // en alçak tepe, eşitlikte en dar düğüm
+        let (i, y) = (0..self.nodes.len())
+            .filter_map(|i| self.fit(i, w, h).map(|y| (i, y)))
+            .min_by_key(|&(i, y)| (y + h, self.nodes[i].2))?;
+        let x = self.nodes[i].0;
+        self.nodes.insert(i, (x, y + h, w));
+
+        let mut j = i + 1;
+        while j < self.nodes.len() {
+            let end = self.nodes[j - 1].0 + self.nodes[j - 1].2;
+            let node = &mut self.nodes[j];
+            if node.0 >= end {
+                break;
+            }
+            let overlap = end - node.0;
+            if node.2 <= overlap {
+                self.nodes.remove(j);
+            } else {
+                node.0 += overlap;
+                node.2 -= overlap;
+                break;
+            }
+        }
+        self.nodes.dedup_by(|b, a| {
+            let merge = a.1 == b.1;
+            if merge {
+                a.2 += b.2;
+            }
+            merge
+        });
+        Some((x, y))
+    }
+}
+
+/// Packs rectangles into the smallest power of two size that fits, positions in input order
+fn pack(sizes: &[(u32, u32)], max_size: u32) -> Result<(UVec2, Vec<UVec2>), AtlasError> {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse((sizes[i].1, sizes[i].0)));
+    let area: u64 = sizes.iter().map(|&(w, h)| w as u64 * h as u64).sum();
+    let widest = sizes.iter().map(|&(w, _)| w).max().unwrap_or(1);
+    let tallest = sizes.iter().map(|&(_, h)| h).max().unwrap_or(1);
+    let mut width = ((area as f64).sqrt().ceil() as u32).max(widest).max(1).next_power_of_two();
+    let mut height = (area.div_ceil(width as u64) as u32).max(tallest).max(1).next_power_of_two();
+    while width <= max_size && height <= max_size {
+        let mut skyline = Skyline::new(width, height);
+        let mut positions = vec![UVec2::ZERO; sizes.len()];
+        if order.iter().all(|&i| skyline.insert(sizes[i].0, sizes[i].1).map(|(x, y)| positions[i] = UVec2::new(x, y)).is_some()) {
+            return Ok((UVec2::new(width, height), positions));
+        }
+        if width <= height {
+            width *= 2;
+        } else {
+            height *= 2;
+        }
+    }
+    Err(AtlasError::TooLarge { max_size })
+}
+
+/// Packs images into one atlas, at build time (see `src/bin/atlas_builder.rs`) or at runtime
+pub struct AtlasBuilder {
+    images: Vec<(String, ImageData)>,
+    padding: u32,
+    extrude: u32,
+    max_size: u32,
+}
+
+impl Default for AtlasBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AtlasBuilder {
+    pub fn new() -> Self {
+        Self { images: Vec::new(), padding: 2, extrude: 1, max_size: 4096 }
+    }
+
+    /// empty pixels between regions, 2 by default
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// edge pixels repeated around every region, 1 by default
+    pub fn with_extrusion(mut self, extrude: u32) -> Self {
+        self.extrude = extrude;
+        self
+    }
+
+    pub fn with_max_size(mut self, max_size: u32) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Adds (or replaces) the region `name`
+    pub fn add<S: Into<String>>(&mut self, name: S, image: ImageData) {
+        let name = name.into();
+        self.images.retain(|(n, _)| *n != name);
+        self.images.push((name, image));
+    }
+
+    /// Adds the png / jpeg files below `dir`, named by their path without the extension
+    pub fn add_dir<P: AsRef<Path>>(&mut self, dir: P) -> Result<usize, AtlasError> {
+        let dir = dir.as_ref();
+        let mut count = 0;
+        let mut dirs = vec![dir.to_path_buf()];
+        while let Some(current) = dirs.pop() {
+            for entry in std::fs::read_dir(&current)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                let extension = path.extension().and_then(|e| e.to_str()).map(str::to_lowercase);
+                if !matches!(extension.as_deref(), Some("png" | "jpg" | "jpeg")) {
+                    continue;
+                }
+                let name = path.strip_prefix(dir).unwrap().with_extension("");
+                let name = name.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+                self.add(name, ImageData::load(&path)?);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    pub fn len(&self) -> usize {
+        self.images.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.images.is_empty()
+    }
+
+    /// The atlas image and its manifest, `image` names the file the caller saves it as
+    pub fn build(&self, image: &str) -> Result<(ImageData, AtlasManifest), AtlasError> {
+        let border = self.extrude * 2 + self.padding;
+        let sizes: Vec<(u32, u32)> = self.images.iter().map(|(_, i)| (i.width + border, i.height + border)).collect();
+        let (size, positions) = pack(&sizes, self.max_size)?;
+        let (width, height) = (size.x, size.y);
+
+        let mut atlas = ImageData { width, height, rgba: vec![0; (width * height * 4) as usize] };
+        let mut regions = BTreeMap::new();
+        for ((name, source), position) in self.images.iter().zip(positions) {
+            let (x, y) = (position.x + self.extrude, position.y + self.extrude);
+            blit_extruded(&mut atlas, source, x, y, self.extrude);
+            regions.insert(name.clone(), AtlasRegion { x, y, width: source.width, height: source.height, trim: None });
+        }
+        Ok((atlas, AtlasManifest { image: image.to_string(), width, height, regions, animations: BTreeMap::new() }))
+    }
+}
+
+/// Copies `source` to (x, y) and repeats its edge pixels `extrude` times around it
+fn blit_extruded(atlas: &mut ImageData, source: &ImageData, x: u32, y: u32, extrude: u32) {
+    if source.width == 0 || source.height == 0 {
+        return;
+    }
+    let e = extrude as i64;
+    for dy in -e..source.height as i64 + e {
+        for dx in -e..source.width as i64 + e {
+            let sx = dx.clamp(0, source.width as i64 - 1) as usize;
+            let sy = dy.clamp(0, source.height as i64 - 1) as usize;
+            let src = (sy * source.width as usize + sx) * 4;
+            let dst = ((y as i64 + dy) as usize * atlas.width as usize + (x as i64 + dx) as usize) * 4;
+            atlas.rgba[dst..dst + 4].copy_from_slice(&source.rgba[src..src + 4]);
+        }
+    }
+}
+
+/// An atlas uploaded for the sprite renderer
+pub struct TextureAtlas {
+    pub texture: Arc<Texture>,
+    pub manifest: AtlasManifest,
+}
+
+impl TextureAtlas {
+    pub fn new(device: &Device, queue: &Queue, image: &ImageData, manifest: AtlasManifest) -> Self {
+        let texture = Arc::new(Texture::from_image(device, queue, image, true, "Texture Atlas"));
+        Self { texture, manifest }
+    }
+
+    pub fn uv(&self, name: &str) -> Option<Rect> {
+        self.manifest.uv(name)
+    }
+
+    /// Sprite of region `name` with its top left corner at `position`. Trimmed regions are
+    /// moved so they line up with the untrimmed image.
+    pub fn sprite(&self, name: &str, position: Vec2) -> Option<Sprite> {
+        let region = self.manifest.regions.get(name)?;
+        let offset = region.trim.map_or(Vec2::ZERO, |t| Vec2::new(t.x as f32, t.y as f32));
+        let mut sprite = Sprite::new(self.texture.clone(), position + offset);
+        sprite.size = Vec2::new(region.width as f32, region.height as f32);
+        sprite.uv = self.uv(name)?;
+        Some(sprite)
+    }
+
+    /// Sprite of `animation` at `time` seconds
+    pub fn animation_sprite(&self, animation: &str, time: f32, position: Vec2) -> Option<Sprite> {
+        let frame = self.manifest.animations.get(animation)?.frame_at(time)?;
+        self.sprite(&frame.region, position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> ImageData {
+        ImageData { width, height, rgba: vec![value; (width * height * 4) as usize] }
+    }
+
+    #[test]
+    fn pack_without_overlap(){
+        let mut builder = AtlasBuilder::new().with_padding(1).with_extrusion(1);
+        for i in 0..20u32 {
+            builder.add(format!("r{}", i), solid(5 + i % 7 * 3, 4 + i % 5 * 6, i as u8 + 1));
+        }
+        let (atlas, manifest) = builder.build("atlas.png").unwrap();
+        assert!(atlas.width.is_power_of_two() && atlas.height.is_power_of_two());
+        let regions: Vec<&AtlasRegion> = manifest.regions.values().collect();
+        for (i, a) in regions.iter().enumerate() {
+            assert!(a.x >= 1 && a.y >= 1 && a.x + a.width < atlas.width && a.y + a.height < atlas.height);
+            for b in &regions[i + 1..] {
+                // çıkıntı dahil çakışma yok
+                let apart = a.x + a.width + 1 < b.x || b.x + b.width + 1 < a.x || a.y + a.height + 1 < b.y || b.y + b.height + 1 < a.y;
+                assert!(apart, "{:?} {:?}", a, b);
+            }
+        }
+        let r = manifest.regions["r3"];
+        let pixel = |x: u32, y: u32| atlas.rgba[((y * atlas.width + x) * 4) as usize];
+        assert_eq!(pixel(r.x - 1, r.y - 1), 4);
+        assert_eq!(pixel(r.x + r.width, r.y + r.height), 4);
+
+        let json = manifest.to_json();
+        assert_eq!(AtlasManifest::from_json(&json).unwrap(), manifest);
+
+        let mut small = AtlasBuilder::new().with_max_size(16);
+        small.add("big", solid(20, 20, 1));
+        assert!(matches!(small.build("x.png"), Err(AtlasError::TooLarge { .. })));
+    }
+
+    #[test]
+    fn ping_pong(){
+        let frame = |region: &str| SpriteFrame { region: region.to_string(), duration: 0.1 };
+        let animation = SpriteAnimation { frames: vec![frame("a"), frame("b"), frame("c")], direction: AnimationDirection::PingPong };
+        let regions: Vec<&str> = (0..5).map(|i| animation.frame_at(i as f32 * 0.1 + 0.05).unwrap().region.as_str()).collect();
+        assert_eq!(regions, ["a", "b", "c", "b", "a"]);
+    }
+}
@@ -0,0 +1,72 @@
+// src/engine/renderer/camera.rs
+
+use glam::{Mat4, Vec3};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    Perspective { fovy: f32 },
+    /// `height` is the visible world height, width follows the aspect ratio
+    Orthographic { height: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    pub position: Vec3,
+    pub target: Vec3,
+    pub up: Vec3,
+    pub projection: Projection,
+    pub znear: f32,
+    pub zfar: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            position: Vec3::new(0.0, 1.0, 3.0),
+            target: Vec3::ZERO,
+            up: Vec3::Y,
+            projection: Projection::Perspective { fovy: 60f32.to_radians() },
+            znear: 0.1,
+            zfar: 1000.0,
+        }
+    }
+}
+
+impl Camera {
+    pub fn view(&self) -> Mat4 {
+        Mat4::look_at_rh(self.position, self.target, self.up)
+    }
+
+    /// wgpu'nun clip space'i z için 0..1, glam'ın `_rh` fonksiyonları da bunu kullanıyor
+    pub fn projection(&self, aspect: f32) -> Mat4 {
+        match self.projection {
+            Projection::Perspective { fovy } => Mat4::perspective_rh(fovy, aspect, self.znear, self.zfar),
+            Projection::Orthographic { height } => {
+                let h = height * 0.5;
+                let w = h * aspect;
+                Mat4::orthographic_rh(-w, w, -h, h, self.znear, self.zfar)
+            }
+        }
+    }
+
+    pub fn view_proj(&self, aspect: f32) -> Mat4 {
+        self.projection(aspect) * self.view()
+    }
+}
+
+/// Camera data as the shaders see it (`CameraUniform` in the wgsl files)
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct CameraUniform {
+    pub view_proj: [[f32; 4]; 4],
+    pub position: [f32; 4],
+}
+
+impl CameraUniform {
+    pub fn new(camera: &Camera, aspect: f32) -> Self {
+        Self {
+            view_proj: camera.view_proj(aspect).to_cols_array_2d(),
+            position: camera.position.extend(1.0).to_array(),
+        }
+    }
+}
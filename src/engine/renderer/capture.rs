@@ -0,0 +1,442 @@
+// src/engine/renderer/capture.rs
+//
+// Screenshots and frame sequences. The finished frame is copied into a staging buffer in
+// the frame's own encoder, mapped asynchronously and turned into a PNG a few frames later,
+// so capturing never stalls the GPU (webgl/webgpu'da beklemek zaten mümkün değil).
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use wgpu::{Device, TextureFormat};
+
+use crate::engine::renderer::gltf_loader::ImageData;
+
+/// readbacks in flight before a frame sequence waits for the GPU
+const MAX_IN_FLIGHT: usize = 3;
+
+#[derive(Debug)]
+pub enum CaptureError {
+    Io(std::io::Error),
+    Image(image::ImageError),
+    Map(wgpu::BufferAsyncError),
+    Unsupported(String),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureError::Io(e) => write!(f, "io error: {}", e),
+            CaptureError::Image(e) => write!(f, "png error: {}", e),
+            CaptureError::Map(e) => write!(f, "readback failed: {}", e),
+            CaptureError::Unsupported(msg) => write!(f, "capture not supported: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+impl From<std::io::Error> for CaptureError {
+    fn from(e: std::io::Error) -> Self {
+        CaptureError::Io(e)
+    }
+}
+
+impl From<image::ImageError> for CaptureError {
+    fn from(e: image::ImageError) -> Self {
+        CaptureError::Image(e)
+    }
+}
+
+impl From<wgpu::BufferAsyncError> for CaptureError {
+    fn from(e: wgpu::BufferAsyncError) -> Self {
+        CaptureError::Map(e)
+    }
+}
+
+/// Where a captured frame goes
+#[derive(Debug)]
+pub enum CaptureTarget {
+    /// PNG file
+    #[cfg(not(target_family = "wasm"))]
+    File(PathBuf),
+    /// browser download with this file name
+    #[cfg(target_family = "wasm")]
+    Download(String),
+    /// resolves (or rejects) a JS promise with an image/png Blob
+    #[cfg(target_family = "wasm")]
+    Blob { resolve: js_sys::Function, reject: js_sys::Function },
+}
+
+impl CaptureTarget {
+    /// a file natively, a download named after the file on the web
+    pub fn path<P: Into<PathBuf>>(path: P) -> Self {
+        let path = path.into();
+        #[cfg(not(target_family = "wasm"))]
+        {
+            CaptureTarget::File(path)
+        }
+        #[cfg(target_family = "wasm")]
+        {
+            let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_else(|| "screenshot.png".to_string());
+            CaptureTarget::Download(name)
+        }
+    }
+}
+
+struct FrameSequence {
+    directory: PathBuf,
+    fps: f32,
+    next_frame: u32,
+    frames_left: Option<u32>,
+}
+
+struct Readback {
+    buffer: wgpu::Buffer,
+    size: (u32, u32),
+    padded_row: u32,
+    format: TextureFormat,
+    target: CaptureTarget,
+    /// set by the map_async callback
+    mapped: Arc<Mutex<Option<Result<(), wgpu::BufferAsyncError>>>>,
+}
+
+/// Screenshot requests and the frame sequence recorder of a `SurfaceManager`
+pub struct FrameCapture {
+    /// the surface was configured with COPY_SRC
+    supported: bool,
+    requests: Vec<CaptureTarget>,
+    sequence: Option<FrameSequence>,
+    /// recorded this frame, mapped after submit
+    recorded: Vec<Readback>,
+    in_flight: Vec<Readback>,
+    results: Vec<Result<PathBuf, CaptureError>>,
+}
+
+impl FrameCapture {
+    pub fn new(supported: bool) -> Self {
+        Self {
+            supported,
+            requests: Vec::new(),
+            sequence: None,
+            recorded: Vec::new(),
+            in_flight: Vec::new(),
+            results: Vec::new(),
+        }
+    }
+
+    /// Captures the next presented frame, overlays included
+    pub fn screenshot(&mut self, target: CaptureTarget) {
+        self.requests.push(target);
+    }
+
+    /// Writes every frame as `frame_00000.png`, `frame_00001.png`... into `directory`
+    /// until `stop_sequence` or until `frames` frames were written. While recording the
+    /// game should advance by `timestep()` per frame instead of the real frame time.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn start_sequence<P: Into<PathBuf>>(&mut self, directory: P, fps: f32, frames: Option<u32>) -> Result<(), CaptureError> {
+        if !self.supported {
+            return Err(CaptureError::Unsupported("the surface can't be copied from".to_string()));
+        }
+        let directory = directory.into();
+        std::fs::create_dir_all(&directory)?;
+        self.sequence = Some(FrameSequence { directory, fps: fps.max(1.0), next_frame: 0, frames_left: frames });
+        Ok(())
+    }
+
+    #[cfg(target_family = "wasm")]
+    pub fn start_sequence<P: Into<PathBuf>>(&mut self, _directory: P, _fps: f32, _frames: Option<u32>) -> Result<(), CaptureError> {
+        Err(CaptureError::Unsupported("frame sequences need a file system".to_string()))
+    }
+
+    pub fn stop_sequence(&mut self) {
+        self.sequence = None;
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.sequence.is_some()
+    }
+
+    /// fixed frame time of the running sequence
+    pub fn timestep(&self) -> Option<f32> {
+        self.sequence.as_ref().map(|s| 1.0 / s.fps)
+    }
+
+    /// true when the current frame has to be copied
+    pub fn wants_frame(&self) -> bool {
+        !self.requests.is_empty() || self.sequence.is_some()
+    }
+
+    /// Saved files and errors since the last call
+    pub fn take_results(&mut self) -> Vec<Result<PathBuf, CaptureError>> {
+        std::mem::take(&mut self.results)
+    }
+
+    /// Copies `texture` (the frame about to be presented) in `encoder`
+    pub fn record(&mut self, device: &Device, encoder: &mut wgpu::CommandEncoder, texture: &wgpu::Texture) {
+        let mut targets: Vec<CaptureTarget> = self.requests.drain(..).collect();
+        if let Some(sequence) = self.sequence.as_mut() {
+            #[cfg(not(target_family = "wasm"))]
+            targets.push(CaptureTarget::File(sequence.directory.join(format!("frame_{:05}.png", sequence.next_frame))));
+            sequence.next_frame += 1;
+            if let Some(left) = sequence.frames_left.as_mut() {
+                *left = left.saturating_sub(1);
+                if *left == 0 {
+                    self.sequence = None;
+                }
+            }
+        }
+        if targets.is_empty() {
+            return;
+        }
+        if !self.supported || pixel_size(texture.format()).is_none() {
+            let reason = if self.supported {
+                format!("{:?} frames can't be saved as png", texture.format())
+            } else {
+                "the surface can't be copied from".to_string()
+            };
+            for target in targets {
+                self.fail(target, CaptureError::Unsupported(reason.clone()));
+            }
+            return;
+        }
+
+        let (width, height) = (texture.width(), texture.height());
+        let unpadded = width * pixel_size(texture.format()).unwrap_or(4);
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_row = unpadded.div_ceil(align) * align;
+        for target in targets {
+            let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Capture Buffer"),
+                size: (padded_row * height) as u64,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            });
+            encoder.copy_texture_to_buffer(
+                texture.as_image_copy(),
+                wgpu::TexelCopyBufferInfo {
+                    buffer: &buffer,
+                    layout: wgpu::TexelCopyBufferLayout {
+                        offset: 0,
+                        bytes_per_row: Some(padded_row),
+                        rows_per_image: Some(height),
+                    },
+                },
+                wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            );
+            self.recorded.push(Readback {
+                buffer,
+                size: (width, height),
+                padded_row,
+                format: texture.format(),
+                target,
+                mapped: Arc::new(Mutex::new(None)),
+            });
+        }
+    }
+
+    /// Starts mapping what `record` copied, call after the frame was submitted.
+    /// Finished readbacks are encoded and delivered here as well.
+    pub fn after_submit(&mut self, device: &Device) {
+        for readback in self.recorded.drain(..) {
+            let mapped = readback.mapped.clone();
+            readback.buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+                *mapped.lock().unwrap() = Some(result);
+            });
+            self.in_flight.push(readback);
+        }
+        if self.in_flight.is_empty() {
+            return;
+        }
+        // kayıt sırasında kare atlamamak için fazla birikirse GPU'yu bekle
+        let poll = if self.sequence.is_some() && self.in_flight.len() > MAX_IN_FLIGHT {
+            wgpu::PollType::wait_indefinitely()
+        } else {
+            wgpu::PollType::Poll
+        };
+        let _ = device.poll(poll);
+
+        let mut index = 0;
+        while index < self.in_flight.len() {
+            let state = self.in_flight[index].mapped.lock().unwrap().take();
+            match state {
+                None => index += 1,
+                Some(result) => {
+                    let readback = self.in_flight.remove(index);
+                    let image = result.map(|()| read_pixels(&readback));
+                    readback.buffer.unmap();
+                    match image {
+                        Ok(image) => self.deliver(readback.target, image),
+                        Err(e) => self.fail(readback.target, e.into()),
+                    }
+                }
+            }
+        }
+    }
+
+    fn deliver(&mut self, target: CaptureTarget, image: ImageData) {
+        let png = match encode_png(&image) {
+            Ok(png) => png,
+            Err(e) => return self.fail(target, e),
+        };
+        match target {
+            #[cfg(not(target_family = "wasm"))]
+            CaptureTarget::File(path) => {
+                let result = std::fs::write(&path, png).map(|()| path).map_err(CaptureError::from);
+                self.results.push(result);
+            }
+            #[cfg(target_family = "wasm")]
+            CaptureTarget::Download(name) => {
+                if let Err(e) = web::download(&png, &name) {
+                    self.results.push(Err(CaptureError::Unsupported(format!("download failed: {:?}", e))));
+                }
+            }
+            #[cfg(target_family = "wasm")]
+            CaptureTarget::Blob { resolve, reject } => {
+                let _ = match web::png_blob(&png) {
+                    Ok(blob) => resolve.call1(&wasm_bindgen::JsValue::NULL, &blob),
+                    Err(e) => reject.call1(&wasm_bindgen::JsValue::NULL, &e),
+                };
+            }
+        }
+    }
+
+    fn fail(&mut self, target: CaptureTarget, error: CaptureError) {
+        #[cfg(target_family = "wasm")]
+        if let CaptureTarget::Blob { reject, .. } = &target {
+            let _ = reject.call1(&wasm_bindgen::JsValue::NULL, &error.to_string().into());
+            return;
+        }
+        let _ = target;
+        self.results.push(Err(error));
+    }
+}
+
+/// bytes per pixel of the formats that can be saved
+fn pixel_size(format: TextureFormat) -> Option<u32> {
+    match format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb
+        | TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => Some(4),
+        TextureFormat::Rgba16Float => Some(8),
+        _ => None,
+    }
+}
+
+/// Mapped staging buffer to opaque RGBA8
+fn read_pixels(readback: &Readback) -> ImageData {
+    let (width, height) = readback.size;
+    let data = readback.buffer.slice(..).get_mapped_range();
+    let mut rgba = Vec::with_capacity((width * height * 4) as usize);
+    for row in data.chunks(readback.padded_row as usize) {
+        match readback.format {
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => {
+                for px in row[..(width * 4) as usize].chunks_exact(4) {
+                    rgba.extend_from_slice(&[px[2], px[1], px[0], 255]);
+                }
+            }
+            TextureFormat::Rgba16Float => {
+                // HDR swapchain lineer, png için sRGB'ye çevriliyor
+                for px in row[..(width * 8) as usize].chunks_exact(8) {
+                    let channel = |i: usize| linear_to_srgb(f16_to_f32(u16::from_le_bytes([px[i * 2], px[i * 2 + 1]])));
+                    rgba.extend_from_slice(&[channel(0), channel(1), channel(2), 255]);
+                }
+            }
+            _ => {
+                for px in row[..(width * 4) as usize].chunks_exact(4) {
+                    rgba.extend_from_slice(&[px[0], px[1], px[2], 255]);
+                }
+            }
+        }
+    }
+    ImageData { width, height, rgba }
+}
+
+fn encode_png(image: &ImageData) -> Result<Vec<u8>, CaptureError> {
+    Ok(image.encode_png()?)
+}
+
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let mantissa = (bits & 0x3ff) as f32;
+    match exponent {
+        0 => sign * mantissa * 2f32.powi(-24),
+        31 => if mantissa == 0.0 { sign * f32::INFINITY } else { f32::NAN },
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+fn linear_to_srgb(value: f32) -> u8 {
+    let v = if value.is_nan() { 0.0 } else { value.clamp(0.0, 1.0) };
+    let s = if v <= 0.0031308 { v * 12.92 } else { 1.055 * v.powf(1.0 / 2.4) - 0.055 };
+    (s * 255.0 + 0.5) as u8
+}
+
+#[cfg(target_family = "wasm")]
+mod web {
+    use std::cell::RefCell;
+
+    use wasm_bindgen::prelude::*;
+    use wasm_bindgen::JsCast;
+
+    use super::CaptureTarget;
+
+    thread_local! {
+        static PENDING_SCREENSHOTS: RefCell<Vec<CaptureTarget>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Captures the next frame, the promise resolves with an image/png Blob
+    #[wasm_bindgen]
+    pub fn take_screenshot() -> js_sys::Promise {
+        js_sys::Promise::new(&mut |resolve, reject| {
+            PENDING_SCREENSHOTS.with(|p| p.borrow_mut().push(CaptureTarget::Blob { resolve, reject }));
+        })
+    }
+
+    /// screenshots asked for from JS since the last call
+    pub fn take_requests() -> Vec<CaptureTarget> {
+        PENDING_SCREENSHOTS.with(|p| std::mem::take(&mut *p.borrow_mut()))
+    }
+
+    pub fn png_blob(png: &[u8]) -> Result<web_sys::Blob, JsValue> {
+        let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(png));
+        let options = web_sys::BlobPropertyBag::new();
+        options.set_type("image/png");
+        web_sys::Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+    }
+
+    pub fn download(png: &[u8], name: &str) -> Result<(), JsValue> {
+        let blob = png_blob(png)?;
+        let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+        let document = web_sys::window().and_then(|w| w.document()).ok_or("no document")?;
+        let link: web_sys::HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+        link.set_href(&url);
+        link.set_download(name);
+        link.click();
+        web_sys::Url::revoke_object_url(&url)
+    }
+}
+
+#[cfg(target_family = "wasm")]
+pub use web::{take_requests as take_js_screenshot_requests, take_screenshot};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_conversion(){
+        assert_eq!(f16_to_f32(0x3c00), 1.0);
+        assert_eq!(f16_to_f32(0xc000), -2.0);
+        assert_eq!(f16_to_f32(0x3800), 0.5);
+        assert_eq!(linear_to_srgb(1.0), 255);
+        assert_eq!(linear_to_srgb(4.0), 255);
+        assert_eq!(linear_to_srgb(0.0), 0);
+        assert!((127..=128).contains(&linear_to_srgb(0.214)));
+
+        let image = ImageData { width: 2, height: 1, rgba: vec![255, 0, 0, 255, 0, 0, 255, 255] };
+        let png = encode_png(&image).unwrap();
+        let decoded = ImageData::decode(&png).unwrap();
+        assert_eq!(decoded.rgba, image.rgba);
+    }
+}
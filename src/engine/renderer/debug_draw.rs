@@ -0,0 +1,323 @@
+// src/engine/renderer/debug_draw.rs
+//
+// Immediate mode debug shapes. The free functions can be called from anywhere, lines are
+// collected in a global list and drawn (then cleared) by `DebugRenderer` once per frame.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use glam::{Mat4, Vec2, Vec3, Vec4Swizzles};
+use once_cell::sync::Lazy;
+use wgpu::{Device, Queue, TextureFormat};
+
+use crate::engine::renderer::mesh::VertexType;
+use crate::engine::renderer::pipeline_cache::{BlendMode, PipelineCache, PipelineKey, ShaderVariant, VertexLayoutKey};
+use crate::engine::renderer::stats;
+
+pub const DEBUG_SHADER: &str = "debug_lines";
+const DEBUG_SHADER_SOURCE: &str = include_str!("../../../gsl/debug_lines.wgsl");
+
+const CIRCLE_SEGMENTS: usize = 32;
+
+pub const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+pub const GREEN: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
+pub const BLUE: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+pub const YELLOW: [f32; 4] = [1.0, 1.0, 0.0, 1.0];
+pub const WHITE: [f32; 4] = [1.0; 4];
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct DebugVertex {
+    pub position: [f32; 3],
+    pub color: [f32; 4],
+}
+
+impl VertexType for DebugVertex {
+    fn layout() -> VertexLayoutKey {
+        VertexLayoutKey {
+            array_stride: std::mem::size_of::<DebugVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: wgpu::vertex_attr_array![
+                0 => Float32x3,
+                1 => Float32x4,
+            ].to_vec(),
+        }
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+static LINES: Lazy<Mutex<Vec<DebugVertex>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Turning it off makes every call a no-op and drops the lines collected so far.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        clear();
+    }
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn clear() {
+    LINES.lock().unwrap().clear();
+}
+
+/// takes the lines collected since the last call, two vertices per line
+pub fn take_lines() -> Vec<DebugVertex> {
+    std::mem::take(&mut *LINES.lock().unwrap())
+}
+
+fn push_lines(segments: impl IntoIterator<Item = (Vec3, Vec3)>, color: [f32; 4]) {
+    if !is_enabled() {
+        return;
+    }
+    let mut lines = LINES.lock().unwrap();
+    for (a, b) in segments {
+        lines.push(DebugVertex { position: a.to_array(), color });
+        lines.push(DebugVertex { position: b.to_array(), color });
+    }
+}
+
+/// connects the points in order, and the last one to the first if `closed`
+fn polyline(points: &[Vec3], closed: bool, color: [f32; 4]) {
+    let segments = points.windows(2).map(|w| (w[0], w[1]));
+    if closed && points.len() > 2 {
+        push_lines(segments.chain(std::iter::once((points[points.len() - 1], points[0]))), color);
+    } else {
+        push_lines(segments, color);
+    }
+}
+
+pub fn line(a: Vec3, b: Vec3, color: [f32; 4]) {
+    push_lines([(a, b)], color);
+}
+
+pub fn line_2d(a: Vec2, b: Vec2, color: [f32; 4]) {
+    line(a.extend(0.0), b.extend(0.0), color);
+}
+
+/// rectangle on the XY plane
+pub fn rect(min: Vec2, max: Vec2, color: [f32; 4]) {
+    let points = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)].map(|p| p.extend(0.0));
+    polyline(&points, true, color);
+}
+
+/// circle on the XY plane
+pub fn circle(center: Vec2, radius: f32, color: [f32; 4]) {
+    circle_3d(center.extend(0.0), Vec3::Z, radius, color);
+}
+
+/// circle around `normal`
+pub fn circle_3d(center: Vec3, normal: Vec3, radius: f32, color: [f32; 4]) {
+    let (u, v) = normal.normalize_or(Vec3::Z).any_orthonormal_pair();
+    let points: Vec<Vec3> = (0..CIRCLE_SEGMENTS).map(|i| {
+        let a = i as f32 / CIRCLE_SEGMENTS as f32 * std::f32::consts::TAU;
+        center + (u * a.cos() + v * a.sin()) * radius
+    }).collect();
+    polyline(&points, true, color);
+}
+
+pub fn sphere(center: Vec3, radius: f32, color: [f32; 4]) {
+    circle_3d(center, Vec3::X, radius, color);
+    circle_3d(center, Vec3::Y, radius, color);
+    circle_3d(center, Vec3::Z, radius, color);
+}
+
+/// line with a head at `to`, the head is 20% of the length
+pub fn arrow(from: Vec3, to: Vec3, color: [f32; 4]) {
+    let dir = to - from;
+    let length = dir.length();
+    if length <= f32::EPSILON {
+        return;
+    }
+    let d = dir / length;
+    // 2D oklar (XY düzlemi) için başı da aynı düzlemde aç
+    let side = if d.z.abs() < 0.99 { d.cross(Vec3::Z).normalize() } else { d.any_orthogonal_vector() };
+    let head = length * 0.2;
+    let back = to - d * head;
+    push_lines([
+        (from, to),
+        (to, back + side * head * 0.5),
+        (to, back - side * head * 0.5),
+    ], color);
+}
+
+pub fn aabb(min: Vec3, max: Vec3, color: [f32; 4]) {
+    let c = |x: bool, y: bool, z: bool| Vec3::new(
+        if x { max.x } else { min.x },
+        if y { max.y } else { min.y },
+        if z { max.z } else { min.z },
+    );
+    // alt ve üst kare, aradaki dikey kenarlar
+    let bottom = [c(false, false, false), c(true, false, false), c(true, false, true), c(false, false, true)];
+    let top = bottom.map(|p| Vec3::new(p.x, max.y, p.z));
+    let mut segments = Vec::with_capacity(12);
+    for i in 0..4 {
+        let j = (i + 1) % 4;
+        segments.push((bottom[i], bottom[j]));
+        segments.push((top[i], top[j]));
+        segments.push((bottom[i], top[i]));
+    }
+    push_lines(segments, color);
+}
+
+/// the three axes of `transform` as red, green and blue lines of `size` length
+pub fn gizmo(transform: Mat4, size: f32) {
+    let origin = transform.w_axis.xyz();
+    line(origin, origin + transform.x_axis.xyz().normalize_or_zero() * size, RED);
+    line(origin, origin + transform.y_axis.xyz().normalize_or_zero() * size, GREEN);
+    line(origin, origin + transform.z_axis.xyz().normalize_or_zero() * size, BLUE);
+}
+
+/// grid of `count` x `count` cells on the XZ plane around `center`
+pub fn grid(center: Vec3, cell: f32, count: u32, color: [f32; 4]) {
+    let half = cell * count as f32 * 0.5;
+    let segments = (0..=count).flat_map(|i| {
+        let o = -half + i as f32 * cell;
+        [
+            (center + Vec3::new(o, 0.0, -half), center + Vec3::new(o, 0.0, half)),
+            (center + Vec3::new(-half, 0.0, o), center + Vec3::new(half, 0.0, o)),
+        ]
+    });
+    push_lines(segments, color);
+}
+
+/// edges of the frustum that `view_proj` sees, e.g. `camera.view_proj(aspect)`
+pub fn frustum(view_proj: Mat4, color: [f32; 4]) {
+    let inv = view_proj.inverse();
+    let corner = |x: f32, y: f32, z: f32| inv.project_point3(Vec3::new(x, y, z));
+    let near = [corner(-1.0, -1.0, 0.0), corner(1.0, -1.0, 0.0), corner(1.0, 1.0, 0.0), corner(-1.0, 1.0, 0.0)];
+    let far = [corner(-1.0, -1.0, 1.0), corner(1.0, -1.0, 1.0), corner(1.0, 1.0, 1.0), corner(-1.0, 1.0, 1.0)];
+    polyline(&near, true, color);
+    polyline(&far, true, color);
+    push_lines((0..4).map(|i| (near[i], far[i])), color);
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct DebugUniform {
+    view_proj: [[f32; 4]; 4],
+}
+
+/// Draws the collected debug lines as a line list on top of the finished frame.
+pub struct DebugRenderer {
+    vertex_buffer: wgpu::Buffer,
+    capacity: u64,
+    vertex_count: u32,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl DebugRenderer {
+    pub fn new(device: &Device, cache: &mut PipelineCache) -> Self {
+        cache.register_shader(DEBUG_SHADER, DEBUG_SHADER_SOURCE);
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Debug Uniform Buffer"),
+            size: std::mem::size_of::<DebugUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let layout = cache.bind_group_layout(device, &Self::entries());
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("debug_bind_group"),
+            layout: &layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() }],
+        });
+        let capacity = 4096;
+        Self {
+            vertex_buffer: Self::create_vertex_buffer(device, capacity),
+            capacity,
+            vertex_count: 0,
+            uniform_buffer,
+            bind_group,
+        }
+    }
+
+    fn create_vertex_buffer(device: &Device, vertices: u64) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Debug Vertex Buffer"),
+            size: vertices * std::mem::size_of::<DebugVertex>() as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    pub fn entries() -> Vec<wgpu::BindGroupLayoutEntry> {
+        vec![wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }]
+    }
+
+    pub fn pipeline_key(format: TextureFormat) -> PipelineKey {
+        let mut key = PipelineKey::new(ShaderVariant::new(DEBUG_SHADER), format);
+        key.bind_groups = vec![Self::entries()];
+        key.vertex_layouts = vec![DebugVertex::layout()];
+        key.topology = wgpu::PrimitiveTopology::LineList;
+        key.cull_mode = None;
+        key.blend = BlendMode::Alpha;
+        key
+    }
+
+    pub fn line_count(&self) -> u32 {
+        self.vertex_count / 2
+    }
+
+    /// Takes this frame's lines from the global list and uploads them.
+    pub fn prepare(&mut self, device: &Device, queue: &Queue, view_proj: Mat4) {
+        let vertices = take_lines();
+        self.vertex_count = vertices.len() as u32;
+        if vertices.is_empty() {
+            return;
+        }
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&DebugUniform { view_proj: view_proj.to_cols_array_2d() }));
+        stats::upload(std::mem::size_of::<DebugUniform>());
+        let needed = vertices.len() as u64;
+        if needed > self.capacity {
+            self.capacity = needed.next_power_of_two();
+            self.vertex_buffer = Self::create_vertex_buffer(device, self.capacity);
+        }
+        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&vertices));
+        stats::upload(std::mem::size_of_val(vertices.as_slice()));
+    }
+
+    pub fn draw(&self, device: &Device, cache: &mut PipelineCache, format: TextureFormat, render_pass: &mut wgpu::RenderPass<'_>) {
+        if self.vertex_count == 0 {
+            return;
+        }
+        let pipeline = cache.pipeline(device, &Self::pipeline_key(format));
+        render_pass.set_pipeline(&pipeline);
+        stats::pipeline();
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.draw(0..self.vertex_count, 0..1);
+        stats::draw(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes_emit_lines(){
+        // diğer testler de global listeyi kullanabilir, sadece bu testin eklediklerine bak
+        let before = LINES.lock().unwrap().len();
+        aabb(Vec3::ZERO, Vec3::ONE, WHITE);
+        gizmo(Mat4::IDENTITY, 1.0);
+        let lines = LINES.lock().unwrap()[before..].to_vec();
+        assert_eq!(lines.len(), (12 + 3) * 2);
+        // her köşe üç kenarda geçiyor
+        let origin = lines.iter().take(24).filter(|v| v.position == [0.0, 0.0, 0.0]).count();
+        assert_eq!(origin, 3);
+        assert_eq!(lines[24].color, RED);
+    }
+}
@@ -0,0 +1,551 @@
+// src/engine/renderer/gltf_loader.rs
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+use base64::Engine;
+use glam::{Mat4, Vec3};
+use wgpu::Device;
+
+use crate::engine::renderer::mesh::{Indices, Mesh, MeshData, MeshRenderer, Vertex3d};
+
+#[derive(Debug)]
+pub enum GltfError {
+    Io(std::io::Error),
+    Gltf(gltf::Error),
+    Image(image::ImageError),
+    MissingPositions { mesh: usize, primitive: usize },
+    /// an attribute doesn't have one value per position
+    AttributeCount { mesh: usize, primitive: usize, attribute: &'static str, count: usize, expected: usize },
+    IndexOutOfRange { mesh: usize, primitive: usize, index: u32, vertices: usize },
+}
+
+impl fmt::Display for GltfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GltfError::Io(e) => write!(f, "io error: {}", e),
+            GltfError::Gltf(e) => write!(f, "gltf error: {}", e),
+            GltfError::Image(e) => write!(f, "image error: {}", e),
+            GltfError::MissingPositions { mesh, primitive } =>
+                write!(f, "mesh {} primitive {} has no POSITION attribute", mesh, primitive),
+            GltfError::AttributeCount { mesh, primitive, attribute, count, expected } =>
+                write!(f, "mesh {} primitive {} has {} {} values for {} positions", mesh, primitive, count, attribute, expected),
+            GltfError::IndexOutOfRange { mesh, primitive, index, vertices } =>
+                write!(f, "mesh {} primitive {} uses vertex {} of {}", mesh, primitive, index, vertices),
+        }
+    }
+}
+
+impl std::error::Error for GltfError {}
+
+impl From<gltf::Error> for GltfError {
+    fn from(e: gltf::Error) -> Self {
+        GltfError::Gltf(e)
+    }
+}
+
+impl From<image::ImageError> for GltfError {
+    fn from(e: image::ImageError) -> Self {
+        GltfError::Image(e)
+    }
+}
+
+impl From<std::io::Error> for GltfError {
+    fn from(e: std::io::Error) -> Self {
+        GltfError::Io(e)
+    }
+}
+
+/// Decoded image, always converted to RGBA8
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageData {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl ImageData {
+    /// Decodes a PNG or JPEG image
+    pub fn decode(bytes: &[u8]) -> Result<Self, image::ImageError> {
+        let image = image::load_from_memory(bytes)?.to_rgba8();
+        Ok(Self { width: image.width(), height: image.height(), rgba: image.into_raw() })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, image::ImageError> {
+        Self::decode(&std::fs::read(path)?)
+    }
+
+    pub fn encode_png(&self) -> Result<Vec<u8>, image::ImageError> {
+        use image::ImageEncoder;
+        let mut png = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut png)
+            .write_image(&self.rgba, self.width, self.height, image::ExtendedColorType::Rgba8)?;
+        Ok(png)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlphaMode {
+    Opaque,
+    Mask(f32),
+    Blend,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureRef {
+    /// index into `GltfScene::images`
+    pub image: usize,
+    pub tex_coord: u32,
+}
+
+/// Metallic-roughness material as described in the glTF file
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfMaterial {
+    pub name: Option<String>,
+    pub base_color_factor: [f32; 4],
+    pub base_color_texture: Option<TextureRef>,
+    pub metallic_factor: f32,
+    pub roughness_factor: f32,
+    pub metallic_roughness_texture: Option<TextureRef>,
+    pub normal_texture: Option<TextureRef>,
+    pub normal_scale: f32,
+    pub occlusion_texture: Option<TextureRef>,
+    pub occlusion_strength: f32,
+    pub emissive_factor: [f32; 3],
+    pub emissive_texture: Option<TextureRef>,
+    pub alpha_mode: AlphaMode,
+    pub double_sided: bool,
+}
+
+impl Default for GltfMaterial {
+    fn default() -> Self {
+        // glTF spesifikasyonundaki varsayılan malzeme
+        Self {
+            name: None,
+            base_color_factor: [1.0; 4],
+            base_color_texture: None,
+            metallic_factor: 1.0,
+            roughness_factor: 1.0,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            normal_scale: 1.0,
+            occlusion_texture: None,
+            occlusion_strength: 1.0,
+            emissive_factor: [0.0; 3],
+            emissive_texture: None,
+            alpha_mode: AlphaMode::Opaque,
+            double_sided: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfPrimitive {
+    pub data: MeshData<Vertex3d>,
+    pub material: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfMesh {
+    pub name: Option<String>,
+    pub primitives: Vec<GltfPrimitive>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfNode {
+    pub name: Option<String>,
+    pub local_transform: Mat4,
+    pub mesh: Option<usize>,
+    pub parent: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+/// Everything imported from a .gltf / .glb file, still on the CPU
+#[derive(Debug, Clone, PartialEq)]
+pub struct GltfScene {
+    pub meshes: Vec<GltfMesh>,
+    pub materials: Vec<GltfMaterial>,
+    pub images: Vec<ImageData>,
+    pub nodes: Vec<GltfNode>,
+    /// root nodes of the default scene
+    pub roots: Vec<usize>,
+    /// what was left out, e.g. line and point primitives
+    pub warnings: Vec<String>,
+}
+
+impl GltfScene {
+    /// Loads a .gltf or .glb file, external buffers and images are resolved next to it.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, GltfError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)?;
+        Self::from_slice(&bytes, path.parent())
+    }
+
+    /// `base` is the directory relative uris are resolved against, with `None` only
+    /// embedded (glb / data uri) buffers can be used.
+    pub fn from_slice(bytes: &[u8], base: Option<&Path>) -> Result<Self, GltfError> {
+        Self::from_slice_with(bytes, |uri| match base {
+            Some(base) => std::fs::read(base.join(uri)),
+            None => Err(io::Error::new(io::ErrorKind::NotFound, format!("{} is external and there is no base directory", uri))),
+        })
+    }
+
+    /// Like `from_slice`, external buffers and images are read by `read` from their
+    /// (percent decoded) relative uri, e.g. through the asset `Vfs`
+    pub fn from_slice_with<F: FnMut(&str) -> io::Result<Vec<u8>>>(bytes: &[u8], mut read: F) -> Result<Self, GltfError> {
+        let gltf::Gltf { document, mut blob } = gltf::Gltf::from_slice(bytes)?;
+        let mut buffers = Vec::new();
+        for buffer in document.buffers() {
+            let data = match buffer.source() {
+                gltf::buffer::Source::Bin => blob.take().ok_or(gltf::Error::MissingBlob)?,
+                gltf::buffer::Source::Uri(uri) => read_uri(uri, &mut read)?,
+            };
+            if data.len() < buffer.length() {
+                return Err(gltf::Error::BufferLength { buffer: buffer.index(), expected: buffer.length(), actual: data.len() }.into());
+            }
+            buffers.push(data);
+        }
+        let mut images = Vec::new();
+        for image in document.images() {
+            let encoded = match image.source() {
+                gltf::image::Source::View { view, .. } => buffers[view.buffer().index()]
+                    .get(view.offset()..view.offset() + view.length())
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("image {} is outside of its buffer", image.index())))?
+                    .to_vec(),
+                gltf::image::Source::Uri { uri, .. } => read_uri(uri, &mut read)?,
+            };
+            images.push(ImageData::decode(&encoded)?);
+        }
+
+        let materials = document.materials().map(convert_material).collect();
+
+        let mut meshes = Vec::new();
+        let mut warnings = Vec::new();
+        for mesh in document.meshes() {
+            let mut primitives = Vec::new();
+            for primitive in mesh.primitives() {
+                let (m, p) = (mesh.index(), primitive.index());
+                let mode = primitive.mode();
+                if !matches!(mode, gltf::mesh::Mode::Triangles | gltf::mesh::Mode::TriangleStrip | gltf::mesh::Mode::TriangleFan) {
+                    // çizgi ve nokta yardımcıları çizilmiyor, modelin geri kalanı yükleniyor
+                    warnings.push(format!("mesh {} primitive {} skipped, {:?} isn't supported", m, p, mode));
+                    continue;
+                }
+                let reader = primitive.reader(|b| Some(&buffers[b.index()][..]));
+                let positions: Vec<[f32; 3]> = reader.read_positions()
+                    .ok_or(GltfError::MissingPositions { mesh: m, primitive: p })?
+                    .collect();
+                let indices: Vec<u32> = match reader.read_indices() {
+                    Some(i) => i.into_u32().collect(),
+                    None => (0..positions.len() as u32).collect(),
+                };
+                if let Some(index) = indices.iter().find(|i| **i as usize >= positions.len()) {
+                    return Err(GltfError::IndexOutOfRange { mesh: m, primitive: p, index: *index, vertices: positions.len() });
+                }
+                let indices = triangulate(mode, indices);
+                let check = |attribute: &'static str, count: usize| match count == positions.len() {
+                    true => Ok(()),
+                    false => Err(GltfError::AttributeCount { mesh: m, primitive: p, attribute, count, expected: positions.len() }),
+                };
+                let normals: Vec<[f32; 3]> = match reader.read_normals() {
+                    Some(n) => n.collect(),
+                    None => smooth_normals(&positions, &indices),
+                };
+                check("NORMAL", normals.len())?;
+                let uvs: Vec<[f32; 2]> = reader.read_tex_coords(0)
+                    .map(|t| t.into_f32().collect())
+                    .unwrap_or_else(|| vec![[0.0; 2]; positions.len()]);
+                check("TEXCOORD_0", uvs.len())?;
+                let tangents: Option<Vec<[f32; 4]>> = reader.read_tangents().map(|t| t.collect());
+                if let Some(t) = &tangents {
+                    check("TANGENT", t.len())?;
+                }
+
+                let vertices = positions.iter().enumerate().map(|(i, p)| {
+                    let mut v = Vertex3d::new(*p, normals[i], uvs[i]);
+                    if let Some(t) = &tangents {
+                        v.tangent = t[i];
+                    }
+                    v
+                }).collect();
+
+                primitives.push(GltfPrimitive {
+                    data: MeshData::new(vertices, Some(Indices::from_u32(indices))),
+                    material: primitive.material().index(),
+                });
+            }
+            meshes.push(GltfMesh { name: mesh.name().map(str::to_string), primitives });
+        }
+
+        let mut nodes: Vec<GltfNode> = document.nodes().map(|node| GltfNode {
+            name: node.name().map(str::to_string),
+            local_transform: Mat4::from_cols_array_2d(&node.transform().matrix()),
+            mesh: node.mesh().map(|m| m.index()),
+            parent: None,
+            children: node.children().map(|c| c.index()).collect(),
+        }).collect();
+        for i in 0..nodes.len() {
+            for c in nodes[i].children.clone() {
+                nodes[c].parent = Some(i);
+            }
+        }
+
+        let roots = match document.default_scene().or_else(|| document.scenes().next()) {
+            Some(scene) => scene.nodes().map(|n| n.index()).collect(),
+            // sahne tanımı yoksa parent'ı olmayan her node kök sayılır
+            None => (0..nodes.len()).filter(|i| nodes[*i].parent.is_none()).collect(),
+        };
+
+        Ok(Self { meshes, materials, images, nodes, roots, warnings })
+    }
+
+    /// world transform of every node, nodes outside the default scene keep their local transform
+    pub fn world_transforms(&self) -> Vec<Mat4> {
+        let mut world: Vec<Mat4> = self.nodes.iter().map(|n| n.local_transform).collect();
+        let mut stack: Vec<(usize, Mat4)> = self.roots.iter().map(|r| (*r, Mat4::IDENTITY)).collect();
+        while let Some((node, parent)) = stack.pop() {
+            let m = parent * self.nodes[node].local_transform;
+            world[node] = m;
+            for c in &self.nodes[node].children {
+                stack.push((*c, m));
+            }
+        }
+        world
+    }
+
+    pub fn upload(&self, device: &Device) -> GpuModel {
+        let meshes = self.meshes.iter().map(|mesh| {
+            mesh.primitives.iter()
+                .map(|p| (Arc::new(Mesh::new(device, &p.data)), p.material))
+                .collect()
+        }).collect();
+
+        let world = self.world_transforms();
+        let mut instances = Vec::new();
+        let mut stack: Vec<usize> = self.roots.clone();
+        while let Some(node) = stack.pop() {
+            if let Some(mesh) = self.nodes[node].mesh {
+                instances.push((mesh, world[node]));
+            }
+            stack.extend_from_slice(&self.nodes[node].children);
+        }
+
+        GpuModel {
+            meshes,
+            instances,
+            base_colors: self.materials.iter().map(|m| m.base_color_factor).collect(),
+        }
+    }
+}
+
+/// A glTF scene uploaded to the GPU, ready to be queued every frame
+pub struct GpuModel {
+    /// primitives of every glTF mesh together with their material index
+    pub meshes: Vec<Vec<(Arc<Mesh>, Option<usize>)>>,
+    /// (mesh index, world transform) for every node of the default scene that has a mesh
+    pub instances: Vec<(usize, Mat4)>,
+    base_colors: Vec<[f32; 4]>,
+}
+
+impl GpuModel {
+    pub fn queue(&self, renderer: &mut MeshRenderer, transform: Mat4) {
+        for (mesh, world) in &self.instances {
+            for (primitive, material) in &self.meshes[*mesh] {
+                let color = material.map(|m| self.base_colors[m]).unwrap_or([1.0; 4]);
+                renderer.queue_draw(primitive.clone(), transform * *world, color);
+            }
+        }
+    }
+}
+
+/// Strips and fans as a triangle list, winding kept
+fn triangulate(mode: gltf::mesh::Mode, indices: Vec<u32>) -> Vec<u32> {
+    let triangles = indices.len().saturating_sub(2);
+    match mode {
+        gltf::mesh::Mode::TriangleStrip => (0..triangles)
+            .flat_map(|i| match i % 2 {
+                0 => [indices[i], indices[i + 1], indices[i + 2]],
+                _ => [indices[i + 1], indices[i], indices[i + 2]],
+            })
+            .collect(),
+        gltf::mesh::Mode::TriangleFan => (0..triangles)
+            .flat_map(|i| [indices[0], indices[i + 1], indices[i + 2]])
+            .collect(),
+        _ => indices,
+    }
+}
+
+fn smooth_normals(positions: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    let mut normals = vec![Vec3::ZERO; positions.len()];
+    for tri in indices.chunks_exact(3) {
+        let a = Vec3::from(positions[tri[0] as usize]);
+        let b = Vec3::from(positions[tri[1] as usize]);
+        let c = Vec3::from(positions[tri[2] as usize]);
+        // alanla ağırlıklı, normalize etmeden topluyoruz
+        let n = (b - a).cross(c - a);
+        for i in tri {
+            normals[*i as usize] += n;
+        }
+    }
+    normals.into_iter().map(|n| n.normalize_or(Vec3::Y).to_array()).collect()
+}
+
+fn texture_ref(info: Option<gltf::texture::Info<'_>>) -> Option<TextureRef> {
+    info.map(|i| TextureRef { image: i.texture().source().index(), tex_coord: i.tex_coord() })
+}
+
+fn convert_material(material: gltf::Material<'_>) -> GltfMaterial {
+    let pbr = material.pbr_metallic_roughness();
+    let normal = material.normal_texture();
+    let occlusion = material.occlusion_texture();
+    GltfMaterial {
+        name: material.name().map(str::to_string),
+        base_color_factor: pbr.base_color_factor(),
+        base_color_texture: texture_ref(pbr.base_color_texture()),
+        metallic_factor: pbr.metallic_factor(),
+        roughness_factor: pbr.roughness_factor(),
+        metallic_roughness_texture: texture_ref(pbr.metallic_roughness_texture()),
+        normal_scale: normal.as_ref().map(|n| n.scale()).unwrap_or(1.0),
+        normal_texture: normal.map(|n| TextureRef { image: n.texture().source().index(), tex_coord: n.tex_coord() }),
+        occlusion_strength: occlusion.as_ref().map(|o| o.strength()).unwrap_or(1.0),
+        occlusion_texture: occlusion.map(|o| TextureRef { image: o.texture().source().index(), tex_coord: o.tex_coord() }),
+        emissive_factor: material.emissive_factor(),
+        emissive_texture: texture_ref(material.emissive_texture()),
+        alpha_mode: match material.alpha_mode() {
+            gltf::material::AlphaMode::Opaque => AlphaMode::Opaque,
+            gltf::material::AlphaMode::Mask => AlphaMode::Mask(material.alpha_cutoff().unwrap_or(0.5)),
+            gltf::material::AlphaMode::Blend => AlphaMode::Blend,
+        },
+        double_sided: material.double_sided(),
+    }
+}
+
+/// Bytes behind a buffer or image uri, data uris are decoded here
+fn read_uri(uri: &str, read: &mut impl FnMut(&str) -> io::Result<Vec<u8>>) -> Result<Vec<u8>, GltfError> {
+    if let Some(data) = uri.strip_prefix("data:") {
+        let Some((_, encoded)) = data.split_once(";base64,") else {
+            return Err(gltf::Error::UnsupportedScheme.into());
+        };
+        return base64::engine::general_purpose::STANDARD.decode(encoded)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e).into());
+    }
+    // yalnızca göreli yollar, file:// ve http:// desteklenmiyor
+    if uri.contains("://") {
+        return Err(gltf::Error::UnsupportedScheme.into());
+    }
+    Ok(read(&percent_decode(uri))?)
+}
+
+/// `%20` and the like in uris
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3)
+            .filter(|h| bytes[i] == b'%' && h.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|h| u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok());
+        match hex {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // tek üçgen, buffer data uri olarak gömülü
+    const TRIANGLE: &str = r#"{
+        "asset": { "version": "2.0" },
+        "scene": 0,
+        "scenes": [ { "nodes": [0] } ],
+        "nodes": [
+            { "name": "root", "translation": [0.0, 2.0, 0.0], "children": [1] },
+            { "name": "tri", "mesh": 0, "translation": [1.0, 0.0, 0.0] }
+        ],
+        "meshes": [ { "primitives": [ { "attributes": { "POSITION": 0 }, "indices": 1, "material": 0 } ] } ],
+        "materials": [ { "pbrMetallicRoughness": { "baseColorFactor": [1.0, 0.0, 0.0, 1.0], "roughnessFactor": 0.5 } } ],
+        "buffers": [ { "byteLength": 44, "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAABAAIAAAA=" } ],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+            { "buffer": 0, "byteOffset": 36, "byteLength": 6 }
+        ],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0] },
+            { "bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR" }
+        ]
+    }"#;
+
+    #[test]
+    fn embedded_triangle(){
+        let scene = GltfScene::from_slice(TRIANGLE.as_bytes(), None).unwrap();
+        assert_eq!(scene.meshes.len(), 1);
+        let prim = &scene.meshes[0].primitives[0];
+        assert_eq!(prim.data.vertices.len(), 3);
+        assert_eq!(prim.data.indices, Some(Indices::U16(vec![0, 1, 2])));
+        // normal yoktu, hesaplanmış olmalı
+        assert_eq!(prim.data.vertices[0].normal, [0.0, 0.0, 1.0]);
+        assert_eq!(prim.material, Some(0));
+        assert_eq!(scene.materials[0].base_color_factor, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(scene.materials[0].roughness_factor, 0.5);
+
+        assert_eq!(scene.roots, vec![0]);
+        assert_eq!(scene.nodes[1].parent, Some(0));
+        let world = scene.world_transforms();
+        assert_eq!(world[1].transform_point3(Vec3::ZERO), Vec3::new(1.0, 2.0, 0.0));
+    }
+
+    /// quad as a fan plus a point helper, `primitive` is added to the mesh
+    fn quad(primitive: &str) -> String {
+        let positions = [[0.0f32, 0.0, 0.0], [1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]];
+        let mut buffer: Vec<u8> = positions.iter().flatten().flat_map(|f| f.to_le_bytes()).collect();
+        buffer.extend([0u16, 1, 2, 3, 0, 1, 7, 0].iter().flat_map(|i| i.to_le_bytes()));
+        let data = base64::engine::general_purpose::STANDARD.encode(&buffer);
+        format!(r#"{{
+            "asset": {{ "version": "2.0" }},
+            "meshes": [ {{ "primitives": [
+                {{ "attributes": {{ "POSITION": 0 }}, "indices": 1, "mode": 6 }},
+                {{ "attributes": {{ "POSITION": 0 }}, "mode": 0 }}
+                {}
+            ] }} ],
+            "buffers": [ {{ "byteLength": 64, "uri": "data:application/octet-stream;base64,{}" }} ],
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 48 }},
+                {{ "buffer": 0, "byteOffset": 48, "byteLength": 8 }},
+                {{ "buffer": 0, "byteOffset": 56, "byteLength": 6 }}
+            ],
+            "accessors": [
+                {{ "bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3", "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0] }},
+                {{ "bufferView": 1, "componentType": 5123, "count": 4, "type": "SCALAR" }},
+                {{ "bufferView": 2, "componentType": 5123, "count": 3, "type": "SCALAR" }},
+                {{ "bufferView": 0, "componentType": 5126, "count": 2, "type": "VEC3" }}
+            ]
+        }}"#, primitive, data)
+    }
+
+    #[test]
+    fn modes_and_broken_primitives(){
+        let scene = GltfScene::from_slice(quad("").as_bytes(), None).unwrap();
+        let primitives = &scene.meshes[0].primitives;
+        assert_eq!(primitives.len(), 1);
+        assert_eq!(primitives[0].data.indices, Some(Indices::U16(vec![0, 1, 2, 0, 2, 3])));
+        assert_eq!(scene.warnings.len(), 1);
+
+        // panik yerine hata
+        let bad_index = quad(r#", { "attributes": { "POSITION": 0 }, "indices": 2 }"#);
+        assert!(matches!(GltfScene::from_slice(bad_index.as_bytes(), None), Err(GltfError::IndexOutOfRange { index: 7, .. })));
+        let short_normals = quad(r#", { "attributes": { "POSITION": 0, "NORMAL": 3 } }"#);
+        assert!(matches!(GltfScene::from_slice(short_normals.as_bytes(), None), Err(GltfError::AttributeCount { count: 2, expected: 4, .. })));
+    }
+}
@@ -0,0 +1,247 @@
+// src/engine/renderer/hot_reload.rs
+//
+// Shaders and textures edited while the game runs. A new shader source is compiled together
+// with every cached pipeline that uses it (and the particle simulation) inside a validation
+// error scope, and only replaces the old one when nothing failed. Natively the check blocks,
+// on the web it finishes in a later frame; either way the outcome comes out of
+// `take_shader_reloads`. Textures made from image assets are uploaded again when the image
+// changes, see `TextureAssets`.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use wgpu::{Device, Queue};
+
+use crate::engine::assets::{AssetId, AssetServer, Handle};
+use crate::engine::renderer::gltf_loader::ImageData;
+use crate::engine::renderer::particles::PARTICLE_SIM_SHADER;
+use crate::engine::renderer::pipeline_cache::ShaderCandidate;
+use crate::engine::renderer::texture::Texture;
+use crate::engine::renderer::SurfaceManager;
+
+/// A shader built but not installed yet
+struct ShaderReload {
+    candidate: ShaderCandidate,
+    simulation: Option<wgpu::ComputePipeline>,
+}
+
+/// Reload results not taken yet and, on the web, checks still running
+#[derive(Default)]
+pub(crate) struct ShaderReloads {
+    done: Vec<(&'static str, Result<(), String>)>,
+    #[cfg(target_family = "wasm")]
+    pending: Vec<(ShaderReload, std::rc::Rc<std::cell::RefCell<Option<Option<String>>>>)>,
+}
+
+impl SurfaceManager {
+    fn build_reload(&mut self, device: &Device, name: &'static str, source: &str) -> ShaderReload {
+        let candidate = self.pipeline_cache.build_candidate(device, name, source.to_string());
+        let simulation = if name == PARTICLE_SIM_SHADER {
+            self.particles.build_simulation(device, &mut self.pipeline_cache, &candidate.module)
+        } else {
+            None
+        };
+        ShaderReload { candidate, simulation }
+    }
+
+    fn install_reload(&mut self, reload: ShaderReload) {
+        self.pipeline_cache.install_candidate(reload.candidate);
+        if let Some(simulation) = reload.simulation {
+            self.particles.set_simulation(simulation);
+        }
+    }
+
+    /// Starts replacing the WGSL source of `name`. A source that doesn't compile or breaks a
+    /// pipeline is rejected with wgpu's message and the old one stays in use.
+    pub fn reload_shader(&mut self, device: &Device, name: &'static str, source: &str) {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let reload = self.build_reload(device, name, source);
+        let error = device.pop_error_scope();
+        #[cfg(not(target_family = "wasm"))]
+        {
+            let result = match pollster::block_on(error) {
+                Some(error) => Err(error.to_string()),
+                None => {
+                    self.install_reload(reload);
+                    Ok(())
+                }
+            };
+            self.shader_reloads.done.push((name, result));
+        }
+        #[cfg(target_family = "wasm")]
+        {
+            let slot = std::rc::Rc::new(std::cell::RefCell::new(None));
+            let filled = slot.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                *filled.borrow_mut() = Some(error.await.map(|e| e.to_string()));
+            });
+            self.shader_reloads.pending.push((reload, slot));
+        }
+    }
+
+    /// Outcomes of `reload_shader` known since the last call, successful ones are in use
+    pub fn take_shader_reloads(&mut self) -> Vec<(&'static str, Result<(), String>)> {
+        #[cfg(target_family = "wasm")]
+        {
+            let pending = std::mem::take(&mut self.shader_reloads.pending);
+            for (reload, slot) in pending {
+                let outcome = slot.borrow_mut().take();
+                match outcome {
+                    None => self.shader_reloads.pending.push((reload, slot)),
+                    Some(Some(error)) => self.shader_reloads.done.push((reload.candidate.name, Err(error))),
+                    Some(None) => {
+                        let name = reload.candidate.name;
+                        self.install_reload(reload);
+                        self.shader_reloads.done.push((name, Ok(())));
+                    }
+                }
+            }
+        }
+        std::mem::take(&mut self.shader_reloads.done)
+    }
+}
+
+struct TextureSlot {
+    srgb: bool,
+    label: String,
+    texture: Option<Arc<Texture>>,
+    /// loaded or changed image not uploaded yet
+    pending: Option<Arc<ImageData>>,
+    version: u32,
+}
+
+/// A texture made from an image asset. Ask for it with `get` when drawing: an edit of the
+/// same size is written into the same texture, a resized image gets a new one and the sprite
+/// batch builds a bind group for it.
+#[derive(Clone)]
+pub struct TextureAsset {
+    inner: Arc<Mutex<TextureSlot>>,
+}
+
+impl TextureAsset {
+    /// `None` until the image is loaded and uploaded
+    pub fn get(&self) -> Option<Arc<Texture>> {
+        self.inner.lock().unwrap().texture.clone()
+    }
+
+    /// counts the uploads, changes whenever the pixels do
+    pub fn version(&self) -> u32 {
+        self.inner.lock().unwrap().version
+    }
+
+    fn upload(&self, device: &Device, queue: &Queue) {
+        let mut slot = self.inner.lock().unwrap();
+        let Some(image) = slot.pending.take() else {
+            return;
+        };
+        let rewritten = slot.texture.as_ref().is_some_and(|texture| texture.rewrite(queue, &image));
+        if !rewritten {
+            slot.texture = Some(Arc::new(Texture::from_image(device, queue, &image, slot.srgb, &slot.label)));
+        }
+        slot.version += 1;
+    }
+}
+
+/// Image assets bound to textures by asset id, the way the engine binds shader sources
+#[derive(Default)]
+pub struct TextureAssets {
+    entries: Vec<(Handle<ImageData>, TextureAsset)>,
+}
+
+impl TextureAssets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts loading `path`, the texture follows the file from now on
+    pub fn bind<P: AsRef<Path>>(&mut self, assets: &AssetServer, path: P, srgb: bool) -> TextureAsset {
+        let handle = assets.load::<ImageData, _>(path.as_ref());
+        let texture = TextureAsset {
+            inner: Arc::new(Mutex::new(TextureSlot {
+                srgb,
+                label: path.as_ref().display().to_string(),
+                texture: None,
+                // zaten yüklüyse Loaded olayı bir daha gelmiyor
+                pending: assets.get(&handle),
+                version: 0,
+            })),
+        };
+        self.entries.push((handle, texture.clone()));
+        texture
+    }
+
+    /// Asset `id` was loaded or modified, its textures are uploaded by the next `upload`
+    pub fn image_changed(&mut self, assets: &AssetServer, id: AssetId) {
+        for (handle, texture) in self.entries.iter().filter(|(handle, _)| handle.id() == id) {
+            texture.inner.lock().unwrap().pending = assets.get(handle);
+        }
+    }
+
+    /// The textures belong to a device that is gone, every image is uploaded to the new one
+    pub fn device_changed(&mut self, assets: &AssetServer) {
+        for (handle, texture) in &self.entries {
+            let mut slot = texture.inner.lock().unwrap();
+            slot.texture = None;
+            slot.pending = assets.get(handle);
+        }
+    }
+
+    pub fn upload(&mut self, device: &Device, queue: &Queue) {
+        // kimsenin tutmadığı dokular bırakılıyor
+        self.entries.retain(|(_, texture)| Arc::strong_count(&texture.inner) > 1);
+        for (_, texture) in &self.entries {
+            texture.upload(device, queue);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    use crate::engine::assets::{AssetEvent, ImageLoader};
+
+    /// events until one about `id` matches
+    fn wait_for(server: &AssetServer, id: AssetId, modified: bool) {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(5) {
+            let found = server.update().iter().any(|e| match e {
+                AssetEvent::Loaded { id: loaded, .. } => !modified && *loaded == id,
+                AssetEvent::Modified { id: changed, .. } => modified && *changed == id,
+                _ => false,
+            });
+            if found {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        panic!("asset {} didn't load", id);
+    }
+
+    #[test]
+    fn modified_image_reaches_texture(){
+        let root = std::env::temp_dir().join(format!("texture_reload_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let image = |width: u32, rgba: [u8; 4]| ImageData { width, height: 1, rgba: rgba.repeat(width as usize) }.encode_png().unwrap();
+        std::fs::write(root.join("hero.png"), image(1, [255, 0, 0, 255])).unwrap();
+
+        let server = AssetServer::new(&root);
+        server.add_loader(ImageLoader);
+        let mut textures = TextureAssets::new();
+        let hero = textures.bind(&server, "hero.png", true);
+        let id = textures.entries[0].0.id();
+        wait_for(&server, id, false);
+        textures.image_changed(&server, id);
+        assert_eq!(hero.inner.lock().unwrap().pending.as_ref().unwrap().rgba, [255, 0, 0, 255]);
+
+        // dosya başka boyutta kaydediliyor
+        std::fs::write(root.join("hero.png"), image(2, [0, 0, 255, 255])).unwrap();
+        assert_eq!(server.reload("hero.png"), 1);
+        wait_for(&server, id, true);
+        textures.image_changed(&server, id);
+        let pending = hero.inner.lock().unwrap().pending.clone().unwrap();
+        assert_eq!((pending.width, &pending.rgba[4..]), (2, &[0, 0, 255, 255][..]));
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,180 @@
+// src/engine/renderer/instancing.rs
+
+use std::marker::PhantomData;
+use std::ops::Range;
+
+use glam::{Mat3, Mat4};
+use wgpu::{Device, Queue};
+
+use crate::engine::renderer::mesh::VertexType;
+use crate::engine::renderer::pipeline_cache::VertexLayoutKey;
+use crate::engine::renderer::stats;
+
+/// First shader location used by `InstanceData`, 0..=3 belongs to `Vertex3d`
+pub const INSTANCE_LOCATION: u32 = 4;
+
+/// Per-instance data of the instanced mesh pipelines, read from a second vertex buffer
+/// (webgl2'de storage buffer yok).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct InstanceData {
+    pub model: [[f32; 4]; 4],
+    /// inverse transpose of the upper 3x3, columns padded to vec4
+    pub normal: [[f32; 4]; 3],
+    pub color: [f32; 4],
+    /// not used by the built-in shaders, free for custom ones
+    pub custom: [f32; 4],
+}
+
+impl InstanceData {
+    pub fn new(transform: Mat4) -> Self {
+        let normal = Mat3::from_mat4(transform).inverse().transpose();
+        Self {
+            model: transform.to_cols_array_2d(),
+            normal: [
+                normal.x_axis.extend(0.0).to_array(),
+                normal.y_axis.extend(0.0).to_array(),
+                normal.z_axis.extend(0.0).to_array(),
+            ],
+            color: [1.0; 4],
+            custom: [0.0; 4],
+        }
+    }
+
+    pub fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_custom(mut self, custom: [f32; 4]) -> Self {
+        self.custom = custom;
+        self
+    }
+
+    pub fn transform(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.model)
+    }
+}
+
+impl VertexType for InstanceData {
+    fn layout() -> VertexLayoutKey {
+        let l = INSTANCE_LOCATION;
+        VertexLayoutKey {
+            array_stride: std::mem::size_of::<InstanceData>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: wgpu::vertex_attr_array![
+                l => Float32x4,
+                l + 1 => Float32x4,
+                l + 2 => Float32x4,
+                l + 3 => Float32x4,
+                l + 4 => Float32x4,
+                l + 5 => Float32x4,
+                l + 6 => Float32x4,
+                l + 7 => Float32x4,
+                l + 8 => Float32x4,
+            ].to_vec(),
+        }
+    }
+}
+
+/// A vertex buffer of per-instance data that grows to the next power of two
+/// when a frame needs more room than it has.
+pub struct InstanceBuffer<T: VertexType> {
+    buffer: wgpu::Buffer,
+    capacity: u64,
+    len: u32,
+    label: &'static str,
+    _marker: PhantomData<T>,
+}
+
+impl<T: VertexType> InstanceBuffer<T> {
+    pub fn new(device: &Device, label: &'static str, capacity: u64) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            buffer: Self::create(device, label, capacity),
+            capacity,
+            len: 0,
+            label,
+            _marker: PhantomData,
+        }
+    }
+
+    fn create(device: &Device, label: &str, capacity: u64) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size: capacity * std::mem::size_of::<T>() as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Uploads `data`, returns true when the buffer had to be recreated
+    pub fn write(&mut self, device: &Device, queue: &Queue, data: &[T]) -> bool {
+        let needed = data.len() as u64;
+        let grown = needed > self.capacity;
+        if grown {
+            self.capacity = needed.next_power_of_two();
+            self.buffer = Self::create(device, self.label, self.capacity);
+        }
+        if !data.is_empty() {
+            queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(data));
+            stats::upload(std::mem::size_of_val(data));
+        }
+        self.len = data.len() as u32;
+        grown
+    }
+
+    pub fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    pub fn bind(&self, render_pass: &mut wgpu::RenderPass<'_>, slot: u32) {
+        render_pass.set_vertex_buffer(slot, self.buffer.slice(..));
+    }
+}
+
+/// Splits already sorted keys into runs of equal neighbours, every run becomes one instanced draw
+pub fn batch_ranges<K: PartialEq>(keys: impl IntoIterator<Item = K>) -> Vec<Range<u32>> {
+    let mut batches: Vec<Range<u32>> = Vec::new();
+    let mut last = None;
+    for (i, key) in keys.into_iter().enumerate() {
+        let i = i as u32;
+        match batches.last_mut() {
+            Some(batch) if last.as_ref() == Some(&key) => batch.end = i + 1,
+            _ => batches.push(i..i + 1),
+        }
+        last = Some(key);
+    }
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec3;
+
+    #[test]
+    fn batches_and_normals(){
+        assert_eq!(batch_ranges([1, 1, 2, 2, 2, 1]), vec![0..2, 2..5, 5..6]);
+        assert!(batch_ranges(Vec::<u8>::new()).is_empty());
+
+        // ölçek normali ters yönde etkilemeli
+        let data = InstanceData::new(Mat4::from_scale(Vec3::new(2.0, 1.0, 1.0)));
+        assert_eq!(data.normal[0][0], 0.5);
+        assert_eq!(data.normal[1][1], 1.0);
+        assert_eq!(std::mem::size_of::<InstanceData>(), 144);
+        assert_eq!(InstanceData::layout().attributes.last().unwrap().shader_location, 12);
+    }
+}
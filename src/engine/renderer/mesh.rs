@@ -0,0 +1,337 @@
+// src/engine/renderer/mesh.rs
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use glam::Mat4;
+use wgpu::util::DeviceExt;
+use wgpu::{Device, Queue};
+
+use crate::engine::renderer::camera::CameraUniform;
+use crate::engine::renderer::instancing::{batch_ranges, InstanceBuffer, InstanceData};
+use crate::engine::renderer::pipeline_cache::{PipelineCache, PipelineKey, ShaderVariant, VertexLayoutKey};
+use crate::engine::renderer::stats;
+use crate::engine::renderer::targets::TargetState;
+
+/// Vertex types that can be put into a vertex buffer
+pub trait VertexType: bytemuck::Pod {
+    fn layout() -> VertexLayoutKey;
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Vertex3d {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub uv: [f32; 2],
+    /// xyz tangent, w bitangent yönü (glTF ile aynı)
+    pub tangent: [f32; 4],
+}
+
+impl Vertex3d {
+    pub fn new(position: [f32; 3], normal: [f32; 3], uv: [f32; 2]) -> Self {
+        Self {
+            position,
+            normal,
+            uv,
+            tangent: [1.0, 0.0, 0.0, 1.0],
+        }
+    }
+}
+
+impl VertexType for Vertex3d {
+    fn layout() -> VertexLayoutKey {
+        VertexLayoutKey {
+            array_stride: std::mem::size_of::<Vertex3d>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: wgpu::vertex_attr_array![
+                0 => Float32x3,
+                1 => Float32x3,
+                2 => Float32x2,
+                3 => Float32x4,
+            ].to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Indices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl Indices {
+    pub fn len(&self) -> usize {
+        match self {
+            Indices::U16(i) => i.len(),
+            Indices::U32(i) => i.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn format(&self) -> wgpu::IndexFormat {
+        match self {
+            Indices::U16(_) => wgpu::IndexFormat::Uint16,
+            Indices::U32(_) => wgpu::IndexFormat::Uint32,
+        }
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Indices::U16(i) => bytemuck::cast_slice(i),
+            Indices::U32(i) => bytemuck::cast_slice(i),
+        }
+    }
+
+    /// picks u16 when every index fits, webgl'de u32 index desteği zaten var ama yarı boyut
+    pub fn from_u32(indices: Vec<u32>) -> Self {
+        if indices.iter().all(|i| *i <= u16::MAX as u32) {
+            Indices::U16(indices.into_iter().map(|i| i as u16).collect())
+        } else {
+            Indices::U32(indices)
+        }
+    }
+
+    pub fn iter(&self) -> Box<dyn Iterator<Item = u32> + '_> {
+        match self {
+            Indices::U16(i) => Box::new(i.iter().map(|x| *x as u32)),
+            Indices::U32(i) => Box::new(i.iter().copied()),
+        }
+    }
+}
+
+/// CPU side geometry, kept around so the GPU mesh can be rebuilt
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshData<V: VertexType> {
+    pub vertices: Vec<V>,
+    pub indices: Option<Indices>,
+}
+
+impl<V: VertexType> MeshData<V> {
+    pub fn new(vertices: Vec<V>, indices: Option<Indices>) -> Self {
+        Self { vertices, indices }
+    }
+}
+
+impl MeshData<Vertex3d> {
+    /// unit cube centered at the origin
+    pub fn cube() -> Self {
+        // (normal, tangent, u ekseni, v ekseni)
+        let faces: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+            ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+            ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+            ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+            ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+            ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+            ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+        ];
+        let mut vertices = Vec::with_capacity(24);
+        let mut indices = Vec::with_capacity(36);
+        for (n, t, b) in faces {
+            let base = vertices.len() as u32;
+            for (su, sv) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+                let p = [
+                    0.5 * (n[0] + su * t[0] + sv * b[0]),
+                    0.5 * (n[1] + su * t[1] + sv * b[1]),
+                    0.5 * (n[2] + su * t[2] + sv * b[2]),
+                ];
+                let mut v = Vertex3d::new(p, n, [(su + 1.0) * 0.5, (1.0 - sv) * 0.5]);
+                v.tangent = [t[0], t[1], t[2], 1.0];
+                vertices.push(v);
+            }
+            indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+        Self::new(vertices, Some(Indices::from_u32(indices)))
+    }
+}
+
+/// Geometry uploaded to the GPU
+pub struct Mesh {
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: Option<(wgpu::Buffer, wgpu::IndexFormat)>,
+    pub vertex_count: u32,
+    pub index_count: u32,
+    pub layout: VertexLayoutKey,
+}
+
+impl Mesh {
+    pub fn new<V: VertexType>(device: &Device, data: &MeshData<V>) -> Self {
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Mesh Vertex Buffer"),
+            contents: bytemuck::cast_slice(&data.vertices),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+        let index_buffer = data.indices.as_ref().map(|indices| {
+            let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Mesh Index Buffer"),
+                contents: indices.bytes(),
+                usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+            });
+            (buffer, indices.format())
+        });
+
+        Self {
+            vertex_buffer,
+            index_buffer,
+            vertex_count: data.vertices.len() as u32,
+            index_count: data.indices.as_ref().map(|i| i.len() as u32).unwrap_or(0),
+            layout: V::layout(),
+        }
+    }
+
+    /// number of triangles, assumes a triangle list
+    pub fn triangle_count(&self) -> u32 {
+        if self.index_buffer.is_some() { self.index_count / 3 } else { self.vertex_count / 3 }
+    }
+
+    pub fn draw(&self, render_pass: &mut wgpu::RenderPass<'_>, instances: Range<u32>) {
+        stats::draw(self.triangle_count() as u64 * instances.len() as u64);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        match &self.index_buffer {
+            Some((buffer, format)) => {
+                render_pass.set_index_buffer(buffer.slice(..), *format);
+                render_pass.draw_indexed(0..self.index_count, 0, instances);
+            }
+            None => render_pass.draw(0..self.vertex_count, instances),
+        }
+    }
+}
+
+pub struct MeshDraw {
+    pub mesh: Arc<Mesh>,
+    pub instance: InstanceData,
+}
+
+/// Draws queued meshes with the unlit mesh shader. Draws are collected during the frame
+/// and flushed by `SurfaceManager::render`, draws of the same mesh become one instanced call.
+pub struct MeshRenderer {
+    camera_buffer: wgpu::Buffer,
+    camera_bind_group: wgpu::BindGroup,
+    instances: InstanceBuffer<InstanceData>,
+    draws: Vec<MeshDraw>,
+    batches: Vec<Range<u32>>,
+}
+
+pub const MESH_SHADER: &str = "mesh_unlit";
+const MESH_SHADER_SOURCE: &str = include_str!("../../../gsl/mesh_unlit.wgsl");
+
+impl MeshRenderer {
+    pub fn new(device: &Device, cache: &mut PipelineCache) -> Self {
+        cache.register_shader(MESH_SHADER, MESH_SHADER_SOURCE);
+
+        let camera_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Camera Buffer"),
+            size: std::mem::size_of::<CameraUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let camera_layout = cache.bind_group_layout(device, &Self::camera_entries());
+        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("camera_bind_group"),
+            layout: &camera_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: camera_buffer.as_entire_binding(),
+            }],
+        });
+
+        Self {
+            camera_buffer,
+            camera_bind_group,
+            instances: InstanceBuffer::new(device, "Mesh Instance Buffer", 64),
+            draws: Vec::new(),
+            batches: Vec::new(),
+        }
+    }
+
+    pub fn camera_entries() -> Vec<wgpu::BindGroupLayoutEntry> {
+        vec![wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }]
+    }
+
+    pub fn queue_draw(&mut self, mesh: Arc<Mesh>, transform: Mat4, color: [f32; 4]) {
+        self.draws.push(MeshDraw { mesh, instance: InstanceData::new(transform).with_color(color) });
+    }
+
+    /// queues many copies of one mesh, drawn with a single call
+    pub fn queue_instances(&mut self, mesh: &Arc<Mesh>, instances: impl IntoIterator<Item = InstanceData>) {
+        self.draws.extend(instances.into_iter().map(|instance| MeshDraw { mesh: mesh.clone(), instance }));
+    }
+
+    /// queued instances of this frame
+    pub fn draw_count(&self) -> usize {
+        self.draws.len()
+    }
+
+    /// draw calls of the last prepared frame
+    pub fn batch_count(&self) -> usize {
+        self.batches.len()
+    }
+
+    pub fn pipeline_key(target: &TargetState) -> PipelineKey {
+        let mut key = target.pipeline_key(ShaderVariant::new(MESH_SHADER));
+        key.bind_groups = vec![Self::camera_entries()];
+        key.vertex_layouts = vec![Vertex3d::layout(), InstanceData::layout()];
+        key
+    }
+
+    /// uploads camera and instance data, must be called before the render pass starts
+    pub fn prepare(&mut self, device: &Device, queue: &Queue, camera: CameraUniform) {
+        queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[camera]));
+        stats::upload(std::mem::size_of::<CameraUniform>());
+        // aynı mesh'ler yan yana gelsin, sıralama stabil
+        self.draws.sort_by_key(|d| Arc::as_ptr(&d.mesh) as usize);
+        self.batches = batch_ranges(self.draws.iter().map(|d| Arc::as_ptr(&d.mesh)));
+        let data: Vec<InstanceData> = self.draws.iter().map(|d| d.instance).collect();
+        self.instances.write(device, queue, &data);
+    }
+
+    /// records the queued draws and clears the queue for the next frame
+    pub fn draw(&mut self, render_pass: &mut wgpu::RenderPass<'_>, pipeline: &wgpu::RenderPipeline) {
+        if self.draws.is_empty() {
+            return;
+        }
+        render_pass.set_pipeline(pipeline);
+        stats::pipeline();
+        render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+        self.instances.bind(render_pass, 1);
+        for batch in &self.batches {
+            self.draws[batch.start as usize].mesh.draw(render_pass, batch.clone());
+        }
+        self.draws.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cube_is_closed(){
+        let cube = MeshData::cube();
+        assert_eq!(cube.vertices.len(), 24);
+        let indices = cube.indices.unwrap();
+        assert_eq!(indices.format(), wgpu::IndexFormat::Uint16);
+        assert_eq!(indices.len(), 36);
+        // her üçgen dışarı bakmalı (ccw)
+        let idx: Vec<u32> = indices.iter().collect();
+        for tri in idx.chunks(3) {
+            let a = glam::Vec3::from(cube.vertices[tri[0] as usize].position);
+            let b = glam::Vec3::from(cube.vertices[tri[1] as usize].position);
+            let c = glam::Vec3::from(cube.vertices[tri[2] as usize].position);
+            let n = glam::Vec3::from(cube.vertices[tri[0] as usize].normal);
+            assert!((b - a).cross(c - a).dot(n) > 0.0);
+        }
+    }
+}
@@ -1,9 +1,59 @@
 // src/engine/renderer/mod.rs
 
+use std::sync::{Arc, Mutex};
+
 use wgpu::{Instance, Surface, Adapter, Device, Queue, SurfaceConfiguration, SurfaceCapabilities};
 use wgpu::util::DeviceExt; // create_buffer_init için gerekli
 use crate::engine::window::GameWindow;
 
+pub mod pipeline_cache;
+pub mod camera;
+pub mod mesh;
+pub mod gltf_loader;
+pub mod settings;
+pub mod targets;
+pub mod texture;
+pub mod pbr;
+pub mod shadow;
+pub mod post;
+pub mod sprite;
+pub mod text;
+pub mod debug_draw;
+pub mod instancing;
+pub mod particles;
+pub mod tiled;
+pub mod tilemap;
+pub mod capture;
+pub mod stats;
+pub mod recovery;
+pub mod adapter;
+pub mod hot_reload;
+pub mod atlas;
+pub mod sprite_sheet;
+pub use pipeline_cache::{PipelineCache, PipelineKey, ShaderCandidate, ShaderVariant, VertexLayoutKey, BlendMode, DepthKey};
+pub use camera::{Camera, CameraUniform, Projection};
+pub use mesh::{Indices, Mesh, MeshData, MeshRenderer, Vertex3d, VertexType};
+pub use gltf_loader::{GltfError, GltfMaterial, GltfScene, GpuModel};
+pub use settings::{ColorPreference, DepthFormat, PostProcessSettings, RendererSettings, ShadowSettings, Tonemapper, VsyncMode};
+pub use targets::{Attachment, RenderTargets, TargetState};
+pub use texture::Texture;
+pub use pbr::{Light, Material, PbrMaterial, PbrModel, PbrRenderer};
+pub use shadow::ShadowMaps;
+pub use post::{LutError, PostProcess};
+pub use sprite::{Rect, Sprite, SpriteBatch, SpriteVertex};
+pub use text::{Font, FontError, FontId, TextAlign, TextLayout, TextRenderer, TextSection};
+pub use debug_draw::DebugRenderer;
+pub use instancing::{InstanceBuffer, InstanceData};
+pub use particles::{Burst, Curve, EmitterId, EmitterShape, ParticleEmitter, ParticleSystem};
+pub use tiled::{Layer, MapObject, ObjectLayer, ObjectShape, PropertyValue, TileLayer, TiledError, TiledMap, Tileset};
+pub use tilemap::{Tilemap, TilemapId, TilemapRenderer};
+pub use capture::{CaptureError, CaptureTarget, FrameCapture};
+pub use stats::{FrameStats, PassTime, RenderStats};
+pub use recovery::{GraphicsEvent, RendererState};
+pub use adapter::{Backend, GraphicsConfig, GraphicsError, PowerPreference};
+pub use hot_reload::{TextureAsset, TextureAssets};
+pub use atlas::{AnimationDirection, AtlasBuilder, AtlasError, AtlasManifest, AtlasRegion, SpriteAnimation, SpriteFrame, TextureAtlas};
+
 // Shader'daki Uniforms yapısıyla birebir eşleşmeli ve 16-byte hizalı olmalı
 #[repr(C)]
 #[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
@@ -28,37 +78,57 @@ pub struct GraphicsContext {
     adapter: Adapter,
     pub device: Device,
     pub queue: Queue,
+    /// set by the device lost callback
+    lost: Arc<Mutex<Option<String>>>,
 }
 
 pub struct SurfaceManager {
     surface: Surface<'static>,
     config: SurfaceConfiguration,
+    surface_caps: SurfaceCapabilities,
+    settings: RendererSettings,
+    pub targets: RenderTargets,
     pub size: (u32, u32),
-    render_pipeline: wgpu::RenderPipeline,
-    
+    pub pipeline_cache: PipelineCache,
+    pipeline_key: PipelineKey,
+
     // YENİ EKLENENLER:
     uniform_buffer: wgpu::Buffer,
     bind_group: wgpu::BindGroup,
     uniforms: Uniforms, // CPU tarafındaki veriyi tutmak için
+
+    pub camera: Camera,
+    pub mesh_renderer: MeshRenderer,
+    pub pbr: PbrRenderer,
+    pub post: PostProcess,
+    /// screen space, drawn over the post-processed frame
+    pub sprites: SpriteBatch,
+    /// world space with the camera, drawn in the main pass after the tilemaps
+    pub world_sprites: SpriteBatch,
+    pub text: TextRenderer,
+    pub debug: DebugRenderer,
+    pub particles: ParticleSystem,
+    pub tilemaps: TilemapRenderer,
+    pub capture: FrameCapture,
+    pub stats: RenderStats,
+    shader_reloads: hot_reload::ShaderReloads,
 }
 
 impl GraphicsContext {
-    pub async fn new() -> Self {
+    pub async fn new(config: &GraphicsConfig) -> Result<Self, GraphicsError> {
         let instance = Instance::new(&wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::PRIMARY,
+            backends: config.wgpu_backends(),
             ..Default::default()
         });
 
-        let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::default(),
-            compatible_surface: None,
-            force_fallback_adapter: false,
-        }).await.expect("Uygun grafik adaptörü bulunamadı!");
+        let adapter = adapter::select_adapter(&instance, config).await?;
 
+        // GPU süre ölçümü için, yoksa istatistikler sadece CPU tarafını gösterir
+        let timestamps = wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS;
         let (device, queue) = adapter.request_device(
             &wgpu::DeviceDescriptor {
                 label: Some("RealGameEngine Device"),
-                required_features: wgpu::Features::empty(),
+                required_features: adapter.features() & timestamps,
                 required_limits: if cfg!(target_arch = "wasm32") {
                     wgpu::Limits::downlevel_webgl2_defaults()
                 } else {
@@ -66,18 +136,40 @@ impl GraphicsContext {
                 },
                 ..Default::default()
             },
-        ).await.expect("Device oluşturulamadı");
+        ).await?;
+
+        let lost = Arc::new(Mutex::new(None));
+        let flag = lost.clone();
+        device.set_device_lost_callback(move |reason, message| {
+            *flag.lock().unwrap() = Some(format!("{:?}: {}", reason, message));
+        });
+        // kayıp cihazdaki her çağrı hata veriyor, yeniden oluşturulana kadar yok sayılıyor
+        let flag = lost.clone();
+        device.on_uncaptured_error(Arc::new(move |error| {
+            if flag.lock().unwrap().is_none() {
+                panic!("wgpu error: {}", error);
+            }
+        }));
+
+        Ok(Self { instance, adapter, device, queue, lost })
+    }
 
-        Self { instance, adapter, device, queue }
+    /// Why the device was lost. Everything created on it has to be recreated.
+    pub fn device_lost(&self) -> Option<String> {
+        self.lost.lock().unwrap().clone()
     }
     
-    pub fn create_surface_manager(&self, window: &GameWindow) -> SurfaceManager {
-        SurfaceManager::new(&self.instance, &self.adapter, window, &self.device)
+    pub fn adapter(&self) -> &Adapter {
+        &self.adapter
+    }
+
+    pub fn create_surface_manager(&self, window: &GameWindow, settings: RendererSettings) -> Result<SurfaceManager, GraphicsError> {
+        SurfaceManager::new(&self.instance, &self.adapter, window, &self.device, &self.queue, settings)
     }
 }
 
 impl SurfaceManager {
-    fn new(instance: &Instance, adapter: &Adapter, window: &GameWindow, device: &Device) -> Self {
+    fn new(instance: &Instance, adapter: &Adapter, window: &GameWindow, device: &Device, queue: &Queue, settings: RendererSettings) -> Result<Self, GraphicsError> {
         let size = window.inner.size();
         
         let surface = unsafe {
@@ -85,40 +177,45 @@ impl SurfaceManager {
             {
                 use wasm_bindgen::JsCast;
                 use wgpu::web_sys;
-                let win = web_sys::window().unwrap();
-                let doc = win.document().unwrap();
-                let canvas = doc.get_element_by_id("canvas")
-                    .expect("Canvas elementi bulunamadi!")
-                    .dyn_into::<web_sys::HtmlCanvasElement>()
-                    .unwrap();
-                instance.create_surface(wgpu::SurfaceTarget::Canvas(canvas)).unwrap()
+                let canvas = web_sys::window()
+                    .and_then(|win| win.document())
+                    .and_then(|doc| doc.get_element_by_id("canvas"))
+                    .and_then(|element| element.dyn_into::<web_sys::HtmlCanvasElement>().ok())
+                    .ok_or_else(|| GraphicsError::CreateSurface("Canvas elementi bulunamadi!".to_string()))?;
+                instance.create_surface(wgpu::SurfaceTarget::Canvas(canvas))?
             }
             #[cfg(not(target_arch = "wasm32"))]
             {
-                let target = wgpu::SurfaceTargetUnsafe::from_window(window).unwrap();
-                instance.create_surface_unsafe(target).unwrap()
+                let target = wgpu::SurfaceTargetUnsafe::from_window(window)
+                    .map_err(|e| GraphicsError::CreateSurface(e.to_string()))?;
+                instance.create_surface_unsafe(target)?
             }
         };
 
-        let surface_caps = surface.get_capabilities(&adapter);
-        let surface_format = surface_caps.formats.iter()
-            .copied()
-            .find(|f| f.is_srgb())
-            .unwrap_or(surface_caps.formats[0]);
+        let surface_caps = surface.get_capabilities(adapter);
+        if surface_caps.formats.is_empty() {
+            return Err(GraphicsError::SurfaceUnsupported(adapter::describe_adapter(&adapter.get_info())));
+        }
+        let surface_format = settings.color.surface_format(&surface_caps);
 
+        // asıl ayarlar aşağıda apply_settings ile yapılıyor
+        // ekran görüntüsü için swapchain'den kopyalayabilmek gerekiyor
+        let copy_src = surface_caps.usages.contains(wgpu::TextureUsages::COPY_SRC);
         let config = SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage: if copy_src {
+                wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC
+            } else {
+                wgpu::TextureUsages::RENDER_ATTACHMENT
+            },
             format: surface_format,
             width: size.0.max(1),
             height: size.1.max(1),
-            present_mode: surface_caps.present_modes[0],
+            present_mode: settings.vsync.present_mode(&surface_caps),
             alpha_mode: surface_caps.alpha_modes[0],
             view_formats: vec![],
-            desired_maximum_frame_latency: 2,
+            desired_maximum_frame_latency: settings.frame_latency.max(1),
         };
 
-        surface.configure(device, &config);
-
         // --- UNIFORM HAZIRLIĞI ---
         let mut uniforms = Uniforms::new();
         // İstersen burada başlangıç pozisyonu verebilirsin
@@ -132,21 +229,11 @@ impl SurfaceManager {
             }
         );
 
-        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            entries: &[
-                wgpu::BindGroupLayoutEntry {
-                    binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX, // Hem vertex hem fragment kullanıyorsa VERTEX | FRAGMENT
-                    ty: wgpu::BindingType::Buffer {
-                        ty: wgpu::BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                }
-            ],
-            label: Some("uniform_bind_group_layout"),
-        });
+        // --- PIPELINE HAZIRLIĞI ---
+        let mut pipeline_cache = PipelineCache::new(config.format);
+        pipeline_cache.register_shader(TRIANGLE_SHADER, SHADER_SOURCE);
+
+        let bind_group_layout = pipeline_cache.bind_group_layout(device, &uniform_entries());
 
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &bind_group_layout,
@@ -159,66 +246,96 @@ impl SurfaceManager {
             label: Some("uniform_bind_group"),
         });
 
-        // --- PIPELINE HAZIRLIĞI ---
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Shader"),
-            source: wgpu::ShaderSource::Wgsl(SHADER_SOURCE.into()),
-        });
-
-        let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("Render Pipeline Layout"),
-            bind_group_layouts: &[&bind_group_layout], // Layout burada ekleniyor
-            push_constant_ranges: &[],
-        });
-
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Render Pipeline"),
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"), // Option<str> oldu yeni versiyonlarda
-                buffers: &[],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleStrip,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: Some(wgpu::Face::Back),
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-
-            multiview: None,
-            cache: None,
-        });
+        let mesh_renderer = MeshRenderer::new(device, &mut pipeline_cache);
+        let pbr = PbrRenderer::new(device, queue, &mut pipeline_cache);
+        let post = PostProcess::new(device, queue, &mut pipeline_cache);
+        let sprites = SpriteBatch::new(device, queue, &mut pipeline_cache);
+        let world_sprites = SpriteBatch::new(device, queue, &mut pipeline_cache);
+        let text = TextRenderer::new(device);
+        let debug = DebugRenderer::new(device, &mut pipeline_cache);
+        let particles = ParticleSystem::new(device, queue, &mut pipeline_cache);
+        let tilemaps = TilemapRenderer::new(device, &mut pipeline_cache);
 
-        Self {
+        let target_state = TargetState {
+            format: config.format,
+            sample_count: 1,
+            depth_format: None,
+        };
+        let mut sm = Self {
             surface,
             config,
+            surface_caps,
+            settings,
+            targets: RenderTargets::new(device, size, target_state),
             size,
-            render_pipeline,
+            pipeline_cache,
+            pipeline_key: triangle_pipeline_key(&target_state),
             uniform_buffer,
             bind_group,
             uniforms,
-        }
+            camera: Camera::default(),
+            mesh_renderer,
+            pbr,
+            post,
+            sprites,
+            world_sprites,
+            text,
+            debug,
+            particles,
+            tilemaps,
+            capture: FrameCapture::new(copy_src),
+            stats: RenderStats::new(device, queue),
+            shader_reloads: Default::default(),
+        };
+        sm.apply_settings(adapter, device, settings);
+        // ilk kareyi beklemeden pipeline'ı şimdiden derle
+        sm.pipeline_cache.pipeline(device, &sm.pipeline_key);
+        Ok(sm)
+    }
+
+    pub fn settings(&self) -> RendererSettings {
+        self.settings
+    }
+
+    /// Reconfigures the surface and recreates the depth / msaa attachments.
+    /// Values the adapter can't do are clamped, the settings actually in use are returned.
+    pub fn apply_settings(&mut self, adapter: &Adapter, device: &Device, settings: RendererSettings) -> RendererSettings {
+        let format = settings.color.surface_format(&self.surface_caps);
+        let depth_format = settings.depth_format.map(|d| d.to_wgpu());
+
+        // post-processing açıksa ana geçiş swapchain'e değil HDR hedefe çiziyor
+        let main_format = if settings.post.enabled { post::hdr_format(adapter) } else { format };
+
+        let mut effective = settings;
+        effective.msaa_samples = supported_sample_count(adapter, main_format, depth_format, settings.msaa_samples);
+        effective.frame_latency = settings.frame_latency.max(1);
+
+        self.config.format = format;
+        self.config.present_mode = settings.vsync.present_mode(&self.surface_caps);
+        self.config.desired_maximum_frame_latency = effective.frame_latency;
+        self.surface.configure(device, &self.config);
+
+        // format değiştiyse eski pipeline'lar artık kullanılamaz
+        self.pipeline_cache.set_surface_format(format);
+        let state = TargetState {
+            format: main_format,
+            sample_count: effective.msaa_samples,
+            depth_format,
+        };
+        self.targets = RenderTargets::new(device, self.size, state);
+        self.pipeline_key = triangle_pipeline_key(&state);
+        self.pbr.set_shadow_settings(device, &mut self.pipeline_cache, settings.shadows);
+        effective.shadows = self.pbr.shadow_settings();
+        let surface_size = (self.config.width, self.config.height);
+        self.post.configure(device, &mut self.pipeline_cache, settings.post, surface_size, main_format, format);
+        // tone mapping post-processing'de yapılıyor
+        self.pbr.tonemap = !settings.post.enabled;
+        self.settings = effective;
+        effective
+    }
+
+    pub fn aspect(&self) -> f32 {
+        self.config.width as f32 / self.config.height as f32
     }
 
     pub fn resize(&mut self, new_size: (u32, u32), device: &Device) {
@@ -227,11 +344,14 @@ impl SurfaceManager {
             self.config.width = new_size.0;
             self.config.height = new_size.1;
             self.surface.configure(device, &self.config); 
+            self.targets = RenderTargets::new(device, new_size, self.targets.state);
+            self.post.resize(device, &mut self.pipeline_cache, new_size);
         }
     }
 
     pub fn render(&mut self, device: &Device, queue: &Queue) -> Result<(), wgpu::SurfaceError> {
         let output = self.surface.get_current_texture()?;
+        self.stats.begin_frame();
         let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
 
         // Animasyon için zamanı güncelle
@@ -239,40 +359,127 @@ impl SurfaceManager {
         
         // GPU'ya yeni veriyi yükle
         queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[self.uniforms]));
+        stats::upload(std::mem::size_of::<Uniforms>());
+
+        let render_pipeline = self.pipeline_cache.pipeline(device, &self.pipeline_key);
+        let mesh_pipeline = self.pipeline_cache.pipeline(device, &MeshRenderer::pipeline_key(&self.targets.state));
+        let aspect = self.aspect();
+        self.mesh_renderer.prepare(device, queue, CameraUniform::new(&self.camera, aspect));
+        self.pbr.prepare(device, queue, &self.camera, aspect);
+        self.tilemaps.prepare(device, queue, self.camera.view_proj(aspect));
+        self.world_sprites.projection = Some(self.camera.view_proj(aspect));
+        self.world_sprites.prepare(device, queue, (self.config.width, self.config.height));
 
         let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Render Encoder"),
         });
+        self.stats.mark(&mut encoder, "shadows");
+        self.pbr.render_shadows(device, &mut self.pipeline_cache, &mut encoder);
+        self.stats.mark(&mut encoder, "particles");
+        self.particles.prepare(device, queue, &mut self.pipeline_cache, &mut encoder, &self.camera, aspect);
 
+        self.stats.mark(&mut encoder, "main");
         {
+            let target = self.post.target_view().unwrap_or(&view);
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
+                color_attachments: &[Some(self.targets.color_attachment(target, self.settings.clear_color()))],
+                depth_stencil_attachment: self.targets.depth_attachment(),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(&render_pipeline);
+            stats::pipeline();
+            // Binding'i (Group 0) pipeline'a bağla
+            render_pass.set_bind_group(0, &self.bind_group, &[]); 
+            render_pass.draw(0..3, 0..1);
+            stats::draw(1);
+
+            // tilemap'ler derinliğe yazmıyor, 3D sahne her zaman önünde
+            self.tilemaps.draw(device, &mut self.pipeline_cache, &self.targets.state, &mut render_pass);
+            self.world_sprites.draw_world(device, &mut self.pipeline_cache, &self.targets.state, &mut render_pass);
+            self.mesh_renderer.draw(&mut render_pass, &mesh_pipeline);
+            self.pbr.draw(device, &mut self.pipeline_cache, &self.targets.state, &mut render_pass);
+            // saydam oldukları için opak geometriden sonra
+            self.particles.draw(device, &mut self.pipeline_cache, &self.targets.state, &mut render_pass);
+        }
+        self.stats.mark(&mut encoder, "post");
+        self.post.run(device, queue, &mut self.pipeline_cache, &mut encoder, &view);
+
+        // debug çizgileri ve 2D arayüz post-processing'den sonra, doğrudan swapchain'e çiziliyor
+        self.debug.prepare(device, queue, self.camera.view_proj(aspect));
+        self.stats.queue_overlay(device, queue, &mut self.text, &mut self.sprites);
+        self.stats.mark(&mut encoder, "overlay");
+        if !self.sprites.is_empty() || self.debug.line_count() > 0 {
+            self.sprites.prepare(device, queue, (self.config.width, self.config.height));
+            let mut overlay_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Overlay Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view: &view,
                     resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK ),
-                        store: wgpu::StoreOp::Store,
-                    },
+                    ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: wgpu::StoreOp::Store },
                     depth_slice: None,
                 })],
                 depth_stencil_attachment: None,
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
-
-            render_pass.set_pipeline(&self.render_pipeline);
-            // Binding'i (Group 0) pipeline'a bağla
-            render_pass.set_bind_group(0, &self.bind_group, &[]); 
-            render_pass.draw(0..3, 0..1);
+            self.debug.draw(device, &mut self.pipeline_cache, self.config.format, &mut overlay_pass);
+            self.sprites.draw(device, &mut self.pipeline_cache, self.config.format, &mut overlay_pass);
         }
 
+        self.stats.end_frame(&mut encoder);
+        if self.capture.wants_frame() {
+            self.capture.record(device, &mut encoder, &output.texture);
+        }
         queue.submit(std::iter::once(encoder.finish()));
+        self.capture.after_submit(device);
+        self.stats.after_submit(device);
         output.present();
 
         Ok(())
     }
 }
 
+fn uniform_entries() -> Vec<wgpu::BindGroupLayoutEntry> {
+    vec![
+        wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX, // Hem vertex hem fragment kullanıyorsa VERTEX | FRAGMENT
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }
+    ]
+}
+
+fn triangle_pipeline_key(target: &TargetState) -> PipelineKey {
+    let mut key = target.pipeline_key(ShaderVariant::new(TRIANGLE_SHADER));
+    key.bind_groups = vec![uniform_entries()];
+    key.topology = wgpu::PrimitiveTopology::TriangleStrip;
+    // arka plan üçgeni derinliğe yazmasın, mesh'ler her zaman önünde kalsın
+    if let Some(depth) = key.depth.as_mut() {
+        depth.write = false;
+        depth.compare = wgpu::CompareFunction::Always;
+    }
+    key
+}
+
+/// highest sample count up to `requested` that both the color and the depth format support
+fn supported_sample_count(adapter: &Adapter, format: wgpu::TextureFormat, depth: Option<wgpu::TextureFormat>, requested: u32) -> u32 {
+    let supports = |f: wgpu::TextureFormat, count: u32| {
+        adapter.get_texture_format_features(f).flags.sample_count_supported(count)
+    };
+    [16, 8, 4, 2].into_iter()
+        .filter(|c| *c <= requested)
+        .find(|c| supports(format, *c) && depth.is_none_or(|d| supports(d, *c)))
+        .unwrap_or(1)
+}
+
 // Dosya yolu structure'a göre ayarlanmalı. Eğer proje kökünden çalışıyorsan bu yol doğru olabilir.
+const TRIANGLE_SHADER: &str = "triangle_anim";
 const SHADER_SOURCE: &str = include_str!("../../../gsl/triangle_anim.wgsl");
\ No newline at end of file
@@ -0,0 +1,937 @@
+// src/engine/renderer/particles.rs
+//
+// Particle emitters simulated in a compute pass and drawn as instanced billboards.
+// Devices without compute shaders (webgl2) run the same simulation on the CPU and
+// upload the result, the render path is the same for both.
+
+use std::ops::{Add, Mul};
+use std::sync::Arc;
+
+use glam::{Vec3, Vec4};
+use wgpu::{Device, Queue};
+
+use crate::engine::renderer::camera::Camera;
+use crate::engine::renderer::gltf_loader::ImageData;
+use crate::engine::renderer::mesh::VertexType;
+use crate::engine::renderer::pipeline_cache::{BlendMode, PipelineCache, PipelineKey, ShaderVariant, VertexLayoutKey};
+use crate::engine::renderer::stats;
+use crate::engine::renderer::targets::TargetState;
+use crate::engine::renderer::texture::Texture;
+
+pub const PARTICLE_SHADER: &str = "particles";
+const PARTICLE_SHADER_SOURCE: &str = include_str!("../../../gsl/particles.wgsl");
+pub const PARTICLE_SIM_SHADER: &str = "particles_sim";
+const PARTICLE_SIM_SHADER_SOURCE: &str = include_str!("../../../gsl/particles_sim.wgsl");
+
+/// curve'ler shader'a sabit sayıda örnek olarak gidiyor
+const CURVE_SAMPLES: usize = 16;
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Value over the normalized lifetime of a particle, linear between keys
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curve<T> {
+    keys: Vec<(f32, T)>,
+}
+
+impl<T: Copy + Add<Output = T> + Mul<f32, Output = T>> Curve<T> {
+    /// `keys` are (time in 0..=1, value) pairs, at least one is needed
+    pub fn new(mut keys: Vec<(f32, T)>) -> Self {
+        assert!(!keys.is_empty(), "a curve needs at least one key");
+        keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { keys }
+    }
+
+    pub fn constant(value: T) -> Self {
+        Self::new(vec![(0.0, value)])
+    }
+
+    pub fn linear(from: T, to: T) -> Self {
+        Self::new(vec![(0.0, from), (1.0, to)])
+    }
+
+    pub fn with_key(mut self, time: f32, value: T) -> Self {
+        let index = self.keys.partition_point(|(t, _)| *t <= time);
+        self.keys.insert(index, (time, value));
+        self
+    }
+
+    pub fn sample(&self, t: f32) -> T {
+        let (first_time, first) = self.keys[0];
+        if t <= first_time {
+            return first;
+        }
+        for pair in self.keys.windows(2) {
+            let ((t0, a), (t1, b)) = (pair[0], pair[1]);
+            if t <= t1 {
+                let f = (t - t0) / (t1 - t0).max(1e-6);
+                return a * (1.0 - f) + b * f;
+            }
+        }
+        self.keys[self.keys.len() - 1].1
+    }
+
+    fn samples(&self) -> [T; CURVE_SAMPLES] {
+        std::array::from_fn(|i| self.sample(i as f32 / (CURVE_SAMPLES - 1) as f32))
+    }
+}
+
+/// Where new particles appear
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum EmitterShape {
+    #[default]
+    Point,
+    /// anywhere inside the sphere, moving outward
+    Sphere { radius: f32 },
+    Box { half_extents: Vec3 },
+    /// disk around the emitter direction, particles leave within `angle` radians of it
+    Cone { radius: f32, angle: f32 },
+}
+
+impl EmitterShape {
+    fn to_gpu(self) -> [f32; 4] {
+        match self {
+            EmitterShape::Point => [0.0; 4],
+            EmitterShape::Sphere { radius } => [1.0, radius, 0.0, 0.0],
+            EmitterShape::Box { half_extents } => [2.0, half_extents.x, half_extents.y, half_extents.z],
+            EmitterShape::Cone { radius, angle } => [3.0, radius, angle, 0.0],
+        }
+    }
+}
+
+/// `count` particles at once, `time` seconds into every emitter cycle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Burst {
+    pub time: f32,
+    pub count: u32,
+}
+
+/// Emitter description. Changes made through `ParticleSystem::emitter_mut` apply on the
+/// next frame, changing `max_particles` or `texture` drops the live particles.
+#[derive(Clone)]
+pub struct ParticleEmitter {
+    pub position: Vec3,
+    pub direction: Vec3,
+    /// radians around `direction`, used by the point and box shapes
+    pub spread: f32,
+    pub shape: EmitterShape,
+    /// particles per second
+    pub rate: f32,
+    pub bursts: Vec<Burst>,
+    /// length of one cycle in seconds, 0 means endless (bursts fire once)
+    pub duration: f32,
+    pub looping: bool,
+    pub emitting: bool,
+    /// (min, max) seconds
+    pub lifetime: (f32, f32),
+    /// (min, max) initial speed
+    pub speed: (f32, f32),
+    pub gravity: Vec3,
+    /// fraction of the velocity lost per second
+    pub drag: f32,
+    pub color: Curve<Vec4>,
+    pub size: Curve<f32>,
+    /// multiplies the velocity over the lifetime
+    pub speed_over_life: Curve<f32>,
+    pub max_particles: u32,
+    /// alpha blended particles are not sorted
+    pub blend: BlendMode,
+    /// `None` uses a soft round sprite
+    pub texture: Option<Arc<Texture>>,
+}
+
+impl Default for ParticleEmitter {
+    fn default() -> Self {
+        Self {
+            position: Vec3::ZERO,
+            direction: Vec3::Y,
+            spread: 0.3,
+            shape: EmitterShape::Point,
+            rate: 20.0,
+            bursts: Vec::new(),
+            duration: 5.0,
+            looping: true,
+            emitting: true,
+            lifetime: (1.0, 2.0),
+            speed: (1.0, 2.0),
+            gravity: Vec3::ZERO,
+            drag: 0.0,
+            color: Curve::linear(Vec4::ONE, Vec4::new(1.0, 1.0, 1.0, 0.0)),
+            size: Curve::constant(0.2),
+            speed_over_life: Curve::constant(1.0),
+            max_particles: 1024,
+            blend: BlendMode::Additive,
+            texture: None,
+        }
+    }
+}
+
+impl ParticleEmitter {
+    pub fn with_position(mut self, position: Vec3) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_direction(mut self, direction: Vec3, spread: f32) -> Self {
+        self.direction = direction;
+        self.spread = spread;
+        self
+    }
+
+    pub fn with_shape(mut self, shape: EmitterShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn with_rate(mut self, rate: f32) -> Self {
+        self.rate = rate;
+        self
+    }
+
+    pub fn with_burst(mut self, time: f32, count: u32) -> Self {
+        self.bursts.push(Burst { time, count });
+        self
+    }
+
+    pub fn with_duration(mut self, duration: f32, looping: bool) -> Self {
+        self.duration = duration;
+        self.looping = looping;
+        self
+    }
+
+    pub fn with_lifetime(mut self, min: f32, max: f32) -> Self {
+        self.lifetime = (min, max);
+        self
+    }
+
+    pub fn with_speed(mut self, min: f32, max: f32) -> Self {
+        self.speed = (min, max);
+        self
+    }
+
+    pub fn with_gravity(mut self, gravity: Vec3) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    pub fn with_drag(mut self, drag: f32) -> Self {
+        self.drag = drag;
+        self
+    }
+
+    pub fn with_color(mut self, color: Curve<Vec4>) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_size(mut self, size: Curve<f32>) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_speed_over_life(mut self, speed: Curve<f32>) -> Self {
+        self.speed_over_life = speed;
+        self
+    }
+
+    pub fn with_max_particles(mut self, max_particles: u32) -> Self {
+        self.max_particles = max_particles;
+        self
+    }
+
+    pub fn with_blend(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    pub fn with_texture(mut self, texture: Arc<Texture>) -> Self {
+        self.texture = Some(texture);
+        self
+    }
+
+    fn uniform(&self, capacity: u32, spawn: SpawnRange) -> EmitterUniform {
+        let pack = |curve: &Curve<f32>| {
+            let s = curve.samples();
+            std::array::from_fn(|i| [s[i * 4], s[i * 4 + 1], s[i * 4 + 2], s[i * 4 + 3]])
+        };
+        EmitterUniform {
+            origin: self.position.extend(1.0).to_array(),
+            direction: self.direction.normalize_or(Vec3::Y).extend(self.spread).to_array(),
+            shape: self.shape.to_gpu(),
+            ranges: [self.lifetime.0, self.lifetime.1, self.speed.0, self.speed.1],
+            forces: self.gravity.extend(self.drag).to_array(),
+            spawn: [spawn.start, spawn.count, capacity, spawn.seed],
+            time: [spawn.dt, 0.0, 0.0, 0.0],
+            color: self.color.samples().map(|c| c.to_array()),
+            size: pack(&self.size),
+            speed: pack(&self.speed_over_life),
+        }
+    }
+}
+
+/// One particle slot, also the per-instance vertex data of the billboards.
+/// A slot is dead once `age >= lifetime`, an all zero buffer is empty.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Particle {
+    pub position: [f32; 3],
+    pub age: f32,
+    pub velocity: [f32; 3],
+    pub lifetime: f32,
+}
+
+impl Particle {
+    pub fn is_alive(&self) -> bool {
+        self.age < self.lifetime
+    }
+}
+
+impl VertexType for Particle {
+    fn layout() -> VertexLayoutKey {
+        VertexLayoutKey {
+            array_stride: std::mem::size_of::<Particle>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: wgpu::vertex_attr_array![
+                0 => Float32x4,
+                1 => Float32x4,
+            ].to_vec(),
+        }
+    }
+}
+
+// particles.wgsl ve particles_sim.wgsl'deki EmitterUniform ile aynı
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct EmitterUniform {
+    origin: [f32; 4],
+    direction: [f32; 4],
+    shape: [f32; 4],
+    ranges: [f32; 4],
+    forces: [f32; 4],
+    spawn: [u32; 4],
+    time: [f32; 4],
+    color: [[f32; 4]; CURVE_SAMPLES],
+    size: [[f32; 4]; CURVE_SAMPLES / 4],
+    speed: [[f32; 4]; CURVE_SAMPLES / 4],
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct ParticleCameraUniform {
+    view_proj: [[f32; 4]; 4],
+    right: [f32; 4],
+    up: [f32; 4],
+}
+
+/// Slots to respawn and the time step of one simulation dispatch
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct SpawnRange {
+    start: u32,
+    count: u32,
+    dt: f32,
+    seed: u32,
+}
+
+/// Emission timing of one emitter, spawned particles go into a ring of slots so the
+/// oldest ones are replaced when the emitter runs out of room.
+#[derive(Debug, Default)]
+struct Spawner {
+    time: f32,
+    accumulator: f32,
+    finished: bool,
+    head: u32,
+    pending: SpawnRange,
+}
+
+impl Spawner {
+    fn advance(&mut self, emitter: &ParticleEmitter, capacity: u32, dt: f32) {
+        self.pending.dt += dt;
+        if !emitter.emitting || self.finished {
+            return;
+        }
+        self.accumulator += emitter.rate.max(0.0) * dt;
+        let mut count = self.accumulator.floor();
+        self.accumulator -= count;
+
+        // döngü sınırını geçen frame'de iki aralıktaki burst'ler de sayılıyor
+        let (mut from, mut to) = (self.time, self.time + dt);
+        loop {
+            let end = if emitter.duration > 0.0 { to.min(emitter.duration) } else { to };
+            for burst in &emitter.bursts {
+                if burst.time >= from && burst.time < end {
+                    count += burst.count as f32;
+                }
+            }
+            if emitter.duration <= 0.0 || to < emitter.duration {
+                break;
+            }
+            if !emitter.looping {
+                self.finished = true;
+                break;
+            }
+            to -= emitter.duration;
+            from = 0.0;
+        }
+        self.time = to;
+        self.emit(capacity, count as u32);
+    }
+
+    fn emit(&mut self, capacity: u32, count: u32) {
+        let count = count.min(capacity.saturating_sub(self.pending.count));
+        if self.pending.count == 0 {
+            self.pending.start = self.head;
+        }
+        self.pending.count += count;
+        self.head = (self.head + count) % capacity;
+    }
+
+    fn take(&mut self) -> SpawnRange {
+        let range = self.pending;
+        self.pending = SpawnRange { seed: range.seed.wrapping_add(1), ..Default::default() };
+        range
+    }
+}
+
+struct EmitterState {
+    emitter: ParticleEmitter,
+    capacity: u32,
+    texture: Arc<Texture>,
+    spawner: Spawner,
+    particles: wgpu::Buffer,
+    uniform_buffer: wgpu::Buffer,
+    render_bind_group: wgpu::BindGroup,
+    compute_bind_group: Option<wgpu::BindGroup>,
+    /// CPU simülasyonunda parçacıkların kopyası
+    cpu: Vec<Particle>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EmitterId(pub usize);
+
+/// Owns the particle emitters. Call `update` with the frame time from the game loop,
+/// `SurfaceManager::render` simulates and draws them in the main pass.
+pub struct ParticleSystem {
+    camera_buffer: wgpu::Buffer,
+    camera_bind_group: wgpu::BindGroup,
+    default_texture: Arc<Texture>,
+    compute: Option<wgpu::ComputePipeline>,
+    gpu_simulation: bool,
+    emitters: Vec<Option<EmitterState>>,
+}
+
+impl ParticleSystem {
+    pub fn new(device: &Device, queue: &Queue, cache: &mut PipelineCache) -> Self {
+        cache.register_shader(PARTICLE_SHADER, PARTICLE_SHADER_SOURCE);
+
+        let camera_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Camera Buffer"),
+            size: std::mem::size_of::<ParticleCameraUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let camera_layout = cache.bind_group_layout(device, &Self::camera_entries());
+        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("particle_camera_bind_group"),
+            layout: &camera_layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: camera_buffer.as_entire_binding() }],
+        });
+
+        let compute = Self::supports_compute(device).then(|| {
+            cache.register_shader(PARTICLE_SIM_SHADER, PARTICLE_SIM_SHADER_SOURCE);
+            Self::create_simulation(device, cache)
+        });
+
+        Self {
+            camera_buffer,
+            camera_bind_group,
+            default_texture: Arc::new(Texture::from_image(device, queue, &soft_circle(32), true, "Particle Texture")),
+            gpu_simulation: compute.is_some(),
+            compute,
+            emitters: Vec::new(),
+        }
+    }
+
+    fn create_simulation(device: &Device, cache: &mut PipelineCache) -> wgpu::ComputePipeline {
+        let module = cache.shader(device, PARTICLE_SIM_SHADER);
+        Self::create_simulation_with(device, cache, &module)
+    }
+
+    fn create_simulation_with(device: &Device, cache: &mut PipelineCache, module: &wgpu::ShaderModule) -> wgpu::ComputePipeline {
+        let layout = cache.bind_group_layout(device, &Self::sim_entries());
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Particle Simulation Layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("Particle Simulation"),
+            layout: Some(&pipeline_layout),
+            module,
+            entry_point: Some("cs_main"),
+            compilation_options: Default::default(),
+            cache: None,
+        })
+    }
+
+    /// The compute pipeline built from `module` instead of the registered
+    /// `PARTICLE_SIM_SHADER`, `None` when the simulation runs on the CPU only
+    pub fn build_simulation(&self, device: &Device, cache: &mut PipelineCache, module: &wgpu::ShaderModule) -> Option<wgpu::ComputePipeline> {
+        self.compute.is_some().then(|| Self::create_simulation_with(device, cache, module))
+    }
+
+    /// Uses a pipeline from `build_simulation`
+    pub fn set_simulation(&mut self, pipeline: wgpu::ComputePipeline) {
+        if self.compute.is_some() {
+            self.compute = Some(pipeline);
+        }
+    }
+
+    /// webgl2 limitlerinde compute ve storage buffer yok
+    pub fn supports_compute(device: &Device) -> bool {
+        let limits = device.limits();
+        limits.max_compute_workgroups_per_dimension > 0 && limits.max_storage_buffers_per_shader_stage > 0
+    }
+
+    pub fn uses_gpu_simulation(&self) -> bool {
+        self.gpu_simulation
+    }
+
+    /// Switches between the compute and the CPU simulation, ignored when compute isn't
+    /// supported. Live particles are dropped.
+    pub fn set_gpu_simulation(&mut self, device: &Device, cache: &mut PipelineCache, enabled: bool) {
+        let enabled = enabled && self.compute.is_some();
+        if enabled == self.gpu_simulation {
+            return;
+        }
+        self.gpu_simulation = enabled;
+        let emitters: Vec<Option<ParticleEmitter>> = self.emitters.drain(..)
+            .map(|slot| slot.map(|state| state.emitter))
+            .collect();
+        self.emitters = emitters.into_iter()
+            .map(|slot| slot.map(|emitter| self.create_state(device, cache, emitter)))
+            .collect();
+    }
+
+    pub fn camera_entries() -> Vec<wgpu::BindGroupLayoutEntry> {
+        vec![wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }]
+    }
+
+    pub fn emitter_entries() -> Vec<wgpu::BindGroupLayoutEntry> {
+        vec![
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ]
+    }
+
+    fn sim_entries() -> Vec<wgpu::BindGroupLayoutEntry> {
+        vec![
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::COMPUTE,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Storage { read_only: false },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+        ]
+    }
+
+    pub fn pipeline_key(target: &TargetState, blend: BlendMode) -> PipelineKey {
+        let mut key = target.pipeline_key(ShaderVariant::new(PARTICLE_SHADER));
+        key.bind_groups = vec![Self::camera_entries(), Self::emitter_entries()];
+        key.vertex_layouts = vec![Particle::layout()];
+        key.cull_mode = None;
+        key.blend = blend;
+        if let Some(depth) = key.depth.as_mut() {
+            depth.write = false;
+        }
+        key
+    }
+
+    fn create_state(&self, device: &Device, cache: &mut PipelineCache, emitter: ParticleEmitter) -> EmitterState {
+        let capacity = emitter.max_particles.max(1);
+        let texture = emitter.texture.clone().unwrap_or_else(|| self.default_texture.clone());
+
+        let mut usage = wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST;
+        if self.gpu_simulation {
+            usage |= wgpu::BufferUsages::STORAGE;
+        }
+        // sıfırlanmış buffer'da tüm slotlar ölü
+        let particles = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Buffer"),
+            size: capacity as u64 * std::mem::size_of::<Particle>() as u64,
+            usage,
+            mapped_at_creation: false,
+        });
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Emitter Uniform Buffer"),
+            size: std::mem::size_of::<EmitterUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let render_layout = cache.bind_group_layout(device, &Self::emitter_entries());
+        let render_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("particle_emitter_bind_group"),
+            layout: &render_layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(&texture.view) },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&texture.sampler) },
+            ],
+        });
+        let compute_bind_group = self.gpu_simulation.then(|| {
+            let layout = cache.bind_group_layout(device, &Self::sim_entries());
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("particle_sim_bind_group"),
+                layout: &layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() },
+                    wgpu::BindGroupEntry { binding: 1, resource: particles.as_entire_binding() },
+                ],
+            })
+        });
+
+        EmitterState {
+            cpu: if self.gpu_simulation { Vec::new() } else { vec![bytemuck::Zeroable::zeroed(); capacity as usize] },
+            emitter,
+            capacity,
+            texture,
+            spawner: Spawner::default(),
+            particles,
+            uniform_buffer,
+            render_bind_group,
+            compute_bind_group,
+        }
+    }
+
+    pub fn add_emitter(&mut self, device: &Device, cache: &mut PipelineCache, emitter: ParticleEmitter) -> EmitterId {
+        let state = Some(self.create_state(device, cache, emitter));
+        match self.emitters.iter().position(|e| e.is_none()) {
+            Some(index) => {
+                self.emitters[index] = state;
+                EmitterId(index)
+            }
+            None => {
+                self.emitters.push(state);
+                EmitterId(self.emitters.len() - 1)
+            }
+        }
+    }
+
+    pub fn remove_emitter(&mut self, id: EmitterId) -> Option<ParticleEmitter> {
+        self.emitters.get_mut(id.0)?.take().map(|state| state.emitter)
+    }
+
+    /// Removes every emitter, the slot of an `EmitterId` is its index
+    pub fn take_emitters(&mut self) -> Vec<Option<ParticleEmitter>> {
+        self.emitters.drain(..).map(|e| e.map(|state| state.emitter)).collect()
+    }
+
+    /// Replaces every emitter keeping the ids of `take_emitters`, live particles start over
+    pub fn set_emitters(&mut self, device: &Device, cache: &mut PipelineCache, emitters: Vec<Option<ParticleEmitter>>) {
+        self.emitters = emitters.into_iter()
+            .map(|e| e.map(|emitter| self.create_state(device, cache, emitter)))
+            .collect();
+    }
+
+    pub fn emitter(&self, id: EmitterId) -> Option<&ParticleEmitter> {
+        self.emitters.get(id.0)?.as_ref().map(|state| &state.emitter)
+    }
+
+    pub fn emitter_mut(&mut self, id: EmitterId) -> Option<&mut ParticleEmitter> {
+        self.emitters.get_mut(id.0)?.as_mut().map(|state| &mut state.emitter)
+    }
+
+    pub fn emitter_count(&self) -> usize {
+        self.emitters.iter().flatten().count()
+    }
+
+    /// spawns `count` particles on the next frame regardless of rate and bursts
+    pub fn emit(&mut self, id: EmitterId, count: u32) {
+        if let Some(Some(state)) = self.emitters.get_mut(id.0) {
+            state.spawner.emit(state.capacity, count);
+        }
+    }
+
+    /// Restarts the emission cycle of an emitter, live particles keep going
+    pub fn restart(&mut self, id: EmitterId) {
+        if let Some(Some(state)) = self.emitters.get_mut(id.0) {
+            state.spawner.time = 0.0;
+            state.spawner.accumulator = 0.0;
+            state.spawner.finished = false;
+        }
+    }
+
+    /// Advances emission by `dt` seconds. The simulation itself runs in `prepare`
+    /// with the time accumulated since the last frame.
+    pub fn update(&mut self, dt: f32) {
+        for state in self.emitters.iter_mut().flatten() {
+            state.spawner.advance(&state.emitter, state.capacity, dt);
+        }
+    }
+
+    /// Simulates this frame's step, on the GPU a compute pass is recorded into `encoder`.
+    /// Must be called before the render pass that draws the particles.
+    pub fn prepare(&mut self, device: &Device, queue: &Queue, cache: &mut PipelineCache, encoder: &mut wgpu::CommandEncoder, camera: &Camera, aspect: f32) {
+        let view = camera.view();
+        let camera_uniform = ParticleCameraUniform {
+            view_proj: camera.view_proj(aspect).to_cols_array_2d(),
+            right: view.row(0).truncate().extend(0.0).to_array(),
+            up: view.row(1).truncate().extend(0.0).to_array(),
+        };
+        queue.write_buffer(&self.camera_buffer, 0, bytemuck::bytes_of(&camera_uniform));
+        stats::upload(std::mem::size_of_val(&camera_uniform));
+
+        // kapasite ya da texture değiştiyse buffer'lar yeniden kuruluyor
+        for index in 0..self.emitters.len() {
+            let stale = self.emitters[index].as_ref().is_some_and(|state| {
+                let texture = state.emitter.texture.as_ref().unwrap_or(&self.default_texture);
+                state.capacity != state.emitter.max_particles.max(1) || !Arc::ptr_eq(texture, &state.texture)
+            });
+            if stale {
+                let state = self.emitters[index].take().unwrap();
+                self.emitters[index] = Some(self.create_state(device, cache, state.emitter));
+            }
+        }
+
+        let mut dispatches = Vec::new();
+        for state in self.emitters.iter_mut().flatten() {
+            let spawn = state.spawner.take();
+            if spawn.dt <= 0.0 && spawn.count == 0 {
+                continue;
+            }
+            let uniform = state.emitter.uniform(state.capacity, spawn);
+            queue.write_buffer(&state.uniform_buffer, 0, bytemuck::bytes_of(&uniform));
+            stats::upload(std::mem::size_of_val(&uniform));
+            match &state.compute_bind_group {
+                Some(bind_group) => dispatches.push((bind_group, state.capacity.div_ceil(WORKGROUP_SIZE))),
+                None => {
+                    simulate(&uniform, &mut state.cpu);
+                    queue.write_buffer(&state.particles, 0, bytemuck::cast_slice(&state.cpu));
+                    stats::upload(std::mem::size_of_val(state.cpu.as_slice()));
+                }
+            }
+        }
+
+        if let (Some(pipeline), false) = (&self.compute, dispatches.is_empty()) {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Particle Simulation Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(pipeline);
+            stats::pipeline();
+            for (bind_group, workgroups) in dispatches {
+                pass.set_bind_group(0, bind_group, &[]);
+                pass.dispatch_workgroups(workgroups, 1, 1);
+                stats::dispatch();
+            }
+        }
+    }
+
+    pub fn draw(&self, device: &Device, cache: &mut PipelineCache, target: &TargetState, render_pass: &mut wgpu::RenderPass<'_>) {
+        if self.emitters.iter().all(|e| e.is_none()) {
+            return;
+        }
+        render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+        for state in self.emitters.iter().flatten() {
+            let pipeline = cache.pipeline(device, &Self::pipeline_key(target, state.emitter.blend));
+            render_pass.set_pipeline(&pipeline);
+            stats::pipeline();
+            render_pass.set_bind_group(1, &state.render_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, state.particles.slice(..));
+            render_pass.draw(0..6, 0..state.capacity);
+            stats::draw(2 * state.capacity as u64);
+        }
+    }
+}
+
+/// white sprite whose alpha falls off towards the edge
+fn soft_circle(size: u32) -> ImageData {
+    let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let dx = (x as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+            let dy = (y as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+            let falloff = (1.0 - (dx * dx + dy * dy).sqrt()).clamp(0.0, 1.0);
+            rgba.extend_from_slice(&[255, 255, 255, (falloff * falloff * 255.0) as u8]);
+        }
+    }
+    ImageData { width: size, height: size, rgba }
+}
+
+// Aşağıdakiler particles_sim.wgsl'in birebir kopyası, iki yol aynı sonucu vermeli
+
+fn pcg(v: u32) -> u32 {
+    let state = v.wrapping_mul(747796405).wrapping_add(2891336453);
+    let word = ((state >> ((state >> 28) + 4)) ^ state).wrapping_mul(277803737);
+    (word >> 22) ^ word
+}
+
+fn random(seed: u32, index: u32, k: u32) -> f32 {
+    pcg(seed ^ pcg(index.wrapping_mul(8).wrapping_add(k))) as f32 / u32::MAX as f32
+}
+
+fn cone_direction(axis: Vec3, angle: f32, u: f32, v: f32) -> Vec3 {
+    let cos_theta = 1.0 + (angle.cos() - 1.0) * u;
+    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+    let phi = v * std::f32::consts::TAU;
+    let helper = if axis.x.abs() > 0.9 { Vec3::Y } else { Vec3::X };
+    let t = helper.cross(axis).normalize();
+    let b = axis.cross(t);
+    (t * phi.cos() + b * phi.sin()) * sin_theta + axis * cos_theta
+}
+
+fn sample_packed(values: &[[f32; 4]; CURVE_SAMPLES / 4], t: f32) -> f32 {
+    let x = t.clamp(0.0, 1.0) * (CURVE_SAMPLES - 1) as f32;
+    let i = x.floor() as usize;
+    let j = (i + 1).min(CURVE_SAMPLES - 1);
+    let (a, b) = (values[i / 4][i % 4], values[j / 4][j % 4]);
+    a + (b - a) * x.fract()
+}
+
+fn spawn_particle(u: &EmitterUniform, index: u32) -> Particle {
+    let seed = u.spawn[3];
+    let axis = Vec3::from_slice(&u.direction[..3]);
+    let mut position = Vec3::from_slice(&u.origin[..3]);
+    let mut direction = cone_direction(axis, u.direction[3], random(seed, index, 0), random(seed, index, 1));
+    match u.shape[0] as u32 {
+        1 => {
+            let d = cone_direction(Vec3::Y, std::f32::consts::PI, random(seed, index, 2), random(seed, index, 3));
+            position += d * u.shape[1] * random(seed, index, 4).powf(1.0 / 3.0);
+            direction = d;
+        }
+        2 => {
+            let r = Vec3::new(random(seed, index, 2), random(seed, index, 3), random(seed, index, 4));
+            position += (r * 2.0 - Vec3::ONE) * Vec3::from_slice(&u.shape[1..]);
+        }
+        3 => {
+            let disk = cone_direction(axis, std::f32::consts::FRAC_PI_2, 1.0, random(seed, index, 2));
+            position += disk * u.shape[1] * random(seed, index, 4).sqrt();
+            direction = cone_direction(axis, u.shape[2], random(seed, index, 0), random(seed, index, 1));
+        }
+        _ => {}
+    }
+    let speed = u.ranges[2] + (u.ranges[3] - u.ranges[2]) * random(seed, index, 5);
+    let lifetime = u.ranges[0] + (u.ranges[1] - u.ranges[0]) * random(seed, index, 6);
+    Particle {
+        position: position.to_array(),
+        age: 0.0,
+        velocity: (direction * speed).to_array(),
+        lifetime,
+    }
+}
+
+fn simulate(u: &EmitterUniform, particles: &mut [Particle]) {
+    let [start, count, capacity, _] = u.spawn;
+    let dt = u.time[0];
+    let gravity = Vec3::from_slice(&u.forces[..3]);
+    for (index, p) in particles.iter_mut().enumerate() {
+        let index = index as u32;
+        let mut step = dt;
+        let offset = (index + capacity - start) % capacity;
+        if offset < count {
+            *p = spawn_particle(u, index);
+            step = dt * ((count - offset) as f32 - 0.5) / count as f32;
+        }
+        if p.is_alive() {
+            let t = p.age / p.lifetime;
+            let mut velocity = Vec3::from(p.velocity) + gravity * step;
+            velocity *= (1.0 - u.forces[3] * step).max(0.0);
+            let position = Vec3::from(p.position) + velocity * sample_packed(&u.speed, t) * step;
+            p.velocity = velocity.to_array();
+            p.position = position.to_array();
+            p.age += step;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curve_keys(){
+        let curve = Curve::linear(0.0, 1.0).with_key(0.5, 4.0);
+        assert_eq!(curve.sample(-1.0), 0.0);
+        assert_eq!(curve.sample(0.25), 2.0);
+        assert_eq!(curve.sample(0.75), 2.5);
+        assert_eq!(curve.sample(2.0), 1.0);
+        let packed = ParticleEmitter::default().with_size(curve).uniform(1, SpawnRange::default()).size;
+        assert!((sample_packed(&packed, 0.5) - 4.0).abs() < 0.3);
+    }
+
+    #[test]
+    fn burst_and_cpu_simulation(){
+        let emitter = ParticleEmitter::default()
+            .with_rate(0.0)
+            .with_burst(0.0, 10)
+            .with_duration(1.0, false)
+            .with_lifetime(0.5, 0.5)
+            .with_speed(1.0, 1.0)
+            .with_gravity(Vec3::new(0.0, -10.0, 0.0))
+            .with_max_particles(16);
+        let mut spawner = Spawner::default();
+        let mut particles = vec![bytemuck::Zeroable::zeroed(); 16];
+
+        spawner.advance(&emitter, 16, 0.1);
+        simulate(&emitter.uniform(16, spawner.take()), &mut particles);
+        assert_eq!(particles.iter().filter(|p| p.is_alive()).count(), 10);
+
+        // burst döngü başına bir kez, döngü bitince emisyon duruyor
+        for _ in 0..20 {
+            spawner.advance(&emitter, 16, 0.1);
+            let spawn = spawner.take();
+            assert_eq!(spawn.count, 0);
+            simulate(&emitter.uniform(16, spawn), &mut particles);
+        }
+        assert!(spawner.finished);
+        assert!(particles.iter().all(|p| !p.is_alive()));
+        // yerçekimi hızı aşağı çekmiş olmalı
+        assert!(particles[..10].iter().all(|p| p.velocity[1] < 0.0));
+    }
+}
@@ -0,0 +1,710 @@
+// src/engine/renderer/pbr.rs
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use glam::{Mat4, Vec3};
+use wgpu::{Device, Queue};
+
+use crate::engine::renderer::camera::{Camera, CameraUniform};
+use crate::engine::renderer::gltf_loader::{AlphaMode, GltfMaterial, GltfScene, ImageData, TextureRef};
+use crate::engine::renderer::instancing::{batch_ranges, InstanceBuffer, InstanceData};
+use crate::engine::renderer::mesh::{Mesh, Vertex3d, VertexType};
+use crate::engine::renderer::pipeline_cache::{BlendMode, PipelineCache, PipelineKey, ShaderVariant};
+use crate::engine::renderer::settings::ShadowSettings;
+use crate::engine::renderer::shadow::ShadowMaps;
+use crate::engine::renderer::stats;
+use crate::engine::renderer::targets::TargetState;
+use crate::engine::renderer::texture::{generate_mips, write_rgba8, Texture};
+
+pub const PBR_SHADER: &str = "pbr";
+const PBR_SHADER_SOURCE: &str = include_str!("../../../gsl/pbr.wgsl");
+
+/// uniform buffer'da sabit boyutlu dizi, webgl2'de storage buffer yok
+pub const MAX_LIGHTS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Light {
+    Directional {
+        direction: Vec3,
+        color: Vec3,
+        intensity: f32,
+        /// only the first shadow casting directional light gets cascades
+        cast_shadows: bool,
+    },
+    Point {
+        position: Vec3,
+        color: Vec3,
+        intensity: f32,
+        /// 0 means infinite
+        range: f32,
+    },
+    Spot {
+        position: Vec3,
+        direction: Vec3,
+        color: Vec3,
+        intensity: f32,
+        range: f32,
+        /// radians
+        inner_angle: f32,
+        outer_angle: f32,
+        cast_shadows: bool,
+    },
+}
+
+impl Light {
+    fn to_gpu(self) -> GpuLight {
+        match self {
+            Light::Directional { direction, color, intensity, .. } => GpuLight {
+                position: [0.0, 0.0, 0.0, 0.0],
+                direction: direction.normalize_or(Vec3::NEG_Y).extend(0.0).to_array(),
+                color: color.extend(intensity).to_array(),
+                cone: [0.0; 4],
+            },
+            Light::Point { position, color, intensity, range } => GpuLight {
+                position: position.extend(1.0).to_array(),
+                direction: [0.0, 0.0, 0.0, range],
+                color: color.extend(intensity).to_array(),
+                cone: [0.0; 4],
+            },
+            Light::Spot { position, direction, color, intensity, range, inner_angle, outer_angle, .. } => GpuLight {
+                position: position.extend(2.0).to_array(),
+                direction: direction.normalize_or(Vec3::NEG_Y).extend(range).to_array(),
+                color: color.extend(intensity).to_array(),
+                cone: [inner_angle.cos(), outer_angle.cos(), 0.0, 0.0],
+            },
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct GpuLight {
+    position: [f32; 4],
+    direction: [f32; 4],
+    color: [f32; 4],
+    cone: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct LightsUniform {
+    ambient: [f32; 4],
+    params: [f32; 4],
+    counts: [u32; 4],
+    lights: [GpuLight; MAX_LIGHTS],
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct MaterialUniform {
+    base_color: [f32; 4],
+    emissive: [f32; 4],
+    params: [f32; 4],
+}
+
+impl MaterialUniform {
+    fn new(material: &PbrMaterial) -> Self {
+        let alpha_cutoff = match material.alpha_mode {
+            AlphaMode::Mask(cutoff) => cutoff,
+            _ => 0.0,
+        };
+        Self {
+            base_color: material.base_color,
+            emissive: [material.emissive[0], material.emissive[1], material.emissive[2], material.normal_scale],
+            params: [material.metallic, material.roughness, material.occlusion_strength, alpha_cutoff],
+        }
+    }
+}
+
+/// Metallic-roughness material, textures that are `None` fall back to neutral defaults
+#[derive(Clone)]
+pub struct PbrMaterial {
+    pub base_color: [f32; 4],
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: [f32; 3],
+    pub normal_scale: f32,
+    pub occlusion_strength: f32,
+    pub alpha_mode: AlphaMode,
+    pub double_sided: bool,
+    pub base_color_texture: Option<Arc<Texture>>,
+    pub metallic_roughness_texture: Option<Arc<Texture>>,
+    pub normal_texture: Option<Arc<Texture>>,
+    pub occlusion_texture: Option<Arc<Texture>>,
+    pub emissive_texture: Option<Arc<Texture>>,
+}
+
+impl Default for PbrMaterial {
+    fn default() -> Self {
+        Self {
+            base_color: [1.0; 4],
+            metallic: 0.0,
+            roughness: 0.5,
+            emissive: [0.0; 3],
+            normal_scale: 1.0,
+            occlusion_strength: 1.0,
+            alpha_mode: AlphaMode::Opaque,
+            double_sided: false,
+            base_color_texture: None,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            occlusion_texture: None,
+            emissive_texture: None,
+        }
+    }
+}
+
+/// Material uploaded to the GPU, created with `PbrRenderer::create_material`
+pub struct Material {
+    pub alpha_mode: AlphaMode,
+    pub double_sided: bool,
+    buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+impl Material {
+    /// Updates the factors of the material, textures and alpha mode can't change
+    /// since they are baked into the bind group and pipeline.
+    pub fn update_factors(&self, queue: &Queue, material: &PbrMaterial) {
+        queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(&MaterialUniform::new(material)));
+        stats::upload(std::mem::size_of::<MaterialUniform>());
+    }
+}
+
+struct PbrDraw {
+    mesh: Arc<Mesh>,
+    material: Arc<Material>,
+    instance: InstanceData,
+}
+
+/// Forward PBR renderer: metallic-roughness materials, up to `MAX_LIGHTS` lights
+/// and an equirectangular environment map for ambient light.
+pub struct PbrRenderer {
+    pub lights: Vec<Light>,
+    pub ambient_color: Vec3,
+    pub ambient_intensity: f32,
+    pub exposure: f32,
+    /// false when the output goes through the post-processing stack
+    pub tonemap: bool,
+
+    camera_buffer: wgpu::Buffer,
+    lights_buffer: wgpu::Buffer,
+    environment: wgpu::TextureView,
+    environment_mips: u32,
+    environment_sampler: wgpu::Sampler,
+    /// kept to upload it again after a device loss
+    environment_image: Option<ImageData>,
+    shadows: ShadowMaps,
+    frame_bind_group: wgpu::BindGroup,
+
+    instances: InstanceBuffer<InstanceData>,
+
+    material_sampler: wgpu::Sampler,
+    white: Arc<Texture>,
+    flat_normal: Arc<Texture>,
+    linear_white: Arc<Texture>,
+
+    draws: Vec<PbrDraw>,
+    batches: Vec<Range<u32>>,
+}
+
+impl PbrRenderer {
+    pub fn new(device: &Device, queue: &Queue, cache: &mut PipelineCache) -> Self {
+        cache.register_shader(PBR_SHADER, PBR_SHADER_SOURCE);
+
+        let camera_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("PBR Camera Buffer"),
+            size: std::mem::size_of::<CameraUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let lights_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Lights Buffer"),
+            size: std::mem::size_of::<LightsUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // varsayılan ortam: düz gri gökyüzü
+        let env_image = ImageData { width: 1, height: 1, rgba: vec![128, 128, 128, 255] };
+        let (environment, environment_mips) = create_environment(device, queue, &env_image);
+        let environment_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Environment Sampler"),
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let shadows = ShadowMaps::new(device, cache, ShadowSettings::default());
+        let frame_bind_group = Self::create_frame_bind_group(
+            device, cache, &camera_buffer, &lights_buffer, &environment, &environment_sampler, &shadows,
+        );
+
+        let material_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Material Sampler"),
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        Self {
+            lights: Vec::new(),
+            ambient_color: Vec3::ONE,
+            ambient_intensity: 0.3,
+            exposure: 1.0,
+            tonemap: true,
+            camera_buffer,
+            lights_buffer,
+            environment,
+            environment_mips,
+            environment_sampler,
+            environment_image: None,
+            shadows,
+            frame_bind_group,
+            instances: InstanceBuffer::new(device, "PBR Instance Buffer", 64),
+            material_sampler,
+            white: Arc::new(Texture::white(device, queue)),
+            flat_normal: Arc::new(Texture::flat_normal(device, queue)),
+            linear_white: Arc::new(Texture::solid(device, queue, [255; 4], false, "Linear White Texture")),
+            draws: Vec::new(),
+            batches: Vec::new(),
+        }
+    }
+
+    pub fn frame_entries() -> Vec<wgpu::BindGroupLayoutEntry> {
+        let uniform = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let mut entries = vec![
+            uniform(0),
+            uniform(1),
+            texture_entry(2),
+            sampler_entry(3),
+        ];
+        entries.extend(ShadowMaps::sample_entries(4));
+        entries
+    }
+
+    pub fn material_entries() -> Vec<wgpu::BindGroupLayoutEntry> {
+        let mut entries = vec![wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }];
+        entries.extend((1..=5).map(texture_entry));
+        entries.push(sampler_entry(6));
+        entries
+    }
+
+    fn create_frame_bind_group(
+        device: &Device,
+        cache: &mut PipelineCache,
+        camera: &wgpu::Buffer,
+        lights: &wgpu::Buffer,
+        environment: &wgpu::TextureView,
+        sampler: &wgpu::Sampler,
+        shadows: &ShadowMaps,
+    ) -> wgpu::BindGroup {
+        let layout = cache.bind_group_layout(device, &Self::frame_entries());
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("pbr_frame_bind_group"),
+            layout: &layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: camera.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: lights.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::TextureView(environment) },
+                wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::Sampler(sampler) },
+                wgpu::BindGroupEntry { binding: 4, resource: shadows.uniform_buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 5, resource: wgpu::BindingResource::TextureView(&shadows.view) },
+                wgpu::BindGroupEntry { binding: 6, resource: wgpu::BindingResource::Sampler(&shadows.sampler) },
+            ],
+        })
+    }
+
+    /// Replaces the environment used for image based ambient light. `image` is an
+    /// equirectangular panorama, its mip chain is used as a cheap prefiltered map.
+    pub fn set_environment(&mut self, device: &Device, queue: &Queue, cache: &mut PipelineCache, image: &ImageData) {
+        let (environment, mips) = create_environment(device, queue, image);
+        self.environment = environment;
+        self.environment_mips = mips;
+        self.environment_image = Some(image.clone());
+        self.rebuild_frame_bind_group(device, cache);
+    }
+
+    /// the image given to `set_environment`, None for the default sky
+    pub fn environment_image(&self) -> Option<&ImageData> {
+        self.environment_image.as_ref()
+    }
+
+    fn rebuild_frame_bind_group(&mut self, device: &Device, cache: &mut PipelineCache) {
+        self.frame_bind_group = Self::create_frame_bind_group(
+            device, cache, &self.camera_buffer, &self.lights_buffer, &self.environment, &self.environment_sampler, &self.shadows,
+        );
+    }
+
+    pub fn shadow_settings(&self) -> ShadowSettings {
+        self.shadows.settings
+    }
+
+    pub fn set_shadow_settings(&mut self, device: &Device, cache: &mut PipelineCache, settings: ShadowSettings) {
+        if self.shadows.set_settings(device, settings) {
+            self.rebuild_frame_bind_group(device, cache);
+        }
+    }
+
+    pub fn create_material(&self, device: &Device, cache: &mut PipelineCache, material: &PbrMaterial) -> Arc<Material> {
+        let uniform = MaterialUniform::new(material);
+        let buffer = wgpu::util::DeviceExt::create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Material Buffer"),
+            contents: bytemuck::bytes_of(&uniform),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let pick = |t: &Option<Arc<Texture>>, default: &Arc<Texture>| t.clone().unwrap_or_else(|| default.clone());
+        // emissive rengi faktörde, texture yoksa beyazla çarpılıyor
+        let textures = [
+            pick(&material.base_color_texture, &self.white),
+            pick(&material.metallic_roughness_texture, &self.linear_white),
+            pick(&material.normal_texture, &self.flat_normal),
+            pick(&material.occlusion_texture, &self.linear_white),
+            pick(&material.emissive_texture, &self.white),
+        ];
+
+        let layout = cache.bind_group_layout(device, &Self::material_entries());
+        let mut entries = vec![wgpu::BindGroupEntry { binding: 0, resource: buffer.as_entire_binding() }];
+        for (i, texture) in textures.iter().enumerate() {
+            entries.push(wgpu::BindGroupEntry {
+                binding: i as u32 + 1,
+                resource: wgpu::BindingResource::TextureView(&texture.view),
+            });
+        }
+        entries.push(wgpu::BindGroupEntry { binding: 6, resource: wgpu::BindingResource::Sampler(&self.material_sampler) });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("material_bind_group"),
+            layout: &layout,
+            entries: &entries,
+        });
+
+        Arc::new(Material {
+            alpha_mode: material.alpha_mode,
+            double_sided: material.double_sided,
+            buffer,
+            bind_group,
+        })
+    }
+
+    pub fn pipeline_key(&self, target: &TargetState, material: &Material) -> PipelineKey {
+        let shader = ShaderVariant::new(PBR_SHADER)
+            .with_constant("ALPHA_MASK", matches!(material.alpha_mode, AlphaMode::Mask(_)) as u8 as f64)
+            .with_constant("TONEMAP", self.tonemap as u8 as f64);
+        let mut key = target.pipeline_key(shader);
+        key.bind_groups = vec![Self::frame_entries(), Self::material_entries()];
+        key.vertex_layouts = vec![Vertex3d::layout(), InstanceData::layout()];
+        key.cull_mode = if material.double_sided { None } else { Some(wgpu::Face::Back) };
+        if material.alpha_mode == AlphaMode::Blend {
+            key.blend = BlendMode::Alpha;
+            if let Some(depth) = key.depth.as_mut() {
+                depth.write = false;
+            }
+        }
+        key
+    }
+
+    pub fn queue_draw(&mut self, mesh: Arc<Mesh>, material: Arc<Material>, transform: Mat4) {
+        self.draws.push(PbrDraw { mesh, material, instance: InstanceData::new(transform) });
+    }
+
+    /// Queues many copies of one mesh and material. Opaque copies are drawn with a
+    /// single call, the instance color multiplies the base color.
+    pub fn queue_instances(&mut self, mesh: &Arc<Mesh>, material: &Arc<Material>, instances: impl IntoIterator<Item = InstanceData>) {
+        self.draws.extend(instances.into_iter().map(|instance| PbrDraw {
+            mesh: mesh.clone(),
+            material: material.clone(),
+            instance,
+        }));
+    }
+
+    /// queued instances of this frame
+    pub fn draw_count(&self) -> usize {
+        self.draws.len()
+    }
+
+    /// draw calls of the last prepared frame
+    pub fn batch_count(&self) -> usize {
+        self.batches.len()
+    }
+
+    /// Sorts the queued draws into instanced batches and uploads camera, lights, shadow
+    /// matrices and instance data.
+    /// Must be called before `render_shadows` and the render pass.
+    pub fn prepare(&mut self, device: &Device, queue: &Queue, camera: &Camera, aspect: f32) {
+        let camera_uniform = CameraUniform::new(camera, aspect);
+        queue.write_buffer(&self.camera_buffer, 0, bytemuck::bytes_of(&camera_uniform));
+        stats::upload(std::mem::size_of_val(&camera_uniform));
+        let shadow_lights = &self.lights[..self.lights.len().min(MAX_LIGHTS)];
+        self.shadows.update(queue, shadow_lights, camera, aspect);
+
+        let mut lights = LightsUniform {
+            ambient: self.ambient_color.extend(self.ambient_intensity).to_array(),
+            params: [self.exposure, self.environment_mips as f32, 0.0, 0.0],
+            counts: [self.lights.len().min(MAX_LIGHTS) as u32, 0, 0, 0],
+            lights: [bytemuck::Zeroable::zeroed(); MAX_LIGHTS],
+        };
+        for (slot, light) in lights.lights.iter_mut().zip(&self.lights) {
+            *slot = light.to_gpu();
+        }
+        queue.write_buffer(&self.lights_buffer, 0, bytemuck::bytes_of(&lights));
+        stats::upload(std::mem::size_of_val(&lights));
+
+        // opaklar önce ve malzeme/mesh'e göre gruplu, saydamlar arkadan öne
+        let eye = camera.position;
+        self.draws.sort_by(|a, b| {
+            let a_blend = a.material.alpha_mode == AlphaMode::Blend;
+            let b_blend = b.material.alpha_mode == AlphaMode::Blend;
+            a_blend.cmp(&b_blend).then_with(|| {
+                if a_blend {
+                    let da = a.instance.transform().w_axis.truncate().distance_squared(eye);
+                    let db = b.instance.transform().w_axis.truncate().distance_squared(eye);
+                    db.total_cmp(&da)
+                } else {
+                    (Arc::as_ptr(&a.material) as usize, Arc::as_ptr(&a.mesh) as usize)
+                        .cmp(&(Arc::as_ptr(&b.material) as usize, Arc::as_ptr(&b.mesh) as usize))
+                }
+            })
+        });
+        // saydamlarda sadece ardışık aynı çizimler birleşir, sıra bozulmaz
+        self.batches = batch_ranges(self.draws.iter().map(|d| (Arc::as_ptr(&d.material), Arc::as_ptr(&d.mesh))));
+        let data: Vec<InstanceData> = self.draws.iter().map(|d| d.instance).collect();
+        self.instances.write(device, queue, &data);
+    }
+
+    /// Renders the shadow maps of this frame's draws, blended draws don't cast shadows.
+    /// Has to be recorded before the main pass that samples them.
+    pub fn render_shadows(&self, device: &Device, cache: &mut PipelineCache, encoder: &mut wgpu::CommandEncoder) {
+        let casters: Vec<(&Mesh, Range<u32>)> = self.batches.iter()
+            .map(|batch| (&self.draws[batch.start as usize], batch.clone()))
+            .filter(|(d, _)| d.material.alpha_mode != AlphaMode::Blend)
+            .map(|(d, batch)| (d.mesh.as_ref(), batch))
+            .collect();
+        self.shadows.render(device, cache, encoder, &self.instances, &casters);
+    }
+
+    /// records the queued draws into `render_pass` and clears the queue
+    pub fn draw(&mut self, device: &Device, cache: &mut PipelineCache, target: &TargetState, render_pass: &mut wgpu::RenderPass<'_>) {
+        if self.draws.is_empty() {
+            return;
+        }
+        render_pass.set_bind_group(0, &self.frame_bind_group, &[]);
+        self.instances.bind(render_pass, 1);
+        let mut pipelines: HashMap<PipelineKey, wgpu::RenderPipeline> = HashMap::new();
+        for batch in &self.batches {
+            let draw = &self.draws[batch.start as usize];
+            let key = self.pipeline_key(target, &draw.material);
+            let pipeline = pipelines.entry(key)
+                .or_insert_with_key(|key| cache.pipeline(device, key));
+            render_pass.set_pipeline(pipeline);
+            stats::pipeline();
+            render_pass.set_bind_group(1, &draw.material.bind_group, &[]);
+            draw.mesh.draw(render_pass, batch.clone());
+        }
+        self.draws.clear();
+    }
+}
+
+fn texture_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    }
+}
+
+fn sampler_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    }
+}
+
+fn create_environment(device: &Device, queue: &Queue, image: &ImageData) -> (wgpu::TextureView, u32) {
+    let mips = generate_mips(image);
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Environment Texture"),
+        size: wgpu::Extent3d { width: image.width, height: image.height, depth_or_array_layers: 1 },
+        mip_level_count: mips.len() as u32,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8UnormSrgb,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    for (level, mip) in mips.iter().enumerate() {
+        write_rgba8(queue, &texture, level as u32, mip);
+    }
+    (texture.create_view(&wgpu::TextureViewDescriptor::default()), mips.len() as u32)
+}
+
+/// A glTF scene uploaded with its materials and textures, drawn by `PbrRenderer`
+pub struct PbrModel {
+    pub meshes: Vec<Vec<(Arc<Mesh>, Arc<Material>)>>,
+    /// (mesh index, world transform) for every node of the default scene that has a mesh
+    pub instances: Vec<(usize, Mat4)>,
+}
+
+impl PbrModel {
+    pub fn new(device: &Device, queue: &Queue, cache: &mut PipelineCache, pbr: &PbrRenderer, scene: &GltfScene) -> Self {
+        // aynı görüntü hem sRGB hem lineer kullanılabilir, ikisi ayrı texture
+        let mut textures: HashMap<(usize, bool), Arc<Texture>> = HashMap::new();
+        let mut texture = |r: Option<TextureRef>, srgb: bool| {
+            r.map(|r| textures.entry((r.image, srgb))
+                .or_insert_with(|| Arc::new(Texture::from_image(device, queue, &scene.images[r.image], srgb, "glTF Texture")))
+                .clone())
+        };
+
+        let mut materials: Vec<Arc<Material>> = scene.materials.iter().map(|m: &GltfMaterial| {
+            let desc = PbrMaterial {
+                base_color: m.base_color_factor,
+                metallic: m.metallic_factor,
+                roughness: m.roughness_factor,
+                emissive: m.emissive_factor,
+                normal_scale: m.normal_scale,
+                occlusion_strength: m.occlusion_strength,
+                alpha_mode: m.alpha_mode,
+                double_sided: m.double_sided,
+                base_color_texture: texture(m.base_color_texture, true),
+                metallic_roughness_texture: texture(m.metallic_roughness_texture, false),
+                normal_texture: texture(m.normal_texture, false),
+                occlusion_texture: texture(m.occlusion_texture, false),
+                emissive_texture: texture(m.emissive_texture, true),
+            };
+            pbr.create_material(device, cache, &desc)
+        }).collect();
+        // malzemesi olmayan primitive'ler için glTF varsayılanı
+        let default_material = materials.len();
+        let gltf_default = GltfMaterial::default();
+        materials.push(pbr.create_material(device, cache, &PbrMaterial {
+            metallic: gltf_default.metallic_factor,
+            roughness: gltf_default.roughness_factor,
+            ..Default::default()
+        }));
+
+        let meshes = scene.meshes.iter().map(|mesh| {
+            mesh.primitives.iter().map(|p| (
+                Arc::new(Mesh::new(device, &p.data)),
+                materials[p.material.unwrap_or(default_material)].clone(),
+            )).collect()
+        }).collect();
+
+        let world = scene.world_transforms();
+        let mut instances = Vec::new();
+        let mut stack = scene.roots.clone();
+        while let Some(node) = stack.pop() {
+            if let Some(mesh) = scene.nodes[node].mesh {
+                instances.push((mesh, world[node]));
+            }
+            stack.extend_from_slice(&scene.nodes[node].children);
+        }
+
+        Self { meshes, instances }
+    }
+
+    pub fn queue(&self, pbr: &mut PbrRenderer, transform: Mat4) {
+        for (mesh, world) in &self.instances {
+            for (primitive, material) in &self.meshes[*mesh] {
+                pbr.queue_draw(primitive.clone(), material.clone(), transform * *world);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::renderer::mesh::MeshData;
+    use crate::engine::renderer::texture::read_texture_rgba8;
+
+    /// Renders a lit cube offscreen on the software (fallback) adapter.
+    /// Skipped when the machine has no fallback adapter at all.
+    #[test]
+    fn offscreen_cube(){
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = match pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::LowPower,
+            compatible_surface: None,
+            force_fallback_adapter: true,
+        })) {
+            Ok(adapter) => adapter,
+            Err(e) => {
+                println!("no fallback adapter, skipping: {}", e);
+                return;
+            }
+        };
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default())).unwrap();
+
+        let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+        let target = TargetState { format, sample_count: 1, depth_format: Some(wgpu::TextureFormat::Depth32Float) };
+        let color = crate::engine::renderer::targets::Attachment::new(
+            &device, "Offscreen Color", (64, 64), format, 1,
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        );
+        let targets = crate::engine::renderer::targets::RenderTargets::new(&device, (64, 64), target);
+
+        let mut cache = PipelineCache::new(format);
+        let mut pbr = PbrRenderer::new(&device, &queue, &mut cache);
+        pbr.lights.push(Light::Directional { direction: Vec3::new(-0.3, -1.0, -0.5), color: Vec3::ONE, intensity: 3.0, cast_shadows: true });
+        let material = pbr.create_material(&device, &mut cache, &PbrMaterial {
+            base_color: [1.0, 0.2, 0.2, 1.0],
+            ..Default::default()
+        });
+        let cube = Arc::new(Mesh::new(&device, &MeshData::cube()));
+        pbr.queue_draw(cube, material, Mat4::IDENTITY);
+
+        let camera = crate::engine::renderer::camera::Camera::default();
+        pbr.prepare(&device, &queue, &camera, 1.0);
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        pbr.render_shadows(&device, &mut cache, &mut encoder);
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Offscreen Pass"),
+                color_attachments: &[Some(targets.color_attachment(&color.view, wgpu::Color::BLACK))],
+                depth_stencil_attachment: targets.depth_attachment(),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pbr.draw(&device, &mut cache, &target, &mut pass);
+        }
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let pixels = read_texture_rgba8(&device, &queue, &color.texture);
+        let center = &pixels[(32 * 64 + 32) * 4..(32 * 64 + 32) * 4 + 4];
+        // kırmızı küp, arka plan siyah
+        assert!(center[0] > 40 && center[0] > center[2], "center pixel {:?}", center);
+        assert_eq!(&pixels[0..4], &[0, 0, 0, 255]);
+    }
+}
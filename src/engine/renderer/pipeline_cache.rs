@@ -0,0 +1,362 @@
+// src/engine/renderer/pipeline_cache.rs
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use wgpu::{Device, TextureFormat};
+
+/// Shader and the pipeline-overridable constants (`override` in WGSL) it is compiled with.
+/// Every distinct set of constants is a separate variant but they all share one shader module.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShaderVariant {
+    pub shader: &'static str,
+    pub vs_entry: &'static str,
+    pub fs_entry: &'static str,
+    // f64 Hash'lenemediği için bit karşılığını tutuyoruz
+    constants: Vec<(&'static str, u64)>,
+}
+
+impl ShaderVariant {
+    pub fn new(shader: &'static str) -> Self {
+        Self {
+            shader,
+            vs_entry: "vs_main",
+            fs_entry: "fs_main",
+            constants: Vec::new(),
+        }
+    }
+
+    pub fn with_entries(mut self, vs_entry: &'static str, fs_entry: &'static str) -> Self {
+        self.vs_entry = vs_entry;
+        self.fs_entry = fs_entry;
+        self
+    }
+
+    /// sets an `override` constant, booleans are passed as 0.0 / 1.0
+    pub fn with_constant(mut self, name: &'static str, value: f64) -> Self {
+        self.constants.retain(|(n, _)| *n != name);
+        self.constants.push((name, value.to_bits()));
+        // aynı sabitler farklı sırada verilse de aynı anahtar çıksın
+        self.constants.sort_by_key(|(n, _)| *n);
+        self
+    }
+
+    pub fn constants(&self) -> Vec<(&'static str, f64)> {
+        self.constants.iter().map(|(n, v)| (*n, f64::from_bits(*v))).collect()
+    }
+}
+
+/// Hashable copy of `wgpu::VertexBufferLayout`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VertexLayoutKey {
+    pub array_stride: wgpu::BufferAddress,
+    pub step_mode: wgpu::VertexStepMode,
+    pub attributes: Vec<wgpu::VertexAttribute>,
+}
+
+impl VertexLayoutKey {
+    pub fn as_wgpu(&self) -> wgpu::VertexBufferLayout<'_> {
+        wgpu::VertexBufferLayout {
+            array_stride: self.array_stride,
+            step_mode: self.step_mode,
+            attributes: &self.attributes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BlendMode {
+    #[default]
+    Replace,
+    Alpha,
+    PremultipliedAlpha,
+    Additive,
+}
+
+impl BlendMode {
+    pub fn to_wgpu(self) -> wgpu::BlendState {
+        match self {
+            BlendMode::Replace => wgpu::BlendState::REPLACE,
+            BlendMode::Alpha => wgpu::BlendState::ALPHA_BLENDING,
+            BlendMode::PremultipliedAlpha => wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+            BlendMode::Additive => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::One,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DepthKey {
+    pub format: TextureFormat,
+    pub write: bool,
+    pub compare: wgpu::CompareFunction,
+}
+
+impl DepthKey {
+    pub fn new(format: TextureFormat) -> Self {
+        Self {
+            format,
+            write: true,
+            compare: wgpu::CompareFunction::Less,
+        }
+    }
+}
+
+/// Everything that makes two render pipelines different.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PipelineKey {
+    pub shader: ShaderVariant,
+    pub bind_groups: Vec<Vec<wgpu::BindGroupLayoutEntry>>,
+    pub vertex_layouts: Vec<VertexLayoutKey>,
+    pub topology: wgpu::PrimitiveTopology,
+    pub cull_mode: Option<wgpu::Face>,
+    pub blend: BlendMode,
+    pub depth: Option<DepthKey>,
+    pub sample_count: u32,
+    pub format: TextureFormat,
+    /// no fragment stage and no color target, `format` and `fs_entry` are ignored (shadow maps)
+    pub depth_only: bool,
+}
+
+impl PipelineKey {
+    pub fn new(shader: ShaderVariant, format: TextureFormat) -> Self {
+        Self {
+            shader,
+            bind_groups: Vec::new(),
+            vertex_layouts: Vec::new(),
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            cull_mode: Some(wgpu::Face::Back),
+            blend: BlendMode::Replace,
+            depth: None,
+            sample_count: 1,
+            format,
+            depth_only: false,
+        }
+    }
+}
+
+/// A replacement shader source with its module and pipelines, made by `build_candidate`
+pub struct ShaderCandidate {
+    pub name: &'static str,
+    source: String,
+    pub module: wgpu::ShaderModule,
+    pipelines: Vec<(PipelineKey, wgpu::RenderPipeline)>,
+}
+
+/// Builds render pipelines, bind group layouts and shader modules on demand and keeps them
+/// across frames. Pipelines that target the surface are dropped when the surface format changes.
+pub struct PipelineCache {
+    sources: HashMap<&'static str, Cow<'static, str>>,
+    shaders: HashMap<&'static str, wgpu::ShaderModule>,
+    bind_group_layouts: HashMap<Vec<wgpu::BindGroupLayoutEntry>, wgpu::BindGroupLayout>,
+    pipelines: HashMap<PipelineKey, wgpu::RenderPipeline>,
+    surface_format: TextureFormat,
+}
+
+impl PipelineCache {
+    pub fn new(surface_format: TextureFormat) -> Self {
+        Self {
+            sources: HashMap::new(),
+            shaders: HashMap::new(),
+            bind_group_layouts: HashMap::new(),
+            pipelines: HashMap::new(),
+            surface_format,
+        }
+    }
+
+    pub fn surface_format(&self) -> TextureFormat {
+        self.surface_format
+    }
+
+    /// Registers (or replaces) the WGSL source of a shader. Replacing a source drops the
+    /// module and every pipeline built from it, they are rebuilt on the next request.
+    pub fn register_shader<S: Into<Cow<'static, str>>>(&mut self, name: &'static str, source: S) {
+        let source = source.into();
+        if let Some(old) = self.sources.get(name) {
+            if *old == source {
+                return;
+            }
+            self.shaders.remove(name);
+            self.pipelines.retain(|k, _| k.shader.shader != name);
+        }
+        self.sources.insert(name, source);
+    }
+
+    pub fn has_shader(&self, name: &str) -> bool {
+        self.sources.contains_key(name)
+    }
+
+    pub fn shader(&mut self, device: &Device, name: &'static str) -> wgpu::ShaderModule {
+        if let Some(module) = self.shaders.get(name) {
+            return module.clone();
+        }
+        let source = self.sources.get(name)
+            .unwrap_or_else(|| panic!("shader `{}` is not registered", name));
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(name),
+            source: wgpu::ShaderSource::Wgsl(source.clone()),
+        });
+        self.shaders.insert(name, module.clone());
+        module
+    }
+
+    pub fn bind_group_layout(&mut self, device: &Device, entries: &[wgpu::BindGroupLayoutEntry]) -> wgpu::BindGroupLayout {
+        if let Some(layout) = self.bind_group_layouts.get(entries) {
+            return layout.clone();
+        }
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("cached_bind_group_layout"),
+            entries,
+        });
+        self.bind_group_layouts.insert(entries.to_vec(), layout.clone());
+        layout
+    }
+
+    pub fn pipeline(&mut self, device: &Device, key: &PipelineKey) -> wgpu::RenderPipeline {
+        if let Some(pipeline) = self.pipelines.get(key) {
+            return pipeline.clone();
+        }
+        let shader = self.shader(device, key.shader.shader);
+        let pipeline = self.create_pipeline(device, key, &shader);
+        self.pipelines.insert(key.clone(), pipeline.clone());
+        pipeline
+    }
+
+    /// Builds `source` as shader `name` together with every cached pipeline that uses it,
+    /// nothing in the cache changes. Errors go to the device's error scopes, see
+    /// `install_candidate`.
+    pub fn build_candidate(&mut self, device: &Device, name: &'static str, source: String) -> ShaderCandidate {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some(name),
+            source: wgpu::ShaderSource::Wgsl(source.as_str().into()),
+        });
+        let keys: Vec<PipelineKey> = self.pipelines.keys().filter(|k| k.shader.shader == name).cloned().collect();
+        let pipelines = keys.into_iter().map(|key| {
+            let pipeline = self.create_pipeline(device, &key, &module);
+            (key, pipeline)
+        }).collect();
+        ShaderCandidate { name, source, module, pipelines }
+    }
+
+    /// Replaces the source of a shader with a candidate that built without errors, its
+    /// pipelines are used from now on
+    pub fn install_candidate(&mut self, candidate: ShaderCandidate) {
+        self.register_shader(candidate.name, candidate.source);
+        self.shaders.insert(candidate.name, candidate.module);
+        self.pipelines.extend(candidate.pipelines);
+    }
+
+    fn create_pipeline(&mut self, device: &Device, key: &PipelineKey, shader: &wgpu::ShaderModule) -> wgpu::RenderPipeline {
+        let layouts: Vec<wgpu::BindGroupLayout> = key.bind_groups.iter()
+            .map(|entries| self.bind_group_layout(device, entries))
+            .collect();
+        let layout_refs: Vec<&wgpu::BindGroupLayout> = layouts.iter().collect();
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Cached Pipeline Layout"),
+            bind_group_layouts: &layout_refs,
+            push_constant_ranges: &[],
+        });
+
+        let vertex_buffers: Vec<wgpu::VertexBufferLayout> = key.vertex_layouts.iter()
+            .map(|l| l.as_wgpu())
+            .collect();
+        let constants = key.shader.constants();
+        let color_targets = [Some(wgpu::ColorTargetState {
+            format: key.format,
+            blend: Some(key.blend.to_wgpu()),
+            write_mask: wgpu::ColorWrites::ALL,
+        })];
+        let compilation_options = wgpu::PipelineCompilationOptions {
+            constants: &constants,
+            ..Default::default()
+        };
+
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(key.shader.shader),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some(key.shader.vs_entry),
+                buffers: &vertex_buffers,
+                compilation_options: compilation_options.clone(),
+            },
+            fragment: (!key.depth_only).then_some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some(key.shader.fs_entry),
+                targets: &color_targets,
+                compilation_options,
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: key.topology,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: key.cull_mode,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: key.depth.map(|d| wgpu::DepthStencilState {
+                format: d.format,
+                depth_write_enabled: d.write,
+                depth_compare: d.compare,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: key.sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    /// Called when the surface is reconfigured. Pipelines rendering into the old surface
+    /// format are dropped, offscreen ones (HDR targets etc.) are kept.
+    pub fn set_surface_format(&mut self, format: TextureFormat) {
+        if format == self.surface_format {
+            return;
+        }
+        let old = self.surface_format;
+        self.pipelines.retain(|k, _| k.depth_only || k.format != old);
+        self.surface_format = format;
+    }
+
+    pub fn pipeline_count(&self) -> usize {
+        self.pipelines.len()
+    }
+
+    /// drops everything except the registered shader sources
+    pub fn clear(&mut self) {
+        self.shaders.clear();
+        self.bind_group_layouts.clear();
+        self.pipelines.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variant_constant_order(){
+        let a = ShaderVariant::new("pbr").with_constant("A", 1.0).with_constant("B", 0.0);
+        let b = ShaderVariant::new("pbr").with_constant("B", 0.0).with_constant("A", 1.0);
+        assert_eq!(a, b);
+        let c = a.clone().with_constant("A", 0.0);
+        assert_ne!(a, c);
+        assert_eq!(c.constants(), vec![("A", 0.0), ("B", 0.0)]);
+    }
+}
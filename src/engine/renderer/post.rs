@@ -0,0 +1,413 @@
+// src/engine/renderer/post.rs
+
+use std::fmt;
+
+use wgpu::{Adapter, Device, Queue, TextureFormat};
+
+use crate::engine::renderer::gltf_loader::ImageData;
+use crate::engine::renderer::pipeline_cache::{BlendMode, PipelineCache, PipelineKey, ShaderVariant};
+use crate::engine::renderer::settings::{PostProcessSettings, Tonemapper};
+use crate::engine::renderer::stats;
+use crate::engine::renderer::targets::Attachment;
+use crate::engine::renderer::texture::write_rgba8;
+
+pub const POST_SHADER: &str = "post";
+const POST_SHADER_SOURCE: &str = include_str!("../../../gsl/post.wgsl");
+
+const MAX_BLOOM_LEVELS: u32 = 6;
+const DEFAULT_LUT_SIZE: u32 = 16;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct PostUniform {
+    params: [f32; 4],
+    bloom: [f32; 4],
+    vignette: [f32; 4],
+    lut: [f32; 4],
+    flags: [u32; 4],
+}
+
+impl PostUniform {
+    fn new(settings: &PostProcessSettings, lut_size: u32) -> Self {
+        let tonemapper = match settings.tonemapper {
+            Tonemapper::None => 0.0,
+            Tonemapper::Aces => 1.0,
+            Tonemapper::Filmic => 2.0,
+        };
+        Self {
+            params: [settings.exposure, tonemapper, settings.bloom_intensity, settings.lut_strength],
+            bloom: [settings.bloom_threshold, settings.bloom_knee, 0.0, 0.0],
+            vignette: [settings.vignette_intensity, settings.vignette_radius, settings.vignette_smoothness, 0.0],
+            lut: [lut_size as f32, 0.0, 0.0, 0.0],
+            flags: [settings.bloom as u32, settings.color_grading as u32, settings.vignette as u32, settings.fxaa as u32],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LutError {
+    /// the image is not a strip of `height` slices of `height` x `height`
+    InvalidSize { width: u32, height: u32 },
+}
+
+impl fmt::Display for LutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LutError::InvalidSize { width, height } => {
+                write!(f, "LUT must be {}x{} for a height of {}, got {}x{}", height * height, height, height, width, height)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LutError {}
+
+/// Textures and bind groups that depend on the surface size
+struct PostTargets {
+    hdr: Attachment,
+    bloom: Vec<Attachment>,
+    ldr: Attachment,
+    bloom_down: Vec<wgpu::BindGroup>,
+    bloom_up: Vec<wgpu::BindGroup>,
+    composite: wgpu::BindGroup,
+    fxaa: wgpu::BindGroup,
+}
+
+/// Post-processing stack. When enabled the main pass renders into an HDR target that
+/// goes through bloom, tone mapping, LUT grading, vignette and FXAA on its way to the surface.
+pub struct PostProcess {
+    settings: PostProcessSettings,
+    size: (u32, u32),
+    hdr_format: TextureFormat,
+    output_format: TextureFormat,
+    uniform_buffer: wgpu::Buffer,
+    sampler: wgpu::Sampler,
+    lut: wgpu::TextureView,
+    lut_size: u32,
+    /// kept to upload it again after a device loss
+    lut_image: Option<ImageData>,
+    targets: Option<PostTargets>,
+}
+
+impl PostProcess {
+    pub fn new(device: &Device, queue: &Queue, cache: &mut PipelineCache) -> Self {
+        cache.register_shader(POST_SHADER, POST_SHADER_SOURCE);
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Post Uniform Buffer"),
+            size: std::mem::size_of::<PostUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Post Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let lut = create_lut(device, queue, &identity_lut(DEFAULT_LUT_SIZE));
+
+        Self {
+            settings: PostProcessSettings { enabled: false, ..Default::default() },
+            size: (1, 1),
+            hdr_format: TextureFormat::Rgba16Float,
+            output_format: cache.surface_format(),
+            uniform_buffer,
+            sampler,
+            lut,
+            lut_size: DEFAULT_LUT_SIZE,
+            lut_image: None,
+            targets: None,
+        }
+    }
+
+    pub fn settings(&self) -> PostProcessSettings {
+        self.settings
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.targets.is_some()
+    }
+
+    pub fn hdr_format(&self) -> TextureFormat {
+        self.hdr_format
+    }
+
+    /// Applies new settings and (re)creates the intermediate targets. `output_format` is the surface format.
+    pub fn configure(
+        &mut self,
+        device: &Device,
+        cache: &mut PipelineCache,
+        settings: PostProcessSettings,
+        size: (u32, u32),
+        hdr_format: TextureFormat,
+        output_format: TextureFormat,
+    ) {
+        self.settings = settings;
+        self.size = size;
+        self.hdr_format = hdr_format;
+        self.output_format = output_format;
+        self.targets = settings.enabled.then(|| self.create_targets(device, cache));
+    }
+
+    pub fn resize(&mut self, device: &Device, cache: &mut PipelineCache, size: (u32, u32)) {
+        self.size = size;
+        if self.targets.is_some() {
+            self.targets = Some(self.create_targets(device, cache));
+        }
+    }
+
+    /// Replaces the grading LUT. `image` is a horizontal strip of N slices of N x N
+    /// (e.g. 256x16 or 1024x32), blue selects the slice. Colors are sRGB encoded.
+    pub fn set_lut(&mut self, device: &Device, queue: &Queue, cache: &mut PipelineCache, image: &ImageData) -> Result<(), LutError> {
+        if image.height < 2 || image.width != image.height * image.height {
+            return Err(LutError::InvalidSize { width: image.width, height: image.height });
+        }
+        self.lut = create_lut(device, queue, image);
+        self.lut_size = image.height;
+        self.lut_image = Some(image.clone());
+        if self.targets.is_some() {
+            self.targets = Some(self.create_targets(device, cache));
+        }
+        Ok(())
+    }
+
+    /// the image given to `set_lut`, None for the identity LUT
+    pub fn lut_image(&self) -> Option<&ImageData> {
+        self.lut_image.as_ref()
+    }
+
+    /// The view the main pass should render into, `None` when post-processing is off
+    pub fn target_view(&self) -> Option<&wgpu::TextureView> {
+        self.targets.as_ref().map(|t| &t.hdr.view)
+    }
+
+    pub fn entries(with_extra: bool) -> Vec<wgpu::BindGroupLayoutEntry> {
+        let texture = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let mut entries = vec![
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            texture(1),
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ];
+        // composite geçişi bloom ve LUT'u da okuyor
+        if with_extra {
+            entries.push(texture(3));
+            entries.push(texture(4));
+        }
+        entries
+    }
+
+    fn pipeline_key(fs_entry: &'static str, format: TextureFormat, blend: BlendMode, prefilter: bool) -> PipelineKey {
+        let shader = ShaderVariant::new(POST_SHADER)
+            .with_entries("vs_fullscreen", fs_entry)
+            .with_constant("PREFILTER", prefilter as u8 as f64);
+        let mut key = PipelineKey::new(shader, format);
+        key.bind_groups = vec![Self::entries(fs_entry == "fs_composite")];
+        key.cull_mode = None;
+        key.blend = blend;
+        key
+    }
+
+    fn bind_group(&self, device: &Device, cache: &mut PipelineCache, source: &wgpu::TextureView, extra: Option<(&wgpu::TextureView, &wgpu::TextureView)>) -> wgpu::BindGroup {
+        let layout = cache.bind_group_layout(device, &Self::entries(extra.is_some()));
+        let mut entries = vec![
+            wgpu::BindGroupEntry { binding: 0, resource: self.uniform_buffer.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::TextureView(source) },
+            wgpu::BindGroupEntry { binding: 2, resource: wgpu::BindingResource::Sampler(&self.sampler) },
+        ];
+        if let Some((bloom, lut)) = extra {
+            entries.push(wgpu::BindGroupEntry { binding: 3, resource: wgpu::BindingResource::TextureView(bloom) });
+            entries.push(wgpu::BindGroupEntry { binding: 4, resource: wgpu::BindingResource::TextureView(lut) });
+        }
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("post_bind_group"),
+            layout: &layout,
+            entries: &entries,
+        })
+    }
+
+    fn create_targets(&self, device: &Device, cache: &mut PipelineCache) -> PostTargets {
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING;
+        let hdr = Attachment::new(device, "HDR Color Texture", self.size, self.hdr_format, 1, usage);
+        let ldr = Attachment::new(device, "Post LDR Texture", self.size, self.output_format, 1, usage);
+
+        // her seviye ayrı texture, aynı texture'ın mip'ine yazıp okumak WebGL'de sorun çıkarıyor
+        let bloom: Vec<Attachment> = bloom_sizes(self.size).into_iter()
+            .map(|size| Attachment::new(device, "Bloom Texture", size, self.hdr_format, 1, usage))
+            .collect();
+
+        let bloom_down = (0..bloom.len()).map(|i| {
+            let source = if i == 0 { &hdr.view } else { &bloom[i - 1].view };
+            self.bind_group(device, cache, source, None)
+        }).collect();
+        // up[i]: bloom[i + 1] -> bloom[i]
+        let bloom_up = (0..bloom.len() - 1)
+            .map(|i| self.bind_group(device, cache, &bloom[i + 1].view, None))
+            .collect();
+        let composite = self.bind_group(device, cache, &hdr.view, Some((&bloom[0].view, &self.lut)));
+        let fxaa = self.bind_group(device, cache, &ldr.view, None);
+
+        PostTargets { hdr, bloom, ldr, bloom_down, bloom_up, composite, fxaa }
+    }
+
+    /// Records the post chain, reading the HDR target and writing `output` (the surface view).
+    /// Does nothing when post-processing is off.
+    pub fn run(&self, device: &Device, queue: &Queue, cache: &mut PipelineCache, encoder: &mut wgpu::CommandEncoder, output: &wgpu::TextureView) {
+        let Some(targets) = &self.targets else {
+            return;
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&PostUniform::new(&self.settings, self.lut_size)));
+        stats::upload(std::mem::size_of::<PostUniform>());
+
+        if self.settings.bloom {
+            let prefilter = cache.pipeline(device, &Self::pipeline_key("fs_bloom_down", self.hdr_format, BlendMode::Replace, true));
+            let down = cache.pipeline(device, &Self::pipeline_key("fs_bloom_down", self.hdr_format, BlendMode::Replace, false));
+            let up = cache.pipeline(device, &Self::pipeline_key("fs_bloom_up", self.hdr_format, BlendMode::Additive, false));
+            for (i, bind_group) in targets.bloom_down.iter().enumerate() {
+                let pipeline = if i == 0 { &prefilter } else { &down };
+                fullscreen_pass(encoder, "Bloom Downsample", &targets.bloom[i].view, true, pipeline, bind_group);
+            }
+            for (i, bind_group) in targets.bloom_up.iter().enumerate().rev() {
+                fullscreen_pass(encoder, "Bloom Upsample", &targets.bloom[i].view, false, &up, bind_group);
+            }
+        }
+
+        let composite = cache.pipeline(device, &Self::pipeline_key("fs_composite", self.output_format, BlendMode::Replace, false));
+        let composite_target = if self.settings.fxaa { &targets.ldr.view } else { output };
+        fullscreen_pass(encoder, "Post Composite", composite_target, true, &composite, &targets.composite);
+
+        if self.settings.fxaa {
+            let fxaa = cache.pipeline(device, &Self::pipeline_key("fs_fxaa", self.output_format, BlendMode::Replace, false));
+            fullscreen_pass(encoder, "FXAA", output, true, &fxaa, &targets.fxaa);
+        }
+    }
+}
+
+fn fullscreen_pass(
+    encoder: &mut wgpu::CommandEncoder,
+    label: &str,
+    target: &wgpu::TextureView,
+    clear: bool,
+    pipeline: &wgpu::RenderPipeline,
+    bind_group: &wgpu::BindGroup,
+) {
+    let load = if clear { wgpu::LoadOp::Clear(wgpu::Color::BLACK) } else { wgpu::LoadOp::Load };
+    let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some(label),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: target,
+            resolve_target: None,
+            ops: wgpu::Operations { load, store: wgpu::StoreOp::Store },
+            depth_slice: None,
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+    pass.set_pipeline(pipeline);
+    stats::pipeline();
+    pass.set_bind_group(0, bind_group, &[]);
+    pass.draw(0..3, 0..1);
+    stats::draw(1);
+}
+
+/// Rgba16Float when the adapter can render into it, WebGL2 without
+/// EXT_color_buffer_float falls back to an 8 bit (LDR) target.
+pub fn hdr_format(adapter: &Adapter) -> TextureFormat {
+    let features = adapter.get_texture_format_features(TextureFormat::Rgba16Float);
+    let usage = wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING;
+    if features.allowed_usages.contains(usage)
+        && features.flags.contains(wgpu::TextureFormatFeatureFlags::FILTERABLE | wgpu::TextureFormatFeatureFlags::BLENDABLE)
+    {
+        TextureFormat::Rgba16Float
+    } else {
+        TextureFormat::Rgba8Unorm
+    }
+}
+
+/// sizes of the bloom chain, starting at half resolution
+fn bloom_sizes(size: (u32, u32)) -> Vec<(u32, u32)> {
+    let mut sizes = Vec::new();
+    let (mut w, mut h) = ((size.0 / 2).max(1), (size.1 / 2).max(1));
+    loop {
+        sizes.push((w, h));
+        if sizes.len() as u32 == MAX_BLOOM_LEVELS || w.min(h) < 4 {
+            break;
+        }
+        w /= 2;
+        h /= 2;
+    }
+    sizes
+}
+
+/// strip LUT that maps every color to itself
+pub fn identity_lut(size: u32) -> ImageData {
+    let mut rgba = Vec::with_capacity((size * size * size * 4) as usize);
+    let scale = |v: u32| (v * 255 / (size - 1)) as u8;
+    for g in 0..size {
+        for b in 0..size {
+            for r in 0..size {
+                rgba.extend_from_slice(&[scale(r), scale(g), scale(b), 255]);
+            }
+        }
+    }
+    ImageData { width: size * size, height: size, rgba }
+}
+
+fn create_lut(device: &Device, queue: &Queue, image: &ImageData) -> wgpu::TextureView {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Color Grading LUT"),
+        size: wgpu::Extent3d { width: image.width, height: image.height, depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        // değerler zaten sRGB kodlu, donanım çevirmesin
+        format: TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    write_rgba8(queue, &texture, 0, image);
+    texture.create_view(&wgpu::TextureViewDescriptor::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bloom_chain(){
+        assert_eq!(bloom_sizes((1280, 720)), vec![(640, 360), (320, 180), (160, 90), (80, 45), (40, 22), (20, 11)]);
+        assert_eq!(bloom_sizes((8, 8)), vec![(4, 4), (2, 2)]);
+        assert_eq!(bloom_sizes((1, 1)), vec![(1, 1)]);
+
+        let lut = identity_lut(4);
+        assert_eq!((lut.width, lut.height), (16, 4));
+        // ikinci dilim (mavi = 85), ilk satır, ikinci sütun (kırmızı = 85)
+        assert_eq!(&lut.rgba[(4 + 1) * 4..(4 + 1) * 4 + 4], &[85, 0, 85, 255]);
+    }
+}
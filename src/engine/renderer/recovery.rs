@@ -0,0 +1,135 @@
+// src/engine/renderer/recovery.rs
+//
+// Surface and device loss. A lost or outdated swapchain is just configured again, a lost
+// device takes every GPU resource with it: the CPU side of the renderer is saved into a
+// `RendererState`, a new `GraphicsContext` is created and the state is uploaded again.
+
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+#[cfg(target_family = "wasm")]
+use ts_rs::TS;
+use wgpu::{Device, Queue};
+
+use crate::engine::renderer::camera::Camera;
+use crate::engine::renderer::gltf_loader::ImageData;
+use crate::engine::renderer::particles::ParticleEmitter;
+use crate::engine::renderer::pbr::Light;
+use crate::engine::renderer::text::{Font, FontId};
+use crate::engine::renderer::tilemap::Tilemap;
+use crate::engine::renderer::SurfaceManager;
+
+/// What happened to the surface or the device, sent as `Message::Graphics` and given to the
+/// game as `EngineEvent::Graphics`
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GraphicsEvent {
+    /// the swapchain was lost or outdated and has been configured again
+    SurfaceReconfigured(String),
+    /// acquiring the next frame timed out, nothing was drawn
+    FrameSkipped,
+    /// every GPU resource is gone, a new device is being created
+    DeviceLost(String),
+    /// The renderer works again. Meshes, textures and materials the game created itself
+    /// belong to the old device and have to be uploaded again from their CPU data; scene
+    /// components still using them are skipped when drawing until they're replaced.
+    DeviceRecreated,
+}
+
+/// The CPU side of a `SurfaceManager`, everything that can be uploaded to a new device
+pub struct RendererState {
+    camera: Camera,
+    fonts: Vec<Font>,
+    lights: Vec<Light>,
+    ambient_color: Vec3,
+    ambient_intensity: f32,
+    exposure: f32,
+    environment: Option<ImageData>,
+    lut: Option<ImageData>,
+    gpu_particles: bool,
+    emitters: Vec<Option<ParticleEmitter>>,
+    tilemaps: Vec<Option<Tilemap>>,
+    stats_overlay: bool,
+    stats_font: Option<FontId>,
+}
+
+impl SurfaceManager {
+    /// Configures the swapchain again after `SurfaceError::Lost` or `Outdated`,
+    /// resizing first when the window size changed in the meantime
+    pub fn reconfigure(&mut self, device: &Device, window_size: (u32, u32)) {
+        if window_size != self.size && window_size.0 > 0 && window_size.1 > 0 {
+            self.resize(window_size, device);
+        } else {
+            self.surface.configure(device, &self.config);
+        }
+    }
+
+    /// Drops the GPU side of the renderer, keeping what `restore_state` needs
+    pub fn save_state(mut self) -> RendererState {
+        RendererState {
+            camera: self.camera,
+            fonts: self.text.take_fonts(),
+            lights: std::mem::take(&mut self.pbr.lights),
+            ambient_color: self.pbr.ambient_color,
+            ambient_intensity: self.pbr.ambient_intensity,
+            exposure: self.pbr.exposure,
+            environment: self.pbr.environment_image().cloned(),
+            lut: self.post.lut_image().cloned(),
+            gpu_particles: self.particles.uses_gpu_simulation(),
+            emitters: self.particles.take_emitters(),
+            tilemaps: self.tilemaps.take_all(),
+            stats_overlay: self.stats.overlay,
+            stats_font: self.stats.overlay_font,
+        }
+    }
+
+    /// Uploads a state saved on another device. Returns what couldn't be brought back.
+    pub fn restore_state(&mut self, device: &Device, queue: &Queue, state: RendererState) -> Vec<String> {
+        let mut warnings = Vec::new();
+        self.camera = state.camera;
+        for font in state.fonts {
+            self.text.add_font(font);
+        }
+        self.pbr.lights = state.lights;
+        self.pbr.ambient_color = state.ambient_color;
+        self.pbr.ambient_intensity = state.ambient_intensity;
+        self.pbr.exposure = state.exposure;
+        if let Some(image) = &state.environment {
+            self.pbr.set_environment(device, queue, &mut self.pipeline_cache, image);
+        }
+        if let Some(image) = &state.lut
+            && let Err(e) = self.post.set_lut(device, queue, &mut self.pipeline_cache, image)
+        {
+            warnings.push(format!("color grading lut couldn't be restored: {}", e));
+        }
+
+        if self.particles.uses_gpu_simulation() != state.gpu_particles {
+            self.particles.set_gpu_simulation(device, &mut self.pipeline_cache, state.gpu_particles);
+        }
+        let emitters = state.emitters.into_iter().enumerate().map(|(index, emitter)| {
+            emitter.map(|mut emitter| {
+                // doku eski cihaza ait, varsayılan doku kullanılıyor
+                if emitter.texture.take().is_some() {
+                    warnings.push(format!("particle emitter {} lost its texture", index));
+                }
+                emitter
+            })
+        }).collect();
+        self.particles.set_emitters(device, &mut self.pipeline_cache, emitters);
+
+        let tilemaps = state.tilemaps.into_iter().enumerate().map(|(index, tilemap)| {
+            let tilemap = tilemap?;
+            match tilemap.reload(device, queue, &mut self.pipeline_cache) {
+                Ok(tilemap) => Some(tilemap),
+                Err(e) => {
+                    warnings.push(format!("tilemap {} couldn't be reloaded: {}", index, e));
+                    None
+                }
+            }
+        }).collect();
+        self.tilemaps.set_all(tilemaps);
+
+        self.stats.overlay = state.stats_overlay;
+        self.stats.overlay_font = state.stats_font;
+        warnings
+    }
+}
@@ -0,0 +1,202 @@
+// src/engine/renderer/settings.rs
+
+use serde::{Deserialize, Serialize};
+#[cfg(target_family = "wasm")]
+use ts_rs::TS;
+use wgpu::{PresentMode, SurfaceCapabilities, TextureFormat};
+
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VsyncMode {
+    /// Fifo, her platformda var
+    On,
+    /// Immediate, yoksa Mailbox
+    Off,
+    /// FifoRelaxed, geç kalan kareler beklemeden gösterilir
+    Adaptive,
+    Mailbox,
+}
+
+impl VsyncMode {
+    /// picks the closest present mode the surface supports
+    pub fn present_mode(self, caps: &SurfaceCapabilities) -> PresentMode {
+        let wanted: &[PresentMode] = match self {
+            VsyncMode::On => &[PresentMode::Fifo],
+            VsyncMode::Off => &[PresentMode::Immediate, PresentMode::Mailbox],
+            VsyncMode::Adaptive => &[PresentMode::FifoRelaxed],
+            VsyncMode::Mailbox => &[PresentMode::Mailbox],
+        };
+        wanted.iter()
+            .copied()
+            .find(|m| caps.present_modes.contains(m))
+            .unwrap_or(PresentMode::Fifo)
+    }
+}
+
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DepthFormat {
+    Depth16,
+    Depth24Plus,
+    Depth32Float,
+}
+
+impl DepthFormat {
+    pub fn to_wgpu(self) -> TextureFormat {
+        match self {
+            DepthFormat::Depth16 => TextureFormat::Depth16Unorm,
+            DepthFormat::Depth24Plus => TextureFormat::Depth24Plus,
+            DepthFormat::Depth32Float => TextureFormat::Depth32Float,
+        }
+    }
+}
+
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorPreference {
+    Srgb,
+    Linear,
+    /// 16 bit float swapchain if the surface offers one, sRGB otherwise
+    Hdr,
+}
+
+impl ColorPreference {
+    pub fn surface_format(self, caps: &SurfaceCapabilities) -> TextureFormat {
+        let find = |f: &dyn Fn(&TextureFormat) -> bool| caps.formats.iter().copied().find(|x| f(x));
+        let srgb = find(&|f| f.is_srgb());
+        let picked = match self {
+            ColorPreference::Srgb => srgb,
+            ColorPreference::Linear => find(&|f| !f.is_srgb()),
+            ColorPreference::Hdr => find(&|f| *f == TextureFormat::Rgba16Float).or(srgb),
+        };
+        picked.unwrap_or(caps.formats[0])
+    }
+}
+
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ShadowSettings {
+    pub enabled: bool,
+    /// width and height of every shadow map layer
+    pub resolution: u32,
+    /// 1..=4 cascades for the directional light
+    pub cascades: u32,
+    /// 0 uniform, 1 logarithmic cascade splits
+    pub split_lambda: f32,
+    /// shadows are only rendered up to this distance from the camera
+    pub max_distance: f32,
+    /// subtracted from the receiver depth (NDC units)
+    pub depth_bias: f32,
+    /// receiver position is pushed along its normal by this much (world units)
+    pub normal_bias: f32,
+    /// PCF kernel radius in texels, 0 is a single hardware compare
+    pub pcf_radius: u32,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            resolution: 1024,
+            cascades: 3,
+            split_lambda: 0.7,
+            max_distance: 100.0,
+            depth_bias: 0.002,
+            normal_bias: 0.02,
+            pcf_radius: 1,
+        }
+    }
+}
+
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tonemapper {
+    /// clamps to 0..1
+    None,
+    Aces,
+    /// Hable (Uncharted 2) curve
+    Filmic,
+}
+
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PostProcessSettings {
+    /// false renders straight into the swapchain, the other fields are ignored then
+    pub enabled: bool,
+    pub exposure: f32,
+    pub tonemapper: Tonemapper,
+    pub bloom: bool,
+    /// brightness where bloom starts
+    pub bloom_threshold: f32,
+    /// soft transition around the threshold, 0 is a hard cut
+    pub bloom_knee: f32,
+    pub bloom_intensity: f32,
+    /// grades with the LUT set by `PostProcess::set_lut`, identity until one is set
+    pub color_grading: bool,
+    pub lut_strength: f32,
+    pub vignette: bool,
+    pub vignette_intensity: f32,
+    /// distance from the center (0.5 is the edge midpoint) where darkening starts
+    pub vignette_radius: f32,
+    pub vignette_smoothness: f32,
+    pub fxaa: bool,
+}
+
+impl Default for PostProcessSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            exposure: 1.0,
+            tonemapper: Tonemapper::Aces,
+            bloom: true,
+            bloom_threshold: 1.0,
+            bloom_knee: 0.5,
+            bloom_intensity: 0.05,
+            color_grading: false,
+            lut_strength: 1.0,
+            vignette: false,
+            vignette_intensity: 0.5,
+            vignette_radius: 0.75,
+            vignette_smoothness: 0.45,
+            fxaa: true,
+        }
+    }
+}
+
+/// Renderer options that can be changed while the game is running with
+/// `Message::SetRendererSettings`.
+#[cfg_attr(target_family = "wasm", derive(TS))]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RendererSettings {
+    pub vsync: VsyncMode,
+    /// 1 kapatır, desteklenmeyen değerler 1'e düşer
+    pub msaa_samples: u32,
+    pub depth_format: Option<DepthFormat>,
+    pub color: ColorPreference,
+    pub frame_latency: u32,
+    pub clear_color: [f64; 4],
+    pub shadows: ShadowSettings,
+    pub post: PostProcessSettings,
+}
+
+impl Default for RendererSettings {
+    fn default() -> Self {
+        Self {
+            vsync: VsyncMode::On,
+            msaa_samples: 1,
+            depth_format: Some(DepthFormat::Depth32Float),
+            color: ColorPreference::Srgb,
+            frame_latency: 2,
+            clear_color: [0.0, 0.0, 0.0, 1.0],
+            shadows: ShadowSettings::default(),
+            post: PostProcessSettings::default(),
+        }
+    }
+}
+
+impl RendererSettings {
+    pub fn clear_color(&self) -> wgpu::Color {
+        let [r, g, b, a] = self.clear_color;
+        wgpu::Color { r, g, b, a }
+    }
+}
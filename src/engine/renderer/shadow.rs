@@ -0,0 +1,400 @@
+// src/engine/renderer/shadow.rs
+
+use std::ops::Range;
+
+use glam::{Mat4, Vec3, Vec4Swizzles};
+use wgpu::{Device, Queue};
+
+use crate::engine::renderer::camera::Camera;
+use crate::engine::renderer::instancing::{InstanceBuffer, InstanceData};
+use crate::engine::renderer::mesh::{Mesh, Vertex3d, VertexType};
+use crate::engine::renderer::pbr::Light;
+use crate::engine::renderer::pipeline_cache::{DepthKey, PipelineCache, PipelineKey, ShaderVariant};
+use crate::engine::renderer::settings::ShadowSettings;
+use crate::engine::renderer::stats;
+
+pub const SHADOW_SHADER: &str = "shadow";
+const SHADOW_SHADER_SOURCE: &str = include_str!("../../../gsl/shadow.wgsl");
+
+pub const MAX_CASCADES: usize = 4;
+pub const MAX_SPOT_SHADOWS: usize = 4;
+const SHADOW_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+const VIEW_STRIDE: u64 = 256;
+const NO_LIGHT: u32 = u32::MAX;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct ShadowUniform {
+    cascade_view_proj: [[[f32; 4]; 4]; MAX_CASCADES],
+    spot_view_proj: [[[f32; 4]; 4]; MAX_SPOT_SHADOWS],
+    cascade_splits: [f32; 4],
+    camera_forward: [f32; 4],
+    params: [f32; 4],
+    counts: [u32; 4],
+    spot_lights: [u32; 4],
+}
+
+/// Shadow maps of one cascaded directional light and up to `MAX_SPOT_SHADOWS` spot lights,
+/// all stored as layers of a single depth texture array. Cascades come first, spot lights after.
+pub struct ShadowMaps {
+    pub settings: ShadowSettings,
+    texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    layer_views: Vec<wgpu::TextureView>,
+    pub sampler: wgpu::Sampler,
+    pub uniform_buffer: wgpu::Buffer,
+    view_buffer: wgpu::Buffer,
+    view_bind_group: wgpu::BindGroup,
+    /// light view-projection of every layer rendered this frame
+    active_layers: Vec<Mat4>,
+}
+
+impl ShadowMaps {
+    pub fn new(device: &Device, cache: &mut PipelineCache, settings: ShadowSettings) -> Self {
+        cache.register_shader(SHADOW_SHADER, SHADOW_SHADER_SOURCE);
+
+        let settings = Self::sanitize(settings);
+        let (texture, view, layer_views) = Self::create_texture(device, &settings);
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Shadow Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..Default::default()
+        });
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Shadow Uniform Buffer"),
+            size: std::mem::size_of::<ShadowUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let view_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Shadow View Buffer"),
+            size: (MAX_CASCADES + MAX_SPOT_SHADOWS) as u64 * VIEW_STRIDE,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let view_layout = cache.bind_group_layout(device, &Self::view_entries());
+        let view_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("shadow_view_bind_group"),
+            layout: &view_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: &view_buffer,
+                    offset: 0,
+                    size: wgpu::BufferSize::new(64),
+                }),
+            }],
+        });
+
+        Self {
+            settings,
+            texture,
+            view,
+            layer_views,
+            sampler,
+            uniform_buffer,
+            view_buffer,
+            view_bind_group,
+            active_layers: Vec::new(),
+        }
+    }
+
+    fn sanitize(mut settings: ShadowSettings) -> ShadowSettings {
+        settings.cascades = settings.cascades.clamp(1, MAX_CASCADES as u32);
+        settings.resolution = settings.resolution.max(1);
+        settings.split_lambda = settings.split_lambda.clamp(0.0, 1.0);
+        settings
+    }
+
+    fn create_texture(device: &Device, settings: &ShadowSettings) -> (wgpu::Texture, wgpu::TextureView, Vec<wgpu::TextureView>) {
+        // kapalıyken de shader'a bağlanacak bir texture lazım, 1x1 yeterli
+        let resolution = if settings.enabled { settings.resolution } else { 1 };
+        let layers = settings.cascades + MAX_SPOT_SHADOWS as u32;
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Shadow Map"),
+            size: wgpu::Extent3d { width: resolution, height: resolution, depth_or_array_layers: layers },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: SHADOW_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Shadow Map Array"),
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            ..Default::default()
+        });
+        let layer_views = (0..layers).map(|layer| texture.create_view(&wgpu::TextureViewDescriptor {
+            label: Some("Shadow Map Layer"),
+            dimension: Some(wgpu::TextureViewDimension::D2),
+            base_array_layer: layer,
+            array_layer_count: Some(1),
+            ..Default::default()
+        })).collect();
+        (texture, view, layer_views)
+    }
+
+    /// Returns true when the texture had to be recreated, bind groups using `view` must be rebuilt then.
+    pub fn set_settings(&mut self, device: &Device, settings: ShadowSettings) -> bool {
+        let settings = Self::sanitize(settings);
+        let recreate = settings.enabled != self.settings.enabled
+            || settings.resolution != self.settings.resolution
+            || settings.cascades != self.settings.cascades;
+        self.settings = settings;
+        if recreate {
+            let (texture, view, layer_views) = Self::create_texture(device, &settings);
+            self.texture = texture;
+            self.view = view;
+            self.layer_views = layer_views;
+        }
+        recreate
+    }
+
+    pub fn resolution(&self) -> u32 {
+        self.texture.width()
+    }
+
+    pub fn view_entries() -> Vec<wgpu::BindGroupLayoutEntry> {
+        vec![wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: true,
+                min_binding_size: wgpu::BufferSize::new(64),
+            },
+            count: None,
+        }]
+    }
+
+    /// bindings the PBR shader uses to read the shadow maps (group 0, starting at `first`)
+    pub fn sample_entries(first: u32) -> Vec<wgpu::BindGroupLayoutEntry> {
+        vec![
+            wgpu::BindGroupLayoutEntry {
+                binding: first,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: first + 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Depth,
+                    view_dimension: wgpu::TextureViewDimension::D2Array,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: first + 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                count: None,
+            },
+        ]
+    }
+
+    pub fn pipeline_key() -> PipelineKey {
+        let mut key = PipelineKey::new(ShaderVariant::new(SHADOW_SHADER), SHADOW_FORMAT);
+        key.depth_only = true;
+        key.depth = Some(DepthKey::new(SHADOW_FORMAT));
+        key.bind_groups = vec![Self::view_entries()];
+        key.vertex_layouts = vec![Vertex3d::layout(), InstanceData::layout()];
+        // ince objelerde arka yüz de gölge atsın
+        key.cull_mode = None;
+        key
+    }
+
+    /// Picks the shadow casting lights, computes their matrices and uploads them.
+    pub fn update(&mut self, queue: &Queue, lights: &[Light], camera: &Camera, aspect: f32) {
+        let s = self.settings;
+        let mut uniform = ShadowUniform {
+            cascade_view_proj: [[[0.0; 4]; 4]; MAX_CASCADES],
+            spot_view_proj: [[[0.0; 4]; 4]; MAX_SPOT_SHADOWS],
+            cascade_splits: [0.0; 4],
+            camera_forward: (camera.target - camera.position).normalize_or(Vec3::NEG_Z).extend(0.0).to_array(),
+            params: [s.depth_bias, s.normal_bias, s.pcf_radius as f32, 1.0 / self.resolution() as f32],
+            counts: [0, NO_LIGHT, 0, s.cascades],
+            spot_lights: [NO_LIGHT; 4],
+        };
+        self.active_layers.clear();
+
+        if s.enabled {
+            let directional = lights.iter().enumerate().find_map(|(i, l)| match l {
+                Light::Directional { direction, cast_shadows: true, .. } => Some((i, *direction)),
+                _ => None,
+            });
+            let mut layers = vec![Mat4::ZERO; s.cascades as usize + MAX_SPOT_SHADOWS];
+
+            if let Some((index, direction)) = directional {
+                let splits = cascade_splits(camera.znear, camera.zfar.min(s.max_distance), s.cascades, s.split_lambda);
+                let mut near = camera.znear;
+                for (c, far) in splits.iter().enumerate() {
+                    let m = cascade_matrix(camera, aspect, near, *far, direction, self.resolution());
+                    uniform.cascade_view_proj[c] = m.to_cols_array_2d();
+                    uniform.cascade_splits[c] = *far;
+                    layers[c] = m;
+                    near = *far;
+                }
+                uniform.counts[0] = s.cascades;
+                uniform.counts[1] = index as u32;
+            }
+
+            let spots = lights.iter().enumerate().filter_map(|(i, l)| match l {
+                Light::Spot { position, direction, range, outer_angle, cast_shadows: true, .. } =>
+                    Some((i, spot_matrix(*position, *direction, *range, *outer_angle, s.max_distance))),
+                _ => None,
+            }).take(MAX_SPOT_SHADOWS);
+            for (slot, (index, m)) in spots.enumerate() {
+                uniform.spot_view_proj[slot] = m.to_cols_array_2d();
+                uniform.spot_lights[slot] = index as u32;
+                uniform.counts[2] += 1;
+                layers[s.cascades as usize + slot] = m;
+            }
+
+            self.active_layers = layers;
+            let mut data = vec![0u8; self.active_layers.len() * VIEW_STRIDE as usize];
+            for (i, m) in self.active_layers.iter().enumerate() {
+                let offset = i * VIEW_STRIDE as usize;
+                data[offset..offset + 64].copy_from_slice(bytemuck::cast_slice(&m.to_cols_array()));
+            }
+            queue.write_buffer(&self.view_buffer, 0, &data);
+            stats::upload(data.len());
+        }
+
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniform));
+        stats::upload(std::mem::size_of_val(&uniform));
+    }
+
+    /// Renders one depth pass per active layer. `casters` are meshes with their
+    /// instance range in `instances`.
+    pub fn render(
+        &self,
+        device: &Device,
+        cache: &mut PipelineCache,
+        encoder: &mut wgpu::CommandEncoder,
+        instances: &InstanceBuffer<InstanceData>,
+        casters: &[(&Mesh, Range<u32>)],
+    ) {
+        if !self.settings.enabled {
+            return;
+        }
+        let pipeline = cache.pipeline(device, &Self::pipeline_key());
+        for (layer, m) in self.active_layers.iter().enumerate() {
+            // bu frame kullanılmayan katmanlar
+            if *m == Mat4::ZERO {
+                continue;
+            }
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Shadow Pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.layer_views[layer],
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&pipeline);
+            stats::pipeline();
+            pass.set_bind_group(0, &self.view_bind_group, &[(layer as u64 * VIEW_STRIDE) as u32]);
+            instances.bind(&mut pass, 1);
+            for (mesh, range) in casters {
+                mesh.draw(&mut pass, range.clone());
+            }
+        }
+    }
+}
+
+/// Far distance of every cascade, blend of uniform and logarithmic splits
+pub fn cascade_splits(near: f32, far: f32, count: u32, lambda: f32) -> Vec<f32> {
+    (1..=count).map(|i| {
+        let p = i as f32 / count as f32;
+        let log = near * (far / near).powf(p);
+        let uniform = near + (far - near) * p;
+        lambda * log + (1.0 - lambda) * uniform
+    }).collect()
+}
+
+/// Orthographic light matrix around the bounding sphere of one cascade slice. The sphere
+/// keeps the size constant while the camera rotates and the center is snapped to whole
+/// texels so the shadow edges don't shimmer when the camera moves.
+fn cascade_matrix(camera: &Camera, aspect: f32, near: f32, far: f32, direction: Vec3, resolution: u32) -> Mat4 {
+    let mut slice = *camera;
+    slice.znear = near;
+    slice.zfar = far;
+    let inv = slice.view_proj(aspect).inverse();
+    let corners: Vec<Vec3> = [-1.0, 1.0].iter().flat_map(|x| {
+        [-1.0, 1.0].iter().flat_map(move |y| {
+            [0.0, 1.0].iter().map(move |z| {
+                let p = inv * glam::Vec4::new(*x, *y, *z, 1.0);
+                p.xyz() / p.w
+            })
+        })
+    }).collect();
+    let center = corners.iter().fold(Vec3::ZERO, |a, c| a + *c) / corners.len() as f32;
+    let radius = corners.iter().map(|c| c.distance(center)).fold(0.0f32, f32::max).max(0.01);
+
+    let dir = direction.normalize_or(Vec3::NEG_Y);
+    let up = if dir.abs().y > 0.99 { Vec3::Z } else { Vec3::Y };
+    let light_view = Mat4::look_at_rh(Vec3::ZERO, dir, up);
+
+    // merkezi ışık uzayında texel ızgarasına oturt
+    let texel = 2.0 * radius / resolution as f32;
+    let mut c = light_view.transform_point3(center);
+    c.x = (c.x / texel).floor() * texel;
+    c.y = (c.y / texel).floor() * texel;
+    let snapped = light_view.inverse().transform_point3(c);
+
+    // kameranın arkasındaki gölge atanlar da haritaya girsin diye geri çekiyoruz
+    let back = radius * 4.0;
+    let view = Mat4::look_at_rh(snapped - dir * back, snapped, up);
+    let proj = Mat4::orthographic_rh(-radius, radius, -radius, radius, 0.0, back + radius);
+    proj * view
+}
+
+fn spot_matrix(position: Vec3, direction: Vec3, range: f32, outer_angle: f32, max_distance: f32) -> Mat4 {
+    let dir = direction.normalize_or(Vec3::NEG_Y);
+    let up = if dir.abs().y > 0.99 { Vec3::Z } else { Vec3::Y };
+    let far = if range > 0.0 { range } else { max_distance };
+    let fov = (outer_angle * 2.0).clamp(0.01, std::f32::consts::PI - 0.01);
+    Mat4::perspective_rh(fov, 1.0, 0.05, far) * Mat4::look_at_rh(position, position + dir, up)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_are_increasing(){
+        let splits = cascade_splits(0.1, 100.0, 4, 0.5);
+        assert_eq!(splits.len(), 4);
+        assert!((splits[3] - 100.0).abs() < 1e-3);
+        assert!(splits.windows(2).all(|w| w[0] < w[1]));
+        // lambda 0 düz bölme
+        assert_eq!(cascade_splits(1.0, 11.0, 2, 0.0), vec![6.0, 11.0]);
+    }
+
+    #[test]
+    fn cascade_contains_slice(){
+        let camera = Camera::default();
+        let m = cascade_matrix(&camera, 1.0, 0.1, 10.0, Vec3::new(-0.3, -1.0, -0.2), 1024);
+        // kameranın baktığı nokta haritanın içinde olmalı
+        let p = m.project_point3(camera.target);
+        assert!(p.x.abs() <= 1.0 && p.y.abs() <= 1.0 && (0.0..=1.0).contains(&p.z), "{:?}", p);
+    }
+}
@@ -0,0 +1,351 @@
+// src/engine/renderer/sprite.rs
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::{Arc, Weak};
+
+use glam::{Mat4, Vec2};
+use wgpu::{Device, Queue, TextureFormat};
+
+use crate::engine::renderer::mesh::VertexType;
+use crate::engine::renderer::pipeline_cache::{BlendMode, PipelineCache, PipelineKey, ShaderVariant, VertexLayoutKey};
+use crate::engine::renderer::stats;
+use crate::engine::renderer::targets::TargetState;
+use crate::engine::renderer::texture::Texture;
+
+pub const SPRITE_SHADER: &str = "sprite";
+pub(crate) const SPRITE_SHADER_SOURCE: &str = include_str!("../../../gsl/sprite.wgsl");
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SpriteVertex {
+    pub position: [f32; 2],
+    pub uv: [f32; 2],
+    pub color: [f32; 4],
+}
+
+impl VertexType for SpriteVertex {
+    fn layout() -> VertexLayoutKey {
+        VertexLayoutKey {
+            array_stride: std::mem::size_of::<SpriteVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: wgpu::vertex_attr_array![
+                0 => Float32x2,
+                1 => Float32x2,
+                2 => Float32x4,
+            ].to_vec(),
+        }
+    }
+}
+
+/// Axis aligned rectangle, used for screen areas and uv regions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Rect {
+    pub const UNIT: Rect = Rect { min: Vec2::ZERO, max: Vec2::ONE };
+
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_pos_size(position: Vec2, size: Vec2) -> Self {
+        Self { min: position, max: position + size }
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+}
+
+#[derive(Clone)]
+pub struct Sprite {
+    pub texture: Arc<Texture>,
+    /// where `origin` ends up
+    pub position: Vec2,
+    pub size: Vec2,
+    /// uv region of the texture, the whole texture by default
+    pub uv: Rect,
+    pub color: [f32; 4],
+    /// radians, around `origin`
+    pub rotation: f32,
+    /// pivot in 0..1, (0, 0) is the top left corner
+    pub origin: Vec2,
+}
+
+impl Sprite {
+    pub fn new(texture: Arc<Texture>, position: Vec2) -> Self {
+        let size = Vec2::new(texture.size.0 as f32, texture.size.1 as f32);
+        Self {
+            texture,
+            position,
+            size,
+            uv: Rect::UNIT,
+            color: [1.0; 4],
+            rotation: 0.0,
+            origin: Vec2::ZERO,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct SpriteUniform {
+    view_proj: [[f32; 4]; 4],
+}
+
+struct Batch {
+    texture: Arc<Texture>,
+    indices: Range<u32>,
+}
+
+/// Immediate mode 2D quad renderer. Quads are drawn in submission order, consecutive
+/// quads that share a texture end up in the same draw call (text and sprites included).
+pub struct SpriteBatch {
+    /// None draws in window pixels with (0, 0) at the top left
+    pub projection: Option<Mat4>,
+    vertices: Vec<SpriteVertex>,
+    batches: Vec<Batch>,
+    vertex_buffer: wgpu::Buffer,
+    index_buffer: wgpu::Buffer,
+    capacity: u64,
+    uniform_buffer: wgpu::Buffer,
+    frame_bind_group: wgpu::BindGroup,
+    /// texture bind groups, dropped when the texture is gone
+    texture_bind_groups: HashMap<usize, (Weak<Texture>, wgpu::BindGroup)>,
+    white: Arc<Texture>,
+}
+
+impl SpriteBatch {
+    pub fn new(device: &Device, queue: &Queue, cache: &mut PipelineCache) -> Self {
+        cache.register_shader(SPRITE_SHADER, SPRITE_SHADER_SOURCE);
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Sprite Uniform Buffer"),
+            size: std::mem::size_of::<SpriteUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let layout = cache.bind_group_layout(device, &Self::frame_entries());
+        let frame_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("sprite_frame_bind_group"),
+            layout: &layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() }],
+        });
+        let capacity = 1024;
+        let (vertex_buffer, index_buffer) = Self::create_buffers(device, capacity);
+
+        Self {
+            projection: None,
+            vertices: Vec::new(),
+            batches: Vec::new(),
+            vertex_buffer,
+            index_buffer,
+            capacity,
+            uniform_buffer,
+            frame_bind_group,
+            texture_bind_groups: HashMap::new(),
+            white: Arc::new(Texture::white(device, queue)),
+        }
+    }
+
+    fn create_buffers(device: &Device, quads: u64) -> (wgpu::Buffer, wgpu::Buffer) {
+        let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Sprite Vertex Buffer"),
+            size: quads * 4 * std::mem::size_of::<SpriteVertex>() as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        // indeksler hiç değişmiyor, bir kere yazılıyor
+        let indices: Vec<u32> = (0..quads as u32)
+            .flat_map(|q| [0, 1, 2, 2, 3, 0].map(|i| q * 4 + i))
+            .collect();
+        let index_buffer = wgpu::util::DeviceExt::create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Sprite Index Buffer"),
+            contents: bytemuck::cast_slice(&indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+        (vertex_buffer, index_buffer)
+    }
+
+    pub fn frame_entries() -> Vec<wgpu::BindGroupLayoutEntry> {
+        vec![wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }]
+    }
+
+    pub fn texture_entries() -> Vec<wgpu::BindGroupLayoutEntry> {
+        vec![
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ]
+    }
+
+    /// overlay pipeline: no depth, single sample, alpha blended
+    pub fn pipeline_key(format: TextureFormat) -> PipelineKey {
+        let mut key = PipelineKey::new(ShaderVariant::new(SPRITE_SHADER), format);
+        key.bind_groups = vec![Self::frame_entries(), Self::texture_entries()];
+        key.vertex_layouts = vec![SpriteVertex::layout()];
+        key.cull_mode = None;
+        key.blend = BlendMode::Alpha;
+        key
+    }
+
+    /// main pass pipeline: alpha blended and without depth test or writes
+    pub fn world_pipeline_key(target: &TargetState) -> PipelineKey {
+        let mut key = target.pipeline_key(ShaderVariant::new(SPRITE_SHADER));
+        key.bind_groups = vec![Self::frame_entries(), Self::texture_entries()];
+        key.vertex_layouts = vec![SpriteVertex::layout()];
+        key.cull_mode = None;
+        key.blend = BlendMode::Alpha;
+        if let Some(depth) = key.depth.as_mut() {
+            depth.write = false;
+            depth.compare = wgpu::CompareFunction::Always;
+        }
+        key
+    }
+
+    pub fn quad_count(&self) -> usize {
+        self.vertices.len() / 4
+    }
+
+    pub fn batch_count(&self) -> usize {
+        self.batches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Adds a quad, corners are top left, top right, bottom right, bottom left.
+    pub fn draw_quad(&mut self, texture: &Arc<Texture>, corners: [Vec2; 4], uv: Rect, color: [f32; 4]) {
+        let uvs = [
+            uv.min,
+            Vec2::new(uv.max.x, uv.min.y),
+            uv.max,
+            Vec2::new(uv.min.x, uv.max.y),
+        ];
+        for (p, t) in corners.iter().zip(uvs) {
+            self.vertices.push(SpriteVertex { position: p.to_array(), uv: t.to_array(), color });
+        }
+
+        let end = (self.vertices.len() / 4 * 6) as u32;
+        match self.batches.last_mut() {
+            Some(batch) if Arc::ptr_eq(&batch.texture, texture) => batch.indices.end = end,
+            _ => self.batches.push(Batch { texture: texture.clone(), indices: end - 6..end }),
+        }
+    }
+
+    pub fn draw_rect(&mut self, texture: &Arc<Texture>, rect: Rect, uv: Rect, color: [f32; 4]) {
+        let corners = [rect.min, Vec2::new(rect.max.x, rect.min.y), rect.max, Vec2::new(rect.min.x, rect.max.y)];
+        self.draw_quad(texture, corners, uv, color);
+    }
+
+    /// untextured rectangle
+    pub fn fill_rect(&mut self, rect: Rect, color: [f32; 4]) {
+        let white = self.white.clone();
+        self.draw_rect(&white, rect, Rect::UNIT, color);
+    }
+
+    pub fn draw_sprite(&mut self, sprite: &Sprite) {
+        let pivot = sprite.origin * sprite.size;
+        let (sin, cos) = sprite.rotation.sin_cos();
+        let corners = [Vec2::ZERO, Vec2::new(sprite.size.x, 0.0), sprite.size, Vec2::new(0.0, sprite.size.y)]
+            .map(|c| {
+                let p = c - pivot;
+                sprite.position + Vec2::new(p.x * cos - p.y * sin, p.x * sin + p.y * cos)
+            });
+        self.draw_quad(&sprite.texture, corners, sprite.uv, sprite.color);
+    }
+
+    /// Uploads the queued quads. `screen_size` is used when there is no custom projection.
+    pub fn prepare(&mut self, device: &Device, queue: &Queue, screen_size: (u32, u32)) {
+        let view_proj = self.projection.unwrap_or_else(|| {
+            Mat4::orthographic_rh(0.0, screen_size.0 as f32, screen_size.1 as f32, 0.0, -1.0, 1.0)
+        });
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&SpriteUniform { view_proj: view_proj.to_cols_array_2d() }));
+        stats::upload(std::mem::size_of::<SpriteUniform>());
+
+        if self.vertices.is_empty() {
+            return;
+        }
+        let quads = self.quad_count() as u64;
+        if quads > self.capacity {
+            self.capacity = quads.next_power_of_two();
+            let (vertex_buffer, index_buffer) = Self::create_buffers(device, self.capacity);
+            self.vertex_buffer = vertex_buffer;
+            self.index_buffer = index_buffer;
+        }
+        queue.write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&self.vertices));
+        stats::upload(std::mem::size_of_val(self.vertices.as_slice()));
+    }
+
+    /// records the queued quads into the overlay `render_pass` and clears the queue
+    pub fn draw(&mut self, device: &Device, cache: &mut PipelineCache, format: TextureFormat, render_pass: &mut wgpu::RenderPass<'_>) {
+        self.record(device, cache, &Self::pipeline_key(format), render_pass);
+    }
+
+    /// `draw` for a batch in the main pass, under post-processing like the meshes
+    pub fn draw_world(&mut self, device: &Device, cache: &mut PipelineCache, target: &TargetState, render_pass: &mut wgpu::RenderPass<'_>) {
+        self.record(device, cache, &Self::world_pipeline_key(target), render_pass);
+    }
+
+    fn record(&mut self, device: &Device, cache: &mut PipelineCache, key: &PipelineKey, render_pass: &mut wgpu::RenderPass<'_>) {
+        if self.batches.is_empty() {
+            return;
+        }
+        let pipeline = cache.pipeline(device, key);
+        let layout = cache.bind_group_layout(device, &Self::texture_entries());
+        self.texture_bind_groups.retain(|_, (texture, _)| texture.strong_count() > 0);
+
+        render_pass.set_pipeline(&pipeline);
+        stats::pipeline();
+        render_pass.set_bind_group(0, &self.frame_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+        for batch in &self.batches {
+            let (_, bind_group) = self.texture_bind_groups
+                .entry(Arc::as_ptr(&batch.texture) as usize)
+                .or_insert_with(|| {
+                    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                        label: Some("sprite_texture_bind_group"),
+                        layout: &layout,
+                        entries: &[
+                            wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&batch.texture.view) },
+                            wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&batch.texture.sampler) },
+                        ],
+                    });
+                    (Arc::downgrade(&batch.texture), bind_group)
+                });
+            render_pass.set_bind_group(1, &*bind_group, &[]);
+            render_pass.draw_indexed(batch.indices.clone(), 0, 0..1);
+            stats::draw(batch.indices.len() as u64 / 3);
+        }
+        self.vertices.clear();
+        self.batches.clear();
+    }
+}
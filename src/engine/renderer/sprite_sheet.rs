@@ -0,0 +1,192 @@
+// src/engine/renderer/sprite_sheet.rs
+//
+// JSON sprite sheets of Aseprite and TexturePacker, both the hash and the array layout.
+// Frames become regions of an `AtlasManifest`, Aseprite tags become animations.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use crate::engine::renderer::atlas::{AnimationDirection, AtlasError, AtlasManifest, AtlasRegion, SpriteAnimation, SpriteFrame, Trim};
+
+/// frame duration when the sheet has none, seconds
+const DEFAULT_FRAME_DURATION: f32 = 0.1;
+
+#[derive(Deserialize)]
+struct SheetRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct SheetSize {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SheetFrame {
+    /// array layout only
+    filename: Option<String>,
+    frame: SheetRect,
+    #[serde(default)]
+    rotated: bool,
+    #[serde(default)]
+    trimmed: bool,
+    sprite_source_size: Option<SheetRect>,
+    source_size: Option<SheetSize>,
+    /// milliseconds, Aseprite only
+    duration: Option<f32>,
+}
+
+/// JSON object keeping the order of its keys, frame order matters for tags
+struct Ordered<T>(Vec<(String, T)>);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Ordered<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct OrderedVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for OrderedVisitor<T> {
+            type Value = Ordered<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(Ordered(entries))
+            }
+        }
+
+        deserializer.deserialize_map(OrderedVisitor(PhantomData))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SheetFrames {
+    Array(Vec<SheetFrame>),
+    Hash(Ordered<SheetFrame>),
+}
+
+#[derive(Deserialize)]
+struct SheetTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SheetMeta {
+    image: String,
+    size: SheetSize,
+    #[serde(default)]
+    frame_tags: Vec<SheetTag>,
+}
+
+#[derive(Deserialize)]
+struct SheetFile {
+    frames: SheetFrames,
+    meta: SheetMeta,
+}
+
+/// Aseprite / TexturePacker JSON to a manifest, rotated frames aren't supported
+pub(crate) fn import(text: &str) -> Result<AtlasManifest, AtlasError> {
+    let sheet: SheetFile = serde_json::from_str(text)?;
+    let frames: Vec<(String, SheetFrame)> = match sheet.frames {
+        SheetFrames::Hash(Ordered(frames)) => frames,
+        SheetFrames::Array(frames) => frames.into_iter().enumerate()
+            .map(|(i, f)| (f.filename.clone().unwrap_or_else(|| i.to_string()), f))
+            .collect(),
+    };
+
+    let mut regions = BTreeMap::new();
+    for (name, frame) in &frames {
+        if frame.rotated {
+            return Err(AtlasError::Invalid(format!("frame {} is rotated, export without rotation", name)));
+        }
+        let trim = match (frame.trimmed, &frame.sprite_source_size, &frame.source_size) {
+            (true, Some(offset), Some(source)) => Some(Trim { x: offset.x, y: offset.y, source_width: source.w, source_height: source.h }),
+            _ => None,
+        };
+        let region = AtlasRegion { x: frame.frame.x, y: frame.frame.y, width: frame.frame.w, height: frame.frame.h, trim };
+        if region.x + region.width > sheet.meta.size.w || region.y + region.height > sheet.meta.size.h {
+            return Err(AtlasError::Invalid(format!("frame {} is outside of the image", name)));
+        }
+        regions.insert(name.clone(), region);
+    }
+
+    let mut animations = BTreeMap::new();
+    for tag in sheet.meta.frame_tags {
+        let Some(tagged) = frames.get(tag.from..=tag.to) else {
+            return Err(AtlasError::Invalid(format!("tag {} refers to missing frames", tag.name)));
+        };
+        let mut frames: Vec<SpriteFrame> = tagged.iter()
+            .map(|(name, frame)| SpriteFrame {
+                region: name.clone(),
+                duration: frame.duration.map_or(DEFAULT_FRAME_DURATION, |ms| ms / 1000.0),
+            })
+            .collect();
+        let direction = match tag.direction.as_str() {
+            "reverse" => AnimationDirection::Reverse,
+            "pingpong" => AnimationDirection::PingPong,
+            "pingpong_reverse" => {
+                frames.reverse();
+                AnimationDirection::PingPong
+            }
+            _ => AnimationDirection::Forward,
+        };
+        animations.insert(tag.name, SpriteAnimation { frames, direction });
+    }
+
+    Ok(AtlasManifest {
+        image: sheet.meta.image,
+        width: sheet.meta.size.w,
+        height: sheet.meta.size.h,
+        regions,
+        animations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aseprite_hash(){
+        let json = r#"{
+            "frames": {
+                "hero 10.aseprite": { "frame": {"x":0,"y":0,"w":16,"h":16}, "rotated": false, "trimmed": false,
+                    "spriteSourceSize": {"x":0,"y":0,"w":16,"h":16}, "sourceSize": {"w":16,"h":16}, "duration": 100 },
+                "hero 2.aseprite": { "frame": {"x":16,"y":0,"w":12,"h":14}, "rotated": false, "trimmed": true,
+                    "spriteSourceSize": {"x":2,"y":1,"w":12,"h":14}, "sourceSize": {"w":16,"h":16}, "duration": 250 }
+            },
+            "meta": { "image": "hero.png", "size": {"w":32,"h":16},
+                "frameTags": [ { "name": "walk", "from": 0, "to": 1, "direction": "forward" } ] }
+        }"#;
+        let manifest = AtlasManifest::from_json(json).unwrap();
+        assert_eq!(manifest.image, "hero.png");
+        assert_eq!(manifest.regions["hero 2.aseprite"].trim, Some(Trim { x: 2, y: 1, source_width: 16, source_height: 16 }));
+        // dosyadaki sıra korunuyor, alfabetik değil
+        let walk = &manifest.animations["walk"];
+        assert_eq!(walk.frames[0].region, "hero 10.aseprite");
+        assert_eq!(walk.frames[1].duration, 0.25);
+
+        let packer = r#"{ "frames": [ { "filename": "a", "frame": {"x":0,"y":0,"w":8,"h":8}, "rotated": true } ],
+            "meta": { "image": "a.png", "size": {"w":8,"h":8} } }"#;
+        assert!(matches!(AtlasManifest::from_json(packer), Err(AtlasError::Invalid(_))));
+    }
+}
@@ -0,0 +1,410 @@
+// src/engine/renderer/stats.rs
+//
+// Per-frame renderer statistics. Subsystems report their draws and uploads through the
+// free functions below right next to the wgpu call, `RenderStats` swaps the counters out
+// once per frame. GPU pass times come from timestamp queries when the device has them.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use glam::Vec2;
+use serde::Serialize;
+use wgpu::{Device, Queue};
+
+use crate::engine::renderer::sprite::{Rect, SpriteBatch};
+use crate::engine::renderer::text::{FontId, TextRenderer, TextSection};
+
+/// frame times kept for the overlay graph and averages
+const HISTORY: usize = 120;
+/// timestamps per frame, one more than the number of timed passes
+const MAX_TIMESTAMPS: u32 = 16;
+/// readbacks in flight before a frame goes untimed
+const MAX_READBACKS: usize = 3;
+
+static DRAW_CALLS: AtomicU64 = AtomicU64::new(0);
+static DISPATCHES: AtomicU64 = AtomicU64::new(0);
+static TRIANGLES: AtomicU64 = AtomicU64::new(0);
+static PIPELINES: AtomicU64 = AtomicU64::new(0);
+static UPLOADS: AtomicU64 = AtomicU64::new(0);
+static UPLOAD_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// a draw call with this many triangles (0 for lines)
+pub fn draw(triangles: u64) {
+    DRAW_CALLS.fetch_add(1, Ordering::Relaxed);
+    TRIANGLES.fetch_add(triangles, Ordering::Relaxed);
+}
+
+/// a compute dispatch
+pub fn dispatch() {
+    DISPATCHES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// a `set_pipeline` call
+pub fn pipeline() {
+    PIPELINES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// a `write_buffer` / `write_texture` of `bytes`
+pub fn upload(bytes: usize) {
+    UPLOADS.fetch_add(1, Ordering::Relaxed);
+    UPLOAD_BYTES.fetch_add(bytes as u64, Ordering::Relaxed);
+}
+
+/// milliseconds since some fixed point
+fn now_ms() -> f64 {
+    #[cfg(not(target_family = "wasm"))]
+    {
+        static START: once_cell::sync::Lazy<std::time::Instant> = once_cell::sync::Lazy::new(std::time::Instant::now);
+        START.elapsed().as_secs_f64() * 1000.0
+    }
+    #[cfg(target_family = "wasm")]
+    {
+        js_sys::Date::now()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PassTime {
+    pub name: &'static str,
+    pub ms: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FrameStats {
+    pub frame: u64,
+    pub draw_calls: u64,
+    pub dispatches: u64,
+    pub triangles: u64,
+    pub pipelines_bound: u64,
+    pub buffer_uploads: u64,
+    pub upload_bytes: u64,
+    /// CPU time spent recording and submitting the frame
+    pub cpu_ms: f32,
+    /// time since the previous frame started
+    pub frame_ms: f32,
+    /// GPU time of every pass, a few frames late. Empty without timestamp queries.
+    pub gpu_passes: Vec<PassTime>,
+}
+
+impl FrameStats {
+    pub fn gpu_ms(&self) -> f32 {
+        self.gpu_passes.iter().map(|p| p.ms).sum()
+    }
+}
+
+impl fmt::Display for FrameStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "frame {}: {:.2} ms (cpu {:.2} ms), {} draws, {} dispatches, {} tris, {} pipelines, {} uploads ({} KiB)",
+            self.frame, self.frame_ms, self.cpu_ms, self.draw_calls, self.dispatches, self.triangles,
+            self.pipelines_bound, self.buffer_uploads, self.upload_bytes / 1024,
+        )?;
+        if !self.gpu_passes.is_empty() {
+            write!(f, ", gpu {:.2} ms", self.gpu_ms())?;
+            for pass in &self.gpu_passes {
+                write!(f, " [{} {:.2}]", pass.name, pass.ms)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct TimestampReadback {
+    buffer: wgpu::Buffer,
+    names: Vec<&'static str>,
+    mapped: Arc<Mutex<Option<Result<(), wgpu::BufferAsyncError>>>>,
+}
+
+/// Timestamps written between passes with `CommandEncoder::write_timestamp`
+struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    resolve_buffer: wgpu::Buffer,
+    /// ns per tick
+    period: f32,
+    names: Vec<&'static str>,
+    free: Vec<wgpu::Buffer>,
+    recorded: Option<TimestampReadback>,
+    in_flight: Vec<TimestampReadback>,
+}
+
+impl GpuTimer {
+    fn new(device: &Device, queue: &Queue) -> Self {
+        let size = (MAX_TIMESTAMPS * wgpu::QUERY_SIZE) as u64;
+        Self {
+            query_set: device.create_query_set(&wgpu::QuerySetDescriptor {
+                label: Some("Frame Timestamps"),
+                ty: wgpu::QueryType::Timestamp,
+                count: MAX_TIMESTAMPS,
+            }),
+            resolve_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Timestamp Resolve Buffer"),
+                size,
+                usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            }),
+            period: queue.get_timestamp_period(),
+            names: Vec::new(),
+            free: (0..MAX_READBACKS).map(|_| device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Timestamp Readback Buffer"),
+                size,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            })).collect(),
+            recorded: None,
+            in_flight: Vec::new(),
+        }
+    }
+
+    /// starts a pass named `name`, ending the previous one
+    fn mark(&mut self, encoder: &mut wgpu::CommandEncoder, name: &'static str) {
+        // okunmayı bekleyen tampon yoksa bu kare ölçülmüyor
+        if self.free.is_empty() || self.names.len() as u32 + 1 >= MAX_TIMESTAMPS {
+            return;
+        }
+        encoder.write_timestamp(&self.query_set, self.names.len() as u32);
+        self.names.push(name);
+    }
+
+    fn resolve(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        let names = std::mem::take(&mut self.names);
+        if names.is_empty() {
+            return;
+        }
+        let Some(buffer) = self.free.pop() else {
+            return;
+        };
+        let count = names.len() as u32 + 1;
+        encoder.write_timestamp(&self.query_set, names.len() as u32);
+        encoder.resolve_query_set(&self.query_set, 0..count, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(&self.resolve_buffer, 0, &buffer, 0, (count * wgpu::QUERY_SIZE) as u64);
+        self.recorded = Some(TimestampReadback { buffer, names, mapped: Arc::new(Mutex::new(None)) });
+    }
+
+    /// maps what `resolve` copied, returns the newest finished pass times
+    fn after_submit(&mut self, device: &Device) -> Option<Vec<PassTime>> {
+        if let Some(readback) = self.recorded.take() {
+            let mapped = readback.mapped.clone();
+            readback.buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+                *mapped.lock().unwrap() = Some(result);
+            });
+            self.in_flight.push(readback);
+        }
+        if self.in_flight.is_empty() {
+            return None;
+        }
+        let _ = device.poll(wgpu::PollType::Poll);
+
+        let mut newest = None;
+        let mut index = 0;
+        while index < self.in_flight.len() {
+            let state = self.in_flight[index].mapped.lock().unwrap().take();
+            match state {
+                None => index += 1,
+                Some(result) => {
+                    let readback = self.in_flight.remove(index);
+                    if result.is_ok() {
+                        let data = readback.buffer.slice(..).get_mapped_range();
+                        let ticks: Vec<u64> = data.chunks_exact(8)
+                            .take(readback.names.len() + 1)
+                            .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+                            .collect();
+                        drop(data);
+                        newest = Some(pass_times(&readback.names, &ticks, self.period));
+                    }
+                    readback.buffer.unmap();
+                    self.free.push(readback.buffer);
+                }
+            }
+        }
+        newest
+    }
+}
+
+/// durations between consecutive timestamps
+fn pass_times(names: &[&'static str], ticks: &[u64], period: f32) -> Vec<PassTime> {
+    names.iter().zip(ticks.windows(2))
+        .map(|(name, t)| PassTime { name, ms: t[1].saturating_sub(t[0]) as f32 * period / 1_000_000.0 })
+        .collect()
+}
+
+/// Frame statistics of a `SurfaceManager`, with an optional on-screen overlay
+pub struct RenderStats {
+    /// draws the overlay in the top left corner
+    pub overlay: bool,
+    /// font of the overlay text, without one only the frame time graph is drawn
+    pub overlay_font: Option<FontId>,
+    last: FrameStats,
+    history: VecDeque<f32>,
+    frame: u64,
+    frame_start: Option<f64>,
+    gpu: Option<GpuTimer>,
+    gpu_passes: Vec<PassTime>,
+}
+
+impl RenderStats {
+    /// GPU timing needs `TIMESTAMP_QUERY` and `TIMESTAMP_QUERY_INSIDE_ENCODERS`
+    pub fn new(device: &Device, queue: &Queue) -> Self {
+        let timestamps = wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::TIMESTAMP_QUERY_INSIDE_ENCODERS;
+        Self {
+            overlay: false,
+            overlay_font: None,
+            last: FrameStats::default(),
+            history: VecDeque::with_capacity(HISTORY),
+            frame: 0,
+            frame_start: None,
+            gpu: device.features().contains(timestamps).then(|| GpuTimer::new(device, queue)),
+            gpu_passes: Vec::new(),
+        }
+    }
+
+    pub fn gpu_timing_supported(&self) -> bool {
+        self.gpu.is_some()
+    }
+
+    /// stats of the last finished frame
+    pub fn last(&self) -> &FrameStats {
+        &self.last
+    }
+
+    /// average frame time over the last frames
+    pub fn average_frame_ms(&self) -> f32 {
+        if self.history.is_empty() {
+            return 0.0;
+        }
+        self.history.iter().sum::<f32>() / self.history.len() as f32
+    }
+
+    /// Resets the counters, call before anything of the frame is recorded
+    pub fn begin_frame(&mut self) {
+        let now = now_ms();
+        if let Some(previous) = self.frame_start {
+            self.history.push_back((now - previous) as f32);
+            if self.history.len() > HISTORY {
+                self.history.pop_front();
+            }
+        }
+        self.frame_start = Some(now);
+        take_counters();
+    }
+
+    /// GPU time from here until the next `mark` (or the end of the frame) is reported as `name`
+    pub fn mark(&mut self, encoder: &mut wgpu::CommandEncoder, name: &'static str) {
+        if let Some(gpu) = self.gpu.as_mut() {
+            gpu.mark(encoder, name);
+        }
+    }
+
+    /// Collects the counters, call right before the frame is submitted
+    pub fn end_frame(&mut self, encoder: &mut wgpu::CommandEncoder) {
+        if let Some(gpu) = self.gpu.as_mut() {
+            gpu.resolve(encoder);
+        }
+        let counters = take_counters();
+        self.frame += 1;
+        self.last = FrameStats {
+            frame: self.frame,
+            cpu_ms: self.frame_start.map(|start| (now_ms() - start) as f32).unwrap_or(0.0),
+            frame_ms: self.history.back().copied().unwrap_or(0.0),
+            gpu_passes: self.gpu_passes.clone(),
+            ..counters
+        };
+    }
+
+    pub fn after_submit(&mut self, device: &Device) {
+        if let Some(passes) = self.gpu.as_mut().and_then(|gpu| gpu.after_submit(device)) {
+            self.gpu_passes = passes;
+        }
+    }
+
+    /// Queues the overlay into `batch`, has to happen before the batch is prepared
+    pub fn queue_overlay(&self, device: &Device, queue: &Queue, text: &mut TextRenderer, batch: &mut SpriteBatch) {
+        if !self.overlay {
+            return;
+        }
+        const ORIGIN: Vec2 = Vec2::new(8.0, 8.0);
+        const WIDTH: f32 = 2.0 * HISTORY as f32;
+        const GRAPH_HEIGHT: f32 = 50.0;
+        // grafikte 1 piksel 0.5 ms, üst sınır 25 ms
+        const PX_PER_MS: f32 = 2.0;
+        const LINE_HEIGHT: f32 = 16.0;
+
+        let lines = self.overlay_font.map(|_| self.overlay_lines()).unwrap_or_default();
+        let text_height = lines.len() as f32 * LINE_HEIGHT;
+        let panel = Rect::from_pos_size(ORIGIN, Vec2::new(WIDTH + 8.0, GRAPH_HEIGHT + text_height + 12.0));
+        batch.fill_rect(panel, [0.0, 0.0, 0.0, 0.6]);
+
+        let graph_bottom = ORIGIN.y + 4.0 + GRAPH_HEIGHT;
+        for (i, ms) in self.history.iter().enumerate() {
+            let height = (ms * PX_PER_MS).min(GRAPH_HEIGHT);
+            let color = if *ms <= 1000.0 / 60.0 {
+                [0.2, 0.9, 0.3, 0.9]
+            } else if *ms <= 1000.0 / 30.0 {
+                [1.0, 0.8, 0.2, 0.9]
+            } else {
+                [1.0, 0.25, 0.2, 0.9]
+            };
+            let x = ORIGIN.x + 4.0 + i as f32 * 2.0;
+            batch.fill_rect(Rect::new(Vec2::new(x, graph_bottom - height), Vec2::new(x + 2.0, graph_bottom)), color);
+        }
+        // 60 fps çizgisi
+        let target = graph_bottom - 1000.0 / 60.0 * PX_PER_MS;
+        batch.fill_rect(Rect::new(Vec2::new(ORIGIN.x + 4.0, target), Vec2::new(ORIGIN.x + 4.0 + WIDTH, target + 1.0)), [1.0, 1.0, 1.0, 0.5]);
+
+        if let Some(font) = self.overlay_font {
+            for (i, line) in lines.iter().enumerate() {
+                let position = Vec2::new(ORIGIN.x + 4.0, graph_bottom + 4.0 + i as f32 * LINE_HEIGHT);
+                text.queue(device, queue, batch, &TextSection::new(line, font, 13.0).with_position(position));
+            }
+        }
+    }
+
+    fn overlay_lines(&self) -> Vec<String> {
+        let stats = &self.last;
+        let average = self.average_frame_ms();
+        let fps = if average > 0.0 { 1000.0 / average } else { 0.0 };
+        let mut lines = vec![
+            format!("{:.0} fps  {:.2} ms  cpu {:.2} ms", fps, average, stats.cpu_ms),
+            format!("{} draws  {} tris  {} pipelines", stats.draw_calls, stats.triangles, stats.pipelines_bound),
+            format!("{} uploads  {} KiB  {} dispatches", stats.buffer_uploads, stats.upload_bytes / 1024, stats.dispatches),
+        ];
+        if !stats.gpu_passes.is_empty() {
+            lines.push(format!("gpu {:.2} ms", stats.gpu_ms()));
+            lines.extend(stats.gpu_passes.iter().map(|p| format!("  {} {:.2} ms", p.name, p.ms)));
+        }
+        lines
+    }
+}
+
+/// swaps the global counters back to zero
+fn take_counters() -> FrameStats {
+    FrameStats {
+        draw_calls: DRAW_CALLS.swap(0, Ordering::Relaxed),
+        dispatches: DISPATCHES.swap(0, Ordering::Relaxed),
+        triangles: TRIANGLES.swap(0, Ordering::Relaxed),
+        pipelines_bound: PIPELINES.swap(0, Ordering::Relaxed),
+        buffer_uploads: UPLOADS.swap(0, Ordering::Relaxed),
+        upload_bytes: UPLOAD_BYTES.swap(0, Ordering::Relaxed),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps_to_passes(){
+        let passes = pass_times(&["shadows", "main"], &[100, 1_100_100, 3_100_100], 1.0);
+        assert_eq!(passes.len(), 2);
+        assert_eq!(passes[0].name, "shadows");
+        assert!((passes[0].ms - 1.1).abs() < 1e-4);
+        assert!((passes[1].ms - 2.0).abs() < 1e-4);
+
+        let stats = FrameStats { gpu_passes: passes, ..Default::default() };
+        assert!((stats.gpu_ms() - 3.1).abs() < 1e-4);
+        assert!(stats.to_string().contains("[main 2.00]"));
+    }
+}
@@ -0,0 +1,99 @@
+// src/engine/renderer/targets.rs
+
+use wgpu::{Device, TextureFormat};
+
+use crate::engine::renderer::pipeline_cache::{DepthKey, PipelineKey, ShaderVariant};
+
+pub struct Attachment {
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+}
+
+impl Attachment {
+    pub fn new(device: &Device, label: &str, size: (u32, u32), format: TextureFormat, sample_count: u32, usage: wgpu::TextureUsages) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width: size.0.max(1),
+                height: size.1.max(1),
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Self { texture, view }
+    }
+}
+
+/// What the main pass renders into, pipelines drawn in that pass must match it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TargetState {
+    pub format: TextureFormat,
+    pub sample_count: u32,
+    pub depth_format: Option<TextureFormat>,
+}
+
+impl TargetState {
+    /// key with the format, sample count and (default) depth state of this target
+    pub fn pipeline_key(&self, shader: ShaderVariant) -> PipelineKey {
+        let mut key = PipelineKey::new(shader, self.format);
+        key.sample_count = self.sample_count;
+        key.depth = self.depth_format.map(DepthKey::new);
+        key
+    }
+}
+
+/// Depth and multisampled color attachments of the main pass, recreated on resize
+/// and whenever the renderer settings change.
+pub struct RenderTargets {
+    pub state: TargetState,
+    pub depth: Option<Attachment>,
+    pub msaa: Option<Attachment>,
+}
+
+impl RenderTargets {
+    pub fn new(device: &Device, size: (u32, u32), state: TargetState) -> Self {
+        let depth = state.depth_format.map(|format| Attachment::new(
+            device, "Depth Texture", size, format, state.sample_count,
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        ));
+        let msaa = (state.sample_count > 1).then(|| Attachment::new(
+            device, "MSAA Color Texture", size, state.format, state.sample_count,
+            wgpu::TextureUsages::RENDER_ATTACHMENT,
+        ));
+        Self { state, depth, msaa }
+    }
+
+    /// color attachment for `target`, goes through the msaa texture when multisampling is on
+    pub fn color_attachment<'a>(&'a self, target: &'a wgpu::TextureView, clear: wgpu::Color) -> wgpu::RenderPassColorAttachment<'a> {
+        let (view, resolve_target, store) = match &self.msaa {
+            Some(msaa) => (&msaa.view, Some(target), wgpu::StoreOp::Discard),
+            None => (target, None, wgpu::StoreOp::Store),
+        };
+        wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(clear),
+                store,
+            },
+            depth_slice: None,
+        }
+    }
+
+    pub fn depth_attachment(&self) -> Option<wgpu::RenderPassDepthStencilAttachment<'_>> {
+        self.depth.as_ref().map(|depth| wgpu::RenderPassDepthStencilAttachment {
+            view: &depth.view,
+            depth_ops: Some(wgpu::Operations {
+                load: wgpu::LoadOp::Clear(1.0),
+                store: wgpu::StoreOp::Store,
+            }),
+            stencil_ops: None,
+        })
+    }
+}
@@ -0,0 +1,538 @@
+// src/engine/renderer/text.rs
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+use ab_glyph::{Font as _, FontArc, GlyphId, PxScale, ScaleFont};
+use glam::Vec2;
+use wgpu::{Device, Queue};
+
+use crate::engine::renderer::sprite::{Rect, SpriteBatch};
+use crate::engine::renderer::stats;
+use crate::engine::renderer::texture::Texture;
+
+const ATLAS_START_SIZE: u32 = 512;
+const ATLAS_MAX_SIZE: u32 = 4096;
+/// glyphs are rasterized at 1/4 pixel horizontal offsets
+const SUBPIXEL_STEPS: f32 = 4.0;
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(std::io::Error),
+    /// not a TrueType / OpenType font
+    Invalid,
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::Io(e) => write!(f, "font io error: {}", e),
+            FontError::Invalid => write!(f, "invalid font data"),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+impl From<std::io::Error> for FontError {
+    fn from(e: std::io::Error) -> Self {
+        FontError::Io(e)
+    }
+}
+
+/// TrueType / OpenType font
+#[derive(Clone)]
+pub struct Font {
+    inner: FontArc,
+}
+
+impl Font {
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, FontError> {
+        let inner = FontArc::try_from_vec(data).map_err(|_| FontError::Invalid)?;
+        Ok(Self { inner })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, FontError> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    /// `size` is the em size in pixels, like css font-size
+    fn scale(&self, size: f32) -> PxScale {
+        let units = self.inner.units_per_em().unwrap_or(1000.0);
+        PxScale::from(size * self.inner.height_unscaled() / units)
+    }
+
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.inner.glyph_id(c).0 != 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FontId(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TextSection<'a> {
+    pub text: &'a str,
+    /// top left corner of the text block
+    pub position: Vec2,
+    pub font: FontId,
+    /// em size in pixels
+    pub size: f32,
+    pub color: [f32; 4],
+    /// wraps lines longer than this at word boundaries
+    pub max_width: Option<f32>,
+    pub align: TextAlign,
+    /// multiplier of the font's own line spacing
+    pub line_height: f32,
+}
+
+impl<'a> TextSection<'a> {
+    pub fn new(text: &'a str, font: FontId, size: f32) -> Self {
+        Self {
+            text,
+            position: Vec2::ZERO,
+            font,
+            size,
+            color: [1.0; 4],
+            max_width: None,
+            align: TextAlign::Left,
+            line_height: 1.0,
+        }
+    }
+
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutGlyph {
+    /// font the glyph came from, differs from the section font when a fallback was used
+    pub font: FontId,
+    pub id: GlyphId,
+    /// pen position on the baseline, relative to the section position
+    pub position: Vec2,
+    /// byte index in the source text
+    pub byte_index: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextLayout {
+    pub glyphs: Vec<LayoutGlyph>,
+    pub size: Vec2,
+    pub lines: usize,
+}
+
+/// one char of a paragraph with its resolved glyph
+struct ShapedChar {
+    c: char,
+    byte_index: usize,
+    font: usize,
+    id: GlyphId,
+    advance: f32,
+    /// kerning against the previous char, 0 across fonts
+    kern: f32,
+}
+
+/// CJK text has no spaces, a line can break before and after every ideograph
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF | 0xFF00..=0xFFEF)
+}
+
+/// Lays out `section` with `fonts[section.font]`, chars the font doesn't have are taken
+/// from the other fonts in order.
+pub fn layout_text(fonts: &[Font], section: &TextSection) -> TextLayout {
+    let Some(primary) = fonts.get(section.font.0) else {
+        return TextLayout::default();
+    };
+    let scaled = primary.inner.as_scaled(primary.scale(section.size));
+    let ascent = scaled.ascent();
+    let line_advance = (scaled.ascent() - scaled.descent() + scaled.line_gap()) * section.line_height;
+
+    let mut lines: Vec<(Vec<&ShapedChar>, f32)> = Vec::new();
+    let paragraphs: Vec<Vec<ShapedChar>> = section.text.split('\n')
+        .scan(0usize, |offset, p| {
+            let start = *offset;
+            *offset += p.len() + 1;
+            Some(shape_paragraph(fonts, section, p, start))
+        })
+        .collect();
+    for chars in &paragraphs {
+        for range in wrap_paragraph(chars, section.max_width) {
+            let line: Vec<&ShapedChar> = chars[range].iter().collect();
+            // satır sonundaki boşluklar genişliğe sayılmaz
+            let visible = line.iter().rposition(|c| !c.c.is_whitespace()).map_or(0, |i| i + 1);
+            let width = line[..visible].iter().enumerate()
+                .map(|(i, c)| c.advance + if i > 0 { c.kern } else { 0.0 })
+                .sum::<f32>();
+            lines.push((line, width));
+        }
+    }
+
+    let block_width = section.max_width
+        .unwrap_or_else(|| lines.iter().map(|(_, w)| *w).fold(0.0, f32::max));
+    let mut glyphs = Vec::new();
+    for (n, (line, width)) in lines.iter().enumerate() {
+        let mut x = match section.align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => (block_width - width) * 0.5,
+            TextAlign::Right => block_width - width,
+        };
+        let y = ascent + n as f32 * line_advance;
+        for (i, c) in line.iter().enumerate() {
+            if i > 0 {
+                x += c.kern;
+            }
+            glyphs.push(LayoutGlyph { font: FontId(c.font), id: c.id, position: Vec2::new(x, y), byte_index: c.byte_index });
+            x += c.advance;
+        }
+    }
+
+    let width = lines.iter().map(|(_, w)| *w).fold(0.0, f32::max);
+    TextLayout {
+        glyphs,
+        size: Vec2::new(width, lines.len() as f32 * line_advance),
+        lines: lines.len(),
+    }
+}
+
+fn shape_paragraph(fonts: &[Font], section: &TextSection, text: &str, offset: usize) -> Vec<ShapedChar> {
+    let mut chars: Vec<ShapedChar> = Vec::with_capacity(text.len());
+    for (i, c) in text.char_indices() {
+        // sekme dört boşluk genişliğinde
+        let lookup = if c == '\t' { ' ' } else { c };
+        let font = std::iter::once(section.font.0)
+            .chain((0..fonts.len()).filter(|f| *f != section.font.0))
+            .find(|f| fonts[*f].has_glyph(lookup) || c.is_control())
+            .unwrap_or(section.font.0);
+        let scaled = fonts[font].inner.as_scaled(fonts[font].scale(section.size));
+        let id = scaled.glyph_id(lookup);
+        let mut advance = if c.is_control() && c != '\t' { 0.0 } else { scaled.h_advance(id) };
+        if c == '\t' {
+            advance *= 4.0;
+        }
+        let kern = match chars.last() {
+            Some(prev) if prev.font == font => scaled.kern(prev.id, id),
+            _ => 0.0,
+        };
+        chars.push(ShapedChar { c, byte_index: offset + i, font, id, advance, kern });
+    }
+    chars
+}
+
+/// Greedy line breaking, returns the char range of every line
+fn wrap_paragraph(chars: &[ShapedChar], max_width: Option<f32>) -> Vec<std::ops::Range<usize>> {
+    let Some(max_width) = max_width else {
+        return std::iter::once(0..chars.len()).collect();
+    };
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut x = 0.0;
+    let mut last_break: Option<usize> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = &chars[i];
+        if is_cjk(c.c) && i > start {
+            last_break = Some(i);
+        }
+        let w = c.advance + if i > start { c.kern } else { 0.0 };
+        if !c.c.is_whitespace() && i > start && x + w > max_width {
+            let brk = last_break.filter(|b| *b > start).unwrap_or(i);
+            lines.push(start..brk);
+            start = brk;
+            // yeni satır boşlukla başlamasın
+            while start < chars.len() && chars[start].c.is_whitespace() {
+                start += 1;
+            }
+            i = i.max(start);
+            x = chars[start..i].iter().enumerate()
+                .map(|(j, c)| c.advance + if j > 0 { c.kern } else { 0.0 })
+                .sum();
+            last_break = None;
+            continue;
+        }
+        x += w;
+        if c.c.is_whitespace() || is_cjk(c.c) {
+            last_break = Some(i + 1);
+        }
+        i += 1;
+    }
+    lines.push(start..chars.len());
+    lines
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    font: usize,
+    id: GlyphId,
+    size: u32,
+    subpixel: u8,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct AtlasGlyph {
+    uv: Rect,
+    /// top left of the bitmap relative to the (pixel aligned) pen position
+    offset: Vec2,
+    size: Vec2,
+}
+
+struct Shelf {
+    y: u32,
+    height: u32,
+    x: u32,
+}
+
+/// Glyph bitmaps packed into shelves of one RGBA texture (white, coverage in alpha)
+/// so text can be drawn with the sprite pipeline.
+pub struct GlyphAtlas {
+    texture: Arc<Texture>,
+    size: u32,
+    max_size: u32,
+    shelves: Vec<Shelf>,
+    next_y: u32,
+    /// None for glyphs without an outline (spaces)
+    glyphs: HashMap<GlyphKey, Option<AtlasGlyph>>,
+}
+
+impl GlyphAtlas {
+    pub fn new(device: &Device) -> Self {
+        let max_size = device.limits().max_texture_dimension_2d.min(ATLAS_MAX_SIZE);
+        let size = ATLAS_START_SIZE.min(max_size);
+        Self {
+            texture: Arc::new(Self::create_texture(device, size)),
+            size,
+            max_size,
+            shelves: Vec::new(),
+            next_y: 0,
+            glyphs: HashMap::new(),
+        }
+    }
+
+    fn create_texture(device: &Device, size: u32) -> Texture {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Glyph Atlas"),
+            size: wgpu::Extent3d { width: size, height: size, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Glyph Atlas Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        Texture { texture, view, sampler, size: (size, size) }
+    }
+
+    pub fn texture(&self) -> &Arc<Texture> {
+        &self.texture
+    }
+
+    pub fn glyph_count(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// Starts over with an empty texture, twice as big if the limit allows. Quads already
+    /// queued keep the old texture alive until they are drawn.
+    fn reset(&mut self, device: &Device) {
+        self.size = (self.size * 2).min(self.max_size);
+        self.texture = Arc::new(Self::create_texture(device, self.size));
+        self.shelves.clear();
+        self.next_y = 0;
+        self.glyphs.clear();
+    }
+
+    fn allocate(&mut self, w: u32, h: u32) -> Option<(u32, u32)> {
+        // glyph'ler arasında filtrelemede taşma olmasın diye 1 piksel boşluk
+        let (w, h) = (w + 1, h + 1);
+        if let Some(shelf) = self.shelves.iter_mut()
+            .find(|s| s.height >= h && s.height <= h + h / 2 && s.x + w <= self.size)
+        {
+            let x = shelf.x;
+            shelf.x += w;
+            return Some((x, shelf.y));
+        }
+        if self.next_y + h > self.size || w > self.size {
+            return None;
+        }
+        let y = self.next_y;
+        self.shelves.push(Shelf { y, height: h, x: w });
+        self.next_y += h;
+        Some((0, y))
+    }
+
+    fn get(&mut self, device: &Device, queue: &Queue, fonts: &[Font], key: GlyphKey, size: f32) -> Option<AtlasGlyph> {
+        if let Some(glyph) = self.glyphs.get(&key) {
+            return *glyph;
+        }
+        let font = &fonts[key.font];
+        let subpixel = key.subpixel as f32 / SUBPIXEL_STEPS;
+        let glyph = key.id.with_scale_and_position(font.scale(size), ab_glyph::point(subpixel, 0.0));
+        let Some(outlined) = font.inner.outline_glyph(glyph) else {
+            self.glyphs.insert(key, None);
+            return None;
+        };
+        let bounds = outlined.px_bounds();
+        let (w, h) = (bounds.width() as u32, bounds.height() as u32);
+        if w == 0 || h == 0 {
+            self.glyphs.insert(key, None);
+            return None;
+        }
+
+        let (x, y) = match self.allocate(w, h) {
+            Some(p) => p,
+            None => {
+                self.reset(device);
+                self.allocate(w, h)?
+            }
+        };
+        let mut rgba = vec![255u8; (w * h * 4) as usize];
+        outlined.draw(|px, py, coverage| {
+            rgba[((py * w + px) * 4 + 3) as usize] = (coverage.clamp(0.0, 1.0) * 255.0) as u8;
+        });
+        stats::upload(rgba.len());
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.texture.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d { x, y, z: 0 },
+                aspect: wgpu::TextureAspect::All,
+            },
+            &rgba,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * w),
+                rows_per_image: Some(h),
+            },
+            wgpu::Extent3d { width: w, height: h, depth_or_array_layers: 1 },
+        );
+
+        let atlas = self.size as f32;
+        let entry = AtlasGlyph {
+            uv: Rect::new(Vec2::new(x as f32, y as f32) / atlas, Vec2::new((x + w) as f32, (y + h) as f32) / atlas),
+            offset: Vec2::new(bounds.min.x, bounds.min.y),
+            size: Vec2::new(w as f32, h as f32),
+        };
+        self.glyphs.insert(key, Some(entry));
+        Some(entry)
+    }
+}
+
+/// Loaded fonts and their glyph atlas. Text is turned into quads of a `SpriteBatch`,
+/// so it is drawn in order with (and batched alongside) sprites.
+pub struct TextRenderer {
+    fonts: Vec<Font>,
+    pub atlas: GlyphAtlas,
+}
+
+impl TextRenderer {
+    pub fn new(device: &Device) -> Self {
+        Self {
+            fonts: Vec::new(),
+            atlas: GlyphAtlas::new(device),
+        }
+    }
+
+    /// Fonts added later are used as fallbacks for chars missing in the section font.
+    pub fn add_font(&mut self, font: Font) -> FontId {
+        self.fonts.push(font);
+        FontId(self.fonts.len() - 1)
+    }
+
+    /// removes every font, ids start from 0 again
+    pub fn take_fonts(&mut self) -> Vec<Font> {
+        std::mem::take(&mut self.fonts)
+    }
+
+    pub fn font(&self, id: FontId) -> Option<&Font> {
+        self.fonts.get(id.0)
+    }
+
+    pub fn layout(&self, section: &TextSection) -> TextLayout {
+        layout_text(&self.fonts, section)
+    }
+
+    pub fn measure(&self, section: &TextSection) -> Vec2 {
+        self.layout(section).size
+    }
+
+    /// Rasterizes the missing glyphs and adds one quad per visible glyph to `batch`.
+    pub fn queue(&mut self, device: &Device, queue: &Queue, batch: &mut SpriteBatch, section: &TextSection) {
+        let layout = self.layout(section);
+        for glyph in &layout.glyphs {
+            let pen = section.position + glyph.position;
+            let pixel = Vec2::new(pen.x.floor(), pen.y.round());
+            let subpixel = ((pen.x - pixel.x) * SUBPIXEL_STEPS).round() as u8;
+            // 4/4 bir sonraki piksel demek
+            let (pixel, subpixel) = if subpixel as f32 >= SUBPIXEL_STEPS { (pixel + Vec2::X, 0) } else { (pixel, subpixel) };
+            let key = GlyphKey { font: glyph.font.0, id: glyph.id, size: section.size.to_bits(), subpixel };
+            let Some(entry) = self.atlas.get(device, queue, &self.fonts, key, section.size) else {
+                continue;
+            };
+            let min = pixel + entry.offset;
+            let texture = self.atlas.texture().clone();
+            batch.draw_rect(&texture, Rect::from_pos_size(min, entry.size), entry.uv, section.color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Glyphs without outlines for printable ASCII, every letter has its own advance
+    const LAYOUT_FONT: &[u8] = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/layout_test.ttf"));
+
+    #[test]
+    fn wrap_and_align(){
+        let fonts = vec![Font::from_bytes(LAYOUT_FONT.to_vec()).unwrap()];
+        let section = TextSection::new("hello wide world", FontId(0), 20.0);
+        let single = layout_text(&fonts, &section);
+        assert_eq!(single.lines, 1);
+        assert_eq!(single.glyphs.len(), 16);
+
+        // "hello wide" sığıyor, "world" alt satıra geçmeli
+        let hello_wide = layout_text(&fonts, &TextSection::new("hello wide", FontId(0), 20.0)).size.x;
+        let wrapped = layout_text(&fonts, &section.with_max_width(hello_wide + 1.0));
+        assert_eq!(wrapped.lines, 2);
+        let w = wrapped.glyphs.iter().find(|g| g.byte_index == 11).unwrap();
+        assert_eq!(w.position.x, 0.0);
+        assert!(w.position.y > wrapped.glyphs[0].position.y);
+
+        let right = layout_text(&fonts, &TextSection::new("ab", FontId(0), 20.0).with_max_width(100.0).with_align(TextAlign::Right));
+        let last = right.glyphs.last().unwrap();
+        let scaled = fonts[0].inner.as_scaled(fonts[0].scale(20.0));
+        assert!((last.position.x + scaled.h_advance(last.id) - 100.0).abs() < 1e-3);
+    }
+}
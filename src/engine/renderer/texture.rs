@@ -0,0 +1,191 @@
+// src/engine/renderer/texture.rs
+
+use wgpu::{Device, Queue};
+
+use crate::engine::renderer::gltf_loader::ImageData;
+use crate::engine::renderer::stats;
+
+/// Sampled 2D texture with its default view and sampler
+pub struct Texture {
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    pub sampler: wgpu::Sampler,
+    pub size: (u32, u32),
+}
+
+impl Texture {
+    /// Uploads an RGBA8 image together with a CPU generated mip chain.
+    /// Color textures (base color, emissive) should be `srgb`, data textures (normal, metallic-roughness) not.
+    pub fn from_image(device: &Device, queue: &Queue, image: &ImageData, srgb: bool, label: &str) -> Self {
+        let format = if srgb { wgpu::TextureFormat::Rgba8UnormSrgb } else { wgpu::TextureFormat::Rgba8Unorm };
+        let mips = generate_mips(image);
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width: image.width,
+                height: image.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: mips.len() as u32,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        for (level, mip) in mips.iter().enumerate() {
+            write_rgba8(queue, &texture, level as u32, mip);
+        }
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some(label),
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            address_mode_w: wgpu::AddressMode::Repeat,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        Self { texture, view, sampler, size: (image.width, image.height) }
+    }
+
+    /// Writes `image` with its mips over the current pixels, bind groups using the texture
+    /// keep working. False when the size differs, a new texture is needed then.
+    pub fn rewrite(&self, queue: &Queue, image: &ImageData) -> bool {
+        if (image.width, image.height) != self.size {
+            return false;
+        }
+        for (level, mip) in generate_mips(image).iter().enumerate() {
+            write_rgba8(queue, &self.texture, level as u32, mip);
+        }
+        true
+    }
+
+    /// 1x1 texture filled with one color, used for missing material slots
+    pub fn solid(device: &Device, queue: &Queue, rgba: [u8; 4], srgb: bool, label: &str) -> Self {
+        let image = ImageData { width: 1, height: 1, rgba: rgba.to_vec() };
+        Self::from_image(device, queue, &image, srgb, label)
+    }
+
+    pub fn white(device: &Device, queue: &Queue) -> Self {
+        Self::solid(device, queue, [255; 4], true, "White Texture")
+    }
+
+    /// tangent space'te (0, 0, 1)
+    pub fn flat_normal(device: &Device, queue: &Queue) -> Self {
+        Self::solid(device, queue, [128, 128, 255, 255], false, "Flat Normal Texture")
+    }
+}
+
+pub fn write_rgba8(queue: &Queue, texture: &wgpu::Texture, mip_level: u32, image: &ImageData) {
+    stats::upload(image.rgba.len());
+    queue.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture,
+            mip_level,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        &image.rgba,
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(4 * image.width),
+            rows_per_image: Some(image.height),
+        },
+        wgpu::Extent3d {
+            width: image.width,
+            height: image.height,
+            depth_or_array_layers: 1,
+        },
+    );
+}
+
+/// Copies a 2D RGBA8 (or BGRA8) texture back to the CPU, rows are returned without padding.
+/// Blocks until the GPU is done, so it is meant for tests, screenshots and tools.
+pub fn read_texture_rgba8(device: &Device, queue: &Queue, texture: &wgpu::Texture) -> Vec<u8> {
+    let (width, height) = (texture.width(), texture.height());
+    let unpadded = 4 * width;
+    // copy_texture_to_buffer satırların 256 byte hizalı olmasını istiyor
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let padded = unpadded.div_ceil(align) * align;
+
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Readback Buffer"),
+        size: (padded * height) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Readback Encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        texture.as_image_copy(),
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(padded),
+                rows_per_image: Some(height),
+            },
+        },
+        wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let slice = buffer.slice(..);
+    slice.map_async(wgpu::MapMode::Read, |_| ());
+    let _ = device.poll(wgpu::PollType::wait_indefinitely());
+
+    let data = slice.get_mapped_range();
+    let mut pixels = Vec::with_capacity((unpadded * height) as usize);
+    for row in data.chunks(padded as usize) {
+        pixels.extend_from_slice(&row[..unpadded as usize]);
+    }
+    drop(data);
+    buffer.unmap();
+    pixels
+}
+
+/// Full mip chain with a 2x2 box filter, level 0 is the image itself
+pub fn generate_mips(image: &ImageData) -> Vec<ImageData> {
+    let mut mips = vec![image.clone()];
+    while let Some(prev) = mips.last() {
+        if prev.width == 1 && prev.height == 1 {
+            break;
+        }
+        let w = (prev.width / 2).max(1);
+        let h = (prev.height / 2).max(1);
+        let mut rgba = vec![0u8; (w * h * 4) as usize];
+        for y in 0..h {
+            for x in 0..w {
+                for c in 0..4 {
+                    let mut sum = 0u32;
+                    for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                        // tek boyutlu kenarlarda aynı pikseli tekrar kullan
+                        let sx = (x * 2 + dx).min(prev.width - 1);
+                        let sy = (y * 2 + dy).min(prev.height - 1);
+                        sum += prev.rgba[((sy * prev.width + sx) * 4 + c) as usize] as u32;
+                    }
+                    rgba[((y * w + x) * 4 + c) as usize] = ((sum + 2) / 4) as u8;
+                }
+            }
+        }
+        mips.push(ImageData { width: w, height: h, rgba });
+    }
+    mips
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mip_chain(){
+        let image = ImageData { width: 4, height: 2, rgba: [[0u8, 0, 0, 255], [255, 255, 255, 255]].repeat(4).concat() };
+        let mips = generate_mips(&image);
+        let sizes: Vec<(u32, u32)> = mips.iter().map(|m| (m.width, m.height)).collect();
+        assert_eq!(sizes, vec![(4, 2), (2, 1), (1, 1)]);
+        assert_eq!(mips[2].rgba, vec![128, 128, 128, 255]);
+    }
+}
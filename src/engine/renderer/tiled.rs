@@ -0,0 +1,935 @@
+// src/engine/renderer/tiled.rs
+//
+// Loader for maps made with the Tiled editor. The JSON (.tmj) and XML (.tmx) formats end
+// up in the same `TiledMap`, external tilesets (.tsj/.tsx) are read next to the map.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use base64::Engine;
+use glam::{UVec2, Vec2};
+use serde::Deserialize;
+
+pub const FLIP_HORIZONTAL: u32 = 0x8000_0000;
+pub const FLIP_VERTICAL: u32 = 0x4000_0000;
+pub const FLIP_DIAGONAL: u32 = 0x2000_0000;
+/// gid bits without the flip flags (hexagonal rotation bit included)
+pub const GID_MASK: u32 = 0x0fff_ffff;
+
+#[derive(Debug)]
+pub enum TiledError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Xml(roxmltree::Error),
+    Image(image::ImageError),
+    Invalid(String),
+}
+
+impl fmt::Display for TiledError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TiledError::Io(e) => write!(f, "io error: {}", e),
+            TiledError::Json(e) => write!(f, "json error: {}", e),
+            TiledError::Xml(e) => write!(f, "xml error: {}", e),
+            TiledError::Image(e) => write!(f, "tileset image error: {}", e),
+            TiledError::Invalid(msg) => write!(f, "invalid map: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TiledError {}
+
+impl From<std::io::Error> for TiledError {
+    fn from(e: std::io::Error) -> Self {
+        TiledError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for TiledError {
+    fn from(e: serde_json::Error) -> Self {
+        TiledError::Json(e)
+    }
+}
+
+impl From<roxmltree::Error> for TiledError {
+    fn from(e: roxmltree::Error) -> Self {
+        TiledError::Xml(e)
+    }
+}
+
+impl From<image::ImageError> for TiledError {
+    fn from(e: image::ImageError) -> Self {
+        TiledError::Image(e)
+    }
+}
+
+fn invalid<T>(msg: impl Into<String>) -> Result<T, TiledError> {
+    Err(TiledError::Invalid(msg.into()))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    /// rgba in 0..1
+    Color([f32; 4]),
+    File(String),
+    /// id of an object on the map
+    Object(u32),
+}
+
+pub type Properties = HashMap<String, PropertyValue>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationFrame {
+    /// local tile id in the same tileset
+    pub tile: u32,
+    /// seconds
+    pub duration: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TileData {
+    pub class: String,
+    pub animation: Vec<AnimationFrame>,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tileset {
+    pub first_gid: u32,
+    pub name: String,
+    pub tile_size: UVec2,
+    pub columns: u32,
+    pub tile_count: u32,
+    pub spacing: u32,
+    pub margin: u32,
+    /// resolved against the map (or the external tileset) file, `None` for image collections
+    pub image: Option<PathBuf>,
+    pub image_size: UVec2,
+    /// only tiles with extra data (animation, properties, class) are listed
+    pub tiles: HashMap<u32, TileData>,
+}
+
+impl Tileset {
+    pub fn contains(&self, gid: u32) -> bool {
+        let gid = gid & GID_MASK;
+        gid >= self.first_gid && gid < self.first_gid + self.tile_count
+    }
+
+    /// Local tile shown at `time` seconds, the tile itself when it isn't animated
+    pub fn animated_tile(&self, local: u32, time: f32) -> u32 {
+        let Some(frames) = self.tiles.get(&local).map(|t| &t.animation).filter(|a| !a.is_empty()) else {
+            return local;
+        };
+        let total: f32 = frames.iter().map(|f| f.duration).sum();
+        if total <= 0.0 {
+            return frames[0].tile;
+        }
+        let mut t = time.rem_euclid(total);
+        for frame in frames {
+            if t < frame.duration {
+                return frame.tile;
+            }
+            t -= frame.duration;
+        }
+        frames[frames.len() - 1].tile
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TileLayer {
+    pub name: String,
+    pub size: UVec2,
+    /// row major gids with the flip flags, 0 is empty
+    pub tiles: Vec<u32>,
+    /// pixels, group offsets included
+    pub offset: Vec2,
+    pub opacity: f32,
+    pub visible: bool,
+    pub properties: Properties,
+}
+
+impl TileLayer {
+    pub fn tile(&self, x: u32, y: u32) -> u32 {
+        if x >= self.size.x || y >= self.size.y {
+            return 0;
+        }
+        self.tiles[(y * self.size.x + x) as usize]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectShape {
+    Rectangle,
+    Ellipse,
+    Point,
+    /// points relative to the object position
+    Polygon(Vec<Vec2>),
+    Polyline(Vec<Vec2>),
+    /// tile object, the gid keeps its flip flags
+    Tile(u32),
+    Text(String),
+}
+
+/// Object of an object layer. Positions are map pixels with y pointing down, the game
+/// decides which entities to spawn from `class`, `name` and `properties`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapObject {
+    pub id: u32,
+    pub name: String,
+    pub class: String,
+    pub position: Vec2,
+    pub size: Vec2,
+    /// degrees, clockwise
+    pub rotation: f32,
+    pub visible: bool,
+    pub shape: ObjectShape,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectLayer {
+    pub name: String,
+    pub objects: Vec<MapObject>,
+    pub offset: Vec2,
+    pub opacity: f32,
+    pub visible: bool,
+    pub properties: Properties,
+}
+
+/// Group layers are flattened into their children, image layers are skipped
+#[derive(Debug, Clone, PartialEq)]
+pub enum Layer {
+    Tiles(TileLayer),
+    Objects(ObjectLayer),
+}
+
+impl Layer {
+    pub fn name(&self) -> &str {
+        match self {
+            Layer::Tiles(l) => &l.name,
+            Layer::Objects(l) => &l.name,
+        }
+    }
+}
+
+/// An orthogonal, finite Tiled map
+#[derive(Debug, Clone, PartialEq)]
+pub struct TiledMap {
+    /// in tiles
+    pub size: UVec2,
+    pub tile_size: UVec2,
+    pub tilesets: Vec<Tileset>,
+    pub layers: Vec<Layer>,
+    pub background: Option<[f32; 4]>,
+    pub properties: Properties,
+}
+
+impl TiledMap {
+    /// Loads a .tmx or .tmj file, the format is detected from the content
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TiledError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text, path.parent())
+    }
+
+    /// .tmx or .tmj content, detected like `load` does
+    pub fn parse(text: &str, base: Option<&Path>) -> Result<Self, TiledError> {
+        Self::parse_with(text, base, |path| std::fs::read(path))
+    }
+
+    /// Like `parse`, external tilesets are read by `read` (e.g. through the asset `Vfs`)
+    pub fn parse_with<F: FnMut(&Path) -> std::io::Result<Vec<u8>>>(text: &str, base: Option<&Path>, mut read: F) -> Result<Self, TiledError> {
+        if is_xml(text) {
+            Self::xml_with(text, base, &mut read)
+        } else {
+            Self::json_with(text, base, &mut read)
+        }
+    }
+
+    /// `base` is the directory external tilesets and images are resolved against
+    pub fn from_json(text: &str, base: Option<&Path>) -> Result<Self, TiledError> {
+        Self::json_with(text, base, &mut |path| std::fs::read(path))
+    }
+
+    fn json_with(text: &str, base: Option<&Path>, read: &mut dyn FnMut(&Path) -> std::io::Result<Vec<u8>>) -> Result<Self, TiledError> {
+        let raw: JsonMap = serde_json::from_str(text)?;
+        check_map(&raw.orientation, raw.infinite)?;
+        let tilesets = raw.tilesets.into_iter()
+            .map(|t| match &t.source {
+                Some(source) => load_external_tileset(&resolve(base, source), t.firstgid, read),
+                None => Ok(t.into_tileset(base)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut layers = Vec::new();
+        for layer in raw.layers {
+            layer.flatten(Vec2::ZERO, 1.0, true, &mut layers)?;
+        }
+        Ok(Self {
+            size: UVec2::new(raw.width, raw.height),
+            tile_size: UVec2::new(raw.tilewidth, raw.tileheight),
+            tilesets: sorted(tilesets),
+            layers,
+            background: raw.backgroundcolor.as_deref().and_then(parse_color),
+            properties: json_properties(raw.properties),
+        })
+    }
+
+    pub fn from_xml(text: &str, base: Option<&Path>) -> Result<Self, TiledError> {
+        Self::xml_with(text, base, &mut |path| std::fs::read(path))
+    }
+
+    fn xml_with(text: &str, base: Option<&Path>, read: &mut dyn FnMut(&Path) -> std::io::Result<Vec<u8>>) -> Result<Self, TiledError> {
+        let doc = roxmltree::Document::parse(text)?;
+        let root = doc.root_element();
+        if !root.has_tag_name("map") {
+            return invalid("root element is not <map>");
+        }
+        check_map(root.attribute("orientation").unwrap_or("orthogonal"), attr_or(root, "infinite", 0u8) != 0)?;
+
+        let mut tilesets = Vec::new();
+        let mut layers = Vec::new();
+        for child in root.children().filter(|n| n.is_element()) {
+            match child.tag_name().name() {
+                "tileset" => {
+                    let first_gid = attr_or(child, "firstgid", 1);
+                    tilesets.push(match child.attribute("source") {
+                        Some(source) => load_external_tileset(&resolve(base, source), first_gid, read)?,
+                        None => xml_tileset(child, first_gid, base),
+                    });
+                }
+                "layer" | "objectgroup" | "group" => xml_layer(child, Vec2::ZERO, 1.0, true, &mut layers)?,
+                _ => {}
+            }
+        }
+        Ok(Self {
+            size: UVec2::new(attr_or(root, "width", 0), attr_or(root, "height", 0)),
+            tile_size: UVec2::new(attr_or(root, "tilewidth", 0), attr_or(root, "tileheight", 0)),
+            tilesets: sorted(tilesets),
+            layers,
+            background: root.attribute("backgroundcolor").and_then(parse_color),
+            properties: xml_properties(root),
+        })
+    }
+
+    /// Tileset a gid belongs to, with its index
+    pub fn tileset_for(&self, gid: u32) -> Option<(usize, &Tileset)> {
+        let gid = gid & GID_MASK;
+        if gid == 0 {
+            return None;
+        }
+        // tileset'ler first_gid'e göre sıralı
+        let index = self.tilesets.partition_point(|t| t.first_gid <= gid).checked_sub(1)?;
+        Some((index, &self.tilesets[index]))
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|l| l.name() == name)
+    }
+
+    /// every object of every object layer
+    pub fn objects(&self) -> impl Iterator<Item = (&ObjectLayer, &MapObject)> {
+        self.layers.iter()
+            .filter_map(|l| match l {
+                Layer::Objects(layer) => Some(layer),
+                _ => None,
+            })
+            .flat_map(|layer| layer.objects.iter().map(move |o| (layer, o)))
+    }
+
+    pub fn object(&self, name: &str) -> Option<&MapObject> {
+        self.objects().map(|(_, o)| o).find(|o| o.name == name)
+    }
+
+    /// size of the map in pixels
+    pub fn pixel_size(&self) -> Vec2 {
+        (self.size * self.tile_size).as_vec2()
+    }
+}
+
+fn check_map(orientation: &str, infinite: bool) -> Result<(), TiledError> {
+    if orientation != "orthogonal" {
+        return invalid(format!("{} maps are not supported, only orthogonal", orientation));
+    }
+    if infinite {
+        return invalid("infinite maps are not supported");
+    }
+    Ok(())
+}
+
+fn is_xml(text: &str) -> bool {
+    text.trim_start().starts_with('<')
+}
+
+fn resolve(base: Option<&Path>, path: &str) -> PathBuf {
+    match base {
+        Some(base) => base.join(path),
+        None => PathBuf::from(path),
+    }
+}
+
+fn sorted(mut tilesets: Vec<Tileset>) -> Vec<Tileset> {
+    tilesets.sort_by_key(|t| t.first_gid);
+    tilesets
+}
+
+fn load_external_tileset(path: &Path, first_gid: u32, read: &mut dyn FnMut(&Path) -> std::io::Result<Vec<u8>>) -> Result<Tileset, TiledError> {
+    let text = String::from_utf8(read(path)?)
+        .or_else(|_| invalid(format!("{} is not utf-8", path.display())))?;
+    let base = path.parent();
+    if is_xml(&text) {
+        let doc = roxmltree::Document::parse(&text)?;
+        Ok(xml_tileset(doc.root_element(), first_gid, base))
+    } else {
+        let mut raw: JsonTileset = serde_json::from_str(&text)?;
+        raw.firstgid = first_gid;
+        Ok(raw.into_tileset(base))
+    }
+}
+
+/// "#RRGGBB" or "#AARRGGBB"
+fn parse_color(s: &str) -> Option<[f32; 4]> {
+    let hex = s.trim_start_matches('#');
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    let (a, rgb) = match hex.len() {
+        6 => (255, value),
+        8 => (value >> 24, value & 0x00ff_ffff),
+        _ => return None,
+    };
+    let c = |shift: u32| ((rgb >> shift) & 0xff) as f32 / 255.0;
+    Some([c(16), c(8), c(0), a as f32 / 255.0])
+}
+
+fn parse_property(kind: &str, value: &str) -> PropertyValue {
+    match kind {
+        "bool" => PropertyValue::Bool(value == "true"),
+        "int" => PropertyValue::Int(value.parse().unwrap_or(0)),
+        "float" => PropertyValue::Float(value.parse().unwrap_or(0.0)),
+        "color" => PropertyValue::Color(parse_color(value).unwrap_or([0.0; 4])),
+        "file" => PropertyValue::File(value.to_string()),
+        "object" => PropertyValue::Object(value.parse().unwrap_or(0)),
+        _ => PropertyValue::String(value.to_string()),
+    }
+}
+
+/// csv or base64 (optionally zlib/gzip compressed) layer data
+fn decode_tiles(encoding: Option<&str>, compression: Option<&str>, data: &str) -> Result<Vec<u32>, TiledError> {
+    match encoding {
+        Some("csv") => data.split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().or_else(|_| invalid(format!("bad tile `{}`", s))))
+            .collect(),
+        Some("base64") => {
+            let compact: String = data.chars().filter(|c| !c.is_whitespace()).collect();
+            let bytes = base64::engine::general_purpose::STANDARD.decode(compact)
+                .or_else(|e| invalid(format!("bad base64 data: {}", e)))?;
+            let bytes = match compression.unwrap_or("") {
+                "" => bytes,
+                "zlib" => read_all(flate2::read::ZlibDecoder::new(&bytes[..]))?,
+                "gzip" => read_all(flate2::read::GzDecoder::new(&bytes[..]))?,
+                other => return invalid(format!("unsupported compression `{}`", other)),
+            };
+            if bytes.len() % 4 != 0 {
+                return invalid("layer data is not a multiple of 4 bytes");
+            }
+            Ok(bytes.chunks_exact(4).map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]])).collect())
+        }
+        other => invalid(format!("unsupported encoding {:?}", other)),
+    }
+}
+
+fn read_all(mut reader: impl Read) -> Result<Vec<u8>, TiledError> {
+    let mut out = Vec::new();
+    reader.read_to_end(&mut out)?;
+    Ok(out)
+}
+
+fn tile_layer(name: String, size: UVec2, tiles: Vec<u32>, offset: Vec2, opacity: f32, visible: bool, properties: Properties) -> Result<Layer, TiledError> {
+    if tiles.len() != (size.x * size.y) as usize {
+        return invalid(format!("layer `{}` has {} tiles, expected {}", name, tiles.len(), size.x * size.y));
+    }
+    Ok(Layer::Tiles(TileLayer { name, size, tiles, offset, opacity, visible, properties }))
+}
+
+// --- JSON ---
+
+fn one() -> f32 {
+    1.0
+}
+
+fn yes() -> bool {
+    true
+}
+
+fn orthogonal() -> String {
+    "orthogonal".to_string()
+}
+
+#[derive(Deserialize)]
+struct JsonMap {
+    width: u32,
+    height: u32,
+    tilewidth: u32,
+    tileheight: u32,
+    #[serde(default = "orthogonal")]
+    orientation: String,
+    #[serde(default)]
+    infinite: bool,
+    backgroundcolor: Option<String>,
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+    #[serde(default)]
+    tilesets: Vec<JsonTileset>,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonData {
+    Tiles(Vec<u32>),
+    Encoded(String),
+}
+
+#[derive(Deserialize)]
+struct JsonLayer {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    width: u32,
+    #[serde(default)]
+    height: u32,
+    data: Option<JsonData>,
+    encoding: Option<String>,
+    compression: Option<String>,
+    #[serde(default)]
+    offsetx: f32,
+    #[serde(default)]
+    offsety: f32,
+    #[serde(default = "one")]
+    opacity: f32,
+    #[serde(default = "yes")]
+    visible: bool,
+    #[serde(default)]
+    objects: Vec<JsonObject>,
+    #[serde(default)]
+    layers: Vec<JsonLayer>,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+impl JsonLayer {
+    fn flatten(self, offset: Vec2, opacity: f32, visible: bool, out: &mut Vec<Layer>) -> Result<(), TiledError> {
+        let offset = offset + Vec2::new(self.offsetx, self.offsety);
+        let opacity = opacity * self.opacity;
+        let visible = visible && self.visible;
+        let properties = json_properties(self.properties);
+        match self.kind.as_str() {
+            "tilelayer" => {
+                let tiles = match self.data {
+                    Some(JsonData::Tiles(tiles)) => tiles,
+                    Some(JsonData::Encoded(data)) =>
+                        decode_tiles(Some(self.encoding.as_deref().unwrap_or("base64")), self.compression.as_deref(), &data)?,
+                    None => return invalid(format!("layer `{}` has no data", self.name)),
+                };
+                out.push(tile_layer(self.name, UVec2::new(self.width, self.height), tiles, offset, opacity, visible, properties)?);
+            }
+            "objectgroup" => out.push(Layer::Objects(ObjectLayer {
+                name: self.name,
+                objects: self.objects.into_iter().map(JsonObject::into_object).collect(),
+                offset,
+                opacity,
+                visible,
+                properties,
+            })),
+            "group" => {
+                for layer in self.layers {
+                    layer.flatten(offset, opacity, visible, out)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonPoint {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Deserialize)]
+struct JsonText {
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct JsonObject {
+    #[serde(default)]
+    id: u32,
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type", alias = "class")]
+    class: String,
+    #[serde(default)]
+    x: f32,
+    #[serde(default)]
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    #[serde(default)]
+    rotation: f32,
+    gid: Option<u32>,
+    #[serde(default = "yes")]
+    visible: bool,
+    #[serde(default)]
+    ellipse: bool,
+    #[serde(default)]
+    point: bool,
+    polygon: Option<Vec<JsonPoint>>,
+    polyline: Option<Vec<JsonPoint>>,
+    text: Option<JsonText>,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+impl JsonObject {
+    fn into_object(self) -> MapObject {
+        let points = |p: Vec<JsonPoint>| p.into_iter().map(|p| Vec2::new(p.x, p.y)).collect();
+        let shape = if let Some(gid) = self.gid {
+            ObjectShape::Tile(gid)
+        } else if let Some(p) = self.polygon {
+            ObjectShape::Polygon(points(p))
+        } else if let Some(p) = self.polyline {
+            ObjectShape::Polyline(points(p))
+        } else if let Some(t) = self.text {
+            ObjectShape::Text(t.text)
+        } else if self.ellipse {
+            ObjectShape::Ellipse
+        } else if self.point {
+            ObjectShape::Point
+        } else {
+            ObjectShape::Rectangle
+        };
+        MapObject {
+            id: self.id,
+            name: self.name,
+            class: self.class,
+            position: Vec2::new(self.x, self.y),
+            size: Vec2::new(self.width, self.height),
+            rotation: self.rotation,
+            visible: self.visible,
+            shape,
+            properties: json_properties(self.properties),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonFrame {
+    tileid: u32,
+    /// milliseconds
+    duration: u32,
+}
+
+#[derive(Deserialize)]
+struct JsonTile {
+    id: u32,
+    #[serde(default, rename = "type", alias = "class")]
+    class: String,
+    #[serde(default)]
+    animation: Vec<JsonFrame>,
+    #[serde(default)]
+    properties: Vec<JsonProperty>,
+}
+
+#[derive(Deserialize)]
+struct JsonTileset {
+    #[serde(default)]
+    firstgid: u32,
+    source: Option<String>,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    tilewidth: u32,
+    #[serde(default)]
+    tileheight: u32,
+    #[serde(default)]
+    columns: u32,
+    #[serde(default)]
+    tilecount: u32,
+    #[serde(default)]
+    spacing: u32,
+    #[serde(default)]
+    margin: u32,
+    image: Option<String>,
+    #[serde(default)]
+    imagewidth: u32,
+    #[serde(default)]
+    imageheight: u32,
+    #[serde(default)]
+    tiles: Vec<JsonTile>,
+}
+
+impl JsonTileset {
+    fn into_tileset(self, base: Option<&Path>) -> Tileset {
+        let tiles = self.tiles.into_iter().map(|t| (t.id, TileData {
+            class: t.class,
+            animation: t.animation.iter()
+                .map(|f| AnimationFrame { tile: f.tileid, duration: f.duration as f32 / 1000.0 })
+                .collect(),
+            properties: json_properties(t.properties),
+        })).collect();
+        Tileset {
+            first_gid: self.firstgid,
+            name: self.name,
+            tile_size: UVec2::new(self.tilewidth, self.tileheight),
+            columns: self.columns,
+            tile_count: self.tilecount,
+            spacing: self.spacing,
+            margin: self.margin,
+            image: self.image.map(|i| resolve(base, &i)),
+            image_size: UVec2::new(self.imagewidth, self.imageheight),
+            tiles,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonProperty {
+    name: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    value: serde_json::Value,
+}
+
+fn json_properties(properties: Vec<JsonProperty>) -> Properties {
+    properties.into_iter().map(|p| {
+        let value = match p.value {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        };
+        (p.name, parse_property(&p.kind, &value))
+    }).collect()
+}
+
+// --- XML ---
+
+fn attr<T: FromStr>(node: roxmltree::Node<'_, '_>, name: &str) -> Option<T> {
+    node.attribute(name).and_then(|v| v.parse().ok())
+}
+
+fn attr_or<T: FromStr>(node: roxmltree::Node<'_, '_>, name: &str, default: T) -> T {
+    attr(node, name).unwrap_or(default)
+}
+
+fn child<'a, 'i>(node: roxmltree::Node<'a, 'i>, name: &str) -> Option<roxmltree::Node<'a, 'i>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+fn xml_class(node: roxmltree::Node<'_, '_>) -> String {
+    node.attribute("class").or(node.attribute("type")).unwrap_or("").to_string()
+}
+
+fn xml_properties(node: roxmltree::Node<'_, '_>) -> Properties {
+    let Some(properties) = child(node, "properties") else {
+        return Properties::new();
+    };
+    properties.children().filter(|n| n.has_tag_name("property")).map(|p| {
+        // çok satırlı string'ler value yerine içerikte
+        let value = p.attribute("value").or(p.text()).unwrap_or("");
+        (p.attribute("name").unwrap_or("").to_string(), parse_property(p.attribute("type").unwrap_or("string"), value))
+    }).collect()
+}
+
+fn xml_tileset(node: roxmltree::Node<'_, '_>, first_gid: u32, base: Option<&Path>) -> Tileset {
+    let image = child(node, "image");
+    let tiles = node.children().filter(|n| n.has_tag_name("tile")).map(|t| {
+        let animation = child(t, "animation").map(|a| a.children()
+            .filter(|f| f.has_tag_name("frame"))
+            .map(|f| AnimationFrame {
+                tile: attr_or(f, "tileid", 0),
+                duration: attr_or(f, "duration", 0u32) as f32 / 1000.0,
+            })
+            .collect())
+            .unwrap_or_default();
+        (attr_or(t, "id", 0), TileData { class: xml_class(t), animation, properties: xml_properties(t) })
+    }).collect();
+    Tileset {
+        first_gid,
+        name: node.attribute("name").unwrap_or("").to_string(),
+        tile_size: UVec2::new(attr_or(node, "tilewidth", 0), attr_or(node, "tileheight", 0)),
+        columns: attr_or(node, "columns", 0),
+        tile_count: attr_or(node, "tilecount", 0),
+        spacing: attr_or(node, "spacing", 0),
+        margin: attr_or(node, "margin", 0),
+        image: image.and_then(|i| i.attribute("source")).map(|s| resolve(base, s)),
+        image_size: image.map(|i| UVec2::new(attr_or(i, "width", 0), attr_or(i, "height", 0))).unwrap_or_default(),
+        tiles,
+    }
+}
+
+fn xml_points(points: &str) -> Vec<Vec2> {
+    points.split_whitespace().filter_map(|pair| {
+        let (x, y) = pair.split_once(',')?;
+        Some(Vec2::new(x.parse().ok()?, y.parse().ok()?))
+    }).collect()
+}
+
+fn xml_object(node: roxmltree::Node<'_, '_>) -> MapObject {
+    let shape = if let Some(gid) = attr(node, "gid") {
+        ObjectShape::Tile(gid)
+    } else if let Some(p) = child(node, "polygon") {
+        ObjectShape::Polygon(xml_points(p.attribute("points").unwrap_or("")))
+    } else if let Some(p) = child(node, "polyline") {
+        ObjectShape::Polyline(xml_points(p.attribute("points").unwrap_or("")))
+    } else if let Some(t) = child(node, "text") {
+        ObjectShape::Text(t.text().unwrap_or("").to_string())
+    } else if child(node, "ellipse").is_some() {
+        ObjectShape::Ellipse
+    } else if child(node, "point").is_some() {
+        ObjectShape::Point
+    } else {
+        ObjectShape::Rectangle
+    };
+    MapObject {
+        id: attr_or(node, "id", 0),
+        name: node.attribute("name").unwrap_or("").to_string(),
+        class: xml_class(node),
+        position: Vec2::new(attr_or(node, "x", 0.0), attr_or(node, "y", 0.0)),
+        size: Vec2::new(attr_or(node, "width", 0.0), attr_or(node, "height", 0.0)),
+        rotation: attr_or(node, "rotation", 0.0),
+        visible: attr_or(node, "visible", 1u8) != 0,
+        shape,
+        properties: xml_properties(node),
+    }
+}
+
+fn xml_layer(node: roxmltree::Node<'_, '_>, offset: Vec2, opacity: f32, visible: bool, out: &mut Vec<Layer>) -> Result<(), TiledError> {
+    let offset = offset + Vec2::new(attr_or(node, "offsetx", 0.0), attr_or(node, "offsety", 0.0));
+    let opacity = opacity * attr_or(node, "opacity", 1.0);
+    let visible = visible && attr_or(node, "visible", 1u8) != 0;
+    let name = node.attribute("name").unwrap_or("").to_string();
+    match node.tag_name().name() {
+        "layer" => {
+            let size = UVec2::new(attr_or(node, "width", 0), attr_or(node, "height", 0));
+            let Some(data) = child(node, "data") else {
+                return invalid(format!("layer `{}` has no data", name));
+            };
+            let tiles = match data.attribute("encoding") {
+                // eski XML formatı: her tile ayrı eleman
+                None => data.children().filter(|n| n.has_tag_name("tile")).map(|t| attr_or(t, "gid", 0)).collect(),
+                encoding => decode_tiles(encoding, data.attribute("compression"), data.text().unwrap_or(""))?,
+            };
+            out.push(tile_layer(name, size, tiles, offset, opacity, visible, xml_properties(node))?);
+        }
+        "objectgroup" => out.push(Layer::Objects(ObjectLayer {
+            name,
+            objects: node.children().filter(|n| n.has_tag_name("object")).map(xml_object).collect(),
+            offset,
+            opacity,
+            visible,
+            properties: xml_properties(node),
+        })),
+        "group" => {
+            for child in node.children().filter(|n| n.is_element()) {
+                if matches!(child.tag_name().name(), "layer" | "objectgroup" | "group") {
+                    xml_layer(child, offset, opacity, visible, out)?;
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const JSON_MAP: &str = r##"{
+        "width": 3, "height": 2, "tilewidth": 16, "tileheight": 16,
+        "orientation": "orthogonal", "infinite": false, "backgroundcolor": "#ff336699",
+        "tilesets": [{
+            "firstgid": 1, "name": "terrain", "tilewidth": 16, "tileheight": 16,
+            "columns": 4, "tilecount": 8, "image": "terrain.png", "imagewidth": 64, "imageheight": 32,
+            "tiles": [{ "id": 2, "animation": [{ "tileid": 2, "duration": 100 }, { "tileid": 3, "duration": 300 }] }]
+        }],
+        "layers": [
+            { "type": "group", "name": "ground", "offsetx": 4, "opacity": 0.5, "layers": [
+                { "type": "tilelayer", "name": "base", "width": 3, "height": 2, "data": [1, 2, 3, 0, 2147483649, 4] }
+            ]},
+            { "type": "objectgroup", "name": "spawns", "objects": [
+                { "id": 7, "name": "player", "type": "spawn", "x": 24, "y": 8, "point": true,
+                  "properties": [{ "name": "health", "type": "int", "value": 3 }] },
+                { "id": 8, "name": "zone", "x": 0, "y": 0, "polygon": [{ "x": 0, "y": 0 }, { "x": 16, "y": 0 }, { "x": 0, "y": 16 }] }
+            ]}
+        ]
+    }"##;
+
+    #[test]
+    fn json_and_xml_agree(){
+        // aynı harita XML'de, veri zlib + base64
+        let tiles: Vec<u8> = [1u32, 2, 3, 0, 0x8000_0001, 4].iter().flat_map(|t| t.to_le_bytes()).collect();
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&tiles).unwrap();
+        let data = base64::engine::general_purpose::STANDARD.encode(encoder.finish().unwrap());
+        let xml = format!(r##"<?xml version="1.0" encoding="UTF-8"?>
+            <map version="1.10" orientation="orthogonal" width="3" height="2" tilewidth="16" tileheight="16" infinite="0" backgroundcolor="#ff336699">
+              <tileset firstgid="1" name="terrain" tilewidth="16" tileheight="16" tilecount="8" columns="4">
+                <image source="terrain.png" width="64" height="32"/>
+                <tile id="2"><animation><frame tileid="2" duration="100"/><frame tileid="3" duration="300"/></animation></tile>
+              </tileset>
+              <group name="ground" offsetx="4" opacity="0.5">
+                <layer name="base" width="3" height="2"><data encoding="base64" compression="zlib">
+                  {}
+                </data></layer>
+              </group>
+              <objectgroup name="spawns">
+                <object id="7" name="player" type="spawn" x="24" y="8">
+                  <properties><property name="health" type="int" value="3"/></properties>
+                  <point/>
+                </object>
+                <object id="8" name="zone" x="0" y="0"><polygon points="0,0 16,0 0,16"/></object>
+              </objectgroup>
+            </map>"##, data);
+
+        let base = Path::new("maps");
+        let json = TiledMap::from_json(JSON_MAP, Some(base)).unwrap();
+        let xml = TiledMap::from_xml(&xml, Some(base)).unwrap();
+        assert_eq!(json, xml);
+
+        let Some(Layer::Tiles(layer)) = json.layer("base") else { panic!("no tile layer") };
+        assert_eq!(layer.offset, Vec2::new(4.0, 0.0));
+        assert_eq!(layer.opacity, 0.5);
+        assert_eq!(layer.tile(1, 1), 0x8000_0001);
+        assert_eq!(json.tileset_for(layer.tile(1, 1)).unwrap().0, 0);
+        assert_eq!(json.tilesets[0].image.as_deref(), Some(Path::new("maps/terrain.png")));
+        assert_eq!(json.background, Some([0x33 as f32 / 255.0, 0.4, 0.6, 1.0]));
+
+        let tileset = &json.tilesets[0];
+        assert_eq!(tileset.animated_tile(2, 0.05), 2);
+        assert_eq!(tileset.animated_tile(2, 0.2), 3);
+        assert_eq!(tileset.animated_tile(2, 0.45), 2);
+
+        let player = json.object("player").unwrap();
+        assert_eq!(player.shape, ObjectShape::Point);
+        assert_eq!(player.class, "spawn");
+        assert_eq!(player.properties["health"], PropertyValue::Int(3));
+        assert_eq!(json.objects().count(), 2);
+    }
+}
@@ -0,0 +1,572 @@
+// src/engine/renderer/tilemap.rs
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use glam::{Mat4, UVec2, Vec2, Vec3};
+use wgpu::{Device, Queue};
+
+use crate::engine::renderer::gltf_loader::ImageData;
+use crate::engine::renderer::mesh::VertexType;
+use crate::engine::renderer::pipeline_cache::{BlendMode, PipelineCache, PipelineKey, ShaderVariant};
+use crate::engine::renderer::sprite::{Rect, SpriteBatch, SpriteVertex, SPRITE_SHADER, SPRITE_SHADER_SOURCE};
+use crate::engine::renderer::stats;
+use crate::engine::renderer::targets::TargetState;
+use crate::engine::renderer::texture::Texture;
+use crate::engine::renderer::tiled::{
+    Layer, TiledError, TiledMap, Tileset, FLIP_DIAGONAL, FLIP_HORIZONTAL, FLIP_VERTICAL, GID_MASK,
+};
+
+/// Chunks are CHUNK_SIZE x CHUNK_SIZE tiles of one layer
+pub const CHUNK_SIZE: u32 = 16;
+const CHUNK_QUADS: u32 = CHUNK_SIZE * CHUNK_SIZE;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct TilemapUniform {
+    view_proj: [[f32; 4]; 4],
+}
+
+/// tiles of one tileset inside a chunk, one draw call
+struct ChunkPart {
+    tileset: usize,
+    buffer: wgpu::Buffer,
+    capacity: u32,
+    quads: u32,
+}
+
+struct Chunk {
+    layer: usize,
+    /// first tile of the chunk
+    origin: UVec2,
+    /// map pixels, grown for tiles bigger than the grid
+    bounds: Rect,
+    parts: Vec<ChunkPart>,
+    animated: bool,
+    dirty: bool,
+}
+
+/// A Tiled map on the GPU. Tile layers are cut into chunks whose vertex buffers are only
+/// rebuilt when they are visible and something changed (a tile, an animation frame).
+pub struct Tilemap {
+    pub map: TiledMap,
+    /// world position of the top left corner of the map
+    pub position: Vec2,
+    /// world units per map pixel
+    pub scale: f32,
+    pub visible: bool,
+    time: f32,
+    textures: Vec<Option<Arc<Texture>>>,
+    texture_bind_groups: Vec<Option<wgpu::BindGroup>>,
+    uniform_buffer: wgpu::Buffer,
+    frame_bind_group: wgpu::BindGroup,
+    chunks: Vec<Chunk>,
+    /// first chunk and chunk grid of every layer, object layers have none
+    layer_chunks: Vec<(usize, UVec2)>,
+    visible_chunks: Vec<usize>,
+    /// current frame of every animated gid
+    animation_frames: HashMap<u32, u32>,
+}
+
+impl Tilemap {
+    /// Loads the tileset images of `map` with nearest filtering
+    pub fn new(device: &Device, queue: &Queue, cache: &mut PipelineCache, map: TiledMap) -> Result<Self, TiledError> {
+        let mut textures = Vec::with_capacity(map.tilesets.len());
+        for tileset in &map.tilesets {
+            let texture = match &tileset.image {
+                Some(path) => Some(Arc::new(load_tileset_texture(device, queue, path)?)),
+                // image collection tileset'leri desteklenmiyor
+                None => None,
+            };
+            textures.push(texture);
+        }
+        Ok(Self::from_textures(device, cache, map, textures))
+    }
+
+    /// Uses already loaded textures, one per tileset in `map.tilesets` order
+    pub fn from_textures(device: &Device, cache: &mut PipelineCache, map: TiledMap, textures: Vec<Option<Arc<Texture>>>) -> Self {
+        let texture_layout = cache.bind_group_layout(device, &SpriteBatch::texture_entries());
+        let texture_bind_groups = textures.iter().map(|texture| texture.as_ref().map(|texture| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("tilemap_texture_bind_group"),
+                layout: &texture_layout,
+                entries: &[
+                    wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&texture.view) },
+                    wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&texture.sampler) },
+                ],
+            })
+        })).collect();
+
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Tilemap Uniform Buffer"),
+            size: std::mem::size_of::<TilemapUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let frame_layout = cache.bind_group_layout(device, &SpriteBatch::frame_entries());
+        let frame_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("tilemap_frame_bind_group"),
+            layout: &frame_layout,
+            entries: &[wgpu::BindGroupEntry { binding: 0, resource: uniform_buffer.as_entire_binding() }],
+        });
+
+        let mut tilemap = Self {
+            map,
+            position: Vec2::ZERO,
+            scale: 1.0,
+            visible: true,
+            time: 0.0,
+            textures,
+            texture_bind_groups,
+            uniform_buffer,
+            frame_bind_group,
+            chunks: Vec::new(),
+            layer_chunks: Vec::new(),
+            visible_chunks: Vec::new(),
+            animation_frames: HashMap::new(),
+        };
+        tilemap.create_chunks();
+        tilemap.update_animations();
+        tilemap
+    }
+
+    /// Loads the tileset images again on another device, position and animation time are kept
+    pub fn reload(&self, device: &Device, queue: &Queue, cache: &mut PipelineCache) -> Result<Self, TiledError> {
+        let mut tilemap = Self::new(device, queue, cache, self.map.clone())?;
+        tilemap.position = self.position;
+        tilemap.scale = self.scale;
+        tilemap.visible = self.visible;
+        tilemap.time = self.time;
+        tilemap.update_animations();
+        Ok(tilemap)
+    }
+
+    pub fn with_position(mut self, position: Vec2) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    fn create_chunks(&mut self) {
+        let tile = self.map.tile_size;
+        // haritanın ızgarasından büyük tile'lar sağa ve yukarı taşıyor
+        let largest = self.map.tilesets.iter().fold(tile, |size, t| size.max(t.tile_size));
+        let overflow = (largest - tile).as_vec2();
+
+        self.chunks.clear();
+        self.layer_chunks.clear();
+        for (index, layer) in self.map.layers.iter().enumerate() {
+            let Layer::Tiles(layer) = layer else {
+                self.layer_chunks.push((self.chunks.len(), UVec2::ZERO));
+                continue;
+            };
+            let grid = (layer.size + CHUNK_SIZE - 1) / CHUNK_SIZE;
+            self.layer_chunks.push((self.chunks.len(), grid));
+            for cy in 0..grid.y {
+                for cx in 0..grid.x {
+                    let origin = UVec2::new(cx, cy) * CHUNK_SIZE;
+                    let end = (origin + CHUNK_SIZE).min(layer.size);
+                    let min = (origin * tile).as_vec2() + layer.offset;
+                    let max = (end * tile).as_vec2() + layer.offset;
+                    self.chunks.push(Chunk {
+                        layer: index,
+                        origin,
+                        bounds: Rect::new(min - Vec2::new(0.0, overflow.y), max + Vec2::new(overflow.x, 0.0)),
+                        parts: Vec::new(),
+                        animated: false,
+                        dirty: true,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Advances tile animations by `dt` seconds
+    pub fn update(&mut self, dt: f32) {
+        self.time += dt;
+        if self.update_animations() {
+            for chunk in self.chunks.iter_mut().filter(|c| c.animated) {
+                chunk.dirty = true;
+            }
+        }
+    }
+
+    /// returns true when a frame changed
+    fn update_animations(&mut self) -> bool {
+        let mut changed = false;
+        for tileset in &self.map.tilesets {
+            for (local, data) in &tileset.tiles {
+                if data.animation.is_empty() {
+                    continue;
+                }
+                let frame = tileset.animated_tile(*local, self.time);
+                if self.animation_frames.insert(tileset.first_gid + local, frame) != Some(frame) {
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Changes one tile, `gid` may carry flip flags and 0 clears the cell
+    pub fn set_tile(&mut self, layer: usize, x: u32, y: u32, gid: u32) {
+        let Some(Layer::Tiles(tiles)) = self.map.layers.get_mut(layer) else {
+            return;
+        };
+        if x >= tiles.size.x || y >= tiles.size.y {
+            return;
+        }
+        tiles.tiles[(y * tiles.size.x + x) as usize] = gid;
+        let (start, grid) = self.layer_chunks[layer];
+        let index = start + ((y / CHUNK_SIZE) * grid.x + x / CHUNK_SIZE) as usize;
+        self.chunks[index].dirty = true;
+    }
+
+    /// Rebuilds every chunk, needed after editing `map` directly
+    pub fn invalidate(&mut self) {
+        self.create_chunks();
+    }
+
+    /// world position of a point in map pixels
+    pub fn map_to_world(&self, point: Vec2) -> Vec2 {
+        self.position + Vec2::new(point.x, -point.y) * self.scale
+    }
+
+    /// tile under a world position, `None` outside the map
+    pub fn world_to_tile(&self, world: Vec2) -> Option<UVec2> {
+        let offset = (world - self.position) / self.scale;
+        let tile = (Vec2::new(offset.x, -offset.y) / self.map.tile_size.as_vec2()).floor();
+        if tile.x < 0.0 || tile.y < 0.0 {
+            return None;
+        }
+        let tile = tile.as_uvec2();
+        (tile.x < self.map.size.x && tile.y < self.map.size.y).then_some(tile)
+    }
+
+    /// map pixels to world, y points down in Tiled and up in the world
+    pub fn model(&self) -> Mat4 {
+        Mat4::from_translation(self.position.extend(0.0)) * Mat4::from_scale(Vec3::new(self.scale, -self.scale, 1.0))
+    }
+
+    pub fn visible_chunk_count(&self) -> usize {
+        self.visible_chunks.len()
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Finds the visible chunks and rebuilds the dirty ones among them
+    pub fn prepare(&mut self, device: &Device, queue: &Queue, view_proj: Mat4) {
+        let view_proj = view_proj * self.model();
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&TilemapUniform { view_proj: view_proj.to_cols_array_2d() }));
+        stats::upload(std::mem::size_of::<TilemapUniform>());
+
+        self.visible_chunks.clear();
+        if !self.visible {
+            return;
+        }
+        // görünür alan bulunamazsa (ufka bakan kamera) hepsi çiziliyor
+        let view = visible_rect(view_proj);
+        for index in 0..self.chunks.len() {
+            let chunk = &self.chunks[index];
+            let Layer::Tiles(layer) = &self.map.layers[chunk.layer] else {
+                continue;
+            };
+            if !layer.visible || view.is_some_and(|v| !overlaps(&v, &chunk.bounds)) {
+                continue;
+            }
+            if chunk.dirty {
+                self.rebuild_chunk(device, queue, index);
+            }
+            if !self.chunks[index].parts.is_empty() {
+                self.visible_chunks.push(index);
+            }
+        }
+    }
+
+    fn rebuild_chunk(&mut self, device: &Device, queue: &Queue, index: usize) {
+        let chunk = &self.chunks[index];
+        let Layer::Tiles(layer) = &self.map.layers[chunk.layer] else {
+            return;
+        };
+        let color = [1.0, 1.0, 1.0, layer.opacity];
+        let tile = self.map.tile_size;
+        let mut quads: Vec<Vec<SpriteVertex>> = vec![Vec::new(); self.map.tilesets.len()];
+        let mut animated = false;
+
+        let end = (chunk.origin + CHUNK_SIZE).min(layer.size);
+        for y in chunk.origin.y..end.y {
+            for x in chunk.origin.x..end.x {
+                let gid = layer.tile(x, y);
+                let Some((ts, tileset)) = self.map.tileset_for(gid) else {
+                    continue;
+                };
+                let Some(texture) = &self.textures[ts] else {
+                    continue;
+                };
+                let frame = self.animation_frames.get(&(gid & GID_MASK));
+                animated |= frame.is_some();
+                let local = frame.copied().unwrap_or((gid & GID_MASK) - tileset.first_gid);
+                // Tiled büyük tile'ları hücrenin sol alt köşesine yerleştiriyor
+                let bottom_left = Vec2::new((x * tile.x) as f32, ((y + 1) * tile.y) as f32) + layer.offset;
+                let size = tileset.tile_size.as_vec2();
+                let rect = Rect::new(bottom_left - Vec2::new(0.0, size.y), bottom_left + Vec2::new(size.x, 0.0));
+                let uv = tile_uv(tileset, local, Vec2::new(texture.size.0 as f32, texture.size.1 as f32));
+                quads[ts].extend(tile_quad(rect, uv, gid, color));
+            }
+        }
+
+        let chunk = &mut self.chunks[index];
+        let mut old_parts = std::mem::take(&mut chunk.parts);
+        for (tileset, vertices) in quads.into_iter().enumerate().filter(|(_, v)| !v.is_empty()) {
+            let count = (vertices.len() / 4) as u32;
+            // eski buffer yetiyorsa tekrar kullan
+            let mut part = match old_parts.iter().position(|p| p.tileset == tileset && p.capacity >= count) {
+                Some(i) => old_parts.swap_remove(i),
+                None => {
+                    let capacity = count.next_power_of_two().min(CHUNK_QUADS);
+                    ChunkPart {
+                        tileset,
+                        buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                            label: Some("Tilemap Chunk Buffer"),
+                            size: (capacity * 4) as u64 * std::mem::size_of::<SpriteVertex>() as u64,
+                            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                            mapped_at_creation: false,
+                        }),
+                        capacity,
+                        quads: 0,
+                    }
+                }
+            };
+            queue.write_buffer(&part.buffer, 0, bytemuck::cast_slice(&vertices));
+            stats::upload(std::mem::size_of_val(vertices.as_slice()));
+            part.quads = count;
+            chunk.parts.push(part);
+        }
+        chunk.animated = animated;
+        chunk.dirty = false;
+    }
+
+    fn draw(&self, render_pass: &mut wgpu::RenderPass<'_>) {
+        if self.visible_chunks.is_empty() {
+            return;
+        }
+        render_pass.set_bind_group(0, &self.frame_bind_group, &[]);
+        for chunk in self.visible_chunks.iter().map(|i| &self.chunks[*i]) {
+            for part in &chunk.parts {
+                let Some(bind_group) = &self.texture_bind_groups[part.tileset] else {
+                    continue;
+                };
+                render_pass.set_bind_group(1, bind_group, &[]);
+                render_pass.set_vertex_buffer(0, part.buffer.slice(..));
+                render_pass.draw_indexed(0..part.quads * 6, 0, 0..1);
+                stats::draw(part.quads as u64 * 2);
+            }
+        }
+    }
+}
+
+fn load_tileset_texture(device: &Device, queue: &Queue, path: &Path) -> Result<Texture, TiledError> {
+    let image = ImageData::load(path)?;
+    let mut texture = Texture::from_image(device, queue, &image, true, "Tileset Texture");
+    // pixel art için yakın filtre, mip'ler komşu tile'lara taşmasın
+    texture.sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some("Tileset Sampler"),
+        mag_filter: wgpu::FilterMode::Nearest,
+        min_filter: wgpu::FilterMode::Nearest,
+        mipmap_filter: wgpu::FilterMode::Nearest,
+        lod_max_clamp: 0.0,
+        ..Default::default()
+    });
+    Ok(texture)
+}
+
+/// uv rectangle of a local tile id
+fn tile_uv(tileset: &Tileset, local: u32, image_size: Vec2) -> Rect {
+    let columns = tileset.columns.max(1);
+    let (col, row) = (local % columns, local / columns);
+    let size = tileset.tile_size;
+    let min = UVec2::new(
+        tileset.margin + col * (size.x + tileset.spacing),
+        tileset.margin + row * (size.y + tileset.spacing),
+    ).as_vec2();
+    Rect::new(min / image_size, (min + size.as_vec2()) / image_size)
+}
+
+/// quad with the Tiled flip flags of `gid` applied, corners top left, top right, bottom right, bottom left
+fn tile_quad(rect: Rect, uv: Rect, gid: u32, color: [f32; 4]) -> [SpriteVertex; 4] {
+    let corners = [rect.min, Vec2::new(rect.max.x, rect.min.y), rect.max, Vec2::new(rect.min.x, rect.max.y)];
+    let mut uvs = [uv.min, Vec2::new(uv.max.x, uv.min.y), uv.max, Vec2::new(uv.min.x, uv.max.y)];
+    // sıra önemli: önce köşegen, sonra yatay ve dikey
+    if gid & FLIP_DIAGONAL != 0 {
+        uvs.swap(1, 3);
+    }
+    if gid & FLIP_HORIZONTAL != 0 {
+        uvs.swap(0, 1);
+        uvs.swap(2, 3);
+    }
+    if gid & FLIP_VERTICAL != 0 {
+        uvs.swap(0, 3);
+        uvs.swap(1, 2);
+    }
+    std::array::from_fn(|i| SpriteVertex { position: corners[i].to_array(), uv: uvs[i].to_array(), color })
+}
+
+/// Area of the z = 0 plane (map pixels) covered by the screen, `None` when the view
+/// doesn't hit the plane in front of the camera.
+fn visible_rect(view_proj: Mat4) -> Option<Rect> {
+    let inverse = view_proj.inverse();
+    let mut min = Vec2::splat(f32::INFINITY);
+    let mut max = Vec2::splat(f32::NEG_INFINITY);
+    for ndc in [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0)] {
+        let near = inverse.project_point3(ndc.extend(0.0));
+        let far = inverse.project_point3(ndc.extend(1.0));
+        let dz = far.z - near.z;
+        let t = if dz.abs() < 1e-6 {
+            // düzleme paralel ışın, ortografik kamera düzlemin üstündeyse yine de geçerli
+            if near.z.abs() > 1e-3 {
+                return None;
+            }
+            0.0
+        } else {
+            -near.z / dz
+        };
+        if t < 0.0 || !t.is_finite() {
+            return None;
+        }
+        let point = (near + (far - near) * t).truncate();
+        min = min.min(point);
+        max = max.max(point);
+    }
+    Some(Rect::new(min, max))
+}
+
+fn overlaps(a: &Rect, b: &Rect) -> bool {
+    a.min.x <= b.max.x && b.min.x <= a.max.x && a.min.y <= b.max.y && b.min.y <= a.max.y
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TilemapId(pub usize);
+
+/// Owns the tilemaps drawn by `SurfaceManager`, behind the 3D scene in the main pass.
+/// Animations advance through `update`, called with the frame time by the game loop.
+pub struct TilemapRenderer {
+    maps: Vec<Option<Tilemap>>,
+    /// bütün chunk'lar aynı indeksleri kullanıyor
+    index_buffer: wgpu::Buffer,
+}
+
+impl TilemapRenderer {
+    pub fn new(device: &Device, cache: &mut PipelineCache) -> Self {
+        cache.register_shader(SPRITE_SHADER, SPRITE_SHADER_SOURCE);
+        let indices: Vec<u16> = (0..CHUNK_QUADS as u16)
+            .flat_map(|q| [0, 1, 2, 2, 3, 0].map(|i| q * 4 + i))
+            .collect();
+        let index_buffer = wgpu::util::DeviceExt::create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Tilemap Index Buffer"),
+            contents: bytemuck::cast_slice(&indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+        Self { maps: Vec::new(), index_buffer }
+    }
+
+    /// sprite shader in the main pass, alpha blended and without depth test or writes
+    pub fn pipeline_key(target: &TargetState) -> PipelineKey {
+        SpriteBatch::world_pipeline_key(target)
+    }
+
+    pub fn add(&mut self, tilemap: Tilemap) -> TilemapId {
+        match self.maps.iter().position(|m| m.is_none()) {
+            Some(index) => {
+                self.maps[index] = Some(tilemap);
+                TilemapId(index)
+            }
+            None => {
+                self.maps.push(Some(tilemap));
+                TilemapId(self.maps.len() - 1)
+            }
+        }
+    }
+
+    /// Removes every map, the slot of a `TilemapId` is its index
+    pub fn take_all(&mut self) -> Vec<Option<Tilemap>> {
+        std::mem::take(&mut self.maps)
+    }
+
+    /// Replaces every map keeping the ids of `take_all`
+    pub fn set_all(&mut self, maps: Vec<Option<Tilemap>>) {
+        self.maps = maps;
+    }
+
+    pub fn remove(&mut self, id: TilemapId) -> Option<Tilemap> {
+        self.maps.get_mut(id.0)?.take()
+    }
+
+    pub fn get(&self, id: TilemapId) -> Option<&Tilemap> {
+        self.maps.get(id.0)?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, id: TilemapId) -> Option<&mut Tilemap> {
+        self.maps.get_mut(id.0)?.as_mut()
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for map in self.maps.iter_mut().flatten() {
+            map.update(dt);
+        }
+    }
+
+    pub fn prepare(&mut self, device: &Device, queue: &Queue, view_proj: Mat4) {
+        for map in self.maps.iter_mut().flatten() {
+            map.prepare(device, queue, view_proj);
+        }
+    }
+
+    /// chunks drawn last frame, over all maps
+    pub fn visible_chunk_count(&self) -> usize {
+        self.maps.iter().flatten().map(Tilemap::visible_chunk_count).sum()
+    }
+
+    pub fn draw(&self, device: &Device, cache: &mut PipelineCache, target: &TargetState, render_pass: &mut wgpu::RenderPass<'_>) {
+        if self.visible_chunk_count() == 0 {
+            return;
+        }
+        let pipeline = cache.pipeline(device, &Self::pipeline_key(target));
+        render_pass.set_pipeline(&pipeline);
+        stats::pipeline();
+        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        for map in self.maps.iter().flatten() {
+            map.draw(render_pass);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flips_and_visible_area(){
+        let rect = Rect::new(Vec2::ZERO, Vec2::ONE);
+        let uv = Rect::new(Vec2::ZERO, Vec2::ONE);
+        let uvs = |gid| tile_quad(rect, uv, gid, [1.0; 4]).map(|v| v.uv);
+        assert_eq!(uvs(1)[0], [0.0, 0.0]);
+        assert_eq!(uvs(1 | FLIP_HORIZONTAL)[0], [1.0, 0.0]);
+        assert_eq!(uvs(1 | FLIP_VERTICAL)[0], [0.0, 1.0]);
+        // köşegen + yatay = saat yönünde 90 derece
+        assert_eq!(uvs(1 | FLIP_DIAGONAL | FLIP_HORIZONTAL), [[0.0, 1.0], [0.0, 0.0], [1.0, 0.0], [1.0, 1.0]]);
+
+        // 100x50 piksellik ortografik görüntü, y aşağı
+        let view_proj = Mat4::orthographic_rh(10.0, 110.0, 60.0, 10.0, -1.0, 1.0);
+        let view = visible_rect(view_proj).unwrap();
+        assert!((view.min - Vec2::new(10.0, 10.0)).length() < 1e-3);
+        assert!((view.max - Vec2::new(110.0, 60.0)).length() < 1e-3);
+        assert!(!overlaps(&view, &Rect::new(Vec2::new(120.0, 0.0), Vec2::new(130.0, 10.0))));
+    }
+}
@@ -0,0 +1,456 @@
+// src/engine/scene/mod.rs
+//
+// Scene graph. Entities form a tree: each has a local transform relative to its parent and a
+// global one computed by `update`, a visibility flag that hides the whole subtree, and
+// components of any type. The engine updates the scene every tick and draws the `Sprite`,
+// `MeshInstance` and `PbrModel` components of visible entities with their global transforms,
+// sprites in world space with the camera like the meshes.
+// Scenes and prefabs are saved as `SceneData` with the components registered for it.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+pub mod prefab;
+pub mod render;
+pub mod serialize;
+pub mod transform;
+pub use prefab::{Override, Overrides};
+pub use render::{LostResources, MeshInstance};
+pub use serialize::{AssetRef, ComponentRegistry, EntityData, SceneComponent, SceneData};
+pub use transform::{GlobalTransform, Transform};
+
+/// Saved as one number, `generation << 32 | index`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(from = "u64", into = "u64")]
+pub struct Entity {
+    index: u32,
+    /// slots are used again, an old id doesn't find the new entity
+    generation: u32,
+}
+
+impl Entity {
+    /// Never alive, what references to entities that weren't saved become
+    pub const DANGLING: Entity = Entity { index: u32::MAX, generation: u32::MAX };
+}
+
+impl From<Entity> for u64 {
+    fn from(entity: Entity) -> u64 {
+        (entity.generation as u64) << 32 | entity.index as u64
+    }
+}
+
+impl From<u64> for Entity {
+    fn from(bits: u64) -> Entity {
+        Entity { index: bits as u32, generation: (bits >> 32) as u32 }
+    }
+}
+
+impl fmt::Display for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+#[derive(Debug)]
+pub enum SceneError {
+    NoEntity(Entity),
+    /// the new parent is the entity itself or one of its children
+    Cycle(Entity),
+    Json(serde_json::Error),
+    /// a saved component whose name isn't registered
+    UnknownComponent(String),
+    /// an override for a path no entity of the prefab has
+    NoPath(String),
+    /// instantiating a prefab without entities
+    EmptyPrefab,
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::NoEntity(e) => write!(f, "no entity {}", e),
+            SceneError::Cycle(e) => write!(f, "entity {} can't be its own ancestor", e),
+            SceneError::Json(e) => write!(f, "json error: {}", e),
+            SceneError::UnknownComponent(name) => write!(f, "unknown component {}", name),
+            SceneError::NoPath(path) => write!(f, "no entity at path \"{}\"", path),
+            SceneError::EmptyPrefab => write!(f, "prefab has no entities"),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<serde_json::Error> for SceneError {
+    fn from(e: serde_json::Error) -> Self {
+        SceneError::Json(e)
+    }
+}
+
+struct Node {
+    name: Option<String>,
+    parent: Option<Entity>,
+    children: Vec<Entity>,
+    local: Transform,
+    global: GlobalTransform,
+    visible: bool,
+    /// visible and every ancestor is
+    visible_in_hierarchy: bool,
+    /// local transform or visibility changed since the last update
+    dirty: bool,
+}
+
+/// Components of one type
+trait Storage: Any {
+    fn remove_entity(&mut self, entity: Entity);
+}
+
+impl<T: 'static> Storage for HashMap<Entity, T> {
+    fn remove_entity(&mut self, entity: Entity) {
+        self.remove(&entity);
+    }
+}
+
+#[derive(Default)]
+pub struct Scene {
+    nodes: Vec<Option<Node>>,
+    generations: Vec<u32>,
+    free: Vec<u32>,
+    /// entities without a parent, in spawn order
+    roots: Vec<Entity>,
+    components: HashMap<TypeId, Box<dyn Storage>>,
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn node(&self, entity: Entity) -> Option<&Node> {
+        if self.generations.get(entity.index as usize) != Some(&entity.generation) {
+            return None;
+        }
+        self.nodes[entity.index as usize].as_ref()
+    }
+
+    fn node_mut(&mut self, entity: Entity) -> Option<&mut Node> {
+        if self.generations.get(entity.index as usize) != Some(&entity.generation) {
+            return None;
+        }
+        self.nodes[entity.index as usize].as_mut()
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.node(entity).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// A new root entity
+    pub fn spawn(&mut self, local: Transform) -> Entity {
+        let node = Node {
+            name: None,
+            parent: None,
+            children: Vec::new(),
+            local,
+            global: GlobalTransform(local.matrix()),
+            visible: true,
+            visible_in_hierarchy: true,
+            dirty: true,
+        };
+        let entity = match self.free.pop() {
+            Some(index) => {
+                self.nodes[index as usize] = Some(node);
+                Entity { index, generation: self.generations[index as usize] }
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.generations.push(0);
+                Entity { index: self.nodes.len() as u32 - 1, generation: 0 }
+            }
+        };
+        self.roots.push(entity);
+        entity
+    }
+
+    pub fn spawn_child(&mut self, parent: Entity, local: Transform) -> Result<Entity, SceneError> {
+        if !self.contains(parent) {
+            return Err(SceneError::NoEntity(parent));
+        }
+        let entity = self.spawn(local);
+        self.set_parent(entity, Some(parent))?;
+        Ok(entity)
+    }
+
+    /// Removes the entity with all of its children and their components, false if it was gone
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        let Some(node) = self.node(entity) else {
+            return false;
+        };
+        match node.parent {
+            Some(parent) => self.node_mut(parent).unwrap().children.retain(|c| *c != entity),
+            None => self.roots.retain(|r| *r != entity),
+        }
+        let mut stack = vec![entity];
+        while let Some(entity) = stack.pop() {
+            let index = entity.index as usize;
+            let node = self.nodes[index].take().unwrap();
+            stack.extend(node.children);
+            self.generations[index] = self.generations[index].wrapping_add(1);
+            self.free.push(entity.index);
+            for storage in self.components.values_mut() {
+                storage.remove_entity(entity);
+            }
+        }
+        true
+    }
+
+    /// Removes every entity
+    pub fn clear(&mut self) {
+        for root in self.roots.clone() {
+            self.despawn(root);
+        }
+    }
+
+    /// Moves `entity` under `parent` (or to the roots), keeping its local transform
+    pub fn set_parent(&mut self, entity: Entity, parent: Option<Entity>) -> Result<(), SceneError> {
+        let old_parent = self.node(entity).ok_or(SceneError::NoEntity(entity))?.parent;
+        if let Some(parent) = parent {
+            if !self.contains(parent) {
+                return Err(SceneError::NoEntity(parent));
+            }
+            // yeni ebeveyn kendisi ya da bir torunu olamaz
+            if self.ancestors(parent).chain([parent]).any(|a| a == entity) {
+                return Err(SceneError::Cycle(entity));
+            }
+        }
+        match old_parent {
+            Some(old) => self.node_mut(old).unwrap().children.retain(|c| *c != entity),
+            None => self.roots.retain(|r| *r != entity),
+        }
+        match parent {
+            Some(parent) => self.node_mut(parent).unwrap().children.push(entity),
+            None => self.roots.push(entity),
+        }
+        let node = self.node_mut(entity).unwrap();
+        node.parent = parent;
+        node.dirty = true;
+        Ok(())
+    }
+
+    /// `set_parent` that keeps the entity where it is in the world, as of the last update
+    pub fn set_parent_in_place(&mut self, entity: Entity, parent: Option<Entity>) -> Result<(), SceneError> {
+        let global = self.global(entity).ok_or(SceneError::NoEntity(entity))?;
+        let parent_global = match parent {
+            Some(p) => self.global(p).ok_or(SceneError::NoEntity(p))?.matrix(),
+            None => glam::Mat4::IDENTITY,
+        };
+        self.set_parent(entity, parent)?;
+        self.set_local(entity, Transform::from_matrix(parent_global.inverse() * global.matrix()));
+        Ok(())
+    }
+
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        self.node(entity)?.parent
+    }
+
+    pub fn children(&self, entity: Entity) -> &[Entity] {
+        self.node(entity).map_or(&[], |n| &n.children)
+    }
+
+    pub fn roots(&self) -> &[Entity] {
+        &self.roots
+    }
+
+    /// Parent, grand parent... up to the root
+    pub fn ancestors(&self, entity: Entity) -> impl Iterator<Item = Entity> + '_ {
+        std::iter::successors(self.parent(entity), |e| self.parent(*e))
+    }
+
+    /// Every entity, parents before their children and siblings in order
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        let mut stack: Vec<Entity> = self.roots.iter().rev().copied().collect();
+        std::iter::from_fn(move || {
+            let entity = stack.pop()?;
+            stack.extend(self.children(entity).iter().rev());
+            Some(entity)
+        })
+    }
+
+    /// The entity and everything under it, in the order of `iter`
+    pub fn descendants(&self, entity: Entity) -> impl Iterator<Item = Entity> + '_ {
+        let mut stack = if self.contains(entity) { vec![entity] } else { Vec::new() };
+        std::iter::from_fn(move || {
+            let entity = stack.pop()?;
+            stack.extend(self.children(entity).iter().rev());
+            Some(entity)
+        })
+    }
+
+    pub fn name(&self, entity: Entity) -> Option<&str> {
+        self.node(entity)?.name.as_deref()
+    }
+
+    pub fn set_name<S: Into<String>>(&mut self, entity: Entity, name: S) {
+        if let Some(node) = self.node_mut(entity) {
+            node.name = Some(name.into());
+        }
+    }
+
+    /// First entity named `name`, in the order of `iter`
+    pub fn find(&self, name: &str) -> Option<Entity> {
+        self.iter().find(|e| self.name(*e) == Some(name))
+    }
+
+    pub fn local(&self, entity: Entity) -> Option<&Transform> {
+        self.node(entity).map(|n| &n.local)
+    }
+
+    /// The global transforms follow on the next `update`
+    pub fn local_mut(&mut self, entity: Entity) -> Option<&mut Transform> {
+        let node = self.node_mut(entity)?;
+        node.dirty = true;
+        Some(&mut node.local)
+    }
+
+    pub fn set_local(&mut self, entity: Entity, local: Transform) {
+        if let Some(transform) = self.local_mut(entity) {
+            *transform = local;
+        }
+    }
+
+    /// As of the last `update`
+    pub fn global(&self, entity: Entity) -> Option<GlobalTransform> {
+        self.node(entity).map(|n| n.global)
+    }
+
+    /// Hides or shows the entity and everything under it
+    pub fn set_visible(&mut self, entity: Entity, visible: bool) {
+        if let Some(node) = self.node_mut(entity) {
+            node.visible = visible;
+            node.dirty = true;
+        }
+    }
+
+    /// the entity's own flag
+    pub fn is_visible(&self, entity: Entity) -> bool {
+        self.node(entity).is_some_and(|n| n.visible)
+    }
+
+    /// false when the entity or one of its ancestors is hidden, as of the last `update`
+    pub fn is_visible_in_hierarchy(&self, entity: Entity) -> bool {
+        self.node(entity).is_some_and(|n| n.visible_in_hierarchy)
+    }
+
+    /// Computes the global transforms and visibility of the subtrees that changed
+    pub fn update(&mut self) {
+        // (varlık, ebeveyn değişti mi, ebeveynin dünya matrisi, ebeveyn görünür mü)
+        let mut stack: Vec<(Entity, bool, glam::Mat4, bool)> = self.roots.iter().map(|r| (*r, false, glam::Mat4::IDENTITY, true)).collect();
+        while let Some((entity, parent_changed, parent_matrix, parent_visible)) = stack.pop() {
+            let node = self.nodes[entity.index as usize].as_mut().unwrap();
+            let changed = parent_changed || node.dirty;
+            if changed {
+                node.global = GlobalTransform(parent_matrix * node.local.matrix());
+                node.visible_in_hierarchy = parent_visible && node.visible;
+                node.dirty = false;
+            }
+            let (matrix, visible) = (node.global.matrix(), node.visible_in_hierarchy);
+            stack.extend(node.children.iter().map(|c| (*c, changed, matrix, visible)));
+        }
+    }
+
+    fn storage<T: 'static>(&self) -> Option<&HashMap<Entity, T>> {
+        let storage: &dyn Any = self.components.get(&TypeId::of::<T>())?.as_ref();
+        storage.downcast_ref()
+    }
+
+    fn storage_mut<T: 'static>(&mut self) -> Option<&mut HashMap<Entity, T>> {
+        let storage: &mut dyn Any = self.components.get_mut(&TypeId::of::<T>())?.as_mut();
+        storage.downcast_mut()
+    }
+
+    /// Adds or replaces the `T` of the entity, the old one is returned. `Err` gives the
+    /// component back when there is no such entity.
+    pub fn insert<T: 'static>(&mut self, entity: Entity, component: T) -> Result<Option<T>, T> {
+        if !self.contains(entity) {
+            return Err(component);
+        }
+        let storage = self.components.entry(TypeId::of::<T>()).or_insert_with(|| Box::new(HashMap::<Entity, T>::new()));
+        let storage: &mut dyn Any = storage.as_mut();
+        Ok(storage.downcast_mut::<HashMap<Entity, T>>().unwrap().insert(entity, component))
+    }
+
+    pub fn remove<T: 'static>(&mut self, entity: Entity) -> Option<T> {
+        self.storage_mut::<T>()?.remove(&entity)
+    }
+
+    pub fn get<T: 'static>(&self, entity: Entity) -> Option<&T> {
+        self.storage::<T>()?.get(&entity)
+    }
+
+    pub fn get_mut<T: 'static>(&mut self, entity: Entity) -> Option<&mut T> {
+        self.storage_mut::<T>()?.get_mut(&entity)
+    }
+
+    pub fn has<T: 'static>(&self, entity: Entity) -> bool {
+        self.get::<T>(entity).is_some()
+    }
+
+    /// Every `T` with its entity, in no particular order
+    pub fn query<T: 'static>(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.storage::<T>().into_iter().flat_map(|s| s.iter().map(|(e, c)| (*e, c)))
+    }
+
+    pub fn query_mut<T: 'static>(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        self.storage_mut::<T>().into_iter().flat_map(|s| s.iter_mut().map(|(e, c)| (*e, c)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::{Vec2, Vec3};
+
+    #[test]
+    fn hierarchy(){
+        let mut scene = Scene::new();
+        let ship = scene.spawn(Transform::from_2d(Vec2::new(10.0, 0.0), std::f32::consts::FRAC_PI_2, Vec2::ONE));
+        let turret = scene.spawn_child(ship, Transform::from_xy(2.0, 0.0)).unwrap();
+        let barrel = scene.spawn_child(turret, Transform::from_xy(1.0, 0.0)).unwrap();
+        scene.insert(barrel, "barrel").unwrap();
+        scene.update();
+        // gemi 90 derece dönük: taret yukarıda
+        assert!(scene.global(turret).unwrap().position_2d().abs_diff_eq(Vec2::new(10.0, 2.0), 1e-5));
+        assert!(scene.global(barrel).unwrap().position_2d().abs_diff_eq(Vec2::new(10.0, 3.0), 1e-5));
+
+        scene.local_mut(ship).unwrap().translate(Vec3::new(0.0, 5.0, 0.0));
+        scene.set_visible(turret, false);
+        scene.update();
+        assert!(scene.global(barrel).unwrap().position_2d().abs_diff_eq(Vec2::new(10.0, 8.0), 1e-5));
+        assert!(scene.is_visible_in_hierarchy(ship) && !scene.is_visible_in_hierarchy(barrel));
+        assert_eq!(scene.set_parent(ship, Some(barrel)).unwrap_err().to_string(), format!("entity {} can't be its own ancestor", ship));
+
+        // dünyadaki yeri değişmeden köke taşınıyor
+        scene.set_parent_in_place(barrel, None).unwrap();
+        scene.update();
+        assert!(scene.global(barrel).unwrap().position_2d().abs_diff_eq(Vec2::new(10.0, 8.0), 1e-4));
+        assert!(scene.is_visible_in_hierarchy(barrel));
+        assert_eq!(scene.iter().collect::<Vec<_>>(), vec![ship, turret, barrel]);
+
+        scene.set_parent(barrel, Some(turret)).unwrap();
+        assert!(scene.despawn(ship));
+        assert!(scene.is_empty() && scene.get::<&str>(barrel).is_none());
+        // eski kimlik yeni varlığı bulmuyor
+        let reused = scene.spawn(Transform::IDENTITY);
+        assert!(!scene.contains(ship) && !scene.contains(barrel) && scene.contains(reused));
+        assert_eq!(scene.query::<&str>().count(), 0);
+    }
+}
@@ -0,0 +1,191 @@
+// src/engine/scene/prefab.rs
+//
+// A prefab is a `SceneData` saved from a subtree. Each instance gets its own entities and can
+// change what was saved: entities are addressed by the names from the prefab root down, and
+// component changes are json merge patches over the saved components.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::engine::scene::{ComponentRegistry, Entity, Scene, SceneData, SceneError, Transform};
+
+/// Changes to one entity of an instance
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Override {
+    /// names from the root down separated by `/`, empty for the root
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transform: Option<Transform>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visible: Option<bool>,
+    /// merged into the saved component (or added), `null` removes the component
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub components: BTreeMap<String, Value>,
+}
+
+impl Override {
+    pub(crate) fn apply(&self, scene: &mut Scene, entity: Entity, components: &mut BTreeMap<String, Value>) {
+        if let Some(transform) = self.transform {
+            scene.set_local(entity, transform);
+        }
+        if let Some(visible) = self.visible {
+            scene.set_visible(entity, visible);
+        }
+        for (name, patch) in &self.components {
+            if patch.is_null() {
+                components.remove(name);
+                continue;
+            }
+            merge(components.entry(name.clone()).or_insert(Value::Null), patch.clone());
+        }
+    }
+}
+
+/// RFC 7386: objects are merged key by key, `null` removes a key, anything else replaces
+fn merge(target: &mut Value, patch: Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(&key);
+            } else {
+                merge(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// Per instance changes, applied in order
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Overrides {
+    pub entries: Vec<Override>,
+}
+
+impl Overrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entry(&mut self, path: &str) -> &mut Override {
+        let index = match self.entries.iter().position(|o| o.path == path) {
+            Some(index) => index,
+            None => {
+                self.entries.push(Override { path: path.to_string(), ..Default::default() });
+                self.entries.len() - 1
+            }
+        };
+        &mut self.entries[index]
+    }
+
+    pub fn with_transform(mut self, path: &str, transform: Transform) -> Self {
+        self.entry(path).transform = Some(transform);
+        self
+    }
+
+    pub fn with_visible(mut self, path: &str, visible: bool) -> Self {
+        self.entry(path).visible = Some(visible);
+        self
+    }
+
+    /// `patch` is merged into the component registered as `name`
+    pub fn with_component(mut self, path: &str, name: &str, patch: Value) -> Self {
+        self.entry(path).components.insert(name.to_string(), patch);
+        self
+    }
+}
+
+impl SceneData {
+    /// Spawns a prefab under `parent` and returns its root, the first saved entity. Every
+    /// instance has new entities and references between them point to the new ones.
+    pub fn instantiate(&self, scene: &mut Scene, registry: &ComponentRegistry, parent: Option<Entity>, overrides: &Overrides) -> Result<Entity, SceneError> {
+        if self.entities.is_empty() {
+            return Err(SceneError::EmptyPrefab);
+        }
+        Ok(self.spawn_with(scene, registry, parent, overrides)?[0])
+    }
+
+    /// Path of every entity for `Override::path`, `None` when it or an ancestor has no name
+    pub(crate) fn paths(&self) -> Vec<Option<String>> {
+        let index: HashMap<Entity, usize> = self.entities.iter().enumerate().map(|(i, e)| (e.id, i)).collect();
+        (0..self.entities.len()).map(|i| self.path(&index, i, 0)).collect()
+    }
+
+    fn path(&self, index: &HashMap<Entity, usize>, i: usize, depth: usize) -> Option<String> {
+        // elle yazılmış dosyalarda döngü olabilir
+        if depth > self.entities.len() {
+            return None;
+        }
+        let data = &self.entities[i];
+        let Some(parent) = data.parent else {
+            return Some(String::new());
+        };
+        let parent_path = self.path(index, *index.get(&parent)?, depth + 1)?;
+        let name = data.name.as_deref()?;
+        Some(if parent_path.is_empty() { name.to_string() } else { format!("{}/{}", parent_path, name) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Health {
+        hp: i32,
+        max: i32,
+    }
+
+    impl crate::engine::scene::SceneComponent for Health {}
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Aim {
+        target: Entity,
+    }
+
+    impl crate::engine::scene::SceneComponent for Aim {
+        fn map_entities(&mut self, map: &dyn Fn(Entity) -> Entity) {
+            self.target = map(self.target);
+        }
+    }
+
+    #[test]
+    fn instances(){
+        let mut registry = ComponentRegistry::new();
+        registry.register::<Health>("Health").register::<Aim>("Aim");
+        let mut scene = Scene::new();
+        let tank = scene.spawn(Transform::from_xy(5.0, 0.0));
+        let turret = scene.spawn_child(tank, Transform::from_xy(0.0, 1.0)).unwrap();
+        scene.set_name(turret, "turret");
+        scene.insert(tank, Health { hp: 100, max: 100 }).unwrap();
+        scene.insert(turret, Aim { target: tank }).unwrap();
+        let prefab = SceneData::save_subtree(&scene, &registry, tank).unwrap();
+
+        let overrides = Overrides::new()
+            .with_transform("", Transform::from_xy(-3.0, 0.0))
+            .with_component("", "Health", json!({ "hp": 40 }))
+            .with_component("turret", "Aim", Value::Null);
+        let a = prefab.instantiate(&mut scene, &registry, None, &Overrides::new()).unwrap();
+        let b = prefab.instantiate(&mut scene, &registry, Some(a), &overrides).unwrap();
+        scene.update();
+        assert_eq!(scene.get::<Health>(b), Some(&Health { hp: 40, max: 100 }));
+        assert!(scene.global(b).unwrap().position_2d().abs_diff_eq(glam::Vec2::new(2.0, 0.0), 1e-5));
+        let turret_a = scene.children(a)[0];
+        let turret_b = *scene.children(b).iter().find(|c| scene.name(**c) == Some("turret")).unwrap();
+        assert_eq!(scene.get::<Aim>(turret_a), Some(&Aim { target: a }));
+        assert!(!scene.has::<Aim>(turret_b));
+
+        let typo = Overrides::new().with_visible("turet", false);
+        assert!(matches!(prefab.instantiate(&mut scene, &registry, None, &typo), Err(SceneError::NoPath(_))));
+        assert_eq!(scene.len(), 6);
+    }
+}
@@ -0,0 +1,165 @@
+// src/engine/scene/render.rs
+
+use std::any::Any;
+use std::collections::HashSet;
+use std::sync::{Arc, Weak};
+
+use glam::{Mat4, Vec2};
+
+use crate::engine::renderer::{Material, Mesh, PbrModel, PbrRenderer, Sprite, SpriteBatch};
+use crate::engine::scene::{GlobalTransform, Scene};
+
+/// A mesh drawn by `PbrRenderer` at the entity's global transform
+#[derive(Clone)]
+pub struct MeshInstance {
+    pub mesh: Arc<Mesh>,
+    pub material: Arc<Material>,
+    /// relative to the entity
+    pub transform: Mat4,
+}
+
+impl MeshInstance {
+    pub fn new(mesh: Arc<Mesh>, material: Arc<Material>) -> Self {
+        Self { mesh, material, transform: Mat4::IDENTITY }
+    }
+
+    pub fn with_transform(mut self, transform: Mat4) -> Self {
+        self.transform = transform;
+        self
+    }
+}
+
+/// `SpriteBatch::draw_sprite` in world space: the corners are taken through `global`, so the
+/// sprite's position, rotation and size are world units relative to the entity. Y points up
+/// like in the scene, the image is not drawn upside down.
+pub fn draw_sprite_at(batch: &mut SpriteBatch, sprite: &Sprite, global: &GlobalTransform) {
+    let pivot = sprite.origin * sprite.size;
+    let (sin, cos) = sprite.rotation.sin_cos();
+    let corners = [Vec2::ZERO, Vec2::new(sprite.size.x, 0.0), sprite.size, Vec2::new(0.0, sprite.size.y)]
+        .map(|c| {
+            // resim satırları aşağı doğru, sahnede y yukarı
+            let p = (c - pivot) * Vec2::new(1.0, -1.0);
+            global.transform_point_2d(sprite.position + Vec2::new(p.x * cos - p.y * sin, p.x * sin + p.y * cos))
+        });
+    batch.draw_quad(&sprite.texture, corners, sprite.uv, sprite.color);
+}
+
+/// GPU resources of scene components that were made on a lost device. Components still
+/// using one aren't drawn until the game replaces them, see `GraphicsEvent::DeviceRecreated`.
+#[derive(Default)]
+pub struct LostResources {
+    addresses: HashSet<usize>,
+    // zayıf referans belleği tutuyor, yeni bir kaynak aynı adrese düşemez
+    pinned: Vec<Weak<dyn Any>>,
+}
+
+fn address<T>(arc: &Arc<T>) -> usize {
+    Arc::as_ptr(arc) as *const () as usize
+}
+
+impl LostResources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add<T: 'static>(&mut self, resource: &Arc<T>) {
+        if self.addresses.insert(address(resource)) {
+            self.pinned.push(Arc::downgrade(resource) as Weak<dyn Any>);
+        }
+    }
+
+    pub fn contains<T>(&self, resource: &Arc<T>) -> bool {
+        self.addresses.contains(&address(resource))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+
+    /// Every resource the drawn components of `scene` use, called when the device is lost
+    pub fn add_scene(&mut self, scene: &Scene) {
+        for entity in scene.iter() {
+            if let Some(sprite) = scene.get::<Sprite>(entity) {
+                self.add(&sprite.texture);
+            }
+            if let Some(instance) = scene.get::<MeshInstance>(entity) {
+                self.add(&instance.mesh);
+                self.add(&instance.material);
+            }
+            if let Some(model) = scene.get::<Arc<PbrModel>>(entity) {
+                self.add(model);
+            }
+        }
+    }
+
+    /// Forgets the resources nobody holds anymore
+    pub fn release_dropped(&mut self) {
+        let addresses = &mut self.addresses;
+        self.pinned.retain(|weak| {
+            let alive = weak.strong_count() > 0;
+            if !alive {
+                addresses.remove(&(weak.as_ptr() as *const () as usize));
+            }
+            alive
+        });
+    }
+}
+
+impl Scene {
+    /// Queues the `Sprite`, `MeshInstance` and `Arc<PbrModel>` components of entities visible
+    /// in the hierarchy, parents before children so children's sprites end up on top.
+    /// `sprites` should be a world space batch like `SurfaceManager::world_sprites`.
+    /// Uses the global transforms of the last `update`.
+    pub fn queue_draws(&self, sprites: &mut SpriteBatch, pbr: &mut PbrRenderer) {
+        self.queue_draws_skipping(sprites, pbr, &LostResources::new());
+    }
+
+    /// `queue_draws` without the components that use a resource in `lost`
+    pub fn queue_draws_skipping(&self, sprites: &mut SpriteBatch, pbr: &mut PbrRenderer, lost: &LostResources) {
+        for entity in self.iter() {
+            if !self.is_visible_in_hierarchy(entity) {
+                continue;
+            }
+            let Some(global) = self.global(entity) else {
+                continue;
+            };
+            if let Some(sprite) = self.get::<Sprite>(entity)
+                && !lost.contains(&sprite.texture)
+            {
+                draw_sprite_at(sprites, sprite, &global);
+            }
+            if let Some(instance) = self.get::<MeshInstance>(entity)
+                && !lost.contains(&instance.mesh)
+                && !lost.contains(&instance.material)
+            {
+                pbr.queue_draw(instance.mesh.clone(), instance.material.clone(), global.matrix() * instance.transform);
+            }
+            if let Some(model) = self.get::<Arc<PbrModel>>(entity)
+                && !lost.contains(model)
+            {
+                model.queue(pbr, global.matrix());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lost_resources_until_dropped(){
+        let mut lost = LostResources::new();
+        let old = Arc::new(1u32);
+        lost.add(&old);
+        lost.add(&old.clone());
+        assert!(lost.contains(&old));
+        assert!(!lost.contains(&Arc::new(1u32)));
+
+        lost.release_dropped();
+        assert!(lost.contains(&old));
+        drop(old);
+        lost.release_dropped();
+        assert!(lost.is_empty());
+    }
+}
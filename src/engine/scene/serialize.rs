@@ -0,0 +1,357 @@
+// src/engine/scene/serialize.rs
+//
+// Scenes as json. Only components registered in a `ComponentRegistry` are saved, under the
+// name they were registered with. Entities are numbered from 0 in hierarchy order when saved
+// and get new ids every time they are spawned; components holding entities fix them up in
+// `SceneComponent::map_entities`. Assets are saved as their paths with `AssetRef`.
+
+use std::any::TypeId;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::engine::assets::{Asset, AssetServer, Handle};
+use crate::engine::scene::{Entity, Overrides, Scene, SceneError, Transform};
+
+/// A component that can be saved in a scene
+pub trait SceneComponent: Serialize + DeserializeOwned + 'static {
+    /// Replaces every `Entity` the component holds with `map(entity)`
+    fn map_entities(&mut self, _map: &dyn Fn(Entity) -> Entity) {}
+
+    /// Starts loading the assets the component refers to, called after it's spawned
+    fn load_assets(&mut self, _assets: &AssetServer) {}
+}
+
+/// An asset saved as its path, loaded when the component is spawned
+pub struct AssetRef<T> {
+    path: String,
+    handle: Option<Handle<T>>,
+}
+
+impl<T: Asset> AssetRef<T> {
+    /// `path` relative to the asset root, nothing is loaded until `load`
+    pub fn new<S: Into<String>>(path: S) -> Self {
+        Self { path: path.into(), handle: None }
+    }
+
+    /// `None` for assets added in code, they have no path
+    pub fn from_handle(assets: &AssetServer, handle: Handle<T>) -> Option<Self> {
+        let path = assets.path(handle.id())?;
+        Some(Self { path: path.to_string_lossy().replace('\\', "/"), handle: Some(handle) })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn handle(&self) -> Option<&Handle<T>> {
+        self.handle.as_ref()
+    }
+
+    pub fn load(&mut self, assets: &AssetServer) -> &Handle<T> {
+        self.handle.get_or_insert_with(|| assets.load(&self.path))
+    }
+}
+
+impl<T> Clone for AssetRef<T> {
+    fn clone(&self) -> Self {
+        Self { path: self.path.clone(), handle: self.handle.clone() }
+    }
+}
+
+impl<T> fmt::Debug for AssetRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AssetRef").field("path", &self.path).field("handle", &self.handle).finish()
+    }
+}
+
+impl<T> Serialize for AssetRef<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.path)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for AssetRef<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|path| Self { path, handle: None })
+    }
+}
+
+impl<T: Asset> SceneComponent for AssetRef<T> {
+    fn load_assets(&mut self, assets: &AssetServer) {
+        self.load(assets);
+    }
+}
+
+type SaveFn = fn(&Scene, Entity, &dyn Fn(Entity) -> Entity) -> Option<Result<Value, serde_json::Error>>;
+type LoadFn = fn(&mut Scene, Entity, Value, &dyn Fn(Entity) -> Entity, Option<&AssetServer>) -> Result<(), serde_json::Error>;
+
+#[derive(Clone)]
+struct Registration {
+    name: String,
+    type_id: TypeId,
+    save: SaveFn,
+    load: LoadFn,
+}
+
+fn save<T: SceneComponent>(scene: &Scene, entity: Entity, map: &dyn Fn(Entity) -> Entity) -> Option<Result<Value, serde_json::Error>> {
+    let component = scene.get::<T>(entity)?;
+    // T'nin Clone olması gerekmesin, kopyası json'dan okunuyor
+    Some(serde_json::to_value(component).and_then(|value| {
+        let mut copy: T = serde_json::from_value(value)?;
+        copy.map_entities(map);
+        serde_json::to_value(copy)
+    }))
+}
+
+fn load<T: SceneComponent>(scene: &mut Scene, entity: Entity, value: Value, map: &dyn Fn(Entity) -> Entity, assets: Option<&AssetServer>) -> Result<(), serde_json::Error> {
+    let mut component: T = serde_json::from_value(value)?;
+    component.map_entities(map);
+    if let Some(assets) = assets {
+        component.load_assets(assets);
+    }
+    let _ = scene.insert(entity, component);
+    Ok(())
+}
+
+/// Component types that are saved, by name. Components of other types stay out of the files.
+#[derive(Clone, Default)]
+pub struct ComponentRegistry {
+    registrations: Vec<Registration>,
+    assets: Option<AssetServer>,
+}
+
+impl ComponentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawned components load their `AssetRef`s from `assets`
+    pub fn with_assets(mut self, assets: AssetServer) -> Self {
+        self.assets = Some(assets);
+        self
+    }
+
+    /// Saves `T` components as `name`. Registering a type or a name again replaces the old one.
+    pub fn register<T: SceneComponent>(&mut self, name: &str) -> &mut Self {
+        let type_id = TypeId::of::<T>();
+        self.registrations.retain(|r| r.type_id != type_id && r.name != name);
+        self.registrations.push(Registration { name: name.to_string(), type_id, save: save::<T>, load: load::<T> });
+        self
+    }
+
+    pub fn name_of<T: 'static>(&self) -> Option<&str> {
+        let type_id = TypeId::of::<T>();
+        self.registrations.iter().find(|r| r.type_id == type_id).map(|r| r.name.as_str())
+    }
+
+    pub fn is_registered<T: 'static>(&self) -> bool {
+        self.name_of::<T>().is_some()
+    }
+
+    fn save_components(&self, scene: &Scene, entity: Entity, map: &dyn Fn(Entity) -> Entity) -> Result<BTreeMap<String, Value>, serde_json::Error> {
+        let mut components = BTreeMap::new();
+        for registration in &self.registrations {
+            if let Some(value) = (registration.save)(scene, entity, map) {
+                components.insert(registration.name.clone(), value?);
+            }
+        }
+        Ok(components)
+    }
+
+    fn load_component(&self, scene: &mut Scene, entity: Entity, name: &str, value: Value, map: &dyn Fn(Entity) -> Entity) -> Result<(), SceneError> {
+        let registration = self.registrations.iter().find(|r| r.name == name).ok_or_else(|| SceneError::UnknownComponent(name.to_string()))?;
+        Ok((registration.load)(scene, entity, value, map, self.assets.as_ref())?)
+    }
+}
+
+/// Saved entities, parents before their children. Also what prefabs are.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SceneData {
+    pub entities: Vec<EntityData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityData {
+    /// only meaningful inside the file
+    pub id: Entity,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// `None` for roots, including the root of a saved subtree
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Entity>,
+    #[serde(default, skip_serializing_if = "is_identity")]
+    pub transform: Transform,
+    #[serde(default = "visible", skip_serializing_if = "is_visible")]
+    pub visible: bool,
+    /// registered name -> component
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub components: BTreeMap<String, Value>,
+}
+
+fn is_identity(transform: &Transform) -> bool {
+    *transform == Transform::IDENTITY
+}
+
+fn visible() -> bool {
+    true
+}
+
+fn is_visible(visible: &bool) -> bool {
+    *visible
+}
+
+impl SceneData {
+    /// Every entity of the scene
+    pub fn save(scene: &Scene, registry: &ComponentRegistry) -> Result<Self, SceneError> {
+        Self::save_entities(scene, registry, scene.iter().collect())
+    }
+
+    /// `root` and everything under it, for prefabs
+    pub fn save_subtree(scene: &Scene, registry: &ComponentRegistry, root: Entity) -> Result<Self, SceneError> {
+        if !scene.contains(root) {
+            return Err(SceneError::NoEntity(root));
+        }
+        Self::save_entities(scene, registry, scene.descendants(root).collect())
+    }
+
+    fn save_entities(scene: &Scene, registry: &ComponentRegistry, entities: Vec<Entity>) -> Result<Self, SceneError> {
+        let ids: HashMap<Entity, Entity> = entities.iter().enumerate().map(|(i, e)| (*e, Entity::from(i as u64))).collect();
+        // kaydedilmeyen varlıklara bağlantılar kopuyor
+        let map = |entity: Entity| ids.get(&entity).copied().unwrap_or(Entity::DANGLING);
+        let entities = entities.iter().map(|&entity| {
+            Ok(EntityData {
+                id: ids[&entity],
+                name: scene.name(entity).map(str::to_string),
+                parent: scene.parent(entity).and_then(|p| ids.get(&p).copied()),
+                transform: *scene.local(entity).unwrap(),
+                visible: scene.is_visible(entity),
+                components: registry.save_components(scene, entity, &map)?,
+            })
+        }).collect::<Result<_, SceneError>>()?;
+        Ok(Self { entities })
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, SceneError> {
+        Ok(serde_json::from_str(text)?)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("scene data is always serializable")
+    }
+
+    /// Adds the entities to `scene` with the saved roots under `parent`, returns the new
+    /// entities in file order. Global transforms follow on the next `Scene::update`.
+    pub fn spawn(&self, scene: &mut Scene, registry: &ComponentRegistry, parent: Option<Entity>) -> Result<Vec<Entity>, SceneError> {
+        self.spawn_with(scene, registry, parent, &Overrides::default())
+    }
+
+    /// `spawn` with `overrides` applied on top of the saved entities. Nothing is added when
+    /// it fails.
+    pub fn spawn_with(&self, scene: &mut Scene, registry: &ComponentRegistry, parent: Option<Entity>, overrides: &Overrides) -> Result<Vec<Entity>, SceneError> {
+        if let Some(parent) = parent
+            && !scene.contains(parent)
+        {
+            return Err(SceneError::NoEntity(parent));
+        }
+        let entities: Vec<Entity> = self.entities.iter().map(|data| scene.spawn(data.transform)).collect();
+        match self.fill(scene, registry, parent, overrides, &entities) {
+            Ok(()) => Ok(entities),
+            Err(e) => {
+                for entity in &entities {
+                    scene.despawn(*entity);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    fn fill(&self, scene: &mut Scene, registry: &ComponentRegistry, parent: Option<Entity>, overrides: &Overrides, entities: &[Entity]) -> Result<(), SceneError> {
+        let ids: HashMap<Entity, Entity> = self.entities.iter().zip(entities).map(|(data, e)| (data.id, *e)).collect();
+        let map = |entity: Entity| ids.get(&entity).copied().unwrap_or(Entity::DANGLING);
+        let paths = self.paths();
+        if let Some(missing) = overrides.entries.iter().find(|o| !paths.iter().any(|p| p.as_deref() == Some(o.path.as_str()))) {
+            return Err(SceneError::NoPath(missing.path.clone()));
+        }
+
+        for ((data, &entity), path) in self.entities.iter().zip(entities).zip(&paths) {
+            let parent = match data.parent {
+                Some(p) => Some(*ids.get(&p).ok_or(SceneError::NoEntity(p))?),
+                None => parent,
+            };
+            if parent.is_some() {
+                scene.set_parent(entity, parent)?;
+            }
+            if let Some(name) = &data.name {
+                scene.set_name(entity, name.clone());
+            }
+            scene.set_visible(entity, data.visible);
+            let mut components = data.components.clone();
+            for o in overrides.entries.iter().filter(|o| Some(o.path.as_str()) == path.as_deref()) {
+                o.apply(scene, entity, &mut components);
+            }
+            for (name, value) in components {
+                registry.load_component(scene, entity, &name, value, &map)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::renderer::gltf_loader::ImageData;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Icon {
+        image: AssetRef<ImageData>,
+        size: f32,
+    }
+
+    impl SceneComponent for Icon {
+        fn load_assets(&mut self, assets: &AssetServer) {
+            self.image.load(assets);
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Score(u32);
+
+    impl SceneComponent for Score {}
+
+    #[test]
+    fn round_trip(){
+        let mut registry = ComponentRegistry::new();
+        registry.register::<Icon>("Icon").register::<Score>("Score");
+        let mut scene = Scene::new();
+        let player = scene.spawn(Transform::from_xy(1.0, 2.0));
+        scene.set_name(player, "player");
+        let hud = scene.spawn_child(player, Transform::IDENTITY).unwrap();
+        scene.set_visible(hud, false);
+        scene.insert(hud, Icon { image: AssetRef::new("ui/heart.png"), size: 16.0 }).unwrap();
+        scene.insert(player, Score(7)).unwrap();
+        // kayıtlı olmayan bileşen dosyaya girmiyor
+        scene.insert(player, "not saved").unwrap();
+
+        let json = SceneData::save(&scene, &registry).unwrap().to_json();
+        assert!(json.contains("\"ui/heart.png\"") && !json.contains("not saved"));
+        let data = SceneData::from_json(&json).unwrap();
+        let mut loaded = Scene::new();
+        let entities = data.spawn(&mut loaded, &registry, None).unwrap();
+        assert_eq!(loaded.find("player"), Some(entities[0]));
+        assert_eq!(loaded.children(entities[0]), &[entities[1]]);
+        assert_eq!(loaded.local(entities[0]), Some(&Transform::from_xy(1.0, 2.0)));
+        assert!(!loaded.is_visible(entities[1]));
+        assert_eq!(loaded.get::<Score>(entities[0]), Some(&Score(7)));
+        let icon = loaded.get::<Icon>(entities[1]).unwrap();
+        assert_eq!((icon.image.path(), icon.size), ("ui/heart.png", 16.0));
+
+        let unknown = SceneData::from_json(r#"{ "entities": [{ "id": 0, "components": { "Ghost": 1 } }] }"#).unwrap();
+        assert_eq!(unknown.spawn(&mut loaded, &registry, None).unwrap_err().to_string(), "unknown component Ghost");
+        assert_eq!(loaded.len(), 2);
+    }
+}
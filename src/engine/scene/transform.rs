@@ -0,0 +1,159 @@
+// src/engine/scene/transform.rs
+
+use glam::{Mat4, Quat, Vec2, Vec3};
+use serde::{Deserialize, Serialize};
+
+/// Position, rotation and scale relative to the parent. 2D objects live on the xy plane and
+/// turn around z, `angle` and `position_2d` read them that way.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self { translation: Vec3::ZERO, rotation: Quat::IDENTITY, scale: Vec3::ONE };
+
+    pub fn from_translation(translation: Vec3) -> Self {
+        Self { translation, ..Self::IDENTITY }
+    }
+
+    pub fn from_xy(x: f32, y: f32) -> Self {
+        Self::from_translation(Vec3::new(x, y, 0.0))
+    }
+
+    /// `angle` in radians around z
+    pub fn from_2d(position: Vec2, angle: f32, scale: Vec2) -> Self {
+        Self {
+            translation: position.extend(0.0),
+            rotation: Quat::from_rotation_z(angle),
+            scale: scale.extend(1.0),
+        }
+    }
+
+    /// Scale, shear and perspective are lost if there are any
+    pub fn from_matrix(matrix: Mat4) -> Self {
+        let (scale, rotation, translation) = matrix.to_scale_rotation_translation();
+        Self { translation, rotation, scale }
+    }
+
+    pub fn with_rotation(mut self, rotation: Quat) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_angle(mut self, angle: f32) -> Self {
+        self.rotation = Quat::from_rotation_z(angle);
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec3) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+
+    pub fn position_2d(&self) -> Vec2 {
+        self.translation.truncate()
+    }
+
+    pub fn set_position_2d(&mut self, position: Vec2) {
+        self.translation.x = position.x;
+        self.translation.y = position.y;
+    }
+
+    /// rotation around z
+    pub fn angle(&self) -> f32 {
+        let x = self.rotation * Vec3::X;
+        x.y.atan2(x.x)
+    }
+
+    pub fn set_angle(&mut self, angle: f32) {
+        self.rotation = Quat::from_rotation_z(angle);
+    }
+
+    pub fn translate(&mut self, offset: Vec3) {
+        self.translation += offset;
+    }
+
+    pub fn rotate_z(&mut self, angle: f32) {
+        self.rotation = Quat::from_rotation_z(angle) * self.rotation;
+    }
+
+    /// Turns -z towards `target`
+    pub fn look_at(&mut self, target: Vec3, up: Vec3) {
+        self.rotation = Quat::from_mat4(&Mat4::look_at_rh(self.translation, target, up).inverse());
+    }
+}
+
+/// Where a node ends up in the world, parents included. Written by `Scene::update`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlobalTransform(pub Mat4);
+
+impl Default for GlobalTransform {
+    fn default() -> Self {
+        Self(Mat4::IDENTITY)
+    }
+}
+
+impl GlobalTransform {
+    pub fn matrix(&self) -> Mat4 {
+        self.0
+    }
+
+    pub fn translation(&self) -> Vec3 {
+        self.0.w_axis.truncate()
+    }
+
+    pub fn position_2d(&self) -> Vec2 {
+        self.0.w_axis.truncate().truncate()
+    }
+
+    /// rotation around z
+    pub fn angle(&self) -> f32 {
+        self.0.x_axis.y.atan2(self.0.x_axis.x)
+    }
+
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.0.transform_point3(point)
+    }
+
+    pub fn transform_point_2d(&self, point: Vec2) -> Vec2 {
+        self.0.transform_point3(point.extend(0.0)).truncate()
+    }
+
+    /// As a local transform
+    pub fn to_transform(&self) -> Transform {
+        Transform::from_matrix(self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transform_2d(){
+        let t = Transform::from_2d(Vec2::new(3.0, 4.0), 0.5, Vec2::splat(2.0));
+        assert!((t.angle() - 0.5).abs() < 1e-6);
+        let global = GlobalTransform(t.matrix());
+        assert!(global.transform_point_2d(Vec2::X).abs_diff_eq(Vec2::new(3.0, 4.0) + Vec2::from_angle(0.5) * 2.0, 1e-5));
+        assert!((global.angle() - 0.5).abs() < 1e-6);
+        let back = global.to_transform();
+        assert!(back.translation.abs_diff_eq(t.translation, 1e-5) && back.scale.abs_diff_eq(t.scale, 1e-5));
+
+        let mut camera = Transform::from_translation(Vec3::new(0.0, 0.0, 5.0));
+        camera.look_at(Vec3::ZERO, Vec3::Y);
+        assert!((camera.rotation * Vec3::NEG_Z).abs_diff_eq(Vec3::NEG_Z, 1e-5));
+    }
+}
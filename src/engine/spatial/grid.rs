@@ -0,0 +1,279 @@
+// src/engine/spatial/grid.rs
+//
+// Uniform grid over an unbounded plane: only cells with something in them exist. An item is
+// listed in every cell its box touches, so the cell size should be around the usual item size.
+
+use std::collections::{HashMap, HashSet};
+
+use glam::{IVec2, Vec2};
+
+use crate::engine::spatial::{Aabb, ProxyId, SpatialIndex};
+
+struct Entry<T> {
+    aabb: Aabb,
+    /// cells covered, inclusive
+    first: IVec2,
+    last: IVec2,
+    value: T,
+}
+
+pub struct SpatialHash<T> {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<ProxyId>>,
+    /// removed ones are `None`, their slots are used again
+    entries: Vec<Option<Entry<T>>>,
+    free: Vec<usize>,
+    count: usize,
+}
+
+impl<T> SpatialHash<T> {
+    pub fn new(cell_size: f32) -> Self {
+        Self { cell_size, cells: HashMap::new(), entries: Vec::new(), free: Vec::new(), count: 0 }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    pub fn cell(&self, point: Vec2) -> IVec2 {
+        (point / self.cell_size).floor().as_ivec2()
+    }
+
+    fn entry(&self, id: ProxyId) -> Option<&Entry<T>> {
+        self.entries.get(id.0)?.as_ref()
+    }
+
+    fn link(&mut self, id: ProxyId, first: IVec2, last: IVec2) {
+        for y in first.y..=last.y {
+            for x in first.x..=last.x {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(id);
+            }
+        }
+    }
+
+    fn unlink(&mut self, id: ProxyId, first: IVec2, last: IVec2) {
+        for y in first.y..=last.y {
+            for x in first.x..=last.x {
+                let cell = IVec2::new(x, y);
+                if let Some(items) = self.cells.get_mut(&cell) {
+                    items.retain(|i| *i != id);
+                    if items.is_empty() {
+                        self.cells.remove(&cell);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.entries.clear();
+        self.free.clear();
+        self.count = 0;
+    }
+
+    /// Every item with its box
+    pub fn iter(&self) -> impl Iterator<Item = (ProxyId, &T, Aabb)> {
+        self.entries.iter().enumerate().filter_map(|(i, e)| e.as_ref().map(|e| (ProxyId(i), &e.value, e.aabb)))
+    }
+
+    /// Calls `f` with the items listed in the cells from `first` to `last`, each once
+    fn visit(&self, first: IVec2, last: IVec2, seen: &mut HashSet<ProxyId>, mut f: impl FnMut(ProxyId, &Entry<T>) -> bool) -> bool {
+        for y in first.y..=last.y {
+            for x in first.x..=last.x {
+                let Some(items) = self.cells.get(&IVec2::new(x, y)) else {
+                    continue;
+                };
+                for &id in items {
+                    if seen.insert(id) && !f(id, self.entries[id.0].as_ref().unwrap()) {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+impl<T> SpatialIndex<T> for SpatialHash<T> {
+    fn insert(&mut self, aabb: Aabb, value: T) -> ProxyId {
+        let (first, last) = (self.cell(aabb.min), self.cell(aabb.max));
+        let entry = Entry { aabb, first, last, value };
+        let id = match self.free.pop() {
+            Some(index) => {
+                self.entries[index] = Some(entry);
+                ProxyId(index)
+            }
+            None => {
+                self.entries.push(Some(entry));
+                ProxyId(self.entries.len() - 1)
+            }
+        };
+        self.link(id, first, last);
+        self.count += 1;
+        id
+    }
+
+    fn remove(&mut self, id: ProxyId) -> Option<T> {
+        let entry = self.entries.get_mut(id.0)?.take()?;
+        self.unlink(id, entry.first, entry.last);
+        self.free.push(id.0);
+        self.count -= 1;
+        Some(entry.value)
+    }
+
+    fn update(&mut self, id: ProxyId, aabb: Aabb) {
+        let (first, last) = (self.cell(aabb.min), self.cell(aabb.max));
+        let Some(entry) = self.entries.get_mut(id.0).and_then(Option::as_mut) else {
+            return;
+        };
+        let (old_first, old_last) = (entry.first, entry.last);
+        entry.aabb = aabb;
+        // aynı hücrelerde kalan hareket listelere dokunmuyor
+        if (first, last) == (old_first, old_last) {
+            return;
+        }
+        entry.first = first;
+        entry.last = last;
+        self.unlink(id, old_first, old_last);
+        self.link(id, first, last);
+    }
+
+    fn get(&self, id: ProxyId) -> Option<&T> {
+        self.entry(id).map(|e| &e.value)
+    }
+
+    fn bounds(&self, id: ProxyId) -> Option<Aabb> {
+        self.entry(id).map(|e| e.aabb)
+    }
+
+    fn len(&self) -> usize {
+        self.count
+    }
+
+    fn query_aabb(&self, aabb: Aabb, mut f: impl FnMut(ProxyId, &T) -> bool) {
+        let mut seen = HashSet::new();
+        self.visit(self.cell(aabb.min), self.cell(aabb.max), &mut seen, |id, entry| {
+            !entry.aabb.overlaps(&aabb) || f(id, &entry.value)
+        });
+    }
+
+    fn sweep(&self, origin: Vec2, translation: Vec2, half_extents: Vec2, mut hit: impl FnMut(ProxyId, &T) -> Option<f32>) -> Option<(ProxyId, f32)> {
+        // merkezin geçtiği hücreler sırayla, kutunun eni kadar komşularıyla birlikte
+        let reach = (half_extents / self.cell_size).ceil().as_ivec2();
+        let mut cell = self.cell(origin);
+        let end = self.cell(origin + translation);
+        let step = IVec2::new(translation.x.signum() as i32, translation.y.signum() as i32);
+        let mut next = Vec2::INFINITY;
+        let mut delta = Vec2::INFINITY;
+        for axis in 0..2 {
+            if translation[axis].abs() > f32::EPSILON {
+                let boundary = (cell[axis] + (step[axis] > 0) as i32) as f32 * self.cell_size;
+                next[axis] = (boundary - origin[axis]) / translation[axis];
+                delta[axis] = self.cell_size / translation[axis].abs();
+            }
+        }
+
+        let mut best: Option<(ProxyId, f32)> = None;
+        let mut seen = HashSet::new();
+        let mut entered = 0.0;
+        let cells = (end - cell).abs().element_sum() + 1;
+        for _ in 0..cells {
+            let max_fraction = best.map_or(1.0, |b| b.1);
+            if entered > max_fraction {
+                break;
+            }
+            self.visit(cell - reach, cell + reach, &mut seen, |id, entry| {
+                let max_fraction = best.map_or(1.0, |b| b.1);
+                if entry.aabb.grow_by(half_extents).raycast(origin, translation, max_fraction).is_some()
+                    && let Some(fraction) = hit(id, &entry.value).filter(|f| (0.0..=max_fraction).contains(f))
+                {
+                    best = Some((id, fraction));
+                }
+                true
+            });
+            let axis = if next.x < next.y { 0 } else { 1 };
+            entered = next[axis];
+            next[axis] += delta[axis];
+            cell[axis] += step[axis];
+        }
+        best
+    }
+
+    fn nearest(&self, point: Vec2, max_distance: f32, mut distance: impl FnMut(ProxyId, &T) -> Option<f32>) -> Option<(ProxyId, f32)> {
+        if self.count == 0 {
+            return None;
+        }
+        let center = self.cell(point);
+        // dolu hücrelerin ötesine bakmaya gerek yok
+        let farthest = self.cells.keys().map(|c| (*c - center).abs().max_element()).max().unwrap_or(0);
+        let rings = if max_distance.is_finite() { farthest.min((max_distance / self.cell_size).ceil() as i32 + 1) } else { farthest };
+        let mut best: Option<(ProxyId, f32)> = None;
+        let mut seen = HashSet::new();
+        for ring in 0..=rings {
+            let limit = best.map_or(max_distance, |b| b.1);
+            // r. halkadaki bir hücre noktaya en az (r - 1) hücre uzakta
+            if (ring - 1) as f32 * self.cell_size > limit {
+                break;
+            }
+            let mut test = |id: ProxyId, entry: &Entry<T>| {
+                let limit = best.map_or(max_distance, |b| b.1);
+                if entry.aabb.distance_to_point(point) <= limit
+                    && let Some(d) = distance(id, &entry.value).filter(|d| *d <= limit)
+                {
+                    best = Some((id, d));
+                }
+                true
+            };
+            let (min, max) = (center - IVec2::splat(ring), center + IVec2::splat(ring));
+            if ring == 0 {
+                self.visit(min, max, &mut seen, &mut test);
+                continue;
+            }
+            // halkanın üst ve alt satırları, sonra kenarları
+            self.visit(min, IVec2::new(max.x, min.y), &mut seen, &mut test);
+            self.visit(IVec2::new(min.x, max.y), max, &mut seen, &mut test);
+            self.visit(IVec2::new(min.x, min.y + 1), IVec2::new(min.x, max.y - 1), &mut seen, &mut test);
+            self.visit(IVec2::new(max.x, min.y + 1), IVec2::new(max.x, max.y - 1), &mut seen, &mut test);
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_queries(){
+        let mut grid = SpatialHash::new(1.0);
+        let small = grid.insert(Aabb::from_center(Vec2::new(0.5, 0.5), Vec2::splat(0.25)), "small");
+        let wide = grid.insert(Aabb::new(Vec2::new(-3.0, 3.0), Vec2::new(3.0, 3.5)), "wide");
+        let far = grid.insert(Aabb::from_center(Vec2::new(-20.5, -7.5), Vec2::splat(0.25)), "far");
+        assert_eq!(grid.len(), 3);
+
+        let mut found = Vec::new();
+        grid.query_aabb(Aabb::new(Vec2::new(-5.0, 0.0), Vec2::new(5.0, 5.0)), |id, _| {
+            found.push(id);
+            true
+        });
+        found.sort();
+        assert_eq!(found, vec![small, wide]);
+
+        // çapraz ışın: önce geniş kutu
+        assert_eq!(grid.raycast_bounds(Vec2::new(-6.0, 7.0), Vec2::new(12.0, -12.0)).map(|h| h.0), Some(wide));
+        assert_eq!(grid.raycast_bounds(Vec2::new(2.0, 0.5), Vec2::new(-4.0, 0.0)).map(|h| h.0), Some(small));
+        // büyük kutu küçüğün üstünden geçip genişe takılıyor
+        let cast = grid.cast_aabb(Aabb::from_center(Vec2::new(-10.0, 2.0), Vec2::splat(1.2)), Vec2::new(20.0, 0.0), |id, _| grid.bounds(id)?.grow(1.2).raycast(Vec2::new(-10.0, 2.0), Vec2::new(20.0, 0.0), 1.0));
+        assert_eq!(cast.map(|h| h.0), Some(wide));
+
+        let nearest = grid.nearest(Vec2::new(-15.0, -5.0), f32::INFINITY, |id, _| Some(grid.bounds(id)?.distance_to_point(Vec2::new(-15.0, -5.0))));
+        assert_eq!(nearest.map(|h| h.0), Some(far));
+        assert!(grid.nearest(Vec2::new(-15.0, -5.0), 2.0, |_, _| Some(0.0)).is_none());
+
+        grid.update(far, Aabb::from_center(Vec2::new(0.5, 1.5), Vec2::splat(0.25)));
+        assert_eq!(grid.nearest(Vec2::new(0.5, 1.4), 5.0, |id, _| Some(grid.bounds(id)?.distance_to_point(Vec2::new(0.5, 1.4)))).map(|h| h.0), Some(far));
+        assert_eq!(grid.remove(wide), Some("wide"));
+        assert!(grid.raycast_bounds(Vec2::new(-6.0, 3.2), Vec2::new(12.0, 0.0)).is_none());
+    }
+}
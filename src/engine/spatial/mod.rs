@@ -0,0 +1,165 @@
+// src/engine/spatial/mod.rs
+//
+// Spatial indices over boxes: what is here, what does this ray hit, what is closest. They
+// only know boxes and a value per box (an entity, a collider, a sprite index), exact tests
+// are left to callbacks. `AabbTree` suits objects of any size that move, `SpatialHash` many
+// similar sized ones spread over a large area.
+
+use glam::Vec2;
+
+pub mod grid;
+pub mod tree;
+pub use grid::SpatialHash;
+pub use tree::AabbTree;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Aabb {
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_center(center: Vec2, half_extents: Vec2) -> Self {
+        Self { min: center - half_extents, max: center + half_extents }
+    }
+
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x && other.min.x <= self.max.x && self.min.y <= other.max.y && other.min.y <= self.max.y
+    }
+
+    pub fn contains_point(&self, point: Vec2) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    pub fn contains(&self, other: &Aabb) -> bool {
+        other.min.cmpge(self.min).all() && other.max.cmple(self.max).all()
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb { min: self.min.min(other.min), max: self.max.max(other.max) }
+    }
+
+    pub fn grow(&self, amount: f32) -> Aabb {
+        self.grow_by(Vec2::splat(amount))
+    }
+
+    pub fn grow_by(&self, amount: Vec2) -> Aabb {
+        Aabb { min: self.min - amount, max: self.max + amount }
+    }
+
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    /// half the perimeter, what AABB trees minimize
+    pub fn perimeter(&self) -> f32 {
+        let size = self.size();
+        size.x + size.y
+    }
+
+    /// 0 inside
+    pub fn distance_to_point(&self, point: Vec2) -> f32 {
+        (self.min - point).max(point - self.max).max(Vec2::ZERO).length()
+    }
+
+    /// Fraction of `translation` where the segment from `origin` enters the box, 0 when it
+    /// starts inside, `None` when it misses or enters after `max_fraction`
+    pub fn raycast(&self, origin: Vec2, translation: Vec2, max_fraction: f32) -> Option<f32> {
+        let (mut enter, mut exit) = (0.0f32, max_fraction);
+        for axis in 0..2 {
+            let (o, d) = (origin[axis], translation[axis]);
+            if d.abs() <= f32::EPSILON {
+                if o < self.min[axis] || o > self.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let (mut t1, mut t2) = ((self.min[axis] - o) / d, (self.max[axis] - o) / d);
+            if t1 > t2 {
+                std::mem::swap(&mut t1, &mut t2);
+            }
+            enter = enter.max(t1);
+            exit = exit.min(t2);
+            if enter > exit {
+                return None;
+            }
+        }
+        Some(enter)
+    }
+}
+
+/// An item of a spatial index, only meaningful for the index that returned it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProxyId(pub(crate) usize);
+
+/// What both indices answer. Callbacks get the value of each item whose box passes the box
+/// test and do the exact test themselves.
+pub trait SpatialIndex<T> {
+    fn insert(&mut self, aabb: Aabb, value: T) -> ProxyId;
+    fn remove(&mut self, id: ProxyId) -> Option<T>;
+    /// The item moved or changed size
+    fn update(&mut self, id: ProxyId, aabb: Aabb);
+    fn get(&self, id: ProxyId) -> Option<&T>;
+    /// the box given to `insert` or `update`
+    fn bounds(&self, id: ProxyId) -> Option<Aabb>;
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Items whose box overlaps `aabb`, stops when `f` returns false
+    fn query_aabb(&self, aabb: Aabb, f: impl FnMut(ProxyId, &T) -> bool);
+
+    /// Moves a box of `half_extents` from `origin` by `translation`. `hit` gives the exact
+    /// fraction of the translation where it hits the item, or `None`. The closest hit wins.
+    fn sweep(&self, origin: Vec2, translation: Vec2, half_extents: Vec2, hit: impl FnMut(ProxyId, &T) -> Option<f32>) -> Option<(ProxyId, f32)>;
+
+    /// Closest item within `max_distance`, `distance` gives the exact distance to an item or
+    /// `None` to skip it. It can't be less than the distance to the item's box.
+    fn nearest(&self, point: Vec2, max_distance: f32, distance: impl FnMut(ProxyId, &T) -> Option<f32>) -> Option<(ProxyId, f32)>;
+
+    fn query_point(&self, point: Vec2, f: impl FnMut(ProxyId, &T) -> bool) {
+        self.query_aabb(Aabb::new(point, point), f);
+    }
+
+    /// Items whose box is within `radius` of `center`
+    fn query_circle(&self, center: Vec2, radius: f32, mut f: impl FnMut(ProxyId, &T) -> bool) {
+        self.query_aabb(Aabb::from_center(center, Vec2::splat(radius)), |id, value| {
+            let inside = self.bounds(id).is_some_and(|b| b.distance_to_point(center) <= radius);
+            !inside || f(id, value)
+        });
+    }
+
+    /// Every item whose box overlaps `aabb`
+    fn collect_aabb(&self, aabb: Aabb) -> Vec<ProxyId> {
+        let mut found = Vec::new();
+        self.query_aabb(aabb, |id, _| {
+            found.push(id);
+            true
+        });
+        found
+    }
+
+    fn raycast(&self, origin: Vec2, translation: Vec2, hit: impl FnMut(ProxyId, &T) -> Option<f32>) -> Option<(ProxyId, f32)> {
+        self.sweep(origin, translation, Vec2::ZERO, hit)
+    }
+
+    /// First box the ray enters, enough for picking things drawn as rectangles
+    fn raycast_bounds(&self, origin: Vec2, translation: Vec2) -> Option<(ProxyId, f32)> {
+        self.raycast(origin, translation, |id, _| self.bounds(id)?.raycast(origin, translation, 1.0))
+    }
+
+    /// `sweep` with a box
+    fn cast_aabb(&self, aabb: Aabb, translation: Vec2, hit: impl FnMut(ProxyId, &T) -> Option<f32>) -> Option<(ProxyId, f32)> {
+        self.sweep(aabb.center(), translation, aabb.size() * 0.5, hit)
+    }
+}
@@ -0,0 +1,445 @@
+// src/engine/spatial/tree.rs
+//
+// Dynamic AABB tree like Box2D's: leaves hold a fattened box so small moves don't touch the
+// tree, a new leaf goes where it grows the perimeters the least and rotations keep it balanced.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use glam::Vec2;
+
+use crate::engine::spatial::{Aabb, ProxyId, SpatialIndex};
+
+const NULL: usize = usize::MAX;
+
+struct Node<T> {
+    /// fattened for leaves
+    aabb: Aabb,
+    parent: usize,
+    child1: usize,
+    child2: usize,
+    /// 0 for leaves
+    height: u32,
+    /// the box given for leaves
+    bounds: Aabb,
+    value: Option<T>,
+}
+
+impl<T> Node<T> {
+    fn is_leaf(&self) -> bool {
+        self.child1 == NULL
+    }
+}
+
+pub struct AabbTree<T> {
+    /// removed ones are `None`, their slots are used again
+    nodes: Vec<Option<Node<T>>>,
+    free: Vec<usize>,
+    root: usize,
+    count: usize,
+    /// how much leaf boxes are grown by, items moving less than this don't change the tree
+    pub margin: f32,
+}
+
+impl<T> Default for AabbTree<T> {
+    fn default() -> Self {
+        Self::new(0.1)
+    }
+}
+
+/// Queue entry of `nearest`, closest first
+struct Candidate(f32, usize);
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.total_cmp(&self.0)
+    }
+}
+
+impl<T> AabbTree<T> {
+    pub fn new(margin: f32) -> Self {
+        Self { nodes: Vec::new(), free: Vec::new(), root: NULL, count: 0, margin }
+    }
+
+    fn node(&self, index: usize) -> &Node<T> {
+        self.nodes[index].as_ref().unwrap()
+    }
+
+    fn node_mut(&mut self, index: usize) -> &mut Node<T> {
+        self.nodes[index].as_mut().unwrap()
+    }
+
+    fn leaf(&self, id: ProxyId) -> Option<&Node<T>> {
+        self.nodes.get(id.0)?.as_ref().filter(|n| n.is_leaf())
+    }
+
+    fn allocate(&mut self, node: Node<T>) -> usize {
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = Some(node);
+                index
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn release(&mut self, index: usize) -> Node<T> {
+        self.free.push(index);
+        self.nodes[index].take().unwrap()
+    }
+
+    /// height of the root, around log2 of the item count when balanced
+    pub fn height(&self) -> u32 {
+        if self.root == NULL { 0 } else { self.node(self.root).height }
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.root = NULL;
+        self.count = 0;
+    }
+
+    /// Every item with its box
+    pub fn iter(&self) -> impl Iterator<Item = (ProxyId, &T, Aabb)> {
+        self.nodes.iter().enumerate().filter_map(|(i, n)| {
+            let n = n.as_ref()?;
+            n.value.as_ref().map(|v| (ProxyId(i), v, n.bounds))
+        })
+    }
+
+    fn insert_leaf(&mut self, leaf: usize) {
+        if self.root == NULL {
+            self.root = leaf;
+            self.node_mut(leaf).parent = NULL;
+            return;
+        }
+        // en az büyüten kardeşi ara
+        let leaf_aabb = self.node(leaf).aabb;
+        let mut index = self.root;
+        while !self.node(index).is_leaf() {
+            let node = self.node(index);
+            let area = node.aabb.perimeter();
+            let combined = node.aabb.union(&leaf_aabb).perimeter();
+            let cost = 2.0 * combined;
+            let inheritance = 2.0 * (combined - area);
+            let child_cost = |child: usize| {
+                let child = self.node(child);
+                let grown = leaf_aabb.union(&child.aabb).perimeter();
+                if child.is_leaf() { grown + inheritance } else { grown - child.aabb.perimeter() + inheritance }
+            };
+            let (cost1, cost2) = (child_cost(node.child1), child_cost(node.child2));
+            if cost < cost1 && cost < cost2 {
+                break;
+            }
+            index = if cost1 < cost2 { node.child1 } else { node.child2 };
+        }
+
+        let sibling = index;
+        let old_parent = self.node(sibling).parent;
+        let sibling_node = self.node(sibling);
+        let parent = self.allocate(Node {
+            aabb: sibling_node.aabb.union(&leaf_aabb),
+            parent: old_parent,
+            child1: sibling,
+            child2: leaf,
+            height: sibling_node.height + 1,
+            bounds: sibling_node.aabb,
+            value: None,
+        });
+        if old_parent == NULL {
+            self.root = parent;
+        } else {
+            self.replace_child(old_parent, sibling, parent);
+        }
+        self.node_mut(sibling).parent = parent;
+        self.node_mut(leaf).parent = parent;
+        self.refit(self.node(leaf).parent);
+    }
+
+    fn remove_leaf(&mut self, leaf: usize) {
+        if leaf == self.root {
+            self.root = NULL;
+            return;
+        }
+        let parent = self.node(leaf).parent;
+        let grand_parent = self.node(parent).parent;
+        let sibling = if self.node(parent).child1 == leaf { self.node(parent).child2 } else { self.node(parent).child1 };
+        self.release(parent);
+        self.node_mut(sibling).parent = grand_parent;
+        if grand_parent == NULL {
+            self.root = sibling;
+        } else {
+            self.replace_child(grand_parent, parent, sibling);
+            self.refit(grand_parent);
+        }
+    }
+
+    fn replace_child(&mut self, parent: usize, old: usize, new: usize) {
+        let node = self.node_mut(parent);
+        if node.child1 == old {
+            node.child1 = new;
+        } else {
+            node.child2 = new;
+        }
+    }
+
+    /// Balances and fixes the boxes and heights from `index` up to the root
+    fn refit(&mut self, mut index: usize) {
+        while index != NULL {
+            index = self.balance(index);
+            let (child1, child2) = (self.node(index).child1, self.node(index).child2);
+            let aabb = self.node(child1).aabb.union(&self.node(child2).aabb);
+            let height = 1 + self.node(child1).height.max(self.node(child2).height);
+            let node = self.node_mut(index);
+            node.aabb = aabb;
+            node.height = height;
+            index = node.parent;
+        }
+    }
+
+    /// Rotates the higher child of `a` up if the children differ by more than one level,
+    /// returns the node that took the place of `a`
+    fn balance(&mut self, a: usize) -> usize {
+        if self.node(a).is_leaf() || self.node(a).height < 2 {
+            return a;
+        }
+        let (b, c) = (self.node(a).child1, self.node(a).child2);
+        let difference = self.node(c).height as i64 - self.node(b).height as i64;
+        if difference > 1 {
+            self.rotate(a, c, b, false)
+        } else if difference < -1 {
+            self.rotate(a, b, c, true)
+        } else {
+            a
+        }
+    }
+
+    /// `up` (a child of `a`) becomes the parent of `a`, `a` keeps `other` and the lower
+    /// child of `up`. `up_is_child1` tells which side of `a` `up` was on.
+    fn rotate(&mut self, a: usize, up: usize, other: usize, up_is_child1: bool) -> usize {
+        let (f, g) = (self.node(up).child1, self.node(up).child2);
+        let a_parent = self.node(a).parent;
+        {
+            let node = self.node_mut(up);
+            node.child1 = a;
+            node.parent = a_parent;
+        }
+        self.node_mut(a).parent = up;
+        if a_parent == NULL {
+            self.root = up;
+        } else {
+            self.replace_child(a_parent, a, up);
+        }
+        // yüksek torun yukarı çıkanın altında kalıyor, alçak olan a'ya geçiyor
+        let (high, low) = if self.node(f).height > self.node(g).height { (f, g) } else { (g, f) };
+        self.node_mut(up).child2 = high;
+        if up_is_child1 {
+            self.node_mut(a).child1 = low;
+        } else {
+            self.node_mut(a).child2 = low;
+        }
+        self.node_mut(low).parent = a;
+
+        let a_aabb = self.node(other).aabb.union(&self.node(low).aabb);
+        let a_height = 1 + self.node(other).height.max(self.node(low).height);
+        {
+            let node = self.node_mut(a);
+            node.aabb = a_aabb;
+            node.height = a_height;
+        }
+        let up_aabb = a_aabb.union(&self.node(high).aabb);
+        let up_height = 1 + a_height.max(self.node(high).height);
+        let node = self.node_mut(up);
+        node.aabb = up_aabb;
+        node.height = up_height;
+        up
+    }
+}
+
+impl<T> SpatialIndex<T> for AabbTree<T> {
+    fn insert(&mut self, aabb: Aabb, value: T) -> ProxyId {
+        let leaf = self.allocate(Node {
+            aabb: aabb.grow(self.margin),
+            parent: NULL,
+            child1: NULL,
+            child2: NULL,
+            height: 0,
+            bounds: aabb,
+            value: Some(value),
+        });
+        self.insert_leaf(leaf);
+        self.count += 1;
+        ProxyId(leaf)
+    }
+
+    fn remove(&mut self, id: ProxyId) -> Option<T> {
+        self.leaf(id)?;
+        self.remove_leaf(id.0);
+        self.count -= 1;
+        self.release(id.0).value
+    }
+
+    fn update(&mut self, id: ProxyId, aabb: Aabb) {
+        let Some(node) = self.leaf(id) else {
+            return;
+        };
+        let fat = node.aabb;
+        self.node_mut(id.0).bounds = aabb;
+        if fat.contains(&aabb) {
+            return;
+        }
+        self.remove_leaf(id.0);
+        self.node_mut(id.0).aabb = aabb.grow(self.margin);
+        self.insert_leaf(id.0);
+    }
+
+    fn get(&self, id: ProxyId) -> Option<&T> {
+        self.leaf(id)?.value.as_ref()
+    }
+
+    fn bounds(&self, id: ProxyId) -> Option<Aabb> {
+        self.leaf(id).map(|n| n.bounds)
+    }
+
+    fn len(&self) -> usize {
+        self.count
+    }
+
+    fn query_aabb(&self, aabb: Aabb, mut f: impl FnMut(ProxyId, &T) -> bool) {
+        if self.root == NULL {
+            return;
+        }
+        let mut stack = vec![self.root];
+        while let Some(index) = stack.pop() {
+            let node = self.node(index);
+            if !node.aabb.overlaps(&aabb) {
+                continue;
+            }
+            if !node.is_leaf() {
+                stack.push(node.child1);
+                stack.push(node.child2);
+            } else if node.bounds.overlaps(&aabb) && !f(ProxyId(index), node.value.as_ref().unwrap()) {
+                return;
+            }
+        }
+    }
+
+    fn sweep(&self, origin: Vec2, translation: Vec2, half_extents: Vec2, mut hit: impl FnMut(ProxyId, &T) -> Option<f32>) -> Option<(ProxyId, f32)> {
+        if self.root == NULL {
+            return None;
+        }
+        let mut best: Option<(ProxyId, f32)> = None;
+        let mut stack = vec![self.root];
+        while let Some(index) = stack.pop() {
+            let node = self.node(index);
+            let max_fraction = best.map_or(1.0, |b| b.1);
+            let aabb = if node.is_leaf() { node.bounds } else { node.aabb };
+            if aabb.grow_by(half_extents).raycast(origin, translation, max_fraction).is_none() {
+                continue;
+            }
+            if !node.is_leaf() {
+                stack.push(node.child1);
+                stack.push(node.child2);
+                continue;
+            }
+            if let Some(fraction) = hit(ProxyId(index), node.value.as_ref().unwrap())
+                && (0.0..=max_fraction).contains(&fraction)
+            {
+                best = Some((ProxyId(index), fraction));
+            }
+        }
+        best
+    }
+
+    fn nearest(&self, point: Vec2, max_distance: f32, mut distance: impl FnMut(ProxyId, &T) -> Option<f32>) -> Option<(ProxyId, f32)> {
+        if self.root == NULL {
+            return None;
+        }
+        let mut best: Option<(ProxyId, f32)> = None;
+        // kutu uzaklığı gerçek uzaklıktan büyük olamıyor, yakın kutudan başlanıyor
+        let mut queue = BinaryHeap::from([Candidate(self.node(self.root).aabb.distance_to_point(point), self.root)]);
+        while let Some(Candidate(bound, index)) = queue.pop() {
+            let limit = best.map_or(max_distance, |b| b.1);
+            if bound > limit {
+                break;
+            }
+            let node = self.node(index);
+            if node.is_leaf() {
+                if node.bounds.distance_to_point(point) > limit {
+                    continue;
+                }
+                if let Some(d) = distance(ProxyId(index), node.value.as_ref().unwrap())
+                    && d <= limit
+                {
+                    best = Some((ProxyId(index), d));
+                }
+                continue;
+            }
+            for child in [node.child1, node.child2] {
+                queue.push(Candidate(self.node(child).aabb.distance_to_point(point), child));
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f32, y: f32) -> Aabb {
+        Aabb::from_center(Vec2::new(x, y), Vec2::splat(0.5))
+    }
+
+    #[test]
+    fn tree_queries(){
+        let mut tree = AabbTree::new(0.1);
+        let mut ids = Vec::new();
+        for i in 0..100 {
+            ids.push(tree.insert(square(i as f32 * 2.0, 0.0), i));
+        }
+        // sıralı ekleme dengeli kalıyor
+        assert!(tree.height() <= 10, "{}", tree.height());
+        assert_eq!(tree.collect_aabb(Aabb::new(Vec2::new(3.0, -1.0), Vec2::new(7.0, 1.0))).len(), 2);
+
+        let hit = tree.raycast_bounds(Vec2::new(-5.0, 0.0), Vec2::new(20.0, 0.0)).unwrap();
+        assert_eq!((tree.get(hit.0), hit.1), (Some(&0), 0.225));
+        // tam test çift sayıları atlıyor
+        let odd = tree.raycast(Vec2::new(-5.0, 0.0), Vec2::new(20.0, 0.0), |id, v| (v % 2 == 1).then(|| tree.bounds(id).unwrap().raycast(Vec2::new(-5.0, 0.0), Vec2::new(20.0, 0.0), 1.0)).flatten());
+        assert_eq!(tree.get(odd.unwrap().0), Some(&1));
+        let cast = tree.cast_aabb(square(0.8, 5.0), Vec2::new(0.0, -10.0), |id, _| tree.bounds(id)?.grow(0.5).raycast(Vec2::new(0.8, 5.0), Vec2::new(0.0, -10.0), 1.0)).unwrap();
+        assert_eq!((tree.get(cast.0), cast.1), (Some(&0), 0.4));
+
+        let nearest = tree.nearest(Vec2::new(41.2, 3.0), 10.0, |id, _| Some(tree.bounds(id).unwrap().center().distance(Vec2::new(41.2, 3.0))));
+        assert_eq!(tree.get(nearest.unwrap().0), Some(&21));
+        assert!(tree.nearest(Vec2::new(41.2, 30.0), 10.0, |_, _| Some(0.0)).is_none());
+
+        tree.update(ids[50], square(0.0, 10.0));
+        assert_eq!(tree.collect_aabb(square(0.0, 10.0)), vec![ids[50]]);
+        for id in &ids[..60] {
+            tree.remove(*id);
+        }
+        assert_eq!(tree.len(), 40);
+        assert!(tree.height() <= 8);
+        assert!(tree.get(ids[0]).is_none() && tree.collect_aabb(square(0.0, 10.0)).is_empty());
+        assert_eq!(tree.iter().count(), 40);
+    }
+}
@@ -0,0 +1,84 @@
+// src/engine/time.rs
+
+/// milliseconds since some fixed point
+pub(crate) fn now_ms() -> f64 {
+    #[cfg(not(target_family = "wasm"))]
+    {
+        static START: once_cell::sync::Lazy<std::time::Instant> = once_cell::sync::Lazy::new(std::time::Instant::now);
+        START.elapsed().as_secs_f64() * 1000.0
+    }
+    #[cfg(target_family = "wasm")]
+    {
+        js_sys::Date::now()
+    }
+}
+
+/// Turns the frame times into a whole number of fixed steps, what's left waits for the next frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedTimestep {
+    /// seconds
+    pub step: f32,
+    /// steps run in one frame at most, a long hitch is dropped instead of catching up
+    pub max_steps: u32,
+    accumulator: f32,
+    /// ms of the last `advance`
+    last: Option<f64>,
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new(60.0)
+    }
+}
+
+impl FixedTimestep {
+    pub fn new(hz: f32) -> Self {
+        Self { step: 1.0 / hz, max_steps: 8, accumulator: 0.0, last: None }
+    }
+
+    /// Steps to run for the real time since the last call, none on the first
+    pub fn advance(&mut self) -> u32 {
+        let now = now_ms();
+        let seconds = self.last.replace(now).map_or(0.0, |last| ((now - last) / 1000.0) as f32);
+        self.advance_by(seconds)
+    }
+
+    /// Steps to run after `seconds`, e.g. the timestep of a frame capture
+    pub fn advance_by(&mut self, seconds: f32) -> u32 {
+        self.accumulator += seconds.max(0.0);
+        let steps = (self.accumulator / self.step) as u32;
+        self.accumulator -= steps as f32 * self.step;
+        if steps > self.max_steps {
+            self.accumulator = 0.0;
+            return self.max_steps;
+        }
+        steps
+    }
+
+    /// How far between the last step and the next one, for interpolating what's drawn
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.step
+    }
+
+    /// Forgets the time passed, e.g. after a pause
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+        self.last = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps(){
+        let mut timestep = FixedTimestep::new(50.0);
+        assert_eq!(timestep.advance_by(0.05), 2);
+        assert_eq!(timestep.advance_by(0.005), 0);
+        assert!((timestep.alpha() - 0.75).abs() < 1e-4);
+        assert_eq!(timestep.advance_by(0.006), 1);
+        assert_eq!(timestep.advance_by(10.0), 8);
+        assert_eq!(timestep.alpha(), 0.0);
+    }
+}
@@ -0,0 +1,172 @@
+// src/engine/vfs/mod.rs
+//
+// Virtual filesystem. Directories and pack archives are mounted under virtual paths, a file
+// is read from the mount with the highest priority that has it, so mods and patches are
+// just higher priority mounts over the base game.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::engine::assets::server::normalize;
+
+pub mod pack;
+pub use pack::{Pack, PackBuilder, PackError};
+
+/// Something that can be mounted. Paths are relative to the mount point and normalized.
+pub trait MountSource: Send + Sync {
+    fn contains(&self, path: &Path) -> bool;
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    /// used by hot reloading, `None` when the source can't tell
+    fn modified(&self, path: &Path) -> Option<SystemTime>;
+    fn files(&self) -> Vec<PathBuf>;
+}
+
+/// A directory on disk
+pub struct Directory {
+    root: PathBuf,
+}
+
+impl Directory {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl MountSource for Directory {
+    fn contains(&self, path: &Path) -> bool {
+        self.root.join(path).is_file()
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(self.root.join(path))
+    }
+
+    fn modified(&self, path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(self.root.join(path)).and_then(|m| m.modified()).ok()
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        let mut dirs = vec![PathBuf::new()];
+        while let Some(dir) = dirs.pop() {
+            let Ok(entries) = std::fs::read_dir(self.root.join(&dir)) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = dir.join(entry.file_name());
+                match entry.file_type() {
+                    Ok(t) if t.is_dir() => dirs.push(path),
+                    Ok(_) => files.push(path),
+                    Err(_) => (),
+                }
+            }
+        }
+        files.sort();
+        files
+    }
+}
+
+struct Mount {
+    point: PathBuf,
+    priority: i32,
+    source: Box<dyn MountSource>,
+}
+
+impl Mount {
+    /// `path` relative to this mount, `None` when it's outside of it
+    fn relative<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        path.strip_prefix(&self.point).ok()
+    }
+}
+
+#[derive(Default)]
+pub struct Vfs {
+    /// highest priority first, among equals the last mounted first
+    mounts: Vec<Mount>,
+}
+
+impl Vfs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mounts `source` at the virtual directory `point` ("" for the root)
+    pub fn mount<P: AsRef<Path>, S: MountSource + 'static>(&mut self, point: P, priority: i32, source: S) {
+        let index = self.mounts.iter().position(|m| m.priority <= priority).unwrap_or(self.mounts.len());
+        self.mounts.insert(index, Mount { point: normalize(point.as_ref()), priority, source: Box::new(source) });
+    }
+
+    /// Removes every mount at `point`, returns how many there were
+    pub fn unmount<P: AsRef<Path>>(&mut self, point: P) -> usize {
+        let point = normalize(point.as_ref());
+        let before = self.mounts.len();
+        self.mounts.retain(|m| m.point != point);
+        before - self.mounts.len()
+    }
+
+    pub fn mount_count(&self) -> usize {
+        self.mounts.len()
+    }
+
+    /// the mount `path` is read from and the path inside it
+    fn resolve<'a>(&'a self, path: &Path) -> Option<(&'a Mount, PathBuf)> {
+        let path = normalize(path);
+        self.mounts.iter().find_map(|mount| {
+            let relative = mount.relative(&path)?;
+            mount.source.contains(relative).then(|| (mount, relative.to_path_buf()))
+        })
+    }
+
+    pub fn exists<P: AsRef<Path>>(&self, path: P) -> bool {
+        self.resolve(path.as_ref()).is_some()
+    }
+
+    pub fn read<P: AsRef<Path>>(&self, path: P) -> io::Result<Vec<u8>> {
+        let path = path.as_ref();
+        match self.resolve(path) {
+            Some((mount, relative)) => mount.source.read(&relative),
+            None => Err(io::Error::new(io::ErrorKind::NotFound, format!("{} is not in any mount", path.display()))),
+        }
+    }
+
+    pub fn modified<P: AsRef<Path>>(&self, path: P) -> Option<SystemTime> {
+        let (mount, relative) = self.resolve(path.as_ref())?;
+        mount.source.modified(&relative)
+    }
+
+    /// Every virtual file, each listed once
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self.mounts.iter()
+            .flat_map(|mount| mount.source.files().into_iter().map(|f| mount.point.join(f)))
+            .collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_priority(){
+        let mut base = PackBuilder::new();
+        base.add("data/a.txt", b"base a".to_vec());
+        base.add("data/b.txt", b"base b".to_vec());
+        let mut patch = PackBuilder::new();
+        patch.add("a.txt", b"patched a".to_vec());
+
+        let mut vfs = Vfs::new();
+        vfs.mount("", 0, Pack::from_bytes(base.to_bytes().unwrap()).unwrap());
+        vfs.mount("data", 10, Pack::from_bytes(patch.to_bytes().unwrap()).unwrap());
+        assert_eq!(vfs.read("data/a.txt").unwrap(), b"patched a");
+        assert_eq!(vfs.read("./data/../data/b.txt").unwrap(), b"base b");
+        assert!(vfs.read("data/c.txt").is_err());
+        assert_eq!(vfs.files(), vec![PathBuf::from("data/a.txt"), PathBuf::from("data/b.txt")]);
+
+        assert_eq!(vfs.unmount("data"), 1);
+        assert_eq!(vfs.read("data/a.txt").unwrap(), b"base a");
+    }
+}
@@ -0,0 +1,352 @@
+// src/engine/vfs/pack.rs
+//
+// Pack archives: many files in one, for shipping builds and for the web where one download
+// is much cheaper than hundreds of requests.
+//
+//   "RGEPACK1" | entry count u32 | index offset u64 | file data ... | index
+//   index entry: path length u16 | utf-8 path | offset u64 | stored size u64 | size u64 | flags u8
+//
+// All numbers are little endian, paths use `/`. Flag 1 means the data is zlib compressed.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::engine::assets::server::normalize;
+use crate::engine::vfs::{Directory, MountSource};
+
+const MAGIC: &[u8; 8] = b"RGEPACK1";
+const HEADER_SIZE: usize = 20;
+const FLAG_COMPRESSED: u8 = 1;
+/// index entry with an empty path: length, offset, stored, size and flags
+const MIN_ENTRY_LEN: usize = 2 + 8 * 3 + 1;
+/// deflate can't expand more than this, the size from the index is only believed up to it
+const MAX_RATIO: u64 = 1032;
+
+#[derive(Debug)]
+pub enum PackError {
+    Io(io::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PackError::Io(e) => write!(f, "pack io error: {}", e),
+            PackError::Invalid(msg) => write!(f, "invalid pack: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PackError {}
+
+impl From<io::Error> for PackError {
+    fn from(e: io::Error) -> Self {
+        PackError::Io(e)
+    }
+}
+
+/// `a\b/./c` -> `a/b/c`, how paths are stored in the index
+fn key(path: &Path) -> String {
+    normalize(path).components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    offset: u64,
+    stored: u64,
+    size: u64,
+    compressed: bool,
+}
+
+enum PackData {
+    Memory(Vec<u8>),
+    /// only the index is kept in memory, entries are read when requested
+    File(PathBuf),
+}
+
+pub struct Pack {
+    entries: HashMap<String, Entry>,
+    data: PackData,
+    modified: Option<SystemTime>,
+}
+
+/// little endian reader over the index
+struct Cursor<'a> {
+    bytes: &'a [u8],
+}
+
+impl Cursor<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], PackError> {
+        if self.bytes.len() < n {
+            return Err(PackError::Invalid("truncated index".to_string()));
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u16(&mut self) -> Result<u16, PackError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, PackError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, PackError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+/// entry count and index offset
+fn read_header(header: &[u8]) -> Result<(u32, u64), PackError> {
+    if header.len() < HEADER_SIZE || &header[..8] != MAGIC {
+        return Err(PackError::Invalid("not a pack file".to_string()));
+    }
+    let mut cursor = Cursor { bytes: &header[8..] };
+    Ok((cursor.u32()?, cursor.u64()?))
+}
+
+fn read_index(index: &[u8], count: u32, data_end: u64) -> Result<HashMap<String, Entry>, PackError> {
+    // sayı başlıktan geliyor, indekse sığmıyorsa dosya bozuk
+    if count as usize > index.len() / MIN_ENTRY_LEN {
+        return Err(PackError::Invalid(format!("{} entries don't fit in the index", count)));
+    }
+    let mut cursor = Cursor { bytes: index };
+    let mut entries = HashMap::with_capacity(count as usize);
+    for _ in 0..count {
+        let length = cursor.u16()? as usize;
+        let path = String::from_utf8(cursor.take(length)?.to_vec())
+            .map_err(|_| PackError::Invalid("path is not utf-8".to_string()))?;
+        let entry = Entry {
+            offset: cursor.u64()?,
+            stored: cursor.u64()?,
+            size: cursor.u64()?,
+            compressed: cursor.take(1)?[0] & FLAG_COMPRESSED != 0,
+        };
+        if entry.offset < HEADER_SIZE as u64 || entry.offset.checked_add(entry.stored).is_none_or(|end| end > data_end) {
+            return Err(PackError::Invalid(format!("{} is outside of the data", path)));
+        }
+        entries.insert(path, entry);
+    }
+    Ok(entries)
+}
+
+impl Pack {
+    /// Reads the index of a pack file, the data stays on disk
+    pub fn open<P: Into<PathBuf>>(path: P) -> Result<Self, PackError> {
+        let path = path.into();
+        let mut file = std::fs::File::open(&path)?;
+        let mut header = [0; HEADER_SIZE];
+        file.read_exact(&mut header)?;
+        let (count, index_offset) = read_header(&header)?;
+        let mut index = Vec::new();
+        io::Seek::seek(&mut file, io::SeekFrom::Start(index_offset))?;
+        file.read_to_end(&mut index)?;
+        let metadata = file.metadata()?;
+        Ok(Self {
+            entries: read_index(&index, count, index_offset)?,
+            modified: metadata.modified().ok(),
+            data: PackData::File(path),
+        })
+    }
+
+    /// A pack that was downloaded or embedded in the binary
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, PackError> {
+        let (count, index_offset) = read_header(&bytes)?;
+        let index = bytes.get(index_offset as usize..)
+            .ok_or_else(|| PackError::Invalid("index is outside of the file".to_string()))?;
+        Ok(Self {
+            entries: read_index(index, count, index_offset)?,
+            modified: None,
+            data: PackData::Memory(bytes),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn stored(&self, entry: Entry) -> io::Result<Vec<u8>> {
+        match &self.data {
+            PackData::Memory(bytes) => Ok(bytes[entry.offset as usize..(entry.offset + entry.stored) as usize].to_vec()),
+            PackData::File(path) => {
+                let mut file = std::fs::File::open(path)?;
+                io::Seek::seek(&mut file, io::SeekFrom::Start(entry.offset))?;
+                let mut stored = vec![0; entry.stored as usize];
+                file.read_exact(&mut stored)?;
+                Ok(stored)
+            }
+        }
+    }
+}
+
+impl MountSource for Pack {
+    fn contains(&self, path: &Path) -> bool {
+        self.entries.contains_key(&key(path))
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let entry = *self.entries.get(&key(path))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{} is not in the pack", path.display())))?;
+        let stored = self.stored(entry)?;
+        if !entry.compressed {
+            return Ok(stored);
+        }
+        let capacity = entry.size.min(stored.len() as u64 * MAX_RATIO);
+        let mut bytes = Vec::with_capacity(capacity as usize);
+        // bir bayt fazlası okunuyor ki büyük çıkan akış aşağıda reddedilsin
+        flate2::read::ZlibDecoder::new(&stored[..]).take(entry.size.saturating_add(1)).read_to_end(&mut bytes)?;
+        if bytes.len() as u64 != entry.size {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("{} has the wrong size", path.display())));
+        }
+        Ok(bytes)
+    }
+
+    /// the pack's own modification time, the index is read only once anyway
+    fn modified(&self, _path: &Path) -> Option<SystemTime> {
+        self.modified
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self.entries.keys().map(PathBuf::from).collect();
+        files.sort();
+        files
+    }
+}
+
+/// Writes pack files, see `src/bin/pack_builder.rs` for the command line tool
+pub struct PackBuilder {
+    files: Vec<(String, Vec<u8>)>,
+    compression: bool,
+}
+
+impl Default for PackBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PackBuilder {
+    pub fn new() -> Self {
+        Self { files: Vec::new(), compression: true }
+    }
+
+    /// Files are compressed when that makes them smaller, on by default
+    pub fn with_compression(mut self, compression: bool) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Adds (or replaces) a file at the virtual `path`
+    pub fn add<P: AsRef<Path>>(&mut self, path: P, bytes: Vec<u8>) {
+        let path = key(path.as_ref());
+        self.files.retain(|(p, _)| *p != path);
+        self.files.push((path, bytes));
+    }
+
+    /// Adds every file below `dir` under `point`, returns how many were added
+    pub fn add_dir<D: Into<PathBuf>, P: AsRef<Path>>(&mut self, dir: D, point: P) -> io::Result<usize> {
+        let dir = Directory::new(dir);
+        let files = dir.files();
+        for file in &files {
+            self.add(point.as_ref().join(file), dir.read(file)?);
+        }
+        Ok(files.len())
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        let mut index = Vec::new();
+        for (path, bytes) in &self.files {
+            let offset = (HEADER_SIZE + data.len()) as u64;
+            let compressed = if self.compression { Some(compress(bytes)?) } else { None };
+            // küçülmüyorsa (png, ogg...) sıkıştırılmadan saklanıyor
+            let (stored, flags) = match compressed {
+                Some(c) if c.len() < bytes.len() => (c, FLAG_COMPRESSED),
+                _ => (bytes.clone(), 0),
+            };
+            index.extend_from_slice(&(path.len() as u16).to_le_bytes());
+            index.extend_from_slice(path.as_bytes());
+            index.extend_from_slice(&offset.to_le_bytes());
+            index.extend_from_slice(&(stored.len() as u64).to_le_bytes());
+            index.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+            index.push(flags);
+            data.extend_from_slice(&stored);
+        }
+
+        let mut pack = Vec::with_capacity(HEADER_SIZE + data.len() + index.len());
+        pack.extend_from_slice(MAGIC);
+        pack.extend_from_slice(&(self.files.len() as u32).to_le_bytes());
+        pack.extend_from_slice(&((HEADER_SIZE + data.len()) as u64).to_le_bytes());
+        pack.extend_from_slice(&data);
+        pack.extend_from_slice(&index);
+        Ok(pack)
+    }
+
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        std::fs::write(path, self.to_bytes()?)
+    }
+}
+
+fn compress(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip(){
+        let mut builder = PackBuilder::new();
+        builder.add("text/a.txt", "aaaa".repeat(100).into_bytes());
+        builder.add("b.bin", vec![1, 2, 3]);
+        let bytes = builder.to_bytes().unwrap();
+        let pack = Pack::from_bytes(bytes.clone()).unwrap();
+        assert_eq!(pack.len(), 2);
+        assert!(pack.entries["text/a.txt"].compressed);
+        assert!(!pack.entries["b.bin"].compressed);
+        assert_eq!(pack.read(Path::new("text/a.txt")).unwrap(), "aaaa".repeat(100).into_bytes());
+        assert_eq!(pack.read(Path::new("b.bin")).unwrap(), vec![1, 2, 3]);
+
+        let file = std::env::temp_dir().join(format!("pack_test_{}.pak", std::process::id()));
+        std::fs::write(&file, &bytes).unwrap();
+        let pack = Pack::open(&file).unwrap();
+        assert_eq!(pack.read(Path::new("text/a.txt")).unwrap().len(), 400);
+        std::fs::remove_file(file).unwrap();
+
+        assert!(Pack::from_bytes(bytes[..10].to_vec()).is_err());
+        let mut huge = bytes.clone();
+        huge[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(Pack::from_bytes(huge), Err(PackError::Invalid(_))));
+
+        // indeksteki boyut küçültülünce açılan veri reddediliyor
+        let mut short = bytes.clone();
+        let index = u64::from_le_bytes(short[12..20].try_into().unwrap()) as usize;
+        let size = index + 2 + "text/a.txt".len() + 16;
+        short[size..size + 8].copy_from_slice(&10u64.to_le_bytes());
+        let pack = Pack::from_bytes(short).unwrap();
+        assert_eq!(pack.read(Path::new("text/a.txt")).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
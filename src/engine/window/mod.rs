@@ -34,10 +34,10 @@ impl GameWindow {
         #[cfg(not(target_family = "wasm"))]
         let w = sdl_backend::SdlWindow::new(title.clone());
         
-        return Self {
+        Self {
             inner:Box::new(w),
             surface_manager: None, // Başlangıçta yok
-        };
+        }
     }
     pub fn poll_events(&mut self ){
         self.inner.poll_events();
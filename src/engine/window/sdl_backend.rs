@@ -34,6 +34,8 @@ impl SdlWindow {
 
 
         let pump = s.event_pump().unwrap();
+        // SdlWindow sadece ana thread'de kullanılıyor, Send/Sync elle verildi
+        #[allow(clippy::arc_with_non_send_sync)]
         let sdl = Arc::new(Mutex::new(s));
         Self{
             sdlctx: sdl,
@@ -55,14 +57,10 @@ impl InnerWindow for SdlWindow {
         if let Some(ref mut pump_wrapper) = self.event_pump{
             // Olayları tüket (pump) ki OS pencerenin donduğunu sanmasın
             for _event in pump_wrapper.0.poll_iter() {
-                match _event {
-                    sdl3::event::Event::Quit { timestamp } => {
-                        let mut msgs = PENDING_MESSAGES.lock().unwrap();
-                        msgs.push(crate::Message::Kill);
-                        msgs.push(crate::Message::Log(crate::LogMsg::Warn("Killing the game".to_string())));
-                    },
-
-                    _ => ()
+                if let sdl3::event::Event::Quit { .. } = _event {
+                    let mut msgs = PENDING_MESSAGES.lock().unwrap();
+                    msgs.push(crate::Message::Kill);
+                    msgs.push(crate::Message::Log(crate::LogMsg::Warn("Killing the game".to_string())));
                 }
                 // Burada ileride klavye/mouse eventlerini işleyebilirsin
                 // Örn: Message::KeyDown gönderilebilir